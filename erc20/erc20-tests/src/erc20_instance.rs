@@ -166,6 +166,72 @@ impl ERC20Instance {
         );
     }
 
+    pub fn transfer_with_time<T: Into<Key>>(
+        &self,
+        sender: Sender,
+        recipient: T,
+        amount: U256,
+        time: u64,
+    ) {
+        self.0.call_contract_with_time(
+            sender,
+            "transfer",
+            runtime_args! {
+                "recipient" => recipient.into(),
+                "amount" => amount
+            },
+            time,
+        );
+    }
+
+    pub fn delegate<T: Into<Key>>(&self, sender: Sender, delegatee: T, time: u64) {
+        self.0.call_contract_with_time(
+            sender,
+            "delegate",
+            runtime_args! {
+                "delegatee" => delegatee.into()
+            },
+            time,
+        );
+    }
+
+    pub fn get_prior_votes<T: Into<Key>>(&self, sender: Sender, account: T, time: u64, now: u64) {
+        self.0.call_contract_with_time(
+            sender,
+            "get_prior_votes",
+            runtime_args! {
+                "account" => account.into(),
+                "time" => time
+            },
+            now,
+        );
+    }
+
+    pub fn enable_snapshots(&self, sender: Sender) {
+        self.0
+            .call_contract(sender, "enable_snapshots", runtime_args! {});
+    }
+
+    pub fn snapshot(&self, sender: Sender) {
+        self.0.call_contract(sender, "snapshot", runtime_args! {});
+    }
+
+    pub fn balance_of_at<T: Into<Key>>(&self, sender: Sender, owner: T, id: U256) {
+        self.0.call_contract(
+            sender,
+            "balance_of_at",
+            runtime_args! {
+                "owner" => owner.into(),
+                "id" => id
+            },
+        );
+    }
+
+    pub fn total_supply_at(&self, sender: Sender, id: U256) {
+        self.0
+            .call_contract(sender, "total_supply_at", runtime_args! {"id" => id});
+    }
+
     pub fn balance_of<T: Into<Key>>(&self, account: T) -> U256 {
         self.0
             .query_dictionary("balances", key_to_str(&account.into()))
@@ -186,6 +252,31 @@ impl ERC20Instance {
             .unwrap_or_default()
     }
 
+    pub fn delegates<T: Into<Key>>(&self, delegator: T) -> Option<Key> {
+        self.0
+            .query_dictionary("delegates", key_to_str(&delegator.into()))
+    }
+
+    pub fn num_checkpoints<T: Into<Key>>(&self, account: T) -> u32 {
+        self.0
+            .query_dictionary("num_checkpoints", key_to_str(&account.into()))
+            .unwrap_or_default()
+    }
+
+    pub fn checkpoints<T: Into<Key>>(&self, account: T, index: u32) -> (u64, U256) {
+        self.0
+            .query_dictionary("checkpoints", checkpoint_key(&account.into(), index))
+            .unwrap_or_default()
+    }
+
+    pub fn current_votes<T: Into<Key>>(&self, account: T) -> U256 {
+        let account: Key = account.into();
+        match self.num_checkpoints(account) {
+            0 => 0.into(),
+            len => self.checkpoints(account, len - 1).1,
+        }
+    }
+
     pub fn name(&self) -> String {
         self.0.query_named_key(String::from("name"))
     }
@@ -202,6 +293,14 @@ impl ERC20Instance {
         self.0.query_named_key(String::from("total_supply"))
     }
 
+    pub fn snapshots_enabled(&self) -> bool {
+        self.0.query_named_key(String::from("snapshots_enabled"))
+    }
+
+    pub fn current_snapshot_id(&self) -> U256 {
+        self.0.query_named_key(String::from("current_snapshot_id"))
+    }
+
     // Result methods
     pub fn transfer_result(&self) -> Result<(), u32> {
         self.0.query_named_key("transfer_result".to_string())
@@ -217,6 +316,18 @@ impl ERC20Instance {
     pub fn allowance_res(&self) -> U256 {
         self.0.query_named_key("allowance".to_string())
     }
+
+    pub fn prior_votes_res(&self) -> U256 {
+        self.0.query_named_key("prior_votes".to_string())
+    }
+
+    pub fn balance_of_at_res(&self) -> U256 {
+        self.0.query_named_key("balance_of_at".to_string())
+    }
+
+    pub fn total_supply_at_res(&self) -> U256 {
+        self.0.query_named_key("total_supply_at".to_string())
+    }
 }
 
 pub fn key_to_str(key: &Key) -> String {
@@ -235,3 +346,12 @@ pub fn keys_to_str(key_a: &Key, key_b: &Key) -> String {
     hasher.finalize_variable(|hash| ret.clone_from_slice(hash));
     hex::encode(ret)
 }
+
+pub fn checkpoint_key(account: &Key, index: u32) -> String {
+    let mut hasher = VarBlake2b::new(32).unwrap();
+    hasher.update(account.to_bytes().unwrap());
+    hasher.update(index.to_bytes().unwrap());
+    let mut ret = [0u8; 32];
+    hasher.finalize_variable(|hash| ret.clone_from_slice(hash));
+    hex::encode(ret)
+}
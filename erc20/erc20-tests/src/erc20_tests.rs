@@ -228,3 +228,146 @@ fn test_calling_construction() {
         INIT_TOTAL_SUPPLY.into(),
     );
 }
+
+#[test]
+fn test_erc20_delegate() {
+    let (env, _, _, token, owner) = deploy();
+    let user = env.next_user();
+    assert_eq!(token.delegates(owner), None);
+    assert_eq!(token.current_votes(owner), 0.into());
+    // Balances only count as votes once they are delegated.
+    token.delegate(Sender(owner), owner, 1000);
+    assert_eq!(token.delegates(owner), Some(Key::from(owner)));
+    assert_eq!(token.num_checkpoints(owner), 1);
+    assert_eq!(
+        token.checkpoints(owner, 0),
+        (1000, INIT_TOTAL_SUPPLY.into())
+    );
+    token.delegate(Sender(owner), user, 2000);
+    assert_eq!(token.delegates(owner), Some(Key::from(user)));
+    assert_eq!(token.num_checkpoints(owner), 2);
+    assert_eq!(token.checkpoints(owner, 1), (2000, 0.into()));
+    assert_eq!(token.current_votes(user), INIT_TOTAL_SUPPLY.into());
+    assert_eq!(token.balance_of(user), 0.into());
+}
+
+#[test]
+fn test_erc20_votes_move_with_transfers() {
+    let (env, _, _, token, owner) = deploy();
+    let user = env.next_user();
+    token.delegate(Sender(owner), owner, 1000);
+    // Delegating a zero balance writes no checkpoint.
+    token.delegate(Sender(user), user, 1000);
+    assert_eq!(token.num_checkpoints(user), 0);
+    token.transfer_with_time(Sender(owner), user, 300.into(), 2000);
+    assert_eq!(token.checkpoints(owner, 1), (2000, 700.into()));
+    assert_eq!(token.checkpoints(user, 0), (2000, 300.into()));
+    // A second transfer at the same block time updates the last checkpoints in place.
+    token.transfer_with_time(Sender(user), owner, 100.into(), 2000);
+    assert_eq!(token.num_checkpoints(owner), 2);
+    assert_eq!(token.num_checkpoints(user), 1);
+    assert_eq!(token.current_votes(owner), 800.into());
+    assert_eq!(token.current_votes(user), 200.into());
+}
+
+#[test]
+fn test_erc20_get_prior_votes() {
+    let (env, proxy, _, token, owner) = deploy();
+    let user = env.next_user();
+    token.delegate(Sender(owner), owner, 1000);
+    token.transfer_with_time(Sender(owner), user, 400.into(), 3000);
+    token.transfer_with_time(Sender(owner), user, 100.into(), 5000);
+    let expected: [(u64, u64); 7] = [
+        (999, 0),
+        (1000, 1000),
+        (2999, 1000),
+        (3000, 600),
+        (4000, 600),
+        (5000, 500),
+        (9000, 500),
+    ];
+    for (time, votes) in expected.iter() {
+        proxy.get_prior_votes(Sender(owner), owner, *time, 10000);
+        assert_eq!(proxy.prior_votes_res(), (*votes).into());
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_erc20_get_prior_votes_not_yet_determined() {
+    let (_, proxy, _, token, owner) = deploy();
+    token.delegate(Sender(owner), owner, 1000);
+    proxy.get_prior_votes(Sender(owner), owner, 1000, 1000);
+}
+
+#[test]
+fn test_erc20_snapshot() {
+    let (env, proxy, _, token, owner) = deploy();
+    let user = env.next_user();
+    assert!(!token.snapshots_enabled());
+    // owner deployed the token and is its admin
+    token.enable_snapshots(Sender(owner));
+    assert!(token.snapshots_enabled());
+    assert_eq!(token.current_snapshot_id(), 0.into());
+    token.snapshot(Sender(owner));
+    assert_eq!(token.current_snapshot_id(), 1.into());
+    token.transfer(Sender(owner), user, 400.into());
+    token.snapshot(Sender(owner));
+    token.mint(Sender(owner), user, 500.into());
+    token.burn(Sender(owner), owner, 100.into());
+    token.snapshot(Sender(owner));
+    assert_eq!(token.current_snapshot_id(), 3.into());
+    // `(id, owner's balance, user's balance, total supply)` at each snapshot.
+    let expected: [(u64, u64, u64, u64); 3] =
+        [(1, 1000, 0, 1000), (2, 600, 400, 1000), (3, 500, 900, 1400)];
+    for (id, owner_balance, user_balance, total_supply) in expected.iter() {
+        proxy.balance_of_at(Sender(owner), owner, (*id).into());
+        assert_eq!(proxy.balance_of_at_res(), (*owner_balance).into());
+        proxy.balance_of_at(Sender(owner), user, (*id).into());
+        assert_eq!(proxy.balance_of_at_res(), (*user_balance).into());
+        proxy.total_supply_at(Sender(owner), (*id).into());
+        assert_eq!(proxy.total_supply_at_res(), (*total_supply).into());
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_erc20_balance_of_at_zero_snapshot_id() {
+    let (_, proxy, _, token, owner) = deploy();
+    token.enable_snapshots(Sender(owner));
+    token.snapshot(Sender(owner));
+    proxy.balance_of_at(Sender(owner), owner, 0.into());
+}
+
+#[test]
+#[should_panic]
+fn test_erc20_total_supply_at_future_snapshot_id() {
+    let (_, proxy, _, token, owner) = deploy();
+    token.enable_snapshots(Sender(owner));
+    token.snapshot(Sender(owner));
+    proxy.total_supply_at(Sender(owner), 2.into());
+}
+
+#[test]
+#[should_panic]
+fn test_erc20_enable_snapshots_by_non_admin() {
+    let (env, _, _, token, _) = deploy();
+    let user = env.next_user();
+    token.enable_snapshots(Sender(user));
+}
+
+#[test]
+#[should_panic]
+fn test_erc20_snapshot_by_non_admin() {
+    let (env, _, _, token, owner) = deploy();
+    let user = env.next_user();
+    token.enable_snapshots(Sender(owner));
+    token.snapshot(Sender(user));
+}
+
+#[test]
+#[should_panic]
+fn test_erc20_snapshot_before_enabled() {
+    let (_, _, _, token, owner) = deploy();
+    token.snapshot(Sender(owner));
+}
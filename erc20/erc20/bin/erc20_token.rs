@@ -11,8 +11,8 @@ use casper_types::{
     runtime_args, CLType, CLTyped, CLValue, ContractHash, ContractPackageHash, EntryPoint,
     EntryPointAccess, EntryPointType, EntryPoints, Group, Key, Parameter, RuntimeArgs, URef, U256,
 };
-use contract_utils::{ContractContext, OnChainContractStorage};
-use erc20::{self, ERC20};
+use contract_utils::{AdminControl, ContractContext, OnChainContractStorage};
+use erc20::{self, ERC20, ERC20SNAPSHOT};
 
 #[derive(Default)]
 struct Token(OnChainContractStorage);
@@ -23,7 +23,17 @@ impl ContractContext<OnChainContractStorage> for Token {
     }
 }
 
-impl ERC20<OnChainContractStorage> for Token {}
+impl ERC20<OnChainContractStorage> for Token {
+    fn before_token_transfer(&mut self, from: Key, to: Key) {
+        if ERC20SNAPSHOT::snapshots_enabled(self) {
+            ERC20SNAPSHOT::update_snapshots(self, from, to);
+        }
+    }
+}
+
+impl ERC20SNAPSHOT<OnChainContractStorage> for Token {}
+
+impl AdminControl<OnChainContractStorage> for Token {}
 
 impl Token {
     fn constructor(
@@ -47,6 +57,9 @@ impl Token {
             Key::from(contract_hash),
             package_hash,
         );
+        ERC20SNAPSHOT::init(self);
+        AdminControl::init(self);
+        self.add_admin_without_checked(self.get_caller());
         let _ret = ERC20::mint(self, self.get_caller(), initial_supply);
     }
 }
@@ -255,6 +268,15 @@ fn symbol() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
+/// This function is to return the Decimals of contract
+///
+
+#[no_mangle]
+fn decimals() {
+    let ret: u8 = Token::default().decimals();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 /// This function is to return the Allowance of owner and spender that user provided
 ///
 /// # Parameters
@@ -315,6 +337,187 @@ fn decrease_allowance() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
+/// This function is to delegate the votes of the caller to a delegatee, balances only count as votes once they are delegated
+///
+/// # Parameters
+///
+/// * `delegatee` - A Key that holds the account address of the delegatee, which can be the caller itself
+///
+
+#[no_mangle]
+fn delegate() {
+    let delegatee: Key = runtime::get_named_arg("delegatee");
+    Token::default().delegate(delegatee);
+}
+
+/// This function is to return the delegatee of a delegator, the zero account if it has not delegated
+///
+/// # Parameters
+///
+/// * `delegator` - A Key that holds the account address of the delegator
+///
+
+#[no_mangle]
+fn delegates() {
+    let delegator: Key = runtime::get_named_arg("delegator");
+    let ret: Key = Token::default().delegates(delegator);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the current votes of an account
+///
+/// # Parameters
+///
+/// * `account` - A Key that holds the account address of the user
+///
+
+#[no_mangle]
+fn get_current_votes() {
+    let account: Key = runtime::get_named_arg("account");
+    let ret: U256 = Token::default().get_current_votes(account);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the votes of an account at a block time in the past
+///
+/// # Parameters
+///
+/// * `account` - A Key that holds the account address of the user
+///
+/// * `time` - A u64 that holds the block time in milliseconds, which must be before the current block time
+///
+
+#[no_mangle]
+fn get_prior_votes() {
+    let account: Key = runtime::get_named_arg("account");
+    let time: u64 = runtime::get_named_arg("time");
+    let ret: U256 = Token::default().get_prior_votes(account, time);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the number of vote checkpoints of an account
+///
+/// # Parameters
+///
+/// * `account` - A Key that holds the account address of the user
+///
+
+#[no_mangle]
+fn num_checkpoints() {
+    let account: Key = runtime::get_named_arg("account");
+    let ret: u32 = Token::default().num_checkpoints(account);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return a vote checkpoint of an account as the block time in milliseconds it starts at and the votes
+///
+/// # Parameters
+///
+/// * `account` - A Key that holds the account address of the user
+///
+/// * `index` - A u32 that holds the index of the checkpoint
+///
+
+#[no_mangle]
+fn checkpoints() {
+    let account: Key = runtime::get_named_arg("account");
+    let index: u32 = runtime::get_named_arg("index");
+    let ret: (u64, U256) = Token::default().checkpoints(account, index);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to opt the token in to snapshots of balances and total supply which is only possible if the caller is an admin
+///
+
+#[no_mangle]
+fn enable_snapshots() {
+    Token::default().enable_snapshots();
+}
+
+/// This function is to return whether the token has opted in to snapshots
+///
+
+#[no_mangle]
+fn snapshots_enabled() {
+    let ret: bool = Token::default().snapshots_enabled();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to take a snapshot of every balance and of the total supply once the token has opted in which is only possible if the caller is an admin, it returns the id of the snapshot
+///
+
+#[no_mangle]
+fn snapshot() {
+    let ret: U256 = Token::default().snapshot();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the id of the last snapshot taken, 0 if none has been taken
+///
+
+#[no_mangle]
+fn current_snapshot_id() {
+    let ret: U256 = Token::default().get_current_snapshot_id();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the balance of an owner at a snapshot
+///
+/// # Parameters
+///
+/// * `owner` - A Key that holds the account address of the user
+///
+/// * `id` - A U256 that holds the id of the snapshot, which must have been taken
+///
+
+#[no_mangle]
+fn balance_of_at() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let id: U256 = runtime::get_named_arg("id");
+    let ret: U256 = Token::default().balance_of_at(owner, id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the total supply at a snapshot
+///
+/// # Parameters
+///
+/// * `id` - A U256 that holds the id of the snapshot, which must have been taken
+///
+
+#[no_mangle]
+fn total_supply_at() {
+    let id: U256 = runtime::get_named_arg("id");
+    let ret: U256 = Token::default().total_supply_at(id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to add an admin which is only possible if the caller is an admin
+///
+/// # Parameters
+///
+/// * `address` - A Key that holds the Account Hash of the new admin
+///
+
+#[no_mangle]
+fn add_admin() {
+    let address: Key = runtime::get_named_arg("address");
+    Token::default().add_admin(address);
+}
+
+/// This function is to remove an admin which is only possible if the caller is an admin
+///
+/// # Parameters
+///
+/// * `address` - A Key that holds the Account Hash of the admin to remove
+///
+
+#[no_mangle]
+fn disable_admin() {
+    let address: Key = runtime::get_named_arg("address");
+    Token::default().disable_admin(address);
+}
+
 /// This function is to fetch a Contract Package Hash
 ///
 
@@ -466,6 +669,13 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "decimals",
+        vec![],
+        u8::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "increase_allowance",
         vec![
@@ -492,6 +702,113 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "delegate",
+        vec![Parameter::new("delegatee", Key::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "delegates",
+        vec![Parameter::new("delegator", Key::cl_type())],
+        Key::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_current_votes",
+        vec![Parameter::new("account", Key::cl_type())],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_prior_votes",
+        vec![
+            Parameter::new("account", Key::cl_type()),
+            Parameter::new("time", u64::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "num_checkpoints",
+        vec![Parameter::new("account", Key::cl_type())],
+        u32::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "checkpoints",
+        vec![
+            Parameter::new("account", Key::cl_type()),
+            Parameter::new("index", u32::cl_type()),
+        ],
+        <(u64, U256)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "enable_snapshots",
+        vec![],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "snapshots_enabled",
+        vec![],
+        bool::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "snapshot",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "current_snapshot_id",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "balance_of_at",
+        vec![
+            Parameter::new("owner", Key::cl_type()),
+            Parameter::new("id", U256::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "total_supply_at",
+        vec![Parameter::new("id", U256::cl_type())],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "add_admin",
+        vec![Parameter::new("address", Key::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "disable_admin",
+        vec![Parameter::new("address", Key::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
 
     entry_points
 }
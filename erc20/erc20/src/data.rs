@@ -1,12 +1,19 @@
-use alloc::string::String;
+use alloc::{string::String, vec::Vec};
 
-use casper_contract::unwrap_or_revert::UnwrapOrRevert;
-use casper_types::{ContractPackageHash, Key, U256};
-use contract_utils::{get_key, set_key, Dict};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{bytesrepr::ToBytes, ContractPackageHash, Key, U256};
+use contract_utils::{get_key, key_to_str, set_key, Dict, ZERO_ACCOUNT};
 
 pub const BALANCES_DICT: &str = "balances";
 pub const NONCES_DICT: &str = "nonces";
 pub const ALLOWANCES_DICT: &str = "allowances";
+pub const DELEGATES_DICT: &str = "delegates";
+pub const NUM_CHECKPOINTS_DICT: &str = "num_checkpoints";
+pub const CHECKPOINTS_DICT: &str = "checkpoints";
+pub const SNAPSHOT_COUNTS_DICT: &str = "snapshot_counts";
+pub const SNAPSHOTS_DICT: &str = "snapshots";
+pub const CURRENT_SNAPSHOT_ID: &str = "current_snapshot_id";
+pub const SNAPSHOTS_ENABLED: &str = "snapshots_enabled";
 pub const NAME: &str = "name";
 pub const SYMBOL: &str = "symbol";
 pub const DECIMALS: &str = "decimals";
@@ -88,6 +95,153 @@ impl Allowances {
     }
 }
 
+/// Account that each account has delegated its votes to, `ZERO_ACCOUNT` if it has not.
+pub struct Delegates {
+    dict: Dict,
+}
+
+impl Delegates {
+    pub fn instance() -> Delegates {
+        Delegates {
+            dict: dict_or_init(DELEGATES_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(DELEGATES_DICT)
+    }
+
+    pub fn get(&self, delegator: &Key) -> Key {
+        self.dict.get_by_key(delegator).unwrap_or(ZERO_ACCOUNT)
+    }
+
+    pub fn set(&self, delegator: &Key, delegatee: Key) {
+        self.dict.set_by_key(delegator, delegatee);
+    }
+}
+
+/// `(time, votes)`: the votes of an account from the block time `time` on, in
+/// milliseconds, until the time of its next checkpoint.
+pub type Checkpoint = (u64, U256);
+
+/// Votes of every account over time, as a list of checkpoints per account in the order
+/// of their times.
+pub struct Checkpoints {
+    num_checkpoints: Dict,
+    checkpoints: Dict,
+}
+
+impl Checkpoints {
+    pub fn instance() -> Checkpoints {
+        Checkpoints {
+            num_checkpoints: dict_or_init(NUM_CHECKPOINTS_DICT),
+            checkpoints: dict_or_init(CHECKPOINTS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(NUM_CHECKPOINTS_DICT);
+        Dict::init(CHECKPOINTS_DICT);
+    }
+
+    pub fn len(&self, account: &Key) -> u32 {
+        self.num_checkpoints.get_by_key(account).unwrap_or_default()
+    }
+
+    pub fn get(&self, account: &Key, index: u32) -> Checkpoint {
+        self.checkpoints
+            .get(&checkpoint_key(account, index))
+            .unwrap_or_default()
+    }
+
+    /// Sets the checkpoint at `index`, which is either an existing one or the next one.
+    pub fn set(&self, account: &Key, index: u32, checkpoint: Checkpoint) {
+        self.checkpoints
+            .set(&checkpoint_key(account, index), checkpoint);
+        if index >= self.len(account) {
+            self.num_checkpoints.set_by_key(account, index + 1);
+        }
+    }
+}
+
+/// `(id, value)`: the value of a balance or of the total supply when the snapshot `id`
+/// was taken.
+pub type Snapshot = (U256, U256);
+
+/// Balances and total supply at the snapshots that were taken, as a list of snapshots
+/// per owner in the order of their ids. A value is only recorded the first time it
+/// changes after a snapshot, so a snapshot without an entry has the value of the next
+/// entry, or the current value if there is none.
+pub struct Snapshots {
+    counts: Dict,
+    snapshots: Dict,
+}
+
+impl Snapshots {
+    pub fn instance() -> Snapshots {
+        Snapshots {
+            counts: dict_or_init(SNAPSHOT_COUNTS_DICT),
+            snapshots: dict_or_init(SNAPSHOTS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(SNAPSHOT_COUNTS_DICT);
+        Dict::init(SNAPSHOTS_DICT);
+    }
+
+    /// Owner under which the snapshots of the total supply are kept.
+    pub fn total_supply_owner() -> String {
+        String::from(TOTAL_SUPPLY)
+    }
+
+    /// Owner under which the snapshots of the balance of `account` are kept.
+    pub fn account_owner(account: &Key) -> String {
+        key_to_str(account)
+    }
+
+    pub fn len(&self, owner: &str) -> u32 {
+        self.counts.get(owner).unwrap_or_default()
+    }
+
+    pub fn get(&self, owner: &str, index: u32) -> Snapshot {
+        self.snapshots
+            .get(&snapshot_key(owner, index))
+            .unwrap_or_default()
+    }
+
+    pub fn push(&self, owner: &str, snapshot: Snapshot) {
+        let len: u32 = self.len(owner);
+        self.snapshots.set(&snapshot_key(owner, len), snapshot);
+        self.counts.set(owner, len + 1);
+    }
+}
+
+/// Tokens deployed before a dictionary was added and upgraded since do not have it, so
+/// it is created the first time it is used.
+fn dict_or_init(name: &str) -> Dict {
+    if !runtime::has_key(name) {
+        Dict::init(name);
+    }
+    Dict::instance(name)
+}
+
+/// Dictionary keys are limited in length, so the checkpoint of an account is stored
+/// under the hash of the account and the index, as `keys_to_str` does for two keys.
+fn checkpoint_key(account: &Key, index: u32) -> String {
+    let mut bytes: Vec<u8> = account.to_bytes().unwrap_or_revert();
+    bytes.append(&mut index.to_bytes().unwrap_or_revert());
+    hex::encode(runtime::blake2b(bytes))
+}
+
+/// Dictionary keys are limited in length, so a snapshot is stored under the hash of its
+/// owner and index.
+fn snapshot_key(owner: &str, index: u32) -> String {
+    let mut bytes: Vec<u8> = owner.to_bytes().unwrap_or_revert();
+    bytes.append(&mut index.to_bytes().unwrap_or_revert());
+    hex::encode(runtime::blake2b(bytes))
+}
+
 pub fn name() -> String {
     get_key(NAME).unwrap_or_revert()
 }
@@ -120,6 +274,23 @@ pub fn set_total_supply(total_supply: U256) {
     set_key(TOTAL_SUPPLY, total_supply);
 }
 
+/// Id of the last snapshot taken, 0 before the first one.
+pub fn current_snapshot_id() -> U256 {
+    get_key(CURRENT_SNAPSHOT_ID).unwrap_or_default()
+}
+
+pub fn set_current_snapshot_id(current_snapshot_id: U256) {
+    set_key(CURRENT_SNAPSHOT_ID, current_snapshot_id);
+}
+
+pub fn snapshots_enabled() -> bool {
+    get_key(SNAPSHOTS_ENABLED).unwrap_or_default()
+}
+
+pub fn set_snapshots_enabled(snapshots_enabled: bool) {
+    set_key(SNAPSHOTS_ENABLED, snapshots_enabled);
+}
+
 pub fn set_hash(contract_hash: Key) {
     set_key(SELF_CONTRACT_HASH, contract_hash);
 }
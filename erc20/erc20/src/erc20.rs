@@ -1,13 +1,13 @@
-use crate::data::{self, Allowances, Balances, Nonces};
+use crate::data::{self, Allowances, Balances, Checkpoint, Checkpoints, Delegates, Nonces};
 use alloc::{format, string::String, vec::Vec};
 use casper_contract::contract_api::storage;
 use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
 use casper_types::{
-    system::mint::Error as MintError, ApiError, BlockTime, ContractHash, ContractPackageHash, Key,
-    URef, U256,
+    system::mint::Error as MintError, BlockTime, ContractHash, ContractPackageHash, Key, URef, U256,
 };
-use contract_utils::{set_key, ContractContext, ContractStorage};
+use contract_utils::{set_key, ContractContext, ContractStorage, ZERO_ACCOUNT};
 use cryptoxide::ed25519;
+use errors::{CommonError, Erc20Error};
 use hex::encode;
 use renvm_sig::{hash_message, keccak256};
 
@@ -25,6 +25,19 @@ pub enum ERC20Event {
         to: Key,
         value: U256,
     },
+    DelegateChanged {
+        delegator: Key,
+        from_delegate: Key,
+        to_delegate: Key,
+    },
+    DelegateVotesChanged {
+        delegate: Key,
+        previous_balance: U256,
+        new_balance: U256,
+    },
+    Snapshot {
+        id: U256,
+    },
 }
 
 impl ERC20Event {
@@ -40,34 +53,22 @@ impl ERC20Event {
                 to: _,
                 value: _,
             } => "erc20_transfer",
+            ERC20Event::DelegateChanged {
+                delegator: _,
+                from_delegate: _,
+                to_delegate: _,
+            } => "delegate_changed",
+            ERC20Event::DelegateVotesChanged {
+                delegate: _,
+                previous_balance: _,
+                new_balance: _,
+            } => "delegate_votes_changed",
+            ERC20Event::Snapshot { id: _ } => "snapshot",
         }
         .to_string()
     }
 }
 
-#[repr(u16)]
-pub enum Error {
-    /// 65,536 for UniswapV2CoreERC20EXPIRED
-    UniswapV2CoreERC20EXPIRED = 0,
-    /// 65,537 for UniswapV2CoreERC20SignatureVerificatFailed
-    UniswapV2CoreERC20SignatureVerificatFailed = 1,
-    /// 65,539 for UniswapV2CoreERC20OverFlow
-    UniswapV2CoreERC20OverFlow = 2,
-    /// 65,539 for UniswapV2CoreERC20UnderFlow
-    UniswapV2CoreERC20UnderFlow1 = 3,
-    UniswapV2CoreERC20UnderFlow2 = 29,
-    UniswapV2CoreERC20UnderFlow3 = 30,
-    UniswapV2CoreERC20UnderFlow4 = 31,
-    UniswapV2CoreERC20UnderFlow5 = 32,
-    UniswapV2CoreERC20UnderFlow6 = 33,
-}
-
-impl From<Error> for ApiError {
-    fn from(error: Error) -> ApiError {
-        ApiError::User(error as u16)
-    }
-}
-
 pub trait ERC20<Storage: ContractStorage>: ContractContext<Storage> {
     fn init(
         &mut self,
@@ -91,6 +92,8 @@ pub trait ERC20<Storage: ContractStorage>: ContractContext<Storage> {
         nonces.set(&Key::from(self.get_caller()), U256::from(0));
         Balances::init();
         Allowances::init();
+        Delegates::init();
+        Checkpoints::init();
     }
 
     fn balance_of(&mut self, owner: Key) -> U256 {
@@ -133,14 +136,14 @@ pub trait ERC20<Storage: ContractStorage>: ContractContext<Storage> {
 
         let new_allowance: U256 = spender_allowance
             .checked_add(amount)
-            .ok_or(Error::UniswapV2CoreERC20OverFlow)
+            .ok_or(Erc20Error::UniswapV2CoreERC20OverFlow)
             .unwrap_or_revert();
 
         if new_allowance <= owner_balance && owner != spender {
             self._approve(owner, spender, new_allowance);
             return Ok(());
         } else {
-            return Err(4);
+            return Err(CommonError::InvalidAllowance.into());
         }
     }
 
@@ -153,14 +156,14 @@ pub trait ERC20<Storage: ContractStorage>: ContractContext<Storage> {
 
         let new_allowance: U256 = spender_allowance
             .checked_sub(amount)
-            .ok_or(Error::UniswapV2CoreERC20UnderFlow1)
+            .ok_or(Erc20Error::UniswapV2CoreERC20UnderFlow1)
             .unwrap_or_revert();
 
         if new_allowance >= 0.into() && new_allowance < spender_allowance && owner != spender {
             self._approve(owner, spender, new_allowance);
             return Ok(());
         } else {
-            return Err(4);
+            return Err(CommonError::InvalidAllowance.into());
         }
     }
 
@@ -171,7 +174,7 @@ pub trait ERC20<Storage: ContractStorage>: ContractContext<Storage> {
             let spender_allowance: U256 = allowances.get(&owner, &self.get_caller());
             let new_allowance: U256 = spender_allowance
                 .checked_sub(amount)
-                .ok_or(Error::UniswapV2CoreERC20UnderFlow2)
+                .ok_or(Erc20Error::UniswapV2CoreERC20UnderFlow2)
                 .unwrap_or_revert();
             if new_allowance >= 0.into()
                 && new_allowance < spender_allowance
@@ -180,7 +183,7 @@ pub trait ERC20<Storage: ContractStorage>: ContractContext<Storage> {
                 self._approve(owner, self.get_caller(), new_allowance);
                 return Ok(());
             } else {
-                return Err(4);
+                return Err(CommonError::InvalidAllowance.into());
             }
         }
         ret
@@ -285,36 +288,39 @@ pub trait ERC20<Storage: ContractStorage>: ContractContext<Storage> {
                 });
             } else {
                 //signature verification failed
-                runtime::revert(Error::UniswapV2CoreERC20SignatureVerificatFailed);
+                runtime::revert(Erc20Error::UniswapV2CoreERC20SignatureVerificatFailed);
             }
         } else {
             //deadline is equal to or greater than blocktime
-            runtime::revert(Error::UniswapV2CoreERC20EXPIRED);
+            runtime::revert(Erc20Error::UniswapV2CoreERC20EXPIRED);
         }
     }
 
+    /// Called before any balance or the total supply changes, with `ZERO_ACCOUNT` as
+    /// `from` for a mint and as `to` for a burn. It does nothing unless a token
+    /// overrides it, e.g. to keep the snapshots of `ERC20SNAPSHOT` up to date.
+    fn before_token_transfer(&mut self, _from: Key, _to: Key) {}
+
     fn mint(&mut self, recipient: Key, amount: U256) {
+        self.before_token_transfer(ZERO_ACCOUNT, recipient);
         let balances: Balances = Balances::instance();
         let balance: U256 = balances.get(&recipient);
         balances.set(
             &recipient,
             balance
                 .checked_add(amount)
-                .ok_or(Error::UniswapV2CoreERC20OverFlow)
+                .ok_or(Erc20Error::UniswapV2CoreERC20OverFlow)
                 .unwrap_or_revert(),
         );
         data::set_total_supply(
             data::total_supply()
                 .checked_add(amount)
-                .ok_or(Error::UniswapV2CoreERC20OverFlow)
+                .ok_or(Erc20Error::UniswapV2CoreERC20OverFlow)
                 .unwrap_or_revert(),
         );
-        let address_0: Key = Key::from_formatted_str(
-            "account-hash-0000000000000000000000000000000000000000000000000000000000000000",
-        )
-        .unwrap();
+        self.move_delegates(ZERO_ACCOUNT, Delegates::instance().get(&recipient), amount);
         self.emit(&ERC20Event::Transfer {
-            from: address_0,
+            from: ZERO_ACCOUNT,
             to: recipient,
             value: amount,
         });
@@ -324,26 +330,24 @@ pub trait ERC20<Storage: ContractStorage>: ContractContext<Storage> {
         let balances: Balances = Balances::instance();
         let balance: U256 = balances.get(&recipient);
         if balance >= amount {
+            self.before_token_transfer(recipient, ZERO_ACCOUNT);
             balances.set(
                 &recipient,
                 balance
                     .checked_sub(amount)
-                    .ok_or(Error::UniswapV2CoreERC20UnderFlow3)
+                    .ok_or(Erc20Error::UniswapV2CoreERC20UnderFlow3)
                     .unwrap_or_revert(),
             );
             data::set_total_supply(
                 data::total_supply()
                     .checked_sub(amount)
-                    .ok_or(Error::UniswapV2CoreERC20UnderFlow4)
+                    .ok_or(Erc20Error::UniswapV2CoreERC20UnderFlow4)
                     .unwrap_or_revert(),
             );
-            let address_0: Key = Key::from_formatted_str(
-                "account-hash-0000000000000000000000000000000000000000000000000000000000000000",
-            )
-            .unwrap();
+            self.move_delegates(Delegates::instance().get(&recipient), ZERO_ACCOUNT, amount);
             self.emit(&ERC20Event::Transfer {
                 from: recipient,
-                to: address_0,
+                to: ZERO_ACCOUNT,
                 value: amount,
             });
         } else {
@@ -360,13 +364,14 @@ pub trait ERC20<Storage: ContractStorage>: ContractContext<Storage> {
 
     fn make_transfer(&mut self, sender: Key, recipient: Key, amount: U256) -> Result<(), u32> {
         if sender == recipient {
-            return Err(4); // Same sender recipient error
+            return Err(CommonError::SameSenderRecipient.into());
         }
 
         if amount.is_zero() {
-            return Err(5); // Amount to transfer is 0
+            return Err(CommonError::ZeroAmount.into());
         }
 
+        self.before_token_transfer(sender, recipient);
         let balances: Balances = Balances::instance();
         let sender_balance: U256 = balances.get(&sender);
         let recipient_balance: U256 = balances.get(&recipient);
@@ -374,16 +379,18 @@ pub trait ERC20<Storage: ContractStorage>: ContractContext<Storage> {
             &sender,
             sender_balance
                 .checked_sub(amount)
-                .ok_or(Error::UniswapV2CoreERC20UnderFlow5)
+                .ok_or(Erc20Error::UniswapV2CoreERC20UnderFlow5)
                 .unwrap_or_revert(),
         );
         balances.set(
             &recipient,
             recipient_balance
                 .checked_add(amount)
-                .ok_or(Error::UniswapV2CoreERC20OverFlow)
+                .ok_or(Erc20Error::UniswapV2CoreERC20OverFlow)
                 .unwrap_or_revert(),
         );
+        let delegates: Delegates = Delegates::instance();
+        self.move_delegates(delegates.get(&sender), delegates.get(&recipient), amount);
         self.emit(&ERC20Event::Transfer {
             from: sender,
             to: recipient,
@@ -392,6 +399,120 @@ pub trait ERC20<Storage: ContractStorage>: ContractContext<Storage> {
         Ok(())
     }
 
+    /// Delegates the votes of the caller to `delegatee`, which can be the caller itself.
+    /// Balances only count as votes once they are delegated.
+    fn delegate(&mut self, delegatee: Key) {
+        let delegator: Key = self.get_caller();
+        let delegates: Delegates = Delegates::instance();
+        let current_delegate: Key = delegates.get(&delegator);
+        delegates.set(&delegator, delegatee);
+        self.emit(&ERC20Event::DelegateChanged {
+            delegator: delegator,
+            from_delegate: current_delegate,
+            to_delegate: delegatee,
+        });
+        let balance: U256 = Balances::instance().get(&delegator);
+        self.move_delegates(current_delegate, delegatee, balance);
+    }
+
+    fn delegates(&mut self, delegator: Key) -> Key {
+        Delegates::instance().get(&delegator)
+    }
+
+    fn get_current_votes(&mut self, account: Key) -> U256 {
+        let checkpoints: Checkpoints = Checkpoints::instance();
+        match checkpoints.len(&account) {
+            0 => U256::zero(),
+            len => checkpoints.get(&account, len - 1).1,
+        }
+    }
+
+    /// Votes of `account` at the block time `time`, in milliseconds. Only times in the
+    /// past are final, so `time` must be before the current block time.
+    fn get_prior_votes(&mut self, account: Key, time: u64) -> U256 {
+        let now: u64 = runtime::get_blocktime().into();
+        if time >= now {
+            runtime::revert(Erc20Error::UniswapV2CoreERC20VotesNotYetDetermined);
+        }
+        let checkpoints: Checkpoints = Checkpoints::instance();
+        let len: u32 = checkpoints.len(&account);
+        if len == 0 {
+            return U256::zero();
+        }
+        let (last_time, last_votes): Checkpoint = checkpoints.get(&account, len - 1);
+        if last_time <= time {
+            return last_votes;
+        }
+        if checkpoints.get(&account, 0).0 > time {
+            return U256::zero();
+        }
+        // Binary search for the last checkpoint at or before `time`.
+        let mut lower: u32 = 0;
+        let mut upper: u32 = len - 1;
+        while upper > lower {
+            let center: u32 = upper - (upper - lower) / 2;
+            let (center_time, center_votes): Checkpoint = checkpoints.get(&account, center);
+            if center_time == time {
+                return center_votes;
+            } else if center_time < time {
+                lower = center;
+            } else {
+                upper = center - 1;
+            }
+        }
+        checkpoints.get(&account, lower).1
+    }
+
+    fn num_checkpoints(&mut self, account: Key) -> u32 {
+        Checkpoints::instance().len(&account)
+    }
+
+    fn checkpoints(&mut self, account: Key, index: u32) -> Checkpoint {
+        Checkpoints::instance().get(&account, index)
+    }
+
+    /// Moves `amount` votes from the delegate `src` to the delegate `dst`, either of
+    /// which is `ZERO_ACCOUNT` when the votes come from or go to no delegate.
+    fn move_delegates(&mut self, src: Key, dst: Key, amount: U256) {
+        if src == dst || amount.is_zero() {
+            return;
+        }
+        if src != ZERO_ACCOUNT {
+            let votes: U256 = self.get_current_votes(src);
+            let new_votes: U256 = votes
+                .checked_sub(amount)
+                .ok_or(Erc20Error::UniswapV2CoreERC20UnderFlow6)
+                .unwrap_or_revert();
+            self.write_checkpoint(src, votes, new_votes);
+        }
+        if dst != ZERO_ACCOUNT {
+            let votes: U256 = self.get_current_votes(dst);
+            let new_votes: U256 = votes
+                .checked_add(amount)
+                .ok_or(Erc20Error::UniswapV2CoreERC20OverFlow)
+                .unwrap_or_revert();
+            self.write_checkpoint(dst, votes, new_votes);
+        }
+    }
+
+    /// Records the new votes of `delegate`, in place if its last checkpoint is from
+    /// the same block time.
+    fn write_checkpoint(&mut self, delegate: Key, old_votes: U256, new_votes: U256) {
+        let now: u64 = runtime::get_blocktime().into();
+        let checkpoints: Checkpoints = Checkpoints::instance();
+        let len: u32 = checkpoints.len(&delegate);
+        if len > 0 && checkpoints.get(&delegate, len - 1).0 == now {
+            checkpoints.set(&delegate, len - 1, (now, new_votes));
+        } else {
+            checkpoints.set(&delegate, len, (now, new_votes));
+        }
+        self.emit(&ERC20Event::DelegateVotesChanged {
+            delegate: delegate,
+            previous_balance: old_votes,
+            new_balance: new_votes,
+        });
+    }
+
     fn total_supply(&mut self) -> U256 {
         data::total_supply()
     }
@@ -404,6 +525,10 @@ pub trait ERC20<Storage: ContractStorage>: ContractContext<Storage> {
         data::symbol()
     }
 
+    fn decimals(&mut self) -> u8 {
+        data::decimals()
+    }
+
     fn get_permit_type_and_domain_separator(
         &mut self,
         name: &str,
@@ -456,6 +581,39 @@ pub trait ERC20<Storage: ContractStorage>: ContractContext<Storage> {
                 event.insert("value", value.to_string());
                 events.push(event);
             }
+            ERC20Event::DelegateChanged {
+                delegator,
+                from_delegate,
+                to_delegate,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", erc20_event.type_name());
+                event.insert("delegator", delegator.to_string());
+                event.insert("from_delegate", from_delegate.to_string());
+                event.insert("to_delegate", to_delegate.to_string());
+                events.push(event);
+            }
+            ERC20Event::DelegateVotesChanged {
+                delegate,
+                previous_balance,
+                new_balance,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", erc20_event.type_name());
+                event.insert("delegate", delegate.to_string());
+                event.insert("previous_balance", previous_balance.to_string());
+                event.insert("new_balance", new_balance.to_string());
+                events.push(event);
+            }
+            ERC20Event::Snapshot { id } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", erc20_event.type_name());
+                event.insert("id", id.to_string());
+                events.push(event);
+            }
         };
         for event in events {
             let _: URef = storage::new_uref(event);
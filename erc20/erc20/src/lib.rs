@@ -4,5 +4,7 @@ extern crate alloc;
 
 pub mod data;
 mod erc20;
+mod snapshot;
 
-pub use erc20::ERC20;
+pub use erc20::{ERC20Event, ERC20};
+pub use snapshot::ERC20SNAPSHOT;
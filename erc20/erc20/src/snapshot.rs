@@ -0,0 +1,133 @@
+use crate::data::{self, Balances, Snapshot, Snapshots};
+use crate::erc20::{ERC20Event, ERC20};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{Key, U256};
+use contract_utils::{AdminControl, ContractStorage, ZERO_ACCOUNT};
+use errors::Erc20Error;
+
+/// Balances and total supply at snapshots, for airdrops, governance and reward
+/// distributions that need them as they were at a given moment. A token supports them
+/// by implementing this trait, calling `init` from its constructor and calling
+/// `update_snapshots` from `before_token_transfer` once `snapshots_enabled`. An admin
+/// of the token then opts in with `enable_snapshots`. Nothing is copied when a snapshot
+/// is taken, the value of each account is recorded the first time it changes after it.
+pub trait ERC20SNAPSHOT<Storage: ContractStorage>: ERC20<Storage> + AdminControl<Storage> {
+    fn init(&mut self) {
+        data::set_current_snapshot_id(0.into());
+        data::set_snapshots_enabled(false);
+    }
+
+    /// Opts the token in to snapshots, which cannot be undone. Only an admin can call it.
+    fn enable_snapshots(&mut self) {
+        self.assert_caller_is_admin();
+        if !data::snapshots_enabled() {
+            Snapshots::init();
+            data::set_snapshots_enabled(true);
+        }
+    }
+
+    fn snapshots_enabled(&mut self) -> bool {
+        data::snapshots_enabled()
+    }
+
+    /// Takes a snapshot of every balance and of the total supply and returns its id.
+    /// Ids start from 1. Only an admin can take one, once the token has opted in.
+    fn snapshot(&mut self) -> U256 {
+        self.assert_caller_is_admin();
+        if !data::snapshots_enabled() {
+            runtime::revert(Erc20Error::UniswapV2CoreERC20SnapshotsDisabled);
+        }
+        let id: U256 = data::current_snapshot_id()
+            .checked_add(1.into())
+            .ok_or(Erc20Error::UniswapV2CoreERC20OverFlow)
+            .unwrap_or_revert();
+        data::set_current_snapshot_id(id);
+        self.emit(&ERC20Event::Snapshot { id: id });
+        id
+    }
+
+    fn get_current_snapshot_id(&mut self) -> U256 {
+        data::current_snapshot_id()
+    }
+
+    fn balance_of_at(&mut self, owner: Key, id: U256) -> U256 {
+        match value_at(&Snapshots::account_owner(&owner), id) {
+            Some(value) => value,
+            None => self.balance_of(owner),
+        }
+    }
+
+    fn total_supply_at(&mut self, id: U256) -> U256 {
+        match value_at(&Snapshots::total_supply_owner(), id) {
+            Some(value) => value,
+            None => self.total_supply(),
+        }
+    }
+
+    /// Records the balances of `from` and `to`, and the total supply for a mint or a
+    /// burn, as they were at the current snapshot if they have not changed since it.
+    /// It must be called before they change.
+    fn update_snapshots(&mut self, from: Key, to: Key) {
+        let id: U256 = data::current_snapshot_id();
+        if id.is_zero() {
+            return;
+        }
+        let snapshots: Snapshots = Snapshots::instance();
+        if from == ZERO_ACCOUNT || to == ZERO_ACCOUNT {
+            update(
+                &snapshots,
+                &Snapshots::total_supply_owner(),
+                id,
+                data::total_supply(),
+            );
+        }
+        let balances: Balances = Balances::instance();
+        for account in [from, to].iter() {
+            if *account != ZERO_ACCOUNT {
+                update(
+                    &snapshots,
+                    &Snapshots::account_owner(account),
+                    id,
+                    balances.get(account),
+                );
+            }
+        }
+    }
+}
+
+/// Value of `owner` at the snapshot `id`, None if it has not changed since then and the
+/// current value applies.
+fn value_at(owner: &str, id: U256) -> Option<U256> {
+    if id.is_zero() || id > data::current_snapshot_id() {
+        runtime::revert(Erc20Error::UniswapV2CoreERC20InvalidSnapshotId);
+    }
+    let snapshots: Snapshots = Snapshots::instance();
+    let len: u32 = snapshots.len(owner);
+    // Binary search for the first snapshot at or after `id`.
+    let mut lower: u32 = 0;
+    let mut upper: u32 = len;
+    while lower < upper {
+        let center: u32 = lower + (upper - lower) / 2;
+        if snapshots.get(owner, center).0 < id {
+            lower = center + 1;
+        } else {
+            upper = center;
+        }
+    }
+    if lower == len {
+        None
+    } else {
+        Some(snapshots.get(owner, lower).1)
+    }
+}
+
+fn update(snapshots: &Snapshots, owner: &str, id: U256, value: U256) {
+    let len: u32 = snapshots.len(owner);
+    let last: Snapshot = match len {
+        0 => (U256::zero(), U256::zero()),
+        len => snapshots.get(owner, len - 1),
+    };
+    if last.0 < id {
+        snapshots.push(owner, (id, value));
+    }
+}
@@ -73,6 +73,46 @@ fn allowance() {
     mappings::set_key(&mappings::allowance(), ret);
 }
 
+#[no_mangle]
+fn get_prior_votes() {
+    let erc20_address: ContractHash = mappings::get_key(&mappings::erc20_key());
+    let account: Key = runtime::get_named_arg("account");
+    let time: u64 = runtime::get_named_arg("time");
+    let args: RuntimeArgs = runtime_args! {
+        "account" => account,
+        "time" => time,
+    };
+
+    let ret: U256 = runtime::call_contract(erc20_address, "get_prior_votes", args);
+    mappings::set_key(&mappings::prior_votes_key(), ret);
+}
+
+#[no_mangle]
+fn balance_of_at() {
+    let erc20_address: ContractHash = mappings::get_key(&mappings::erc20_key());
+    let owner: Key = runtime::get_named_arg("owner");
+    let id: U256 = runtime::get_named_arg("id");
+    let args: RuntimeArgs = runtime_args! {
+        "owner" => owner,
+        "id" => id,
+    };
+
+    let ret: U256 = runtime::call_contract(erc20_address, "balance_of_at", args);
+    mappings::set_key(&mappings::balance_of_at_key(), ret);
+}
+
+#[no_mangle]
+fn total_supply_at() {
+    let erc20_address: ContractHash = mappings::get_key(&mappings::erc20_key());
+    let id: U256 = runtime::get_named_arg("id");
+    let args: RuntimeArgs = runtime_args! {
+        "id" => id,
+    };
+
+    let ret: U256 = runtime::call_contract(erc20_address, "total_supply_at", args);
+    mappings::set_key(&mappings::total_supply_at_key(), ret);
+}
+
 #[no_mangle]
 fn transfer_from() {
     let erc20_address: ContractHash = mappings::get_key(&mappings::erc20_key());
@@ -195,6 +235,33 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_prior_votes",
+        vec![
+            Parameter::new("account", Key::cl_type()),
+            Parameter::new("time", u64::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "balance_of_at",
+        vec![
+            Parameter::new("owner", Key::cl_type()),
+            Parameter::new("id", U256::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "total_supply_at",
+        vec![Parameter::new("id", U256::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points
 }
 
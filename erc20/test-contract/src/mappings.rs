@@ -66,3 +66,15 @@ pub fn increase_allowance_key() -> String {
 pub fn decrease_allowance_key() -> String {
     format!("decrease_allowance_result")
 }
+
+pub fn prior_votes_key() -> String {
+    format!("prior_votes")
+}
+
+pub fn balance_of_at_key() -> String {
+    format!("balance_of_at")
+}
+
+pub fn total_supply_at_key() -> String {
+    format!("total_supply_at")
+}
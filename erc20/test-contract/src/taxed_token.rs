@@ -0,0 +1,400 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+use alloc::{boxed::Box, collections::BTreeSet, format, string::String, vec};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    runtime_args, CLType, CLTyped, CLValue, ContractHash, ContractPackageHash, EntryPoint,
+    EntryPointAccess, EntryPointType, EntryPoints, Group, Key, Parameter, RuntimeArgs, URef, U256,
+};
+use contract_utils::{get_key, set_key, ContractContext, OnChainContractStorage};
+use erc20::{self, data::Balances, ERC20Event, ERC20};
+use errors::{CommonError, Erc20Error};
+
+/// Named key of the percentage of every transfer that is burnt from the sender, the
+/// recipient gets the rest. It starts at 1 and can be changed with `set_tax_percent`.
+const TAX_PERCENT: &str = "tax_percent";
+
+/// An ERC20 token that takes a fee on `transfer` and `transfer_from`, to test the pair and
+/// the flash swapper against tokens that deliver less than the amount sent.
+#[derive(Default)]
+struct Token(OnChainContractStorage);
+
+impl ContractContext<OnChainContractStorage> for Token {
+    fn storage(&self) -> &OnChainContractStorage {
+        &self.0
+    }
+}
+
+impl ERC20<OnChainContractStorage> for Token {
+    fn make_transfer(&mut self, sender: Key, recipient: Key, amount: U256) -> Result<(), u32> {
+        if sender == recipient {
+            return Err(CommonError::SameSenderRecipient.into());
+        }
+
+        if amount.is_zero() {
+            return Err(CommonError::ZeroAmount.into());
+        }
+
+        let tax_percent: U256 = get_key(TAX_PERCENT).unwrap_or_revert();
+        let tax: U256 = amount * tax_percent / U256::from(100);
+        let balances: Balances = Balances::instance();
+        let sender_balance: U256 = balances.get(&sender);
+        balances.set(
+            &sender,
+            sender_balance
+                .checked_sub(amount)
+                .ok_or(Erc20Error::UniswapV2CoreERC20UnderFlow5)
+                .unwrap_or_revert(),
+        );
+        let recipient_balance: U256 = balances.get(&recipient);
+        balances.set(
+            &recipient,
+            recipient_balance
+                .checked_add(amount - tax)
+                .ok_or(Erc20Error::UniswapV2CoreERC20OverFlow)
+                .unwrap_or_revert(),
+        );
+        erc20::data::set_total_supply(
+            erc20::data::total_supply()
+                .checked_sub(tax)
+                .ok_or(Erc20Error::UniswapV2CoreERC20UnderFlow4)
+                .unwrap_or_revert(),
+        );
+        self.emit(&ERC20Event::Transfer {
+            from: sender,
+            to: recipient,
+            value: amount - tax,
+        });
+        Ok(())
+    }
+}
+
+impl Token {
+    fn constructor(
+        &mut self,
+        name: String,
+        symbol: String,
+        decimals: u8,
+        initial_supply: U256,
+        domain_separator: String,
+        permit_type_hash: String,
+        contract_hash: ContractHash,
+        package_hash: ContractPackageHash,
+    ) {
+        ERC20::init(
+            self,
+            name,
+            symbol,
+            decimals,
+            domain_separator,
+            permit_type_hash,
+            Key::from(contract_hash),
+            package_hash,
+        );
+        ERC20::mint(self, self.get_caller(), initial_supply);
+        set_key(TAX_PERCENT, U256::from(1));
+    }
+}
+
+#[no_mangle]
+fn constructor() {
+    let name: String = runtime::get_named_arg("name");
+    let symbol: String = runtime::get_named_arg("symbol");
+    let decimals: u8 = runtime::get_named_arg("decimals");
+    let initial_supply: U256 = runtime::get_named_arg("initial_supply");
+    let domain_separator: String = runtime::get_named_arg("domain_separator");
+    let permit_type_hash: String = runtime::get_named_arg("permit_type_hash");
+    let contract_hash: ContractHash = runtime::get_named_arg("contract_hash");
+    let package_hash: ContractPackageHash = runtime::get_named_arg("package_hash");
+    Token::default().constructor(
+        name,
+        symbol,
+        decimals,
+        initial_supply,
+        domain_separator,
+        permit_type_hash,
+        contract_hash,
+        package_hash,
+    );
+}
+
+/// Sets the tax on transfers, at most 100 percent. Anyone can call it, it is a test token.
+#[no_mangle]
+fn set_tax_percent() {
+    let tax_percent: U256 = runtime::get_named_arg("tax_percent");
+    set_key(TAX_PERCENT, tax_percent);
+}
+
+#[no_mangle]
+fn transfer() {
+    let recipient: Key = runtime::get_named_arg("recipient");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let ret = Token::default().transfer(recipient, amount);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn transfer_from() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let recipient: Key = runtime::get_named_arg("recipient");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let ret = Token::default().transfer_from(owner, recipient, amount);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn approve() {
+    let spender: Key = runtime::get_named_arg("spender");
+    let amount: U256 = runtime::get_named_arg("amount");
+    Token::default().approve(spender, amount);
+}
+
+#[no_mangle]
+fn mint() {
+    let to: Key = runtime::get_named_arg("to");
+    let amount: U256 = runtime::get_named_arg("amount");
+    Token::default().mint(to, amount);
+}
+
+#[no_mangle]
+fn balance_of() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let ret: U256 = Token::default().balance_of(owner);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn allowance() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let spender: Key = runtime::get_named_arg("spender");
+    let ret: U256 = Token::default().allowance(owner, spender);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn total_supply() {
+    let ret: U256 = Token::default().total_supply();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn name() {
+    let ret: String = Token::default().name();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn symbol() {
+    let ret: String = Token::default().symbol();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn decimals() {
+    let ret: u8 = Token::default().decimals();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn package_hash() {
+    let ret: ContractPackageHash = Token::default().get_package_hash();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new("name", String::cl_type()),
+            Parameter::new("symbol", String::cl_type()),
+            Parameter::new("decimals", u8::cl_type()),
+            Parameter::new("initial_supply", U256::cl_type()),
+            Parameter::new("domain_separator", String::cl_type()),
+            Parameter::new("permit_type_hash", String::cl_type()),
+            Parameter::new("contract_hash", ContractHash::cl_type()),
+            Parameter::new("package_hash", ContractPackageHash::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_tax_percent",
+        vec![Parameter::new("tax_percent", U256::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "transfer",
+        vec![
+            Parameter::new("recipient", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        CLType::Result {
+            ok: Box::new(CLType::Unit),
+            err: Box::new(CLType::U32),
+        },
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "transfer_from",
+        vec![
+            Parameter::new("owner", Key::cl_type()),
+            Parameter::new("recipient", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        CLType::Result {
+            ok: Box::new(CLType::Unit),
+            err: Box::new(CLType::U32),
+        },
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "approve",
+        vec![
+            Parameter::new("spender", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "mint",
+        vec![
+            Parameter::new("to", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "balance_of",
+        vec![Parameter::new("owner", Key::cl_type())],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "allowance",
+        vec![
+            Parameter::new("owner", Key::cl_type()),
+            Parameter::new("spender", Key::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "total_supply",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "name",
+        vec![],
+        String::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "symbol",
+        vec![],
+        String::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "decimals",
+        vec![],
+        u8::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "package_hash",
+        vec![],
+        ContractPackageHash::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
+
+#[no_mangle]
+fn call() {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+    let (package_hash, access_token) = storage::create_contract_package_at_hash();
+    let (contract_hash, _) =
+        storage::add_contract_version(package_hash, get_entry_points(), Default::default());
+
+    let name: String = runtime::get_named_arg("name");
+    let symbol: String = runtime::get_named_arg("symbol");
+    let decimals: u8 = runtime::get_named_arg("decimals");
+    let initial_supply: U256 = runtime::get_named_arg("initial_supply");
+
+    let (domain_separator, permit_type_hash) =
+        Token::default().get_permit_type_and_domain_separator(&name, contract_hash);
+
+    let constructor_args = runtime_args! {
+        "name" => name,
+        "symbol" => symbol,
+        "decimals" => decimals,
+        "initial_supply" => initial_supply,
+        "domain_separator" => domain_separator,
+        "permit_type_hash" => permit_type_hash,
+        "contract_hash" => contract_hash,
+        "package_hash"=> package_hash
+    };
+
+    // Add the constructor group to the package hash with a single URef.
+    let constructor_access: URef =
+        storage::create_contract_user_group(package_hash, "constructor", 1, Default::default())
+            .unwrap_or_revert()
+            .pop()
+            .unwrap_or_revert();
+
+    // Call the constructor entry point
+    let _: () =
+        runtime::call_versioned_contract(package_hash, None, "constructor", constructor_args);
+
+    // Remove all URefs from the constructor group, so no one can call it for the second time.
+    let mut urefs = BTreeSet::new();
+    urefs.insert(constructor_access);
+    storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
+        .unwrap_or_revert();
+
+    // Store contract in the account's named keys.
+    runtime::put_key(
+        &format!("{}_package_hash", contract_name),
+        package_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_hash_wrapped", contract_name),
+        storage::new_uref(package_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash_wrapped", contract_name),
+        storage::new_uref(contract_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_access_token", contract_name),
+        access_token.into(),
+    );
+}
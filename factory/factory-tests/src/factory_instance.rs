@@ -2,7 +2,7 @@ use blake2::{
     digest::{Update, VariableOutput},
     VarBlake2b,
 };
-use casper_types::{bytesrepr::ToBytes, runtime_args, Key, RuntimeArgs};
+use casper_types::{bytesrepr::ToBytes, runtime_args, Key, RuntimeArgs, U128, U256};
 use test_env::{Sender, TestContract, TestEnv};
 
 pub struct FACTORYInstance(pub TestContract);
@@ -25,6 +25,35 @@ impl FACTORYInstance {
         ))
     }
 
+    pub fn upgrade(
+        env: &TestEnv,
+        contract_name: &str,
+        sender: Sender,
+        legacy_white_lists: Vec<Key>,
+    ) -> FACTORYInstance {
+        FACTORYInstance(TestContract::new(
+            env,
+            "factory.wasm",
+            contract_name,
+            sender,
+            runtime_args! {
+                "legacy_white_lists" => legacy_white_lists,
+            },
+        ))
+    }
+
+    pub fn proxy(env: &TestEnv, factory: Key, sender: Sender) -> FACTORYInstance {
+        FACTORYInstance(TestContract::new(
+            env,
+            "factory-test.wasm",
+            "proxy_test",
+            sender,
+            runtime_args! {
+                "factory" => factory
+            },
+        ))
+    }
+
     pub fn constructor<T: Into<Key>>(&self, sender: Sender, fee_to_setter: T) {
         self.0.call_contract(
             sender,
@@ -45,6 +74,111 @@ impl FACTORYInstance {
         );
     }
 
+    pub fn propose_fee_to_setter<T: Into<Key>>(&self, sender: Sender, fee_to_setter: T) {
+        self.0.call_contract(
+            sender,
+            "propose_fee_to_setter",
+            runtime_args! {
+                "fee_to_setter" => fee_to_setter.into(),
+            },
+        );
+    }
+
+    pub fn accept_fee_to_setter(&self, sender: Sender) {
+        self.0
+            .call_contract(sender, "accept_fee_to_setter", runtime_args! {});
+    }
+
+    pub fn accept_fee_to_setter_with_time(&self, sender: Sender, time: u64) {
+        self.0
+            .call_contract_with_time(sender, "accept_fee_to_setter", runtime_args! {}, time);
+    }
+
+    pub fn propose_owner<T: Into<Key>>(&self, sender: Sender, owner: T) {
+        self.0.call_contract(
+            sender,
+            "propose_owner",
+            runtime_args! {
+                "owner" => owner.into(),
+            },
+        );
+    }
+
+    pub fn accept_owner(&self, sender: Sender) {
+        self.0.call_contract(sender, "accept_owner", runtime_args! {});
+    }
+
+    pub fn accept_owner_with_time(&self, sender: Sender, time: u64) {
+        self.0
+            .call_contract_with_time(sender, "accept_owner", runtime_args! {}, time);
+    }
+
+    pub fn propose_transfer_delay(&self, sender: Sender, transfer_delay: u64, time: u64) {
+        self.0.call_contract_with_time(
+            sender,
+            "propose_transfer_delay",
+            runtime_args! {
+                "transfer_delay" => transfer_delay,
+            },
+            time,
+        );
+    }
+
+    pub fn accept_transfer_delay(&self, sender: Sender, time: u64) {
+        self.0
+            .call_contract_with_time(sender, "accept_transfer_delay", runtime_args! {}, time);
+    }
+
+    pub fn update_pairs_factory_hash(&self, sender: Sender, start: U256, count: U256) {
+        self.0.call_contract(
+            sender,
+            "update_pairs_factory_hash",
+            runtime_args! {
+                "start" => start,
+                "count" => count,
+            },
+        );
+    }
+
+    pub fn index_token_pairs(&self, sender: Sender, count: U256) {
+        self.0.call_contract(
+            sender,
+            "index_token_pairs",
+            runtime_args! {
+                "count" => count,
+            },
+        );
+    }
+
+    pub fn all_pairs_range(&self, sender: Sender, start: U256, count: U256) {
+        self.0.call_contract(
+            sender,
+            "all_pairs_range",
+            runtime_args! {
+                "start" => start,
+                "count" => count
+            },
+        );
+    }
+
+    pub fn all_pairs_range_result(&self) -> Vec<Key> {
+        self.0.query_named_key(String::from("all_pairs_range"))
+    }
+
+    pub fn pair_info(&self, sender: Sender, pair: Key) {
+        self.0.call_contract(
+            sender,
+            "pair_info",
+            runtime_args! {
+                "pair" => pair
+            },
+        );
+    }
+
+    pub fn pair_info_result(&self) -> ((Key, Key), (U128, U128, u64), (U256, U256)) {
+        self.0.query_named_key(String::from("pair_info"))
+    }
+
     pub fn set_fee_to<T: Into<Key>>(&self, sender: Sender, fee_to: T) {
         self.0.call_contract(
             sender,
@@ -67,6 +201,26 @@ impl FACTORYInstance {
         );
     }
 
+    pub fn create_stable_pair<T: Into<Key>>(
+        &self,
+        sender: Sender,
+        token_a: T,
+        token_b: T,
+        pair_hash: T,
+        amp: U256,
+    ) {
+        self.0.call_contract(
+            sender,
+            "create_stable_pair",
+            runtime_args! {
+            "token_a" => token_a.into(),
+            "token_b" => token_b.into(),
+            "pair_hash" => pair_hash.into(),
+            "amp" => amp,
+            },
+        );
+    }
+
     pub fn set_white_list<T: Into<Key>>(&self, sender: Sender, white_list: T) {
         self.0.call_contract(
             sender,
@@ -77,9 +231,72 @@ impl FACTORYInstance {
         );
     }
 
-    pub fn get_white_lists<T: Into<Key>>(&self, account: T) -> Key {
+    pub fn grant_role<T: Into<Key>>(&self, sender: Sender, role: u8, account: T) {
+        self.0.call_contract(
+            sender,
+            "grant_role",
+            runtime_args! {
+                "role" => role,
+                "account" => account.into(),
+            },
+        );
+    }
+
+    pub fn revoke_role<T: Into<Key>>(&self, sender: Sender, role: u8, account: T) {
+        self.0.call_contract(
+            sender,
+            "revoke_role",
+            runtime_args! {
+                "role" => role,
+                "account" => account.into(),
+            },
+        );
+    }
+
+    pub fn set_token_denied<T: Into<Key>>(&self, sender: Sender, token: T, denied: bool) {
+        self.0.call_contract(
+            sender,
+            "set_token_denied",
+            runtime_args! {
+                "token" => token.into(),
+                "denied" => denied,
+            },
+        );
+    }
+
+    pub fn is_token_denied<T: Into<Key>>(&self, token: T) -> bool {
         self.0
-            .query_dictionary("white_lists", key_to_str(&account.into()))
+            .query_dictionary("denied_tokens", key_to_str(&token.into()))
+            .unwrap_or_default()
+    }
+
+    pub fn set_permissionless(&self, sender: Sender, permissionless: bool) {
+        self.0.call_contract(
+            sender,
+            "set_permissionless",
+            runtime_args! {
+                "permissionless" => permissionless,
+            },
+        );
+    }
+
+    pub fn has_role<T: Into<Key>>(&self, role: u8, account: T) -> bool {
+        let position: Option<U256> = self.0.query_dictionary(
+            "roles",
+            format!("{}_{}", role, key_to_str(&account.into())),
+        );
+        position.unwrap_or_default() != U256::zero()
+    }
+
+    pub fn role_member_count(&self, role: u8) -> U256 {
+        self.0
+            .query_dictionary("role_member_counts", role.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn role_member(&self, role: u8, index: U256) -> Key {
+        self.0
+            .query_dictionary("role_members", format!("{}_{}", role, index))
             .unwrap()
     }
 
@@ -95,8 +312,54 @@ impl FACTORYInstance {
         self.0.query_named_key(String::from("fee_to_setter"))
     }
 
-    pub fn all_pairs(&self) -> Vec<Key> {
-        self.0.query_named_key(String::from("all_pairs"))
+    pub fn pending_fee_to_setter(&self) -> Key {
+        self.0.query_named_key(String::from("pending_fee_to_setter"))
+    }
+
+    pub fn contract_version(&self) -> u32 {
+        self.0.query_named_key(String::from("contract_version"))
+    }
+
+    pub fn transfer_delay(&self) -> u64 {
+        self.0.query_named_key(String::from("transfer_delay"))
+    }
+
+    pub fn pending_transfer_delay(&self) -> Option<u64> {
+        self.0
+            .query_named_key(String::from("pending_transfer_delay"))
+    }
+
+    pub fn owner(&self) -> Key {
+        self.0.query_named_key(String::from("owner"))
+    }
+
+    pub fn pending_owner(&self) -> Key {
+        self.0.query_named_key(String::from("pending_owner"))
+    }
+
+    pub fn all_pairs(&self, index: U256) -> Key {
+        self.0
+            .query_dictionary("all_pairs_index", index.to_string())
+            .unwrap()
+    }
+
+    pub fn all_pairs_length(&self) -> U256 {
+        self.0.query_named_key(String::from("all_pairs_length"))
+    }
+
+    pub fn pairs_for_token_length<T: Into<Key>>(&self, token: T) -> U256 {
+        self.0
+            .query_dictionary("token_pairs_length", key_to_str(&token.into()))
+            .unwrap_or_default()
+    }
+
+    pub fn pairs_for_token<T: Into<Key>>(&self, token: T, index: U256) -> Key {
+        self.0
+            .query_dictionary(
+                "token_pairs",
+                format!("{}_{}", key_to_str(&token.into()), index),
+            )
+            .unwrap()
     }
 
     pub fn get_pair<T: Into<Key>>(&self, token0: T, token1: T) -> Key {
@@ -106,6 +369,14 @@ impl FACTORYInstance {
             .query_dictionary("pairs", keys_to_str(&token0, &token1))
             .unwrap()
     }
+
+    pub fn get_stable_pair<T: Into<Key>>(&self, token0: T, token1: T) -> Key {
+        let token0: Key = token0.into();
+        let token1: Key = token1.into();
+        self.0
+            .query_dictionary("stable_pairs", keys_to_str(&token0, &token1))
+            .unwrap()
+    }
 }
 
 pub fn key_to_str(key: &Key) -> String {
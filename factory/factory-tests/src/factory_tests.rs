@@ -1,36 +1,47 @@
-use casper_engine_test_support::AccountHash;
-use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+use casper_engine_test_support::{AccountHash, Code};
+use casper_types::{runtime_args, ApiError, ContractPackageHash, Key, RuntimeArgs, U256};
+use errors::{
+    CommonError, Erc20Error, ErrorInfo, FactoryError, FlashSwapperError, GovernorError, LensError,
+    LimitOrderError, MulticallError, PairError, StakingError, WcsprError, ZapError,
+};
 use test_env::{Sender, TestContract, TestEnv};
 
-use crate::factory_instance::FACTORYInstance;
+use crate::factory_instance::{key_to_str, FACTORYInstance};
 
 const NAME_FACTORY: &str = "Factory";
+const PAIR_CREATOR: u8 = 0;
+const FEE_ADMIN: u8 = 1;
 
 fn deploy() -> (TestEnv, FACTORYInstance, AccountHash, TestContract) {
     let env = TestEnv::new();
     let owner = env.next_user();
     let _env_pair = TestEnv::new();
     let token = FACTORYInstance::new(&env, NAME_FACTORY, Sender(owner), owner);
-    let wcspr = deploy_wcspr(&env);
-    let dai = deploy_dai(&env);
+    let pair_contract = deploy_pair(&env, owner, token.self_contract_hash());
+    (env, token, owner, pair_contract)
+}
+
+fn deploy_pair(env: &TestEnv, owner: AccountHash, factory_hash: Key) -> TestContract {
+    let wcspr = deploy_wcspr(env);
+    let dai = deploy_dai(env);
     let name: &str = "ERC20";
     let symbol: &str = "ERC";
     let decimals: u8 = 8;
     let init_total_supply: U256 = 1000.into();
     let callee_contract = TestContract::new(
         //&env_factory,
-        &env,
+        env,
         "flash-swapper.wasm",
         "flash_swapper",
         Sender(owner),
         runtime_args! {
             "wcspr" => Key::Hash(wcspr.contract_hash()),
             "dai" => Key::Hash(dai.contract_hash()),
-            "uniswap_v2_factory" => token.self_contract_hash()
+            "uniswap_v2_factory" => factory_hash
         },
     );
-    let pair_contract = TestContract::new(
-        &env,
+    TestContract::new(
+        env,
         "pair-token.wasm",
         "Pair",
         Sender(owner),
@@ -40,11 +51,10 @@ fn deploy() -> (TestEnv, FACTORYInstance, AccountHash, TestContract) {
         "decimals" => decimals,
         "initial_supply" => init_total_supply,
         "callee_contract_hash" => Key::Hash(callee_contract.contract_hash()),
-        "factory_hash" => token.self_contract_hash(),
+        "factory_hash" => factory_hash,
             // contract_name is passed seperately, so we don't need to pass it here.
         },
-    );
-    (env, token, owner, pair_contract)
+    )
 }
 
 fn deploy_token0(env: &TestEnv) -> TestContract {
@@ -139,9 +149,112 @@ fn test_factory_set_fee_to_setter() {
     let user = env.next_user();
     assert_eq!(token.fee_to_setter(), Key::Account(owner));
     token.set_fee_to_setter(Sender(owner), user);
+    assert_eq!(token.fee_to_setter(), Key::Account(owner));
+    assert_eq!(token.pending_fee_to_setter(), Key::Account(user));
+    token.accept_fee_to_setter(Sender(user));
+    assert_eq!(token.fee_to_setter(), Key::Account(user));
+}
+
+#[test]
+#[should_panic]
+fn test_factory_accept_fee_to_setter_with_non_pending() {
+    let (env, token, owner, _pair_hash) = deploy();
+    let user = env.next_user();
+    let other = env.next_user();
+    token.propose_fee_to_setter(Sender(owner), user);
+    token.accept_fee_to_setter(Sender(other));
+}
+
+#[test]
+#[should_panic]
+fn test_factory_accept_fee_to_setter_before_delay() {
+    let (env, token, owner, _pair_hash) = deploy();
+    let user = env.next_user();
+    token.propose_transfer_delay(Sender(owner), 1000, 0);
+    token.accept_transfer_delay(Sender(owner), 0);
+    token.propose_fee_to_setter(Sender(owner), user);
+    token.accept_fee_to_setter_with_time(Sender(user), 999);
+}
+
+#[test]
+fn test_factory_accept_fee_to_setter_after_delay() {
+    let (env, token, owner, _pair_hash) = deploy();
+    let user = env.next_user();
+    token.propose_transfer_delay(Sender(owner), 1000, 0);
+    token.accept_transfer_delay(Sender(owner), 0);
+    assert_eq!(token.transfer_delay(), 1000);
+    token.propose_fee_to_setter(Sender(owner), user);
+    token.accept_fee_to_setter_with_time(Sender(user), 1000);
     assert_eq!(token.fee_to_setter(), Key::Account(user));
 }
 
+#[test]
+fn test_factory_transfer_delay_change_keeps_the_eta_of_proposals() {
+    let (env, token, owner, _pair_hash) = deploy();
+    let user = env.next_user();
+    token.propose_transfer_delay(Sender(owner), 1000, 0);
+    token.accept_transfer_delay(Sender(owner), 0);
+    token.propose_owner(Sender(owner), user);
+    // a longer delay accepted afterwards doesn't move the eta of the owner proposal
+    token.propose_transfer_delay(Sender(owner), 10_000, 0);
+    assert_eq!(token.pending_transfer_delay(), Some(10_000));
+    token.accept_transfer_delay(Sender(owner), 1000);
+    assert_eq!(token.transfer_delay(), 10_000);
+    assert_eq!(token.pending_transfer_delay(), None);
+    token.accept_owner_with_time(Sender(user), 1000);
+    assert_eq!(token.owner(), Key::Account(user));
+}
+
+#[test]
+#[should_panic]
+fn test_factory_accept_transfer_delay_before_delay() {
+    let (_env, token, owner, _pair_hash) = deploy();
+    token.propose_transfer_delay(Sender(owner), 86_400_000, 0);
+    token.accept_transfer_delay(Sender(owner), 0);
+    // lowering the delay waits for the current one as well
+    token.propose_transfer_delay(Sender(owner), 0, 0);
+    token.accept_transfer_delay(Sender(owner), 86_399_999);
+}
+
+#[test]
+#[should_panic]
+fn test_factory_accept_transfer_delay_without_proposal() {
+    let (_env, token, owner, _pair_hash) = deploy();
+    token.accept_transfer_delay(Sender(owner), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_factory_propose_transfer_delay_with_non_owner() {
+    let (env, token, _owner, _pair_hash) = deploy();
+    let user = env.next_user();
+    token.propose_transfer_delay(Sender(user), 0, 0);
+}
+
+#[test]
+fn test_factory_transfer_owner() {
+    let (env, token, owner, _pair_hash) = deploy();
+    let user = env.next_user();
+    assert_eq!(token.owner(), Key::Account(owner));
+    token.propose_owner(Sender(owner), user);
+    assert_eq!(token.pending_owner(), Key::Account(user));
+    token.accept_owner(Sender(user));
+    assert_eq!(token.owner(), Key::Account(user));
+    // the new owner is now the admin and can manage roles
+    token.grant_role(Sender(user), PAIR_CREATOR, Key::Account(user));
+    assert!(token.has_role(PAIR_CREATOR, Key::Account(user)));
+}
+
+#[test]
+#[should_panic]
+fn test_factory_previous_owner_is_not_admin() {
+    let (env, token, owner, _pair_hash) = deploy();
+    let user = env.next_user();
+    token.propose_owner(Sender(owner), user);
+    token.accept_owner(Sender(user));
+    token.grant_role(Sender(owner), PAIR_CREATOR, Key::Account(owner));
+}
+
 #[test]
 fn test_factory_set_fee_to() {
     let (env, token, owner, _pair_hash) = deploy();
@@ -163,18 +276,447 @@ fn test_factory_create_pair() {
     let pair_hash = Key::Hash(pair_hash.contract_hash());
     let user = env.next_user();
     token.set_white_list(Sender(owner), Key::Account(user));
-    assert_eq!(
-        token.get_white_lists(Key::Account(user)),
-        Key::Account(user)
-    );
+    assert!(token.has_role(PAIR_CREATOR, Key::Account(user)));
     token.create_pair(Sender(user), token0, token1, pair_hash);
     let pair_0_1: Key = token.get_pair(token0, token1);
     let pair_1_0: Key = token.get_pair(token1, token0);
-    let all_pairs: Vec<Key> = token.all_pairs();
     assert_eq!(pair_0_1, pair_1_0);
     assert_eq!(pair_0_1, pair_hash);
     assert_eq!(pair_1_0, pair_hash);
-    assert_eq!(all_pairs.len(), 1);
+    assert_eq!(token.all_pairs_length(), 1.into());
+    assert_eq!(token.all_pairs(0.into()), pair_hash);
+}
+
+#[test]
+fn test_factory_create_stable_pair() {
+    let (env, token, owner, pair_contract) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let pair_hash = Key::Hash(pair_contract.contract_hash());
+    token.set_white_list(Sender(owner), Key::Account(owner));
+    token.create_stable_pair(Sender(owner), token0, token1, pair_hash, 100.into());
+    assert_eq!(token.get_stable_pair(token0, token1), pair_hash);
+    assert_eq!(token.get_stable_pair(token1, token0), pair_hash);
+    assert_eq!(token.all_pairs_length(), 1.into());
+    assert_eq!(token.all_pairs(0.into()), pair_hash);
+    let stable: bool = pair_contract.query_named_key(String::from("stable"));
+    assert!(stable);
+    let amp: U256 = pair_contract.query_named_key(String::from("amp"));
+    assert_eq!(amp, 100.into());
+}
+
+#[test]
+#[should_panic(expected = "User(301)")]
+fn test_factory_create_stable_pair_twice() {
+    let (env, token, owner, pair_contract) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let pair_hash = Key::Hash(pair_contract.contract_hash());
+    token.set_white_list(Sender(owner), Key::Account(owner));
+    token.create_stable_pair(Sender(owner), token0, token1, pair_hash, 100.into());
+    token.create_stable_pair(Sender(owner), token1, token0, pair_hash, 100.into());
+}
+
+#[test]
+fn test_factory_pairs_for_token() {
+    let (env, token, owner, pair_hash) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let dai = Key::Hash(deploy_dai(&env).contract_hash());
+    let pair_hash = Key::Hash(pair_hash.contract_hash());
+    token.set_white_list(Sender(owner), Key::Account(owner));
+    token.create_pair(Sender(owner), token0, token1, pair_hash);
+    assert_eq!(token.pairs_for_token_length(token0), 1.into());
+    assert_eq!(token.pairs_for_token_length(token1), 1.into());
+    assert_eq!(token.pairs_for_token_length(dai), 0.into());
+    assert_eq!(token.pairs_for_token(token0, 0.into()), pair_hash);
+    assert_eq!(token.pairs_for_token(token1, 0.into()), pair_hash);
+}
+
+fn deploy_another_pair(
+    env: &TestEnv,
+    owner: AccountHash,
+    factory_hash: Key,
+    pair_contract: &TestContract,
+    contract_name: &str,
+) -> TestContract {
+    let callee_contract_hash: Key =
+        pair_contract.query_named_key(String::from("callee_contract_hash"));
+    TestContract::new(
+        env,
+        "pair-token.wasm",
+        contract_name,
+        Sender(owner),
+        runtime_args! {
+            "name" => "ERC20",
+            "symbol" => "ERC",
+            "decimals" => 8u8,
+            "initial_supply" => U256::from(1000),
+            "callee_contract_hash" => callee_contract_hash,
+            "factory_hash" => factory_hash,
+        },
+    )
+}
+
+/// Registers three pairs and returns them in the order they were created.
+fn create_three_pairs(
+    env: &TestEnv,
+    owner: AccountHash,
+    factory: &FACTORYInstance,
+    pair_contract: &TestContract,
+) -> Vec<Key> {
+    let token0 = Key::Hash(deploy_token0(env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(env).contract_hash());
+    let dai = Key::Hash(deploy_dai(env).contract_hash());
+    let factory_hash: Key = factory.self_contract_hash();
+    let pair2 = deploy_another_pair(env, owner, factory_hash, pair_contract, "Pair2");
+    let pair3 = deploy_another_pair(env, owner, factory_hash, pair_contract, "Pair3");
+    let pairs: Vec<Key> = vec![
+        Key::Hash(pair_contract.contract_hash()),
+        Key::Hash(pair2.contract_hash()),
+        Key::Hash(pair3.contract_hash()),
+    ];
+    factory.set_white_list(Sender(owner), Key::Account(owner));
+    factory.create_pair(Sender(owner), token0, token1, pairs[0]);
+    factory.create_pair(Sender(owner), token0, dai, pairs[1]);
+    factory.create_pair(Sender(owner), token1, dai, pairs[2]);
+    assert_eq!(factory.all_pairs_length(), 3.into());
+    pairs
+}
+
+#[test]
+fn test_factory_all_pairs_range() {
+    let (env, token, owner, pair_contract) = deploy();
+    let pairs: Vec<Key> = create_three_pairs(&env, owner, &token, &pair_contract);
+    let proxy = FACTORYInstance::proxy(&env, token.self_contract_hash(), Sender(owner));
+
+    proxy.all_pairs_range(Sender(owner), 0.into(), 3.into());
+    assert_eq!(proxy.all_pairs_range_result(), pairs);
+    proxy.all_pairs_range(Sender(owner), 1.into(), 1.into());
+    assert_eq!(proxy.all_pairs_range_result(), vec![pairs[1]]);
+}
+
+#[test]
+fn test_factory_all_pairs_range_past_the_end() {
+    let (env, token, owner, pair_contract) = deploy();
+    let pairs: Vec<Key> = create_three_pairs(&env, owner, &token, &pair_contract);
+    let proxy = FACTORYInstance::proxy(&env, token.self_contract_hash(), Sender(owner));
+
+    // the range is cut at the last pair
+    proxy.all_pairs_range(Sender(owner), 1.into(), 10.into());
+    assert_eq!(proxy.all_pairs_range_result(), pairs[1..].to_vec());
+    // a start at or past the end is an empty range
+    proxy.all_pairs_range(Sender(owner), 3.into(), 10.into());
+    assert_eq!(proxy.all_pairs_range_result(), Vec::<Key>::new());
+    proxy.all_pairs_range(Sender(owner), U256::MAX, U256::MAX);
+    assert_eq!(proxy.all_pairs_range_result(), Vec::<Key>::new());
+}
+
+#[test]
+fn test_factory_all_pairs_range_with_zero_count() {
+    let (env, token, owner, pair_contract) = deploy();
+    create_three_pairs(&env, owner, &token, &pair_contract);
+    let proxy = FACTORYInstance::proxy(&env, token.self_contract_hash(), Sender(owner));
+
+    proxy.all_pairs_range(Sender(owner), 0.into(), 0.into());
+    assert_eq!(proxy.all_pairs_range_result(), Vec::<Key>::new());
+}
+
+#[test]
+fn test_factory_update_pairs_factory_hash() {
+    let (env, token, owner, pair_contract) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let pair_hash = Key::Hash(pair_contract.contract_hash());
+    token.set_white_list(Sender(owner), Key::Account(owner));
+    token.create_pair(Sender(owner), token0, token1, pair_hash);
+    let factory_hash: Key = token.self_contract_hash();
+    let pair_factory_hash: Key = pair_contract.query_named_key(String::from("factory_hash"));
+    assert_eq!(pair_factory_hash, factory_hash);
+
+    // deploying again under the same contract name adds a new version to the package
+    let upgraded = FACTORYInstance::upgrade(&env, NAME_FACTORY, Sender(owner), vec![]);
+    let upgraded_factory_hash: Key = upgraded.self_contract_hash();
+    assert_ne!(upgraded_factory_hash, factory_hash);
+    assert_eq!(upgraded.contract_version(), 4);
+    assert_eq!(upgraded.all_pairs_length(), 1.into());
+    assert_eq!(upgraded.get_pair(token0, token1), pair_hash);
+
+    upgraded.update_pairs_factory_hash(Sender(owner), 0.into(), 10.into());
+    let pair_factory_hash: Key = pair_contract.query_named_key(String::from("factory_hash"));
+    assert_eq!(pair_factory_hash, upgraded_factory_hash);
+}
+
+fn mint(token: &TestContract, owner: AccountHash, to: Key, amount: U256) {
+    token.call_contract(
+        Sender(owner),
+        "mint",
+        runtime_args! {"to" => to, "amount" => amount},
+    );
+}
+
+fn balance_of(token: &TestContract, account: Key) -> U256 {
+    token
+        .query_dictionary("balances", key_to_str(&account))
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_factory_update_pairs_factory_hash_after_pair_upgrade() {
+    let (env, token, owner, pair_contract) = deploy();
+    let token0_contract = deploy_token0(&env);
+    let token1_contract = deploy_token1(&env);
+    let token0 = Key::Hash(token0_contract.contract_hash());
+    let token1 = Key::Hash(token1_contract.contract_hash());
+    let pair_hash = Key::Hash(pair_contract.contract_hash());
+    token.set_white_list(Sender(owner), Key::Account(owner));
+    token.create_pair(Sender(owner), token0, token1, pair_hash);
+    let pair_package: ContractPackageHash =
+        pair_contract.query_named_key(String::from("self_package_hash"));
+    let pair_package = Key::from(pair_package);
+    mint(&token0_contract, owner, pair_package, 1000.into());
+    mint(&token1_contract, owner, pair_package, 1000.into());
+    pair_contract.call_contract(
+        Sender(owner),
+        "mint",
+        runtime_args! {"to" => Key::from(owner)},
+    );
+
+    // the factory keeps the contract hash of the first version of the pair
+    let upgraded_pair = deploy_another_pair(
+        &env,
+        owner,
+        token.self_contract_hash(),
+        &pair_contract,
+        "Pair",
+    );
+    assert_ne!(Key::Hash(upgraded_pair.contract_hash()), pair_hash);
+    assert_eq!(token.get_pair(token0, token1), pair_hash);
+    token.update_pairs_factory_hash(Sender(owner), 0.into(), 10.into());
+    let pair_factory_hash: Key = upgraded_pair.query_named_key(String::from("factory_hash"));
+    assert_eq!(pair_factory_hash, token.self_contract_hash());
+
+    // 100 token0 in for 90 token1 out, through the contract hash in the factory
+    let user = env.next_user();
+    mint(&token0_contract, owner, pair_package, 100.into());
+    let (amount0_out, amount1_out): (U256, U256) = if token0 < token1 {
+        (0.into(), 90.into())
+    } else {
+        (90.into(), 0.into())
+    };
+    env.run(
+        Sender(user),
+        Code::Hash(
+            token.get_pair(token0, token1).into_hash().unwrap(),
+            String::from("swap"),
+        ),
+        runtime_args! {
+            "amount0_out" => amount0_out,
+            "amount1_out" => amount1_out,
+            "to" => Key::from(user),
+            "data" => String::new()
+        },
+    );
+    assert_eq!(balance_of(&token1_contract, Key::from(user)), 90.into());
+}
+
+/// Deploys factory-v0.wasm, built from the factory code before versioning which kept its
+/// pairs in a Vec under the `all_pairs` named key, and a pair for it.
+fn deploy_factory_v0(env: &TestEnv, owner: AccountHash) -> (TestContract, TestContract) {
+    let factory_v0 = TestContract::new(
+        env,
+        "factory-v0.wasm",
+        NAME_FACTORY,
+        Sender(owner),
+        runtime_args! {
+            "fee_to_setter" => Key::from(owner),
+        },
+    );
+    factory_v0.call_contract(
+        Sender(owner),
+        "set_white_list",
+        runtime_args! {"white_list" => Key::from(owner)},
+    );
+    let pair_contract = deploy_pair(env, owner, Key::Hash(factory_v0.contract_hash()));
+    (factory_v0, pair_contract)
+}
+
+fn create_pair_v0(
+    factory_v0: &TestContract,
+    owner: AccountHash,
+    token_a: Key,
+    token_b: Key,
+    pair: Key,
+) {
+    factory_v0.call_contract(
+        Sender(owner),
+        "create_pair",
+        runtime_args! {
+            "token_a" => token_a,
+            "token_b" => token_b,
+            "pair_hash" => pair
+        },
+    );
+}
+
+#[test]
+fn test_factory_upgrade_from_version_0() {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let (factory_v0, pair_contract) = deploy_factory_v0(&env, owner);
+    let factory_v0_hash = Key::Hash(factory_v0.contract_hash());
+    let pair_hash = Key::Hash(pair_contract.contract_hash());
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    create_pair_v0(&factory_v0, owner, token0, token1, pair_hash);
+
+    let user = env.next_user();
+    let upgraded = FACTORYInstance::upgrade(
+        &env,
+        NAME_FACTORY,
+        Sender(owner),
+        vec![Key::from(owner), Key::from(user)],
+    );
+    assert_ne!(upgraded.self_contract_hash(), factory_v0_hash);
+    assert_eq!(upgraded.contract_version(), 4);
+    assert_eq!(upgraded.all_pairs_length(), 1.into());
+    assert_eq!(upgraded.all_pairs(0.into()), pair_hash);
+    assert_eq!(upgraded.get_pair(token0, token1), pair_hash);
+
+    // the whitelisted owner became a pair creator, the user who was never whitelisted did not
+    assert!(upgraded.has_role(PAIR_CREATOR, Key::Account(owner)));
+    assert!(!upgraded.has_role(PAIR_CREATOR, Key::Account(user)));
+
+    // the dictionaries of the later versions exist
+    let dai = Key::Hash(deploy_dai(&env).contract_hash());
+    let pair_contract2 = deploy_another_pair(
+        &env,
+        owner,
+        upgraded.self_contract_hash(),
+        &pair_contract,
+        "Pair2",
+    );
+    let pair_hash2 = Key::Hash(pair_contract2.contract_hash());
+    upgraded.create_pair(Sender(owner), token0, dai, pair_hash2);
+    assert_eq!(upgraded.all_pairs_length(), 2.into());
+    assert_eq!(upgraded.all_pairs(1.into()), pair_hash2);
+    assert_eq!(upgraded.pairs_for_token(dai, 0.into()), pair_hash2);
+
+    upgraded.update_pairs_factory_hash(Sender(owner), 0.into(), 10.into());
+    let pair_factory_hash: Key = pair_contract.query_named_key(String::from("factory_hash"));
+    assert_eq!(pair_factory_hash, upgraded.self_contract_hash());
+}
+
+#[test]
+fn test_factory_index_token_pairs() {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let (factory_v0, pair_contract) = deploy_factory_v0(&env, owner);
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let dai = Key::Hash(deploy_dai(&env).contract_hash());
+    let pair_hash = Key::Hash(pair_contract.contract_hash());
+    create_pair_v0(&factory_v0, owner, token0, token1, pair_hash);
+    let upgraded =
+        FACTORYInstance::upgrade(&env, NAME_FACTORY, Sender(owner), vec![Key::from(owner)]);
+    assert_eq!(upgraded.pairs_for_token_length(token0), 0.into());
+
+    // pairs created after the upgrade are indexed right away
+    let pair_contract2 = deploy_another_pair(
+        &env,
+        owner,
+        upgraded.self_contract_hash(),
+        &pair_contract,
+        "Pair2",
+    );
+    let pair_hash2 = Key::Hash(pair_contract2.contract_hash());
+    upgraded.create_pair(Sender(owner), token0, dai, pair_hash2);
+    assert_eq!(upgraded.pairs_for_token_length(token0), 1.into());
+
+    upgraded.index_token_pairs(Sender(owner), 0.into());
+    assert_eq!(upgraded.pairs_for_token_length(token0), 1.into());
+    upgraded.index_token_pairs(Sender(owner), 10.into());
+    assert_eq!(upgraded.pairs_for_token_length(token0), 2.into());
+    assert_eq!(upgraded.pairs_for_token(token0, 0.into()), pair_hash2);
+    assert_eq!(upgraded.pairs_for_token(token0, 1.into()), pair_hash);
+    assert_eq!(upgraded.pairs_for_token_length(token1), 1.into());
+    assert_eq!(upgraded.pairs_for_token(token1, 0.into()), pair_hash);
+    assert_eq!(upgraded.pairs_for_token_length(dai), 1.into());
+
+    // every pair is added once
+    upgraded.index_token_pairs(Sender(owner), 10.into());
+    assert_eq!(upgraded.pairs_for_token_length(token0), 2.into());
+    assert_eq!(upgraded.pairs_for_token_length(token1), 1.into());
+}
+
+#[test]
+fn test_factory_index_token_pairs_in_batches() {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let (factory_v0, pair_contract) = deploy_factory_v0(&env, owner);
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let dai = Key::Hash(deploy_dai(&env).contract_hash());
+    let pair_hash = Key::Hash(pair_contract.contract_hash());
+    let pair_contract2 = deploy_another_pair(
+        &env,
+        owner,
+        Key::Hash(factory_v0.contract_hash()),
+        &pair_contract,
+        "Pair2",
+    );
+    let pair_hash2 = Key::Hash(pair_contract2.contract_hash());
+    create_pair_v0(&factory_v0, owner, token0, token1, pair_hash);
+    create_pair_v0(&factory_v0, owner, token0, dai, pair_hash2);
+    let upgraded =
+        FACTORYInstance::upgrade(&env, NAME_FACTORY, Sender(owner), vec![Key::from(owner)]);
+
+    upgraded.index_token_pairs(Sender(owner), 1.into());
+    assert_eq!(upgraded.pairs_for_token_length(token0), 1.into());
+    assert_eq!(upgraded.pairs_for_token_length(dai), 0.into());
+    upgraded.index_token_pairs(Sender(owner), 1.into());
+    assert_eq!(upgraded.pairs_for_token_length(token0), 2.into());
+    assert_eq!(upgraded.pairs_for_token(token0, 1.into()), pair_hash2);
+    assert_eq!(upgraded.pairs_for_token(dai, 0.into()), pair_hash2);
+}
+
+#[test]
+#[should_panic]
+fn test_factory_index_token_pairs_with_non_owner() {
+    let (env, token, _, _) = deploy();
+    let user = env.next_user();
+    token.index_token_pairs(Sender(user), 10.into());
+}
+
+#[test]
+fn test_factory_pair_info() {
+    let (env, token, owner, pair_contract) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let pair_hash = Key::Hash(pair_contract.contract_hash());
+    token.set_white_list(Sender(owner), Key::Account(owner));
+    token.create_pair(Sender(owner), token0, token1, pair_hash);
+    let proxy = FACTORYInstance::proxy(&env, token.self_contract_hash(), Sender(owner));
+
+    proxy.pair_info(Sender(owner), pair_hash);
+    let ((info_token0, info_token1), (reserve0, reserve1, _), _) = proxy.pair_info_result();
+    let (expected_token0, expected_token1) = if token0 < token1 {
+        (token0, token1)
+    } else {
+        (token1, token0)
+    };
+    assert_eq!(info_token0, expected_token0);
+    assert_eq!(info_token1, expected_token1);
+    assert_eq!(reserve0, 0.into());
+    assert_eq!(reserve1, 0.into());
+}
+
+#[test]
+#[should_panic]
+fn test_factory_update_pairs_factory_hash_with_non_owner() {
+    let (env, token, _owner, _pair_contract) = deploy();
+    let user = env.next_user();
+    token.update_pairs_factory_hash(Sender(user), 0.into(), 10.into());
 }
 
 #[test]
@@ -183,18 +725,178 @@ fn test_factory_set_white_list() {
     assert_eq!(token.fee_to_setter(), Key::Account(owner));
     let user = env.next_user();
     token.set_white_list(Sender(owner), Key::Account(user));
+    assert!(token.has_role(PAIR_CREATOR, Key::Account(user)));
+    token.set_white_list(Sender(owner), Key::Account(owner));
+    assert!(token.has_role(PAIR_CREATOR, Key::Account(owner)));
+    assert_eq!(token.role_member_count(PAIR_CREATOR), 2.into());
+    assert_eq!(token.role_member(PAIR_CREATOR, 0.into()), Key::Account(user));
+    assert_eq!(token.role_member(PAIR_CREATOR, 1.into()), Key::Account(owner));
+}
+
+#[test]
+fn test_factory_revoke_role() {
+    let (env, token, owner, _pair_hash) = deploy();
+    let user = env.next_user();
+    let user2 = env.next_user();
+    token.grant_role(Sender(owner), PAIR_CREATOR, Key::Account(user));
+    token.grant_role(Sender(owner), PAIR_CREATOR, Key::Account(user2));
+    token.revoke_role(Sender(owner), PAIR_CREATOR, Key::Account(user));
+    assert!(!token.has_role(PAIR_CREATOR, Key::Account(user)));
+    assert!(token.has_role(PAIR_CREATOR, Key::Account(user2)));
+    assert_eq!(token.role_member_count(PAIR_CREATOR), 1.into());
+    assert_eq!(token.role_member(PAIR_CREATOR, 0.into()), Key::Account(user2));
+}
 
-    assert_eq!(
-        token.get_white_lists(Key::Account(user)),
-        Key::Account(user)
+#[test]
+fn test_factory_fee_admin_set_fee_to() {
+    let (env, token, owner, _pair_hash) = deploy();
+    let fee_admin = env.next_user();
+    let user = env.next_user();
+    token.grant_role(Sender(owner), FEE_ADMIN, Key::Account(fee_admin));
+    assert!(!token.has_role(PAIR_CREATOR, Key::Account(fee_admin)));
+    token.set_fee_to(Sender(fee_admin), user);
+    assert_eq!(token.fee_to(), Key::Account(user));
+}
+
+#[test]
+#[should_panic]
+fn test_factory_create_pair_without_role() {
+    let (env, token, _owner, pair_hash) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let user = env.next_user();
+    token.create_pair(Sender(user), token0, token1, Key::Hash(pair_hash.contract_hash()));
+}
+
+#[test]
+fn test_factory_create_pair_permissionless() {
+    let (env, token, owner, pair_hash) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let pair_hash = Key::Hash(pair_hash.contract_hash());
+    let user = env.next_user();
+    token.set_permissionless(Sender(owner), true);
+    token.create_pair(Sender(user), token0, token1, pair_hash);
+    assert_eq!(token.get_pair(token0, token1), pair_hash);
+}
+
+#[test]
+#[should_panic]
+fn test_factory_create_pair_with_account_token() {
+    let (env, token, owner, pair_hash) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let user = env.next_user();
+    token.set_white_list(Sender(owner), Key::Account(owner));
+    token.create_pair(
+        Sender(owner),
+        token0,
+        Key::Account(user),
+        Key::Hash(pair_hash.contract_hash()),
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_factory_create_pair_with_non_token_contract() {
+    let (env, token, owner, pair_hash) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    // the factory has no `balance_of` entry point
+    let not_a_token: Key = token.self_contract_hash();
+    token.set_white_list(Sender(owner), Key::Account(owner));
+    token.create_pair(
+        Sender(owner),
+        token0,
+        not_a_token,
+        Key::Hash(pair_hash.contract_hash()),
     );
+}
+
+#[test]
+#[should_panic]
+fn test_factory_create_pair_with_token_without_decimals() {
+    let (env, token, owner, pair_hash) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    // the proxy answers `total_supply` and `balance_of`, but has no `decimals` entry point
+    let proxy = FACTORYInstance::proxy(&env, token.self_contract_hash(), Sender(owner));
+    let no_decimals: Key = Key::Hash(proxy.0.contract_hash());
     token.set_white_list(Sender(owner), Key::Account(owner));
-    assert_eq!(
-        token.get_white_lists(Key::Account(owner)),
-        Key::Account(owner)
+    token.create_pair(
+        Sender(owner),
+        token0,
+        no_decimals,
+        Key::Hash(pair_hash.contract_hash()),
     );
 }
 
+#[test]
+#[should_panic]
+fn test_factory_create_pair_with_denied_token() {
+    let (env, token, owner, pair_hash) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    token.set_white_list(Sender(owner), Key::Account(owner));
+    token.set_token_denied(Sender(owner), token1, true);
+    token.create_pair(Sender(owner), token0, token1, Key::Hash(pair_hash.contract_hash()));
+}
+
+#[test]
+#[should_panic(expected = "User(312)")]
+fn test_factory_create_pair_with_too_many_decimals() {
+    let (env, token, owner, pair_hash) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = TestContract::new(
+        &env,
+        "erc20-token.wasm",
+        "token1_contract",
+        Sender(owner),
+        runtime_args! {
+            "initial_supply" => U256::from(1000),
+            "name" => "token1",
+            "symbol" => "tk1",
+            "decimals" => 37u8
+        },
+    );
+    token.set_white_list(Sender(owner), Key::Account(owner));
+    token.create_pair(
+        Sender(owner),
+        token0,
+        Key::Hash(token1.contract_hash()),
+        Key::Hash(pair_hash.contract_hash()),
+    );
+}
+
+#[test]
+fn test_factory_create_pair_after_token_allowed() {
+    let (env, token, owner, pair_hash) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let pair_hash = Key::Hash(pair_hash.contract_hash());
+    token.set_white_list(Sender(owner), Key::Account(owner));
+    token.set_token_denied(Sender(owner), token1, true);
+    assert!(token.is_token_denied(token1));
+    token.set_token_denied(Sender(owner), token1, false);
+    assert!(!token.is_token_denied(token1));
+    token.create_pair(Sender(owner), token0, token1, pair_hash);
+    assert_eq!(token.get_pair(token0, token1), pair_hash);
+}
+
+#[test]
+#[should_panic]
+fn test_factory_set_token_denied_with_non_admin() {
+    let (env, token, _owner, _pair_hash) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let user = env.next_user();
+    token.set_token_denied(Sender(user), token0, true);
+}
+
+#[test]
+#[should_panic]
+fn test_factory_grant_role_with_non_admin() {
+    let (env, token, _owner, _pair_hash) = deploy();
+    let user = env.next_user();
+    token.grant_role(Sender(user), FEE_ADMIN, Key::Account(user));
+}
+
 #[test]
 #[should_panic]
 fn test_factory_set_white_list_with_non_owner() {
@@ -211,3 +913,62 @@ fn test_calling_construction() {
     let user = env.next_user();
     token.constructor(Sender(owner), user);
 }
+
+fn assert_registered(code: u16, range: (u16, u16), info: ErrorInfo) {
+    assert!(code >= range.0 && code < range.1);
+    assert_eq!(errors::describe(code), Some(info));
+}
+
+#[test]
+fn test_error_codes_are_registered_in_their_range() {
+    for error in CommonError::ALL {
+        assert_registered(*error as u16, CommonError::RANGE, error.info());
+    }
+    for error in Erc20Error::ALL {
+        assert_registered(*error as u16, Erc20Error::RANGE, error.info());
+    }
+    for error in WcsprError::ALL {
+        assert_registered(*error as u16, WcsprError::RANGE, error.info());
+    }
+    for error in FactoryError::ALL {
+        assert_registered(*error as u16, FactoryError::RANGE, error.info());
+    }
+    for error in PairError::ALL {
+        assert_registered(*error as u16, PairError::RANGE, error.info());
+    }
+    for error in FlashSwapperError::ALL {
+        assert_registered(*error as u16, FlashSwapperError::RANGE, error.info());
+    }
+    for error in StakingError::ALL {
+        assert_registered(*error as u16, StakingError::RANGE, error.info());
+    }
+    for error in ZapError::ALL {
+        assert_registered(*error as u16, ZapError::RANGE, error.info());
+    }
+    for error in LimitOrderError::ALL {
+        assert_registered(*error as u16, LimitOrderError::RANGE, error.info());
+    }
+    for error in MulticallError::ALL {
+        assert_registered(*error as u16, MulticallError::RANGE, error.info());
+    }
+    for error in LensError::ALL {
+        assert_registered(*error as u16, LensError::RANGE, error.info());
+    }
+    for error in GovernorError::ALL {
+        assert_registered(*error as u16, GovernorError::RANGE, error.info());
+    }
+    assert_eq!(errors::describe(0), None);
+    assert_eq!(errors::describe(1200), None);
+}
+
+#[test]
+fn test_error_codes_map_back_from_api_error() {
+    let info: ErrorInfo =
+        errors::describe_api_error(ApiError::from(FactoryError::UniswapV2FactoryNotOwner)).unwrap();
+    assert_eq!(info.contract, "factory");
+    assert_eq!(info.name, "UniswapV2FactoryNotOwner");
+    let info: ErrorInfo = errors::describe_u32(CommonError::ZeroAmount.into()).unwrap();
+    assert_eq!(info.contract, "common");
+    assert_eq!(info.code, 6);
+    assert_eq!(errors::describe_api_error(ApiError::UnexpectedKeyVariant), None);
+}
@@ -13,8 +13,8 @@ use casper_types::{
     runtime_args, CLType, CLTyped, CLValue, ContractHash, ContractPackageHash, EntryPoint,
     EntryPointAccess, EntryPointType, EntryPoints, Group, Key, Parameter, RuntimeArgs, URef, U256,
 };
-use contract_utils::{ContractContext, OnChainContractStorage};
-use factory::{self, FACTORY};
+use contract_utils::{AdminControl, ContractContext, OnChainContractStorage};
+use factory::{self, data::PairInfo, FACTORY};
 
 #[derive(Default)]
 struct Factory(OnChainContractStorage);
@@ -27,31 +27,25 @@ impl ContractContext<OnChainContractStorage> for Factory {
 
 impl FACTORY<OnChainContractStorage> for Factory {}
 
+impl AdminControl<OnChainContractStorage> for Factory {}
+
 impl Factory {
     fn constructor(
         &mut self,
         fee_to_setter: Key,
-        all_pairs: Vec<Key>,
         contract_hash: ContractHash,
         package_hash: ContractPackageHash,
     ) {
-        FACTORY::init(
-            self,
-            fee_to_setter,
-            all_pairs,
-            Key::from(contract_hash),
-            package_hash,
-        );
+        FACTORY::init(self, fee_to_setter, Key::from(contract_hash), package_hash);
     }
 }
 
 #[no_mangle]
 fn constructor() {
     let fee_to_setter: Key = runtime::get_named_arg("fee_to_setter");
-    let all_pairs: Vec<Key> = runtime::get_named_arg("all_pairs");
     let contract_hash: ContractHash = runtime::get_named_arg("contract_hash");
     let package_hash: ContractPackageHash = runtime::get_named_arg("package_hash");
-    Factory::default().constructor(fee_to_setter, all_pairs, contract_hash, package_hash);
+    Factory::default().constructor(fee_to_setter, contract_hash, package_hash);
 }
 
 /// This function is to return the fee to's hash
@@ -72,12 +66,82 @@ fn fee_to_setter() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
-/// This function is to return the all Pairs
+/// This function is to return the Pair created at the index provided by user. If no pair exists at the index it will return hash-0000000000000000000000000000000000000000000000000000000000000000
+///
+/// # Parameters
+///
+/// * `index` - A U256 that holds the index of the pair in creation order
 ///
 
 #[no_mangle]
 fn all_pairs() {
-    let ret: Vec<Key> = Factory::default().get_all_pairs();
+    let index: U256 = runtime::get_named_arg("index");
+    let ret: Key = Factory::default().all_pairs(index);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return at most `count` Pairs starting from the index provided by user
+///
+/// # Parameters
+///
+/// * `start` - A U256 that holds the index of the first pair to return
+///
+/// * `count` - A U256 that holds the maximum number of pairs to return
+///
+
+#[no_mangle]
+fn all_pairs_range() {
+    let start: U256 = runtime::get_named_arg("start");
+    let count: U256 = runtime::get_named_arg("count");
+    let ret: Vec<Key> = Factory::default().all_pairs_range(start, count);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return at most `count` Pairs that contain the token provided by user, starting from the index provided by user
+///
+/// # Parameters
+///
+/// * `token` - A Key that holds the Hash of the token
+///
+/// * `start` - A U256 that holds the index of the first pair to return
+///
+/// * `count` - A U256 that holds the maximum number of pairs to return
+///
+
+#[no_mangle]
+fn pairs_for_token() {
+    let token: Key = runtime::get_named_arg("token");
+    let start: U256 = runtime::get_named_arg("start");
+    let count: U256 = runtime::get_named_arg("count");
+    let ret: Vec<Key> = Factory::default().pairs_for_token(token, start, count);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the number of Pairs that contain the token provided by user
+///
+/// # Parameters
+///
+/// * `token` - A Key that holds the Hash of the token
+///
+
+#[no_mangle]
+fn pairs_for_token_length() {
+    let token: Key = runtime::get_named_arg("token");
+    let ret: U256 = Factory::default().pairs_for_token_length(token);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the tokens, reserves, total supply and treasury fee of the pair provided by user in one call
+///
+/// # Parameters
+///
+/// * `pair` - A Key that holds the Hash of Pair Contract
+///
+
+#[no_mangle]
+fn pair_info() {
+    let pair: Key = runtime::get_named_arg("pair");
+    let ret: PairInfo = Factory::default().pair_info(pair);
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
@@ -86,8 +150,8 @@ fn all_pairs() {
 
 #[no_mangle]
 fn all_pairs_length() {
-    let ret: Vec<Key> = Factory::default().get_all_pairs();
-    runtime::ret(CLValue::from_t(U256::from(ret.len())).unwrap_or_revert());
+    let ret: U256 = Factory::default().all_pairs_length();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
 /// This function is to set the fee to address which is only possible if the caller matched with fee to setter's hash
@@ -103,66 +167,480 @@ fn set_fee_to() {
     Factory::default().set_fee_to(fee_to);
 }
 
-/// This function is to set the fee to setter address who is the only one to set fee to.
+/// This function is to propose the fee to setter address who is the only one to set fee to. The proposed address has to call accept_fee_to_setter.
+///
+/// # Parameters
+///
+/// * `fee_to_setter` - A Key that holds the Account Hash of fee_to_setter
+///
+
+#[no_mangle]
+fn set_fee_to_setter() {
+    let fee_to_setter: Key = runtime::get_named_arg("fee_to_setter");
+    Factory::default().set_fee_to_setter(fee_to_setter);
+}
+
+/// This function is to propose the new fee to setter which is only possible if the caller matched with fee to setter's hash
+///
+/// # Parameters
+///
+/// * `fee_to_setter` - A Key that holds the Account Hash of the proposed fee_to_setter
+///
+
+#[no_mangle]
+fn propose_fee_to_setter() {
+    let fee_to_setter: Key = runtime::get_named_arg("fee_to_setter");
+    Factory::default().propose_fee_to_setter(fee_to_setter);
+}
+
+/// This function is to accept the fee to setter role which is only possible if the caller is the proposed fee to setter and the transfer delay has passed
+///
+
+#[no_mangle]
+fn accept_fee_to_setter() {
+    Factory::default().accept_fee_to_setter();
+}
+
+/// This function is to return the proposed fee to setter's hash
+///
+
+#[no_mangle]
+fn pending_fee_to_setter() {
+    let ret: Key = Factory::default().get_pending_fee_to_setter();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to propose the new owner which is only possible if the caller matched with owner's hash
+///
+/// # Parameters
+///
+/// * `owner` - A Key that holds the Account Hash of the proposed owner
+///
+
+#[no_mangle]
+fn propose_owner() {
+    let owner: Key = runtime::get_named_arg("owner");
+    Factory::default().propose_owner(owner);
+}
+
+/// This function is to accept the ownership which is only possible if the caller is the proposed owner and the transfer delay has passed
+///
+
+#[no_mangle]
+fn accept_owner() {
+    Factory::default().accept_owner();
+}
+
+/// This function is to return the owner's hash
+///
+
+#[no_mangle]
+fn owner() {
+    let ret: Key = Factory::default().get_owner();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the proposed owner's hash
+///
+
+#[no_mangle]
+fn pending_owner() {
+    let ret: Key = Factory::default().get_pending_owner();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to propose the minimum delay between a proposal and its acceptance which is only possible if the caller matched with owner's hash
+///
+/// # Parameters
+///
+/// * `transfer_delay` - A u64 that holds the delay in milliseconds
+///
+
+#[no_mangle]
+fn propose_transfer_delay() {
+    let transfer_delay: u64 = runtime::get_named_arg("transfer_delay");
+    Factory::default().propose_transfer_delay(transfer_delay);
+}
+
+/// This function is to apply the proposed delay which is only possible if the caller matched with owner's hash and the current transfer delay has passed since the proposal
+///
+
+#[no_mangle]
+fn accept_transfer_delay() {
+    Factory::default().accept_transfer_delay();
+}
+
+/// This function is to return the proposed delay in milliseconds, None if no delay is proposed
+///
+
+#[no_mangle]
+fn pending_transfer_delay() {
+    let ret: Option<u64> = Factory::default().get_pending_transfer_delay();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the minimum delay in milliseconds between a proposal and its acceptance
+///
+
+#[no_mangle]
+fn transfer_delay() {
+    let ret: u64 = Factory::default().get_transfer_delay();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to create pair of tokens provided by user agains the pair hash provided by user
+///
+/// # Parameters
+///
+/// * `token_a` - A Key that holds the Hash of token_a
+///
+/// * `token_b` - A Key that holds the Hash of token1_b
+///
+/// * `pair_hash` - A Key that holds the Hash of Pair Contract
+///
+
+#[no_mangle]
+fn create_pair() {
+    let token_a: Key = runtime::get_named_arg("token_a");
+    let token_b: Key = runtime::get_named_arg("token_b");
+    let pair_hash: Key = runtime::get_named_arg("pair_hash");
+    Factory::default().create_pair(token_a, token_b, pair_hash);
+}
+
+/// This function is to return the the pair against tokens provided by user. If pair not found it will return hash-0000000000000000000000000000000000000000000000000000000000000000
+///
+/// # Parameters
+///
+/// * `token0` - A Key that holds the Hash of token0
+///
+/// * `token1` - A Key that holds the Hash of token1
+///
+
+#[no_mangle]
+fn get_pair() {
+    let token0: Key = runtime::get_named_arg("token0");
+    let token1: Key = runtime::get_named_arg("token1");
+    let ret: Key = Factory::default().get_pair(token0, token1);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to create a stable pair of tokens, which trades on the StableSwap invariant, against the pair hash provided by user
+///
+/// # Parameters
+///
+/// * `token_a` - A Key that holds the Hash of token_a
+///
+/// * `token_b` - A Key that holds the Hash of token_b
+///
+/// * `pair_hash` - A Key that holds the Hash of Pair Contract
+///
+/// * `amp` - A U256 that holds the amplification coefficient of the pair
+///
+
+#[no_mangle]
+fn create_stable_pair() {
+    let token_a: Key = runtime::get_named_arg("token_a");
+    let token_b: Key = runtime::get_named_arg("token_b");
+    let pair_hash: Key = runtime::get_named_arg("pair_hash");
+    let amp: U256 = runtime::get_named_arg("amp");
+    Factory::default().create_stable_pair(token_a, token_b, pair_hash, amp);
+}
+
+/// This function is to return the stable pair against tokens provided by user. If pair not found it will return hash-0000000000000000000000000000000000000000000000000000000000000000
+///
+/// # Parameters
+///
+/// * `token0` - A Key that holds the Hash of token0
+///
+/// * `token1` - A Key that holds the Hash of token1
+///
+
+#[no_mangle]
+fn get_stable_pair() {
+    let token0: Key = runtime::get_named_arg("token0");
+    let token1: Key = runtime::get_named_arg("token1");
+    let ret: Key = Factory::default().get_stable_pair(token0, token1);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to set the white list addresses which is only possible if the caller matched with owners's hash
+///
+/// # Parameters
+///
+/// * `white_list` - A Key that holds the Account Hash of fee_to_setter
+///
+
+#[no_mangle]
+fn set_white_list() {
+    let white_list: Key = runtime::get_named_arg("white_list");
+    Factory::default().set_white_list(white_list);
+}
+
+/// This function is to grant a role to an account which is only possible if the caller is an admin
+///
+/// # Parameters
+///
+/// * `role` - A u8 that holds the role, 0 for PairCreator, 1 for FeeAdmin and 2 for Guardian
+///
+/// * `account` - A Key that holds the Account Hash or Package Hash to grant the role to
+///
+
+#[no_mangle]
+fn grant_role() {
+    let role: u8 = runtime::get_named_arg("role");
+    let account: Key = runtime::get_named_arg("account");
+    Factory::default().grant_role(role, account);
+}
+
+/// This function is to revoke a role from an account which is only possible if the caller is an admin
+///
+/// # Parameters
+///
+/// * `role` - A u8 that holds the role, 0 for PairCreator, 1 for FeeAdmin and 2 for Guardian
+///
+/// * `account` - A Key that holds the Account Hash or Package Hash to revoke the role from
+///
+
+#[no_mangle]
+fn revoke_role() {
+    let role: u8 = runtime::get_named_arg("role");
+    let account: Key = runtime::get_named_arg("account");
+    Factory::default().revoke_role(role, account);
+}
+
+/// This function is to return whether the account has the role or not
+///
+/// # Parameters
+///
+/// * `role` - A u8 that holds the role, 0 for PairCreator, 1 for FeeAdmin and 2 for Guardian
+///
+/// * `account` - A Key that holds the Account Hash or Package Hash
+///
+
+#[no_mangle]
+fn has_role() {
+    let role: u8 = runtime::get_named_arg("role");
+    let account: Key = runtime::get_named_arg("account");
+    let ret: bool = Factory::default().has_role(role, account);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the number of accounts that have the role
+///
+/// # Parameters
+///
+/// * `role` - A u8 that holds the role, 0 for PairCreator, 1 for FeeAdmin and 2 for Guardian
+///
+
+#[no_mangle]
+fn get_role_member_count() {
+    let role: u8 = runtime::get_named_arg("role");
+    let ret: U256 = Factory::default().get_role_member_count(role);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the account that has the role at the index provided by user
+///
+/// # Parameters
+///
+/// * `role` - A u8 that holds the role, 0 for PairCreator, 1 for FeeAdmin and 2 for Guardian
+///
+/// * `index` - A U256 that holds the index of the member, less than get_role_member_count
+///
+
+#[no_mangle]
+fn get_role_member() {
+    let role: u8 = runtime::get_named_arg("role");
+    let index: U256 = runtime::get_named_arg("index");
+    let ret: Key = Factory::default().get_role_member(role, index);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to turn the PairCreator whitelist off or on which is only possible if the caller is an admin
+///
+/// # Parameters
+///
+/// * `permissionless` - A bool that holds true if anyone should be able to create pairs
+///
+
+#[no_mangle]
+fn set_permissionless() {
+    let permissionless: bool = runtime::get_named_arg("permissionless");
+    Factory::default().set_permissionless(permissionless);
+}
+
+/// This function is to return whether pair creation is permissionless or not
+///
+
+#[no_mangle]
+fn permissionless() {
+    let ret: bool = Factory::default().get_permissionless();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to pause swap, mint, skim and flash loan on every pair which is only possible if the caller is a guardian
+///
+
+#[no_mangle]
+fn pause() {
+    Factory::default().pause();
+}
+
+/// This function is to unpause the pairs which is only possible if the caller is a guardian
+///
+
+#[no_mangle]
+fn unpause() {
+    Factory::default().unpause();
+}
+
+/// This function is to pause or unpause a single pair which is only possible if the caller is a guardian
+///
+/// # Parameters
+///
+/// * `pair` - A Key that holds the Hash of Pair Contract
+///
+/// * `paused` - A bool that holds true to pause the pair and false to unpause it
+///
+
+#[no_mangle]
+fn set_pair_paused() {
+    let pair: Key = runtime::get_named_arg("pair");
+    let paused: bool = runtime::get_named_arg("paused");
+    Factory::default().set_pair_paused(pair, paused);
+}
+
+/// This function is to return whether the protocol is paused or not
+///
+
+#[no_mangle]
+fn paused() {
+    let ret: bool = Factory::default().get_paused();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return whether the pair is paused or not, either by the protocol wide pause or on its own
+///
+/// # Parameters
+///
+/// * `pair` - A Key that holds the Hash of Pair Contract
+///
+
+#[no_mangle]
+fn is_pair_paused() {
+    let pair: Key = runtime::get_named_arg("pair");
+    let ret: bool = Factory::default().is_pair_paused(pair);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to add or remove a token from the denylist which is only possible if the caller is an admin, pairs can not be created for a denied token
+///
+/// # Parameters
+///
+/// * `token` - A Key that holds the Hash of the token
+///
+/// * `denied` - A bool that holds true to deny the token and false to allow it again
+///
+
+#[no_mangle]
+fn set_token_denied() {
+    let token: Key = runtime::get_named_arg("token");
+    let denied: bool = runtime::get_named_arg("denied");
+    Factory::default().set_token_denied(token, denied);
+}
+
+/// This function is to return whether the token is on the denylist or not
+///
+/// # Parameters
+///
+/// * `token` - A Key that holds the Hash of the token
+///
+
+#[no_mangle]
+fn is_token_denied() {
+    let token: Key = runtime::get_named_arg("token");
+    let ret: bool = Factory::default().is_token_denied(token);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to record the new contract version after an upgrade which is only possible if the caller matched with owner's hash, it is called by the upgrade deploy
+///
+/// # Parameters
+///
+/// * `contract_hash` - A Key that holds the Hash of the new factory contract version
+///
+/// * `legacy_white_lists` - A Vec<Key> that holds the accounts whitelisted by version 0
+///
+
+#[no_mangle]
+fn migrate() {
+    let contract_hash: Key = runtime::get_named_arg("contract_hash");
+    let legacy_white_lists: Vec<Key> = runtime::get_named_arg("legacy_white_lists");
+    Factory::default().migrate(contract_hash, legacy_white_lists);
+}
+
+/// This function is to return the version of the factory contract
+///
+
+#[no_mangle]
+fn contract_version() {
+    let ret: u32 = Factory::default().get_contract_version();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to point the registered pairs to the current factory version which is only possible if the caller matched with owner's hash
 ///
 /// # Parameters
 ///
-/// * `fee_to_setter` - A Key that holds the Account Hash of fee_to_setter
+/// * `start` - A U256 that holds the index of the first pair to update
+///
+/// * `count` - A U256 that holds the maximum number of pairs to update
 ///
 
 #[no_mangle]
-fn set_fee_to_setter() {
-    let fee_to_setter: Key = runtime::get_named_arg("fee_to_setter");
-    Factory::default().set_fee_to_setter(fee_to_setter);
+fn update_pairs_factory_hash() {
+    let start: U256 = runtime::get_named_arg("start");
+    let count: U256 = runtime::get_named_arg("count");
+    Factory::default().update_pairs_factory_hash(start, count);
 }
 
-/// This function is to create pair of tokens provided by user agains the pair hash provided by user
+/// This function is to add the pairs created before the per-token index existed to it, in batches, which is only possible if the caller matched with owner's hash
 ///
 /// # Parameters
 ///
-/// * `token_a` - A Key that holds the Hash of token_a
-///
-/// * `token_b` - A Key that holds the Hash of token1_b
-///
-/// * `pair_hash` - A Key that holds the Hash of Pair Contract
+/// * `count` - A U256 that holds the maximum number of pairs to add
 ///
 
 #[no_mangle]
-fn create_pair() {
-    let token_a: Key = runtime::get_named_arg("token_a");
-    let token_b: Key = runtime::get_named_arg("token_b");
-    let pair_hash: Key = runtime::get_named_arg("pair_hash");
-    Factory::default().create_pair(token_a, token_b, pair_hash);
+fn index_token_pairs() {
+    let count: U256 = runtime::get_named_arg("count");
+    Factory::default().index_token_pairs(count);
 }
 
-/// This function is to return the the pair against tokens provided by user. If pair not found it will return hash-0000000000000000000000000000000000000000000000000000000000000000
+/// This function is to add an admin which is only possible if the caller is an admin
 ///
 /// # Parameters
 ///
-/// * `token0` - A Key that holds the Hash of token0
-///
-/// * `token1` - A Key that holds the Hash of token1
+/// * `address` - A Key that holds the Account Hash of the new admin
 ///
 
 #[no_mangle]
-fn get_pair() {
-    let token0: Key = runtime::get_named_arg("token0");
-    let token1: Key = runtime::get_named_arg("token1");
-    let ret: Key = Factory::default().get_pair(token0, token1);
-    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+fn add_admin() {
+    let address: Key = runtime::get_named_arg("address");
+    Factory::default().add_admin(address);
 }
 
-/// This function is to set the white list addresses which is only possible if the caller matched with owners's hash
+/// This function is to remove an admin which is only possible if the caller is an admin
 ///
 /// # Parameters
 ///
-/// * `white_list` - A Key that holds the Account Hash of fee_to_setter
+/// * `address` - A Key that holds the Account Hash of the admin to remove
 ///
 
 #[no_mangle]
-fn set_white_list() {
-    let white_list: Key = runtime::get_named_arg("white_list");
-    Factory::default().set_white_list(white_list, white_list);
+fn disable_admin() {
+    let address: Key = runtime::get_named_arg("address");
+    Factory::default().disable_admin(address);
 }
 
 /// This function is to fetch a Contract Package Hash
@@ -180,7 +658,6 @@ fn get_entry_points() -> EntryPoints {
         "constructor",
         vec![
             Parameter::new("fee_to_setter", Key::cl_type()),
-            Parameter::new("all_pairs", CLType::List(Box::new(Key::cl_type()))),
             Parameter::new("contract_hash", ContractHash::cl_type()),
             Parameter::new("package_hash", ContractPackageHash::cl_type()),
         ],
@@ -209,6 +686,28 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "create_stable_pair",
+        vec![
+            Parameter::new("token_a", Key::cl_type()),
+            Parameter::new("token_b", Key::cl_type()),
+            Parameter::new("pair_hash", Key::cl_type()),
+            Parameter::new("amp", U256::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_stable_pair",
+        vec![
+            Parameter::new("token0", Key::cl_type()),
+            Parameter::new("token1", Key::cl_type()),
+        ],
+        Key::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "fee_to",
         vec![],
@@ -225,11 +724,46 @@ fn get_entry_points() -> EntryPoints {
     ));
     entry_points.add_entry_point(EntryPoint::new(
         "all_pairs",
-        vec![],
+        vec![Parameter::new("index", U256::cl_type())],
+        Key::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "all_pairs_range",
+        vec![
+            Parameter::new("start", U256::cl_type()),
+            Parameter::new("count", U256::cl_type()),
+        ],
+        CLType::List(Box::new(Key::cl_type())),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "pairs_for_token",
+        vec![
+            Parameter::new("token", Key::cl_type()),
+            Parameter::new("start", U256::cl_type()),
+            Parameter::new("count", U256::cl_type()),
+        ],
         CLType::List(Box::new(Key::cl_type())),
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "pairs_for_token_length",
+        vec![Parameter::new("token", Key::cl_type())],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "pair_info",
+        vec![Parameter::new("pair", Key::cl_type())],
+        PairInfo::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "all_pairs_length",
         vec![],
@@ -251,6 +785,83 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "propose_fee_to_setter",
+        vec![Parameter::new("fee_to_setter", Key::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "accept_fee_to_setter",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "pending_fee_to_setter",
+        vec![],
+        Key::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "propose_owner",
+        vec![Parameter::new("owner", Key::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "accept_owner",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "owner",
+        vec![],
+        Key::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "pending_owner",
+        vec![],
+        Key::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "propose_transfer_delay",
+        vec![Parameter::new("transfer_delay", u64::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "accept_transfer_delay",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "pending_transfer_delay",
+        vec![],
+        Option::<u64>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "transfer_delay",
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "set_white_list",
         vec![Parameter::new("white_list", Key::cl_type())],
@@ -258,6 +869,170 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "grant_role",
+        vec![
+            Parameter::new("role", u8::cl_type()),
+            Parameter::new("account", Key::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "revoke_role",
+        vec![
+            Parameter::new("role", u8::cl_type()),
+            Parameter::new("account", Key::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "has_role",
+        vec![
+            Parameter::new("role", u8::cl_type()),
+            Parameter::new("account", Key::cl_type()),
+        ],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_role_member_count",
+        vec![Parameter::new("role", u8::cl_type())],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_role_member",
+        vec![
+            Parameter::new("role", u8::cl_type()),
+            Parameter::new("index", U256::cl_type()),
+        ],
+        Key::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_permissionless",
+        vec![Parameter::new("permissionless", bool::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "permissionless",
+        vec![],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "pause",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "unpause",
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_pair_paused",
+        vec![
+            Parameter::new("pair", Key::cl_type()),
+            Parameter::new("paused", bool::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "paused",
+        vec![],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "is_pair_paused",
+        vec![Parameter::new("pair", Key::cl_type())],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_token_denied",
+        vec![
+            Parameter::new("token", Key::cl_type()),
+            Parameter::new("denied", bool::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "is_token_denied",
+        vec![Parameter::new("token", Key::cl_type())],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "migrate",
+        vec![
+            Parameter::new("contract_hash", Key::cl_type()),
+            Parameter::new("legacy_white_lists", CLType::List(Box::new(Key::cl_type()))),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "contract_version",
+        vec![],
+        CLType::U32,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "update_pairs_factory_hash",
+        vec![
+            Parameter::new("start", U256::cl_type()),
+            Parameter::new("count", U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "index_token_pairs",
+        vec![Parameter::new("count", U256::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "add_admin",
+        vec![Parameter::new("address", Key::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "disable_admin",
+        vec![Parameter::new("address", Key::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "package_hash",
         vec![],
@@ -283,12 +1058,10 @@ fn call() {
             storage::add_contract_version(package_hash, get_entry_points(), Default::default());
 
         let fee_to_setter: Key = runtime::get_named_arg("fee_to_setter");
-        let all_pairs: Vec<Key> = Vec::new();
 
         // Prepare constructor args
         let constructor_args = runtime_args! {
             "fee_to_setter" => fee_to_setter,
-            "all_pairs" => all_pairs,
             "contract_hash" => contract_hash,
             "package_hash"=> package_hash
         };
@@ -340,9 +1113,25 @@ fn call() {
                                                             .unwrap()
                                                             .into();
 
+        // The named keys of the previous version, and with them all of the state, are carried over to the new version.
         let (contract_hash, _): (ContractHash, _) =
         storage::add_contract_version(package_hash, get_entry_points(), Default::default());
 
+        // Accounts whitelisted by version 0, granted Role::PairCreator by migrate
+        let legacy_white_lists: Vec<Key> = runtime::get_named_arg("legacy_white_lists");
+
+        // Record the new contract hash, update_pairs_factory_hash points the pairs to it
+        let _: () = runtime::call_contract(
+            contract_hash,
+            "migrate",
+            runtime_args! {
+                "contract_hash" => Key::from(contract_hash),
+                "legacy_white_lists" => legacy_white_lists
+            },
+        );
+
+        // The previous version stays enabled, as the factory, routers and other contracts hold its contract hash
+
         // update contract hash
         runtime::put_key(
             &format!("{}_contract_hash", contract_name),
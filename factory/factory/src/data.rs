@@ -1,46 +1,199 @@
-use alloc::vec::Vec;
-use casper_contract::unwrap_or_revert::UnwrapOrRevert;
-use casper_types::{ContractPackageHash, Key};
-use contract_utils::{get_key, set_key, Dict};
+use crate::alloc::string::ToString;
+use alloc::{format, string::String, vec::Vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLType, CLTyped, ContractPackageHash, Key, U128, U256,
+};
+use contract_utils::{get_key, key_to_str, set_key, Dict, ZERO_ACCOUNT, ZERO_HASH};
 
-pub const WHITELISTS_DICT: &str = "white_lists";
+pub const ROLES_DICT: &str = "roles";
+pub const ROLE_MEMBERS_DICT: &str = "role_members";
+pub const ROLE_MEMBER_COUNTS_DICT: &str = "role_member_counts";
+pub const PERMISSIONLESS: &str = "permissionless";
+pub const PAUSED: &str = "paused";
+pub const PAUSED_PAIRS_DICT: &str = "paused_pairs";
+pub const DENIED_TOKENS_DICT: &str = "denied_tokens";
 pub const PAIRS_DICT: &str = "pairs";
+pub const STABLE_PAIRS_DICT: &str = "stable_pairs";
 pub const SELF_CONTRACT_HASH: &str = "self_contract_hash";
 pub const FEE_TO: &str = "fee_to";
 pub const FEE_TO_SETTER: &str = "fee_to_setter";
-pub const ALL_PAIRS: &str = "all_pairs";
+pub const ALL_PAIRS_DICT: &str = "all_pairs_index";
+/// Named key of the `Vec` of pairs kept by version 0, moved to `ALL_PAIRS_DICT` by `migrate`.
+pub const LEGACY_ALL_PAIRS: &str = "all_pairs";
+/// Dictionary of the accounts allowed to create pairs by version 0, moved to
+/// `Role::PairCreator` by `migrate`.
+pub const LEGACY_WHITE_LISTS_DICT: &str = "white_lists";
+pub const ALL_PAIRS_LENGTH: &str = "all_pairs_length";
 pub const OWNER: &str = "owner";
+pub const PENDING_FEE_TO_SETTER: &str = "pending_fee_to_setter";
+pub const FEE_TO_SETTER_ETA: &str = "fee_to_setter_eta";
+pub const PENDING_OWNER: &str = "pending_owner";
+pub const OWNER_ETA: &str = "owner_eta";
+pub const TRANSFER_DELAY: &str = "transfer_delay";
+pub const PENDING_TRANSFER_DELAY: &str = "pending_transfer_delay";
+pub const TRANSFER_DELAY_ETA: &str = "transfer_delay_eta";
+pub const CONTRACT_VERSION: &str = "contract_version";
+pub const TOKEN_PAIRS_DICT: &str = "token_pairs";
+pub const TOKEN_PAIRS_LENGTH_DICT: &str = "token_pairs_length";
+/// Number of pairs created before the per-token index existed, added to it by
+/// `index_token_pairs`.
+pub const TOKEN_PAIRS_TO_INDEX: &str = "token_pairs_to_index";
+pub const TOKEN_PAIRS_INDEXED: &str = "token_pairs_indexed";
 pub const CONTRACT_PACKAGE_HASH: &str = "contract_package_hash";
 
-pub struct Whitelists {
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq)]
+pub enum Role {
+    PairCreator = 0,
+    FeeAdmin = 1,
+    Guardian = 2,
+}
+
+impl Role {
+    pub fn from_u8(role: u8) -> Option<Role> {
+        match role {
+            0 => Some(Role::PairCreator),
+            1 => Some(Role::FeeAdmin),
+            2 => Some(Role::Guardian),
+            _ => None,
+        }
+    }
+}
+
+/// Enumerable role membership. `roles` maps a (role, account) pair to the
+/// 1-based position of the account in `role_members`, so that a revoked
+/// account can be swapped with the last member and popped.
+pub struct RoleMembers {
+    roles: Dict,
+    members: Dict,
+    counts: Dict,
+}
+
+impl RoleMembers {
+    pub fn instance() -> RoleMembers {
+        RoleMembers {
+            roles: Dict::instance(ROLES_DICT),
+            members: Dict::instance(ROLE_MEMBERS_DICT),
+            counts: Dict::instance(ROLE_MEMBER_COUNTS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(ROLES_DICT);
+        Dict::init(ROLE_MEMBERS_DICT);
+        Dict::init(ROLE_MEMBER_COUNTS_DICT);
+    }
+
+    pub fn has_role(&self, role: Role, account: &Key) -> bool {
+        self.position(role, account) != U256::zero()
+    }
+
+    pub fn count(&self, role: Role) -> U256 {
+        self.counts
+            .get(&(role as u8).to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn member(&self, role: Role, index: U256) -> Key {
+        self.members.get(&member_key(role, index)).unwrap_or(ZERO_ACCOUNT)
+    }
+
+    /// Returns `false` if `account` already has `role`.
+    pub fn add(&self, role: Role, account: &Key) -> bool {
+        if self.has_role(role, account) {
+            return false;
+        }
+        let count: U256 = self.count(role);
+        self.members.set(&member_key(role, count), *account);
+        self.roles.set(&role_key(role, account), count + 1);
+        self.counts.set(&(role as u8).to_string(), count + 1);
+        true
+    }
+
+    /// Returns `false` if `account` does not have `role`.
+    pub fn remove(&self, role: Role, account: &Key) -> bool {
+        let position: U256 = self.position(role, account);
+        if position == U256::zero() {
+            return false;
+        }
+        let last: U256 = self.count(role) - 1;
+        let index: U256 = position - 1;
+        if index != last {
+            let last_member: Key = self.member(role, last);
+            self.members.set(&member_key(role, index), last_member);
+            self.roles.set(&role_key(role, &last_member), position);
+        }
+        self.members.remove::<Key>(&member_key(role, last));
+        self.roles.remove::<U256>(&role_key(role, account));
+        self.counts.set(&(role as u8).to_string(), last);
+        true
+    }
+
+    fn position(&self, role: Role, account: &Key) -> U256 {
+        self.roles
+            .get(&role_key(role, account))
+            .unwrap_or_default()
+    }
+}
+
+fn role_key(role: Role, account: &Key) -> String {
+    format!("{}_{}", role as u8, key_to_str(account))
+}
+
+fn member_key(role: Role, index: U256) -> String {
+    format!("{}_{}", role as u8, index)
+}
+
+pub struct PausedPairs {
     dict: Dict,
 }
 
-impl Whitelists {
-    pub fn instance() -> Whitelists {
-        Whitelists {
-            dict: Dict::instance(WHITELISTS_DICT),
+impl PausedPairs {
+    pub fn instance() -> PausedPairs {
+        PausedPairs {
+            dict: Dict::instance(PAUSED_PAIRS_DICT),
         }
     }
 
     pub fn init() {
-        Dict::init(WHITELISTS_DICT)
+        Dict::init(PAUSED_PAIRS_DICT)
+    }
+
+    pub fn get(&self, pair: &Key) -> bool {
+        self.dict.get_by_key(pair).unwrap_or_default()
     }
 
-    pub fn get(&self, owner: &Key) -> Key {
-        match self.dict.get_by_key(owner) {
-            Some(whitelist) => whitelist,
-            None => Key::from_formatted_str(
-                "account-hash-0000000000000000000000000000000000000000000000000000000000000000",
-            )
-            .unwrap(),
+    pub fn set(&self, pair: &Key, value: bool) {
+        self.dict.set_by_key(pair, value);
+    }
+}
+
+pub struct DeniedTokens {
+    dict: Dict,
+}
+
+impl DeniedTokens {
+    pub fn instance() -> DeniedTokens {
+        DeniedTokens {
+            dict: Dict::instance(DENIED_TOKENS_DICT),
         }
     }
 
-    pub fn set(&self, owner: &Key, value: Key) {
-        self.dict.set_by_key(owner, value);
+    pub fn init() {
+        Dict::init(DENIED_TOKENS_DICT)
+    }
+
+    pub fn get(&self, token: &Key) -> bool {
+        self.dict.get_by_key(token).unwrap_or_default()
+    }
+
+    pub fn set(&self, token: &Key, value: bool) {
+        self.dict.set_by_key(token, value);
     }
 }
+
 pub struct Pairs {
     dict: Dict,
 }
@@ -57,20 +210,169 @@ impl Pairs {
     }
 
     pub fn get(&self, token0: &Key, token1: &Key) -> Key {
-        match self.dict.get_by_keys((token0, token1)) {
-            Some(pair) => pair,
-            None => Key::from_formatted_str(
-                "hash-0000000000000000000000000000000000000000000000000000000000000000",
-            )
-            .unwrap(),
+        self.dict.get_by_keys((token0, token1)).unwrap_or(ZERO_HASH)
+    }
+
+    pub fn set(&self, token0: &Key, token1: &Key, value: Key) {
+        self.dict.set_by_keys((token0, token1), value);
+    }
+}
+
+/// Stable pairs by their tokens, kept apart from `Pairs` so that a token pair can have
+/// both a stable and an x * y = k pair.
+pub struct StablePairs {
+    dict: Dict,
+}
+
+impl StablePairs {
+    pub fn instance() -> StablePairs {
+        StablePairs {
+            dict: Dict::instance(STABLE_PAIRS_DICT),
         }
     }
 
+    pub fn init() {
+        Dict::init(STABLE_PAIRS_DICT)
+    }
+
+    pub fn get(&self, token0: &Key, token1: &Key) -> Key {
+        self.dict.get_by_keys((token0, token1)).unwrap_or(ZERO_HASH)
+    }
+
     pub fn set(&self, token0: &Key, token1: &Key, value: Key) {
         self.dict.set_by_keys((token0, token1), value);
     }
 }
 
+pub struct AllPairs {
+    dict: Dict,
+}
+
+impl AllPairs {
+    pub fn instance() -> AllPairs {
+        AllPairs {
+            dict: Dict::instance(ALL_PAIRS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(ALL_PAIRS_DICT)
+    }
+
+    pub fn get(&self, index: &U256) -> Key {
+        self.dict.get(&index.to_string()).unwrap_or(ZERO_HASH)
+    }
+
+    pub fn set(&self, index: &U256, value: Key) {
+        self.dict.set(&index.to_string(), value);
+    }
+}
+
+/// Pairs indexed by each of their tokens, in creation order.
+pub struct TokenPairs {
+    pairs: Dict,
+    lengths: Dict,
+}
+
+impl TokenPairs {
+    pub fn instance() -> TokenPairs {
+        TokenPairs {
+            pairs: Dict::instance(TOKEN_PAIRS_DICT),
+            lengths: Dict::instance(TOKEN_PAIRS_LENGTH_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(TOKEN_PAIRS_DICT);
+        Dict::init(TOKEN_PAIRS_LENGTH_DICT);
+    }
+
+    pub fn length(&self, token: &Key) -> U256 {
+        self.lengths.get_by_key(token).unwrap_or_default()
+    }
+
+    pub fn get(&self, token: &Key, index: U256) -> Key {
+        self.pairs.get(&token_pair_key(token, index)).unwrap_or(ZERO_HASH)
+    }
+
+    pub fn push(&self, token: &Key, pair: Key) {
+        let length: U256 = self.length(token);
+        self.pairs.set(&token_pair_key(token, length), pair);
+        self.lengths.set_by_key(token, length + 1);
+    }
+}
+
+fn token_pair_key(token: &Key, index: U256) -> String {
+    format!("{}_{}", key_to_str(token), index)
+}
+
+/// Everything a router needs to know about a pair, returned by `pair_info`.
+pub struct PairInfo {
+    pub token0: Key,
+    pub token1: Key,
+    pub reserve0: U128,
+    pub reserve1: U128,
+    pub block_timestamp_last: u64,
+    pub total_supply: U256,
+    pub treasury_fee: U256,
+}
+
+/// Serialized as `((token0, token1), (reserve0, reserve1, block_timestamp_last),
+/// (total_supply, treasury_fee))`, so clients can read it as that tuple.
+impl CLTyped for PairInfo {
+    fn cl_type() -> CLType {
+        <((Key, Key), (U128, U128, u64), (U256, U256))>::cl_type()
+    }
+}
+
+impl ToBytes for PairInfo {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result: Vec<u8> = Vec::new();
+        result.append(&mut self.token0.to_bytes()?);
+        result.append(&mut self.token1.to_bytes()?);
+        result.append(&mut self.reserve0.to_bytes()?);
+        result.append(&mut self.reserve1.to_bytes()?);
+        result.append(&mut self.block_timestamp_last.to_bytes()?);
+        result.append(&mut self.total_supply.to_bytes()?);
+        result.append(&mut self.treasury_fee.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.token0.serialized_length()
+            + self.token1.serialized_length()
+            + self.reserve0.serialized_length()
+            + self.reserve1.serialized_length()
+            + self.block_timestamp_last.serialized_length()
+            + self.total_supply.serialized_length()
+            + self.treasury_fee.serialized_length()
+    }
+}
+
+impl FromBytes for PairInfo {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (token0, bytes) = Key::from_bytes(bytes)?;
+        let (token1, bytes) = Key::from_bytes(bytes)?;
+        let (reserve0, bytes) = U128::from_bytes(bytes)?;
+        let (reserve1, bytes) = U128::from_bytes(bytes)?;
+        let (block_timestamp_last, bytes) = u64::from_bytes(bytes)?;
+        let (total_supply, bytes) = U256::from_bytes(bytes)?;
+        let (treasury_fee, bytes) = U256::from_bytes(bytes)?;
+        Ok((
+            PairInfo {
+                token0,
+                token1,
+                reserve0,
+                reserve1,
+                block_timestamp_last,
+                total_supply,
+                treasury_fee,
+            },
+            bytes,
+        ))
+    }
+}
+
 pub fn set_hash(contract_hash: Key) {
     set_key(SELF_CONTRACT_HASH, contract_hash);
 }
@@ -84,13 +386,7 @@ pub fn set_fee_to(fee_to: Key) {
 }
 
 pub fn get_fee_to() -> Key {
-    match get_key(FEE_TO) {
-        Some(fee_to) => fee_to,
-        None => Key::from_formatted_str(
-            "account-hash-0000000000000000000000000000000000000000000000000000000000000000",
-        )
-        .unwrap(),
-    }
+    get_key(FEE_TO).unwrap_or(ZERO_ACCOUNT)
 }
 
 pub fn set_fee_to_setter(fee_to_setter: Key) {
@@ -98,21 +394,31 @@ pub fn set_fee_to_setter(fee_to_setter: Key) {
 }
 
 pub fn get_fee_to_setter() -> Key {
-    match get_key(FEE_TO_SETTER) {
-        Some(fee_to_setter) => fee_to_setter,
-        None => Key::from_formatted_str(
-            "account-hash-0000000000000000000000000000000000000000000000000000000000000000",
-        )
-        .unwrap(),
-    }
+    get_key(FEE_TO_SETTER).unwrap_or(ZERO_ACCOUNT)
+}
+
+pub fn set_all_pairs_length(all_pairs_length: U256) {
+    set_key(ALL_PAIRS_LENGTH, all_pairs_length);
+}
+
+pub fn get_all_pairs_length() -> U256 {
+    get_key(ALL_PAIRS_LENGTH).unwrap_or_default()
+}
+
+pub fn set_permissionless(permissionless: bool) {
+    set_key(PERMISSIONLESS, permissionless);
+}
+
+pub fn get_permissionless() -> bool {
+    get_key(PERMISSIONLESS).unwrap_or_default()
 }
 
-pub fn set_all_pairs(all_pairs: Vec<Key>) {
-    set_key(ALL_PAIRS, all_pairs);
+pub fn set_paused(paused: bool) {
+    set_key(PAUSED, paused);
 }
 
-pub fn get_all_pairs() -> Vec<Key> {
-    get_key(ALL_PAIRS).unwrap_or_revert()
+pub fn get_paused() -> bool {
+    get_key(PAUSED).unwrap_or_default()
 }
 
 pub fn set_package_hash(package_hash: ContractPackageHash) {
@@ -128,11 +434,101 @@ pub fn set_owner(owner: Key) {
 }
 
 pub fn get_owner() -> Key {
-    match get_key(OWNER) {
-        Some(owner) => owner,
-        None => Key::from_formatted_str(
-            "account-hash-0000000000000000000000000000000000000000000000000000000000000000",
-        )
-        .unwrap(),
+    get_key(OWNER).unwrap_or(ZERO_ACCOUNT)
+}
+
+pub fn set_pending_fee_to_setter(pending_fee_to_setter: Key) {
+    set_key(PENDING_FEE_TO_SETTER, pending_fee_to_setter);
+}
+
+pub fn get_pending_fee_to_setter() -> Key {
+    get_key(PENDING_FEE_TO_SETTER).unwrap_or(ZERO_ACCOUNT)
+}
+
+pub fn set_fee_to_setter_eta(eta: u64) {
+    set_key(FEE_TO_SETTER_ETA, eta);
+}
+
+pub fn get_fee_to_setter_eta() -> u64 {
+    get_key(FEE_TO_SETTER_ETA).unwrap_or_default()
+}
+
+pub fn set_pending_owner(pending_owner: Key) {
+    set_key(PENDING_OWNER, pending_owner);
+}
+
+pub fn get_pending_owner() -> Key {
+    get_key(PENDING_OWNER).unwrap_or(ZERO_ACCOUNT)
+}
+
+pub fn set_owner_eta(eta: u64) {
+    set_key(OWNER_ETA, eta);
+}
+
+pub fn get_owner_eta() -> u64 {
+    get_key(OWNER_ETA).unwrap_or_default()
+}
+
+pub fn set_transfer_delay(transfer_delay: u64) {
+    set_key(TRANSFER_DELAY, transfer_delay);
+}
+
+pub fn get_transfer_delay() -> u64 {
+    get_key(TRANSFER_DELAY).unwrap_or_default()
+}
+
+pub fn set_pending_transfer_delay(pending_transfer_delay: Option<u64>) {
+    set_key(PENDING_TRANSFER_DELAY, pending_transfer_delay);
+}
+
+pub fn get_pending_transfer_delay() -> Option<u64> {
+    get_key(PENDING_TRANSFER_DELAY).unwrap_or_default()
+}
+
+pub fn set_transfer_delay_eta(eta: u64) {
+    set_key(TRANSFER_DELAY_ETA, eta);
+}
+
+pub fn get_transfer_delay_eta() -> u64 {
+    get_key(TRANSFER_DELAY_ETA).unwrap_or_default()
+}
+
+pub fn set_token_pairs_to_index(token_pairs_to_index: U256) {
+    set_key(TOKEN_PAIRS_TO_INDEX, token_pairs_to_index);
+}
+
+pub fn get_token_pairs_to_index() -> U256 {
+    get_key(TOKEN_PAIRS_TO_INDEX).unwrap_or_default()
+}
+
+pub fn set_token_pairs_indexed(token_pairs_indexed: U256) {
+    set_key(TOKEN_PAIRS_INDEXED, token_pairs_indexed);
+}
+
+pub fn get_token_pairs_indexed() -> U256 {
+    get_key(TOKEN_PAIRS_INDEXED).unwrap_or_default()
+}
+
+pub fn get_legacy_all_pairs() -> Vec<Key> {
+    get_key(LEGACY_ALL_PAIRS).unwrap_or_default()
+}
+
+pub fn remove_legacy_all_pairs() {
+    runtime::remove_key(LEGACY_ALL_PAIRS);
+}
+
+pub fn is_legacy_white_listed(account: &Key) -> bool {
+    let white_list: Option<Key> = Dict::instance(LEGACY_WHITE_LISTS_DICT).get_by_key(account);
+    match white_list {
+        Some(white_list) => white_list != ZERO_ACCOUNT,
+        None => false,
     }
 }
+
+pub fn set_contract_version(contract_version: u32) {
+    set_key(CONTRACT_VERSION, contract_version);
+}
+
+pub fn get_contract_version() -> u32 {
+    get_key(CONTRACT_VERSION).unwrap_or_default()
+}
@@ -1,13 +1,20 @@
 use crate::alloc::string::ToString;
-use crate::data::{self, get_all_pairs, Pairs, Whitelists};
+use crate::data::{
+    self, AllPairs, DeniedTokens, PairInfo, Pairs, PausedPairs, Role, RoleMembers, StablePairs,
+    TokenPairs,
+};
 use alloc::collections::BTreeMap;
 use alloc::{string::String, vec::Vec};
 use casper_contract::contract_api::runtime;
 use casper_contract::contract_api::storage;
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
 use casper_types::{
-    runtime_args, ApiError, ContractHash, ContractPackageHash, Key, RuntimeArgs, URef, U256,
+    runtime_args, ContractHash, ContractPackageHash, Key, RuntimeArgs, URef, U128, U256,
+};
+use contract_utils::{
+    is_zero, key_to_contract_hash, AdminControl, ContractContext, ContractStorage, ZERO_ACCOUNT,
 };
-use contract_utils::{ContractContext, ContractStorage};
+use errors::FactoryError;
 
 pub enum FACTORYEvent {
     PairCreated {
@@ -16,6 +23,72 @@ pub enum FACTORYEvent {
         pair: Key,
         all_pairs_length: U256,
     },
+    StablePairCreated {
+        token0: Key,
+        token1: Key,
+        pair: Key,
+        amp: U256,
+        all_pairs_length: U256,
+    },
+    RoleGranted {
+        role: u8,
+        account: Key,
+        sender: Key,
+    },
+    RoleRevoked {
+        role: u8,
+        account: Key,
+        sender: Key,
+    },
+    PermissionlessUpdated {
+        permissionless: bool,
+        sender: Key,
+    },
+    FeeToSetterProposed {
+        fee_to_setter: Key,
+        pending_fee_to_setter: Key,
+        eta: u64,
+    },
+    FeeToSetterAccepted {
+        previous_fee_to_setter: Key,
+        fee_to_setter: Key,
+    },
+    OwnerProposed {
+        owner: Key,
+        pending_owner: Key,
+        eta: u64,
+    },
+    OwnerAccepted {
+        previous_owner: Key,
+        owner: Key,
+    },
+    TransferDelayProposed {
+        transfer_delay: u64,
+        eta: u64,
+    },
+    TransferDelayUpdated {
+        transfer_delay: u64,
+    },
+    Paused {
+        sender: Key,
+    },
+    Unpaused {
+        sender: Key,
+    },
+    PairPaused {
+        pair: Key,
+        paused: bool,
+        sender: Key,
+    },
+    Migrated {
+        contract_hash: Key,
+        contract_version: u32,
+    },
+    TokenDenied {
+        token: Key,
+        denied: bool,
+        sender: Key,
+    },
 }
 impl FACTORYEvent {
     pub fn type_name(&self) -> String {
@@ -26,61 +99,119 @@ impl FACTORYEvent {
                 pair: _,
                 all_pairs_length: _,
             } => "pair_created",
+            FACTORYEvent::StablePairCreated {
+                token0: _,
+                token1: _,
+                pair: _,
+                amp: _,
+                all_pairs_length: _,
+            } => "stable_pair_created",
+            FACTORYEvent::RoleGranted {
+                role: _,
+                account: _,
+                sender: _,
+            } => "role_granted",
+            FACTORYEvent::RoleRevoked {
+                role: _,
+                account: _,
+                sender: _,
+            } => "role_revoked",
+            FACTORYEvent::PermissionlessUpdated {
+                permissionless: _,
+                sender: _,
+            } => "permissionless_updated",
+            FACTORYEvent::FeeToSetterProposed {
+                fee_to_setter: _,
+                pending_fee_to_setter: _,
+                eta: _,
+            } => "fee_to_setter_proposed",
+            FACTORYEvent::FeeToSetterAccepted {
+                previous_fee_to_setter: _,
+                fee_to_setter: _,
+            } => "fee_to_setter_accepted",
+            FACTORYEvent::OwnerProposed {
+                owner: _,
+                pending_owner: _,
+                eta: _,
+            } => "owner_proposed",
+            FACTORYEvent::OwnerAccepted {
+                previous_owner: _,
+                owner: _,
+            } => "owner_accepted",
+            FACTORYEvent::TransferDelayProposed {
+                transfer_delay: _,
+                eta: _,
+            } => "transfer_delay_proposed",
+            FACTORYEvent::TransferDelayUpdated { transfer_delay: _ } => "transfer_delay_updated",
+            FACTORYEvent::Paused { sender: _ } => "paused",
+            FACTORYEvent::Unpaused { sender: _ } => "unpaused",
+            FACTORYEvent::PairPaused {
+                pair: _,
+                paused: _,
+                sender: _,
+            } => "pair_paused",
+            FACTORYEvent::Migrated {
+                contract_hash: _,
+                contract_version: _,
+            } => "migrated",
+            FACTORYEvent::TokenDenied {
+                token: _,
+                denied: _,
+                sender: _,
+            } => "token_denied",
         }
         .to_string()
     }
 }
-#[repr(u16)]
-pub enum Error {
-    UniswapV2FactoryZeroAddress = 6,
-    UniswapV2FactoryPairExists = 7,
-    UniswapV2Forbidden = 8,
-    UniswapV2FactoryIdenticalAddresses = 9,
-    UniswapV2FactoryNotInWhiteList = 10,
-    UniswapV2FactoryNotOwner = 11,
-}
 
-impl From<Error> for ApiError {
-    fn from(error: Error) -> ApiError {
-        ApiError::User(error as u16)
-    }
-}
+/// Version of the factory code, stored in `contract_version` by the constructor and by `migrate`.
+pub const VERSION: u32 = 4;
 
-pub trait FACTORY<Storage: ContractStorage>: ContractContext<Storage> {
-    fn init(
-        &mut self,
-        fee_to_setter: Key,
-        all_pairs: Vec<Key>,
-        contract_hash: Key,
-        package_hash: ContractPackageHash,
-    ) {
+/// Most decimals a token of a pair may have. Pairs scale amounts to 18 decimals with powers
+/// of ten, which do not fit in a U256 for much more than this.
+pub const MAX_TOKEN_DECIMALS: u8 = 36;
+
+pub trait FACTORY<Storage: ContractStorage>:
+    ContractContext<Storage> + AdminControl<Storage>
+{
+    fn init(&mut self, fee_to_setter: Key, contract_hash: Key, package_hash: ContractPackageHash) {
         data::set_fee_to_setter(fee_to_setter);
         data::set_owner(self.get_caller());
-        data::set_all_pairs(all_pairs);
+        data::set_all_pairs_length(0.into());
         data::set_hash(contract_hash);
         data::set_package_hash(package_hash);
+        data::set_contract_version(VERSION);
         Pairs::init();
-        Whitelists::init();
+        AllPairs::init();
+        RoleMembers::init();
+        PausedPairs::init();
+        TokenPairs::init();
+        DeniedTokens::init();
+        StablePairs::init();
+        AdminControl::init(self);
+        self.add_admin_without_checked(self.get_caller());
     }
 
     fn create_pair(&mut self, token_a: Key, token_b: Key, pair_hash: Key) {
-        let white_lists: Whitelists = Whitelists::instance();
-        let white_list_user: Key = white_lists.get(&self.get_caller());
-        if white_list_user
-            != Key::from_formatted_str(
-                "account-hash-0000000000000000000000000000000000000000000000000000000000000000",
-            )
-            .unwrap()
+        self.add_pair(token_a, token_b, pair_hash, None);
+    }
+
+    /// Same as `create_pair` for a pair that trades on the StableSwap invariant with the
+    /// amplification coefficient `amp`. Stable pairs are registered apart from the x * y = k
+    /// pairs, so both kinds can exist for the same tokens.
+    fn create_stable_pair(&mut self, token_a: Key, token_b: Key, pair_hash: Key, amp: U256) {
+        self.add_pair(token_a, token_b, pair_hash, Some(amp));
+    }
+
+    fn add_pair(&mut self, token_a: Key, token_b: Key, pair_hash: Key, amp: Option<U256>) {
+        if data::get_permissionless()
+            || RoleMembers::instance().has_role(Role::PairCreator, &self.get_caller())
         {
             if token_a == token_b {
-                runtime::revert(Error::UniswapV2FactoryIdenticalAddresses);
+                runtime::revert(FactoryError::UniswapV2FactoryIdenticalAddresses);
             }
             let token0: Key;
             let token1: Key;
-            let address_0: Key = Key::from_formatted_str(
-                "hash-0000000000000000000000000000000000000000000000000000000000000000",
-            )
-            .unwrap();
             if token_a < token_b {
                 token0 = token_a;
                 token1 = token_b;
@@ -88,43 +219,72 @@ pub trait FACTORY<Storage: ContractStorage>: ContractContext<Storage> {
                 token0 = token_b;
                 token1 = token_a;
             }
-            // in before 0 address was hash-0000000000000000000000000000000000000000000000000000000000000000
-            if token0 == address_0 {
-                runtime::revert(Error::UniswapV2FactoryZeroAddress);
+            if is_zero(&token0) || is_zero(&token1) {
+                runtime::revert(FactoryError::UniswapV2FactoryZeroAddress);
             }
-            let pair_0_1_key: Key = self.get_pair(token0, token1);
-            let pair_1_0_key: Key = self.get_pair(token1, token0);
-            if pair_0_1_key != address_0 {
-                runtime::revert(Error::UniswapV2FactoryPairExists);
+            self.validate_token(token0);
+            self.validate_token(token1);
+            let (pair_0_1_key, pair_1_0_key): (Key, Key) = match amp {
+                Some(_) => (
+                    self.get_stable_pair(token0, token1),
+                    self.get_stable_pair(token1, token0),
+                ),
+                None => (self.get_pair(token0, token1), self.get_pair(token1, token0)),
+            };
+            if !is_zero(&pair_0_1_key) {
+                runtime::revert(FactoryError::UniswapV2FactoryPairExists);
             }
-            if pair_1_0_key != address_0 {
-                runtime::revert(Error::UniswapV2FactoryPairExists);
+            if !is_zero(&pair_1_0_key) {
+                runtime::revert(FactoryError::UniswapV2FactoryPairExists);
             }
-            //convert Key to ContractHash
-            let pair_hash_add_array = match pair_hash {
-                Key::Hash(package) => package,
-                _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-            };
-            let pair_contract_hash = ContractHash::new(pair_hash_add_array);
-            let _ret: () = runtime::call_contract(
-                pair_contract_hash,
-                "initialize",
-                runtime_args! {"token0" => token0, "token1" => token1, "factory_hash" => data::get_hash() },
-            );
+            let pair_contract_hash: ContractHash =
+                key_to_contract_hash(pair_hash).unwrap_or_revert();
             // handling the pair creation by updating the storage
-            self.set_pair(token0, token1, pair_hash);
-            self.set_pair(token1, token0, pair_hash);
-            let mut pairs: Vec<Key> = get_all_pairs();
-            pairs.push(pair_hash);
-            self.set_all_pairs(pairs);
-            self.emit(&FACTORYEvent::PairCreated {
-                token0: token0,
-                token1: token1,
-                pair: pair_hash,
-                all_pairs_length: (get_all_pairs().len()).into(),
-            });
+            match amp {
+                Some(amp) => {
+                    let _ret: () = runtime::call_contract(
+                        pair_contract_hash,
+                        "initialize_stable",
+                        runtime_args! {"token0" => token0, "token1" => token1, "factory_hash" => data::get_hash(), "amp" => amp },
+                    );
+                    let stable_pairs: StablePairs = StablePairs::instance();
+                    stable_pairs.set(&token0, &token1, pair_hash);
+                    stable_pairs.set(&token1, &token0, pair_hash);
+                }
+                None => {
+                    let _ret: () = runtime::call_contract(
+                        pair_contract_hash,
+                        "initialize",
+                        runtime_args! {"token0" => token0, "token1" => token1, "factory_hash" => data::get_hash() },
+                    );
+                    self.set_pair(token0, token1, pair_hash);
+                    self.set_pair(token1, token0, pair_hash);
+                }
+            }
+            let all_pairs_length: U256 = self.all_pairs_length();
+            AllPairs::instance().set(&all_pairs_length, pair_hash);
+            let token_pairs: TokenPairs = TokenPairs::instance();
+            token_pairs.push(&token0, pair_hash);
+            token_pairs.push(&token1, pair_hash);
+            let all_pairs_length: U256 = all_pairs_length + 1;
+            data::set_all_pairs_length(all_pairs_length);
+            match amp {
+                Some(amp) => self.emit(&FACTORYEvent::StablePairCreated {
+                    token0: token0,
+                    token1: token1,
+                    pair: pair_hash,
+                    amp: amp,
+                    all_pairs_length: all_pairs_length,
+                }),
+                None => self.emit(&FACTORYEvent::PairCreated {
+                    token0: token0,
+                    token1: token1,
+                    pair: pair_hash,
+                    all_pairs_length: all_pairs_length,
+                }),
+            }
         } else {
-            runtime::revert(Error::UniswapV2FactoryNotInWhiteList);
+            runtime::revert(FactoryError::UniswapV2FactoryNotInWhiteList);
         }
     }
 
@@ -136,9 +296,62 @@ pub trait FACTORY<Storage: ContractStorage>: ContractContext<Storage> {
         Pairs::instance().set(&token0, &token1, value);
     }
 
+    fn get_stable_pair(&mut self, token0: Key, token1: Key) -> Key {
+        StablePairs::instance().get(&token0, &token1)
+    }
+
+    fn pairs_for_token_length(&mut self, token: Key) -> U256 {
+        TokenPairs::instance().length(&token)
+    }
+
+    /// Returns at most `count` pairs that contain `token`, starting from index `start`.
+    fn pairs_for_token(&mut self, token: Key, start: U256, count: U256) -> Vec<Key> {
+        let token_pairs: TokenPairs = TokenPairs::instance();
+        let length: U256 = token_pairs.length(&token);
+        let mut pairs: Vec<Key> = Vec::new();
+        if start >= length {
+            return pairs;
+        }
+        let end: U256 = if count > length - start {
+            length
+        } else {
+            start + count
+        };
+        let mut index: U256 = start;
+        while index < end {
+            pairs.push(token_pairs.get(&token, index));
+            index = index + 1;
+        }
+        pairs
+    }
+
+    fn pair_info(&mut self, pair: Key) -> PairInfo {
+        let pair_contract_hash: ContractHash = key_to_contract_hash(pair).unwrap_or_revert();
+        let token0: Key = runtime::call_contract(pair_contract_hash, "token0", runtime_args! {});
+        let token1: Key = runtime::call_contract(pair_contract_hash, "token1", runtime_args! {});
+        let (reserve0, reserve1, block_timestamp_last): (U128, U128, u64) =
+            runtime::call_contract(pair_contract_hash, "get_reserves", runtime_args! {});
+        let total_supply: U256 =
+            runtime::call_contract(pair_contract_hash, "total_supply", runtime_args! {});
+        let treasury_fee: U256 =
+            runtime::call_contract(pair_contract_hash, "treasury_fee", runtime_args! {});
+        PairInfo {
+            token0,
+            token1,
+            reserve0,
+            reserve1,
+            block_timestamp_last,
+            total_supply,
+            treasury_fee,
+        }
+    }
+
     fn set_fee_to(&mut self, fee_to: Key) {
-        if self.get_caller() != self.get_fee_to_setter() {
-            runtime::revert(Error::UniswapV2Forbidden);
+        let caller: Key = self.get_caller();
+        if caller != self.get_fee_to_setter()
+            && !RoleMembers::instance().has_role(Role::FeeAdmin, &caller)
+        {
+            runtime::revert(FactoryError::UniswapV2Forbidden);
         }
         data::set_fee_to(fee_to);
     }
@@ -147,32 +360,421 @@ pub trait FACTORY<Storage: ContractStorage>: ContractContext<Storage> {
         data::get_fee_to()
     }
 
+    /// Kept for existing integrations, only proposes `fee_to_setter` which then
+    /// has to call `accept_fee_to_setter`.
     fn set_fee_to_setter(&mut self, fee_to_setter: Key) {
-        if self.get_caller() != self.get_fee_to_setter() {
-            runtime::revert(Error::UniswapV2Forbidden);
+        self.propose_fee_to_setter(fee_to_setter);
+    }
+
+    fn propose_fee_to_setter(&mut self, pending_fee_to_setter: Key) {
+        let fee_to_setter: Key = self.get_fee_to_setter();
+        if self.get_caller() != fee_to_setter {
+            runtime::revert(FactoryError::UniswapV2Forbidden);
+        }
+        let eta: u64 = self.transfer_eta();
+        data::set_pending_fee_to_setter(pending_fee_to_setter);
+        data::set_fee_to_setter_eta(eta);
+        self.emit(&FACTORYEvent::FeeToSetterProposed {
+            fee_to_setter: fee_to_setter,
+            pending_fee_to_setter: pending_fee_to_setter,
+            eta: eta,
+        });
+    }
+
+    fn accept_fee_to_setter(&mut self) {
+        let pending_fee_to_setter: Key = data::get_pending_fee_to_setter();
+        if self.get_caller() != pending_fee_to_setter {
+            runtime::revert(FactoryError::UniswapV2FactoryNotPending);
+        }
+        self.assert_eta_passed(data::get_fee_to_setter_eta());
+        let previous_fee_to_setter: Key = self.get_fee_to_setter();
+        data::set_fee_to_setter(pending_fee_to_setter);
+        data::set_pending_fee_to_setter(ZERO_ACCOUNT);
+        self.emit(&FACTORYEvent::FeeToSetterAccepted {
+            previous_fee_to_setter: previous_fee_to_setter,
+            fee_to_setter: pending_fee_to_setter,
+        });
+    }
+
+    fn get_pending_fee_to_setter(&mut self) -> Key {
+        data::get_pending_fee_to_setter()
+    }
+
+    fn propose_owner(&mut self, pending_owner: Key) {
+        let owner: Key = data::get_owner();
+        if self.get_caller() != owner {
+            runtime::revert(FactoryError::UniswapV2FactoryNotOwner);
+        }
+        let eta: u64 = self.transfer_eta();
+        data::set_pending_owner(pending_owner);
+        data::set_owner_eta(eta);
+        self.emit(&FACTORYEvent::OwnerProposed {
+            owner: owner,
+            pending_owner: pending_owner,
+            eta: eta,
+        });
+    }
+
+    /// The new owner also takes over the admin role of the previous owner.
+    fn accept_owner(&mut self) {
+        let pending_owner: Key = data::get_pending_owner();
+        if self.get_caller() != pending_owner {
+            runtime::revert(FactoryError::UniswapV2FactoryNotPending);
+        }
+        self.assert_eta_passed(data::get_owner_eta());
+        let previous_owner: Key = data::get_owner();
+        data::set_owner(pending_owner);
+        data::set_pending_owner(ZERO_ACCOUNT);
+        if previous_owner != pending_owner {
+            self.add_admin_without_checked(pending_owner);
+            self.disable_admin(previous_owner);
+        }
+        self.emit(&FACTORYEvent::OwnerAccepted {
+            previous_owner: previous_owner,
+            owner: pending_owner,
+        });
+    }
+
+    fn get_owner(&mut self) -> Key {
+        data::get_owner()
+    }
+
+    fn get_pending_owner(&mut self) -> Key {
+        data::get_pending_owner()
+    }
+
+    /// Proposes a new minimum time in milliseconds between a proposal and its
+    /// acceptance. The change itself waits for the current delay, so the delay
+    /// can't be lowered to skip the wait of a proposal.
+    fn propose_transfer_delay(&mut self, transfer_delay: u64) {
+        if self.get_caller() != data::get_owner() {
+            runtime::revert(FactoryError::UniswapV2FactoryNotOwner);
+        }
+        let eta: u64 = self.transfer_eta();
+        data::set_pending_transfer_delay(Some(transfer_delay));
+        data::set_transfer_delay_eta(eta);
+        self.emit(&FACTORYEvent::TransferDelayProposed {
+            transfer_delay: transfer_delay,
+            eta: eta,
+        });
+    }
+
+    fn accept_transfer_delay(&mut self) {
+        if self.get_caller() != data::get_owner() {
+            runtime::revert(FactoryError::UniswapV2FactoryNotOwner);
+        }
+        let transfer_delay: u64 = data::get_pending_transfer_delay()
+            .unwrap_or_revert_with(FactoryError::UniswapV2FactoryNotPending);
+        self.assert_eta_passed(data::get_transfer_delay_eta());
+        data::set_transfer_delay(transfer_delay);
+        data::set_pending_transfer_delay(None);
+        self.emit(&FACTORYEvent::TransferDelayUpdated {
+            transfer_delay: transfer_delay,
+        });
+    }
+
+    fn get_pending_transfer_delay(&mut self) -> Option<u64> {
+        data::get_pending_transfer_delay()
+    }
+
+    fn get_transfer_delay(&mut self) -> u64 {
+        data::get_transfer_delay()
+    }
+
+    /// Pauses `swap`, `mint`, `skim` and `flash_loan` on every pair. `burn` stays
+    /// allowed so that liquidity providers can always exit.
+    fn pause(&mut self) {
+        self.assert_caller_is_guardian();
+        data::set_paused(true);
+        self.emit(&FACTORYEvent::Paused {
+            sender: self.get_caller(),
+        });
+    }
+
+    fn unpause(&mut self) {
+        self.assert_caller_is_guardian();
+        data::set_paused(false);
+        self.emit(&FACTORYEvent::Unpaused {
+            sender: self.get_caller(),
+        });
+    }
+
+    fn set_pair_paused(&mut self, pair: Key, paused: bool) {
+        self.assert_caller_is_guardian();
+        PausedPairs::instance().set(&pair, paused);
+        self.emit(&FACTORYEvent::PairPaused {
+            pair: pair,
+            paused: paused,
+            sender: self.get_caller(),
+        });
+    }
+
+    fn get_paused(&mut self) -> bool {
+        data::get_paused()
+    }
+
+    /// Returns `true` if the protocol is paused or `pair` is paused on its own.
+    fn is_pair_paused(&mut self, pair: Key) -> bool {
+        data::get_paused() || PausedPairs::instance().get(&pair)
+    }
+
+    fn set_token_denied(&mut self, token: Key, denied: bool) {
+        self.assert_caller_is_admin();
+        DeniedTokens::instance().set(&token, denied);
+        self.emit(&FACTORYEvent::TokenDenied {
+            token: token,
+            denied: denied,
+            sender: self.get_caller(),
+        });
+    }
+
+    fn is_token_denied(&mut self, token: Key) -> bool {
+        DeniedTokens::instance().get(&token)
+    }
+
+    /// Reverts unless `token` is a contract hash that is not on the denylist and
+    /// answers `total_supply` and `balance_of` with a U256 and `decimals` with a u8 of
+    /// at most `MAX_TOKEN_DECIMALS`, which pairs read when they are initialized. Casper gives a contract no way to
+    /// catch a failed call, so a contract without these entry points makes the host
+    /// revert the whole deploy on the probe call, with the engine's error instead of a
+    /// factory error.
+    fn validate_token(&mut self, token: Key) {
+        let token_hash: ContractHash = key_to_contract_hash(token)
+            .unwrap_or_revert_with(FactoryError::UniswapV2FactoryInvalidToken);
+        if DeniedTokens::instance().get(&token) {
+            runtime::revert(FactoryError::UniswapV2FactoryTokenDenied);
+        }
+        let _total_supply: U256 =
+            runtime::call_contract(token_hash, "total_supply", runtime_args! {});
+        let _balance: U256 = runtime::call_contract(
+            token_hash,
+            "balance_of",
+            runtime_args! {"owner" => Key::from(data::get_package_hash())},
+        );
+        let decimals: u8 = runtime::call_contract(token_hash, "decimals", runtime_args! {});
+        if decimals > MAX_TOKEN_DECIMALS {
+            runtime::revert(FactoryError::UniswapV2FactoryInvalidDecimals);
+        }
+    }
+
+    /// Called by the upgrade deploy with the hash of the new contract version.
+    /// Named keys and dictionaries are carried over to the new version, so this
+    /// only records the new hash and sets up what the new code expects in addition.
+    /// `legacy_white_lists` lists the accounts whitelisted by version 0, as its
+    /// dictionary cannot be iterated, and is ignored for later versions.
+    fn migrate(&mut self, contract_hash: Key, legacy_white_lists: Vec<Key>) {
+        if self.get_caller() != data::get_owner() {
+            runtime::revert(FactoryError::UniswapV2FactoryNotOwner);
+        }
+        data::set_hash(contract_hash);
+        let contract_version: u32 = data::get_contract_version();
+        if contract_version < 1 {
+            // version 0 kept the pairs in a Vec under the named key that is now moved to
+            // the `AllPairs` dictionary, and had no roles, pausing or admins
+            AllPairs::init();
+            let all_pairs: AllPairs = AllPairs::instance();
+            let legacy_all_pairs: Vec<Key> = data::get_legacy_all_pairs();
+            for (index, pair) in legacy_all_pairs.iter().enumerate() {
+                all_pairs.set(&index.into(), *pair);
+            }
+            data::set_all_pairs_length(legacy_all_pairs.len().into());
+            data::remove_legacy_all_pairs();
+            RoleMembers::init();
+            PausedPairs::init();
+            AdminControl::init(self);
+            self.add_admin_without_checked(data::get_owner());
+            for account in legacy_white_lists {
+                if data::is_legacy_white_listed(&account) {
+                    self.grant_role(Role::PairCreator as u8, account);
+                }
+            }
+        }
+        if contract_version < 2 {
+            // pairs created before version 2 are not in the per-token index until
+            // `index_token_pairs` adds them
+            TokenPairs::init();
+            data::set_token_pairs_to_index(self.all_pairs_length());
+        }
+        if contract_version < 3 {
+            DeniedTokens::init();
+        }
+        if contract_version < 4 {
+            StablePairs::init();
+        }
+        if contract_version < VERSION {
+            data::set_contract_version(VERSION);
+        }
+        self.emit(&FACTORYEvent::Migrated {
+            contract_hash: contract_hash,
+            contract_version: data::get_contract_version(),
+        });
+    }
+
+    fn get_contract_version(&mut self) -> u32 {
+        data::get_contract_version()
+    }
+
+    /// Points at most `count` registered pairs, starting from index `start`, to the
+    /// current factory version. Pairs are called through their package so that the
+    /// latest pair version handles the call. It does not upgrade the code of the pairs,
+    /// which only the holder of each pair's package access token can do.
+    fn update_pairs_factory_hash(&mut self, start: U256, count: U256) {
+        if self.get_caller() != data::get_owner() {
+            runtime::revert(FactoryError::UniswapV2FactoryNotOwner);
+        }
+        let factory_hash: Key = data::get_hash();
+        for pair in self.all_pairs_range(start, count) {
+            let pair_package_hash: ContractPackageHash = runtime::call_contract(
+                key_to_contract_hash(pair).unwrap_or_revert(),
+                "package_hash",
+                runtime_args! {},
+            );
+            let _: () = runtime::call_versioned_contract(
+                pair_package_hash,
+                None,
+                "update_factory_hash",
+                runtime_args! {"factory_hash" => factory_hash},
+            );
+        }
+    }
+
+    /// Adds at most `count` of the pairs created before the per-token index existed to
+    /// it, continuing from where the previous call stopped so that no pair is added
+    /// twice. They come after the pairs created since the upgrade in `pairs_for_token`.
+    fn index_token_pairs(&mut self, count: U256) {
+        if self.get_caller() != data::get_owner() {
+            runtime::revert(FactoryError::UniswapV2FactoryNotOwner);
+        }
+        let start: U256 = data::get_token_pairs_indexed();
+        let remaining: U256 = data::get_token_pairs_to_index().saturating_sub(start);
+        let count: U256 = if count > remaining { remaining } else { count };
+        let token_pairs: TokenPairs = TokenPairs::instance();
+        for pair in self.all_pairs_range(start, count) {
+            let pair_contract_hash: ContractHash = key_to_contract_hash(pair).unwrap_or_revert();
+            let token0: Key =
+                runtime::call_contract(pair_contract_hash, "token0", runtime_args! {});
+            let token1: Key =
+                runtime::call_contract(pair_contract_hash, "token1", runtime_args! {});
+            token_pairs.push(&token0, pair);
+            token_pairs.push(&token1, pair);
+        }
+        data::set_token_pairs_indexed(start + count);
+    }
+
+    fn assert_caller_is_guardian(&mut self) {
+        if !RoleMembers::instance().has_role(Role::Guardian, &self.get_caller()) {
+            runtime::revert(FactoryError::UniswapV2FactoryNotGuardian);
+        }
+    }
+
+    /// Time from which a proposal made now can be accepted. It is stored with the
+    /// proposal, so a later change of the delay doesn't move it.
+    fn transfer_eta(&mut self) -> u64 {
+        let now: u64 = runtime::get_blocktime().into();
+        now.saturating_add(data::get_transfer_delay())
+    }
+
+    fn assert_eta_passed(&mut self, eta: u64) {
+        let now: u64 = runtime::get_blocktime().into();
+        if now < eta {
+            runtime::revert(FactoryError::UniswapV2FactoryTransferDelayNotElapsed);
         }
-        data::set_fee_to_setter(fee_to_setter);
     }
 
     fn get_fee_to_setter(&mut self) -> Key {
         data::get_fee_to_setter()
     }
 
-    fn set_all_pairs(&mut self, all_pairs: Vec<Key>) {
-        data::set_all_pairs(all_pairs);
+    fn all_pairs(&mut self, index: U256) -> Key {
+        AllPairs::instance().get(&index)
     }
 
-    fn get_all_pairs(&mut self) -> Vec<Key> {
-        data::get_all_pairs()
+    fn all_pairs_length(&mut self) -> U256 {
+        data::get_all_pairs_length()
     }
 
-    fn set_white_list(&mut self, white_list: Key, value: Key) {
-        if self.get_caller() == data::get_owner() {
-            Whitelists::instance().set(&white_list, value);
+    fn all_pairs_range(&mut self, start: U256, count: U256) -> Vec<Key> {
+        let all_pairs: AllPairs = AllPairs::instance();
+        let all_pairs_length: U256 = self.all_pairs_length();
+        let mut pairs: Vec<Key> = Vec::new();
+        if start >= all_pairs_length {
+            return pairs;
+        }
+        let end: U256 = if count > all_pairs_length - start {
+            all_pairs_length
         } else {
-            runtime::revert(Error::UniswapV2FactoryNotOwner);
+            start + count
+        };
+        let mut index: U256 = start;
+        while index < end {
+            pairs.push(all_pairs.get(&index));
+            index = index + 1;
+        }
+        pairs
+    }
+
+    /// Kept for existing integrations, grants `Role::PairCreator` to `white_list`.
+    fn set_white_list(&mut self, white_list: Key) {
+        self.grant_role(Role::PairCreator as u8, white_list);
+    }
+
+    fn grant_role(&mut self, role: u8, account: Key) {
+        self.assert_caller_is_admin();
+        let role: Role =
+            Role::from_u8(role).unwrap_or_revert_with(FactoryError::UniswapV2FactoryInvalidRole);
+        if RoleMembers::instance().add(role, &account) {
+            self.emit(&FACTORYEvent::RoleGranted {
+                role: role as u8,
+                account: account,
+                sender: self.get_caller(),
+            });
         }
     }
+
+    fn revoke_role(&mut self, role: u8, account: Key) {
+        self.assert_caller_is_admin();
+        let role: Role =
+            Role::from_u8(role).unwrap_or_revert_with(FactoryError::UniswapV2FactoryInvalidRole);
+        if RoleMembers::instance().remove(role, &account) {
+            self.emit(&FACTORYEvent::RoleRevoked {
+                role: role as u8,
+                account: account,
+                sender: self.get_caller(),
+            });
+        }
+    }
+
+    fn has_role(&mut self, role: u8, account: Key) -> bool {
+        match Role::from_u8(role) {
+            Some(role) => RoleMembers::instance().has_role(role, &account),
+            None => false,
+        }
+    }
+
+    fn get_role_member_count(&mut self, role: u8) -> U256 {
+        let role: Role =
+            Role::from_u8(role).unwrap_or_revert_with(FactoryError::UniswapV2FactoryInvalidRole);
+        RoleMembers::instance().count(role)
+    }
+
+    fn get_role_member(&mut self, role: u8, index: U256) -> Key {
+        let role: Role =
+            Role::from_u8(role).unwrap_or_revert_with(FactoryError::UniswapV2FactoryInvalidRole);
+        RoleMembers::instance().member(role, index)
+    }
+
+    fn set_permissionless(&mut self, permissionless: bool) {
+        self.assert_caller_is_admin();
+        data::set_permissionless(permissionless);
+        self.emit(&FACTORYEvent::PermissionlessUpdated {
+            permissionless: permissionless,
+            sender: self.get_caller(),
+        });
+    }
+
+    fn get_permissionless(&mut self) -> bool {
+        data::get_permissionless()
+    }
     fn emit(&mut self, factory_event: &FACTORYEvent) {
         let mut events = Vec::new();
         let package = data::get_package_hash();
@@ -192,6 +794,177 @@ pub trait FACTORY<Storage: ContractStorage>: ContractContext<Storage> {
                 event.insert("all_pairs_length", all_pairs_length.to_string());
                 events.push(event);
             }
+            FACTORYEvent::StablePairCreated {
+                token0,
+                token1,
+                pair,
+                amp,
+                all_pairs_length,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", factory_event.type_name());
+                event.insert("token0", token0.to_string());
+                event.insert("token1", token1.to_string());
+                event.insert("pair", pair.to_string());
+                event.insert("amp", amp.to_string());
+                event.insert("all_pairs_length", all_pairs_length.to_string());
+                events.push(event);
+            }
+            FACTORYEvent::RoleGranted {
+                role,
+                account,
+                sender,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", factory_event.type_name());
+                event.insert("role", role.to_string());
+                event.insert("account", account.to_string());
+                event.insert("sender", sender.to_string());
+                events.push(event);
+            }
+            FACTORYEvent::RoleRevoked {
+                role,
+                account,
+                sender,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", factory_event.type_name());
+                event.insert("role", role.to_string());
+                event.insert("account", account.to_string());
+                event.insert("sender", sender.to_string());
+                events.push(event);
+            }
+            FACTORYEvent::PermissionlessUpdated {
+                permissionless,
+                sender,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", factory_event.type_name());
+                event.insert("permissionless", permissionless.to_string());
+                event.insert("sender", sender.to_string());
+                events.push(event);
+            }
+            FACTORYEvent::FeeToSetterProposed {
+                fee_to_setter,
+                pending_fee_to_setter,
+                eta,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", factory_event.type_name());
+                event.insert("fee_to_setter", fee_to_setter.to_string());
+                event.insert("pending_fee_to_setter", pending_fee_to_setter.to_string());
+                event.insert("eta", eta.to_string());
+                events.push(event);
+            }
+            FACTORYEvent::FeeToSetterAccepted {
+                previous_fee_to_setter,
+                fee_to_setter,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", factory_event.type_name());
+                event.insert("previous_fee_to_setter", previous_fee_to_setter.to_string());
+                event.insert("fee_to_setter", fee_to_setter.to_string());
+                events.push(event);
+            }
+            FACTORYEvent::OwnerProposed {
+                owner,
+                pending_owner,
+                eta,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", factory_event.type_name());
+                event.insert("owner", owner.to_string());
+                event.insert("pending_owner", pending_owner.to_string());
+                event.insert("eta", eta.to_string());
+                events.push(event);
+            }
+            FACTORYEvent::OwnerAccepted {
+                previous_owner,
+                owner,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", factory_event.type_name());
+                event.insert("previous_owner", previous_owner.to_string());
+                event.insert("owner", owner.to_string());
+                events.push(event);
+            }
+            FACTORYEvent::Paused { sender } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", factory_event.type_name());
+                event.insert("sender", sender.to_string());
+                events.push(event);
+            }
+            FACTORYEvent::Unpaused { sender } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", factory_event.type_name());
+                event.insert("sender", sender.to_string());
+                events.push(event);
+            }
+            FACTORYEvent::PairPaused {
+                pair,
+                paused,
+                sender,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", factory_event.type_name());
+                event.insert("pair", pair.to_string());
+                event.insert("paused", paused.to_string());
+                event.insert("sender", sender.to_string());
+                events.push(event);
+            }
+            FACTORYEvent::Migrated {
+                contract_hash,
+                contract_version,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", factory_event.type_name());
+                event.insert("contract_hash", contract_hash.to_string());
+                event.insert("contract_version", contract_version.to_string());
+                events.push(event);
+            }
+            FACTORYEvent::TokenDenied {
+                token,
+                denied,
+                sender,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", factory_event.type_name());
+                event.insert("token", token.to_string());
+                event.insert("denied", denied.to_string());
+                event.insert("sender", sender.to_string());
+                events.push(event);
+            }
+            FACTORYEvent::TransferDelayProposed {
+                transfer_delay,
+                eta,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", factory_event.type_name());
+                event.insert("transfer_delay", transfer_delay.to_string());
+                event.insert("eta", eta.to_string());
+                events.push(event);
+            }
+            FACTORYEvent::TransferDelayUpdated { transfer_delay } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", factory_event.type_name());
+                event.insert("transfer_delay", transfer_delay.to_string());
+                events.push(event);
+            }
         };
 
         for event in events {
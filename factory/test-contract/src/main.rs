@@ -0,0 +1,205 @@
+#![no_std]
+#![no_main]
+
+// We need to explicitly import the std alloc crate and `alloc::string::String` as we're in a
+// `no_std` environment.
+extern crate alloc;
+use alloc::{collections::BTreeSet, format, vec, vec::Vec};
+use core::convert::TryInto;
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    bytesrepr::{FromBytes, ToBytes},
+    contracts::{ContractHash, ContractPackageHash},
+    runtime_args, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints,
+    Group, Key, Parameter, RuntimeArgs, URef, U128, U256,
+};
+
+const FACTORY_HASH_KEY_NAME: &str = "factory_hash";
+const ALL_PAIRS_RANGE_KEY_NAME: &str = "all_pairs_range";
+const PAIR_INFO_KEY_NAME: &str = "pair_info";
+
+#[no_mangle]
+fn constructor() {
+    let contract_hash: ContractHash = runtime::get_named_arg("contract_hash");
+    let package_hash: ContractPackageHash = runtime::get_named_arg("package_hash");
+    let factory_hash: Key = runtime::get_named_arg("factory");
+    set_key(
+        FACTORY_HASH_KEY_NAME,
+        ContractHash::from(factory_hash.into_hash().unwrap_or_default()),
+    );
+    set_key("self_contract_hash", contract_hash);
+    set_key("self_package_hash", package_hash);
+}
+
+#[no_mangle]
+fn all_pairs_range() {
+    let start: U256 = runtime::get_named_arg("start");
+    let count: U256 = runtime::get_named_arg("count");
+    let ret: Vec<Key> = runtime::call_contract(
+        get_key(FACTORY_HASH_KEY_NAME),
+        "all_pairs_range",
+        runtime_args! {
+            "start" => start,
+            "count" => count
+        },
+    );
+    set_key(ALL_PAIRS_RANGE_KEY_NAME, ret);
+}
+
+#[no_mangle]
+fn pair_info() {
+    let pair: Key = runtime::get_named_arg("pair");
+    let ret: ((Key, Key), (U128, U128, u64), (U256, U256)) = runtime::call_contract(
+        get_key(FACTORY_HASH_KEY_NAME),
+        "pair_info",
+        runtime_args! {
+            "pair" => pair
+        },
+    );
+    set_key(PAIR_INFO_KEY_NAME, ret);
+}
+
+/// With `balance_of`, answers the probe calls of the factory like a token without
+/// `decimals`.
+#[no_mangle]
+fn total_supply() {
+    runtime::ret(CLValue::from_t(U256::zero()).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn balance_of() {
+    let _owner: Key = runtime::get_named_arg("owner");
+    runtime::ret(CLValue::from_t(U256::zero()).unwrap_or_revert());
+}
+
+fn get_key<T: FromBytes + CLTyped + Default>(name: &str) -> T {
+    match runtime::get_key(name) {
+        None => Default::default(),
+        Some(value) => {
+            let key = value.try_into().unwrap_or_revert();
+            storage::read(key).unwrap_or_revert().unwrap_or_revert()
+        }
+    }
+}
+
+fn set_key<T: ToBytes + CLTyped>(name: &str, value: T) {
+    match runtime::get_key(name) {
+        Some(key) => {
+            let key_ref = key.try_into().unwrap_or_revert();
+            storage::write(key_ref, value);
+        }
+        None => {
+            let key = storage::new_uref(value).into();
+            runtime::put_key(name, key);
+        }
+    }
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new("factory", Key::cl_type()),
+            Parameter::new("contract_hash", ContractHash::cl_type()),
+            Parameter::new("package_hash", ContractPackageHash::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "all_pairs_range",
+        vec![
+            Parameter::new("start", U256::cl_type()),
+            Parameter::new("count", U256::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "pair_info",
+        vec![Parameter::new("pair", Key::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "total_supply",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "balance_of",
+        vec![Parameter::new("owner", Key::cl_type())],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
+
+// All session code must have a `call` entrypoint.
+#[no_mangle]
+pub extern "C" fn call() {
+    // Build new package with initial a first version of the contract.
+    let (package_hash, access_token) = storage::create_contract_package_at_hash();
+    let (contract_hash, _): (ContractHash, _) =
+        storage::add_contract_version(package_hash, get_entry_points(), Default::default());
+
+    let factory_hash: Key = runtime::get_named_arg("factory");
+
+    // Prepare constructor args
+    let constructor_args = runtime_args! {
+        "contract_hash" => contract_hash,
+        "package_hash" => package_hash,
+        "factory" => factory_hash,
+    };
+
+    // Add the constructor group to the package hash with a single URef.
+    let constructor_access: URef =
+        storage::create_contract_user_group(package_hash, "constructor", 1, Default::default())
+            .unwrap_or_revert()
+            .pop()
+            .unwrap_or_revert();
+
+    // Call the constructor entry point
+    let _: () =
+        runtime::call_versioned_contract(package_hash, None, "constructor", constructor_args);
+
+    // Remove all URefs from the constructor group, so no one can call it for the second time.
+    let mut urefs = BTreeSet::new();
+    urefs.insert(constructor_access);
+    storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
+        .unwrap_or_revert();
+
+    // Store contract in the account's named keys.
+    let contract_name: alloc::string::String = runtime::get_named_arg("contract_name");
+    runtime::put_key(
+        &format!("{}_package_hash", contract_name),
+        package_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_hash_wrapped", contract_name),
+        storage::new_uref(package_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash_wrapped", contract_name),
+        storage::new_uref(contract_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_access_token", contract_name),
+        access_token.into(),
+    );
+}
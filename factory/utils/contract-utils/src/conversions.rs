@@ -0,0 +1,20 @@
+use casper_types::{U256, U512};
+
+/// Converts a U512 (e.g. motes in a purse) into a U256 token amount.
+/// Returns `None` if the value does not fit in 256 bits.
+pub fn u512_to_u256(value: U512) -> Option<U256> {
+    let mut bytes = [0u8; 64];
+    value.to_little_endian(&mut bytes);
+    if bytes[32..].iter().any(|byte| *byte != 0) {
+        return None;
+    }
+    Some(U256::from_little_endian(&bytes[..32]))
+}
+
+/// Converts a U256 token amount into a U512 (e.g. motes for a purse transfer).
+/// Every U256 fits in a U512 so this never fails.
+pub fn u256_to_u512(value: U256) -> U512 {
+    let mut bytes = [0u8; 32];
+    value.to_little_endian(&mut bytes);
+    U512::from_little_endian(&bytes)
+}
@@ -20,10 +20,21 @@ impl TestEnv {
     }
 
     pub fn run(&self, sender: Sender, session_code: Code, session_args: RuntimeArgs) {
+        self.run_with_time(sender, session_code, session_args, 0);
+    }
+
+    /// Same as `run` with the block time of the deploy set to `time`, in milliseconds.
+    pub fn run_with_time(
+        &self,
+        sender: Sender,
+        session_code: Code,
+        session_args: RuntimeArgs,
+        time: u64,
+    ) {
         self.state
             .lock()
             .unwrap()
-            .run(sender, session_code, session_args);
+            .run(sender, session_code, session_args, time);
     }
 
     pub fn next_user(&self) -> AccountHash {
@@ -84,11 +95,18 @@ impl TestEnvState {
         self.accounts.pop().unwrap()
     }
 
-    pub fn run(&mut self, sender: Sender, session_code: Code, session_args: RuntimeArgs) {
+    pub fn run(
+        &mut self,
+        sender: Sender,
+        session_code: Code,
+        session_args: RuntimeArgs,
+        time: u64,
+    ) {
         let Sender(sender) = sender;
         let session = SessionBuilder::new(session_code, session_args)
             .with_address(sender)
             .with_authorization_keys(&[sender])
+            .with_block_time(time)
             .build();
         self.context.run(session);
     }
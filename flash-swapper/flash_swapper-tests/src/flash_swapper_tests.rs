@@ -1,8 +1,9 @@
 use casper_engine_test_support::AccountHash;
-use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs, U256};
 use test_env::{Sender, TestContract, TestEnv};
 
 use crate::flash_swapper_instance::FlashSwapperInstance;
+use crate::test_instance::key_to_str;
 // use crate::test_instance::TESTInstance;
 
 fn deploy_factory(env: &TestEnv) -> TestContract {
@@ -41,6 +42,71 @@ fn deploy_wcspr(env: &TestEnv) -> TestContract {
     wcspr
 }
 
+// takes 1% of every transfer, see erc20/test-contract
+fn deploy_taxed_token(env: &TestEnv, owner: AccountHash) -> TestContract {
+    TestContract::new(
+        env,
+        "erc20-taxed-token.wasm",
+        "taxed_token",
+        Sender(owner),
+        runtime_args! {
+            "initial_supply" => U256::from(0),
+            "name" => "taxed",
+            "symbol" => "tax",
+            "decimals" => 18u8
+        },
+    )
+}
+
+/// Deploys the repayer, which runs `repay` of the flash swapper on its own, with `amount`
+/// of `token` to repay from.
+fn deploy_repayer(
+    env: &TestEnv,
+    owner: AccountHash,
+    token: &TestContract,
+    amount: U256,
+) -> (TestContract, Key) {
+    let repayer = TestContract::new(
+        env,
+        "flash-swapper-repayer.wasm",
+        "repayer",
+        Sender(owner),
+        runtime_args! {},
+    );
+    let package_hash: ContractPackageHash =
+        repayer.query_named_key(String::from("contract_package_hash"));
+    token.call_contract(
+        Sender(owner),
+        "mint",
+        runtime_args! {"to" => Key::from(package_hash), "amount" => amount},
+    );
+    (repayer, Key::from(package_hash))
+}
+
+fn repay(
+    repayer: &TestContract,
+    owner: AccountHash,
+    token: &TestContract,
+    pair: Key,
+    amount: U256,
+) {
+    repayer.call_contract(
+        Sender(owner),
+        "repay",
+        runtime_args! {
+            "token" => Key::Hash(token.contract_hash()),
+            "pair" => pair,
+            "amount_to_repay" => amount
+        },
+    );
+}
+
+fn balance_of(token: &TestContract, account: Key) -> U256 {
+    token
+        .query_dictionary("balances", key_to_str(&account))
+        .unwrap_or_default()
+}
+
 // fn deploy_pair(env: &TestEnv, factory: &TestContract, calle: Key) -> TestContract {
 //     // deploy wcspr contract
 //     let decimals: u8 = 18;
@@ -265,6 +331,36 @@ fn test_flash_swapper_deploy() {
 //     );
 // }
 
+#[test]
+fn test_flash_swapper_repay_with_taxed_token() {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let pair = Key::from(env.next_user());
+    let token = deploy_taxed_token(&env, owner);
+    let (repayer, repayer_package) = deploy_repayer(&env, owner, &token, 2000.into());
+    repay(&repayer, owner, &token, pair, 1000.into());
+    // 990 of the first 1000 arrive, the 10 missing are sent again and arrive untaxed
+    assert_eq!(balance_of(&token, pair), 1000.into());
+    assert_eq!(balance_of(&token, repayer_package), 990.into());
+}
+
+#[test]
+#[should_panic]
+fn test_flash_swapper_repay_with_token_delivering_nothing() {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let pair = Key::from(env.next_user());
+    let token = deploy_taxed_token(&env, owner);
+    let (repayer, _) = deploy_repayer(&env, owner, &token, 2000.into());
+    token.call_contract(
+        Sender(owner),
+        "set_tax_percent",
+        runtime_args! {"tax_percent" => U256::from(100)},
+    );
+    // the pair receives nothing, so repay reverts with UniswapV2RepaymentNotReceived
+    repay(&repayer, owner, &token, pair, 1000.into());
+}
+
 #[test]
 #[should_panic]
 fn test_calling_construction() {
@@ -1,7 +1,8 @@
 use casper_contract::contract_api::runtime;
 use casper_contract::unwrap_or_revert::UnwrapOrRevert;
-use casper_types::{ApiError, ContractPackageHash, Key, URef};
+use casper_types::{ContractPackageHash, Key, URef};
 use contract_utils::{get_key, set_key};
+use errors::CommonError;
 
 pub const SELF_CONTRACT_HASH: &str = "self_contract_hash";
 pub const WCSPR: &str = "wcspr";
@@ -14,11 +15,6 @@ pub const UNISWAP_V2_PAIR: &str = "uniswap_v2_pair";
 pub const SELF_PURSE: &str = "self_purse";
 pub const CONTRACT_PACKAGE_HASH: &str = "contract_package_hash";
 
-#[repr(u16)]
-pub enum ErrorCodes {
-    Abort = 35,
-}
-
 pub fn set_wcspr(wcspr: Key) {
     set_key(WCSPR, wcspr);
 }
@@ -85,7 +81,7 @@ pub fn get_self_purse() -> URef {
 
     match destination_purse_key.as_uref() {
         Some(uref) => *uref,
-        None => runtime::revert(ApiError::User(ErrorCodes::Abort as u16)),
+        None => runtime::revert(CommonError::Abort),
     }
 }
 
@@ -1,44 +1,14 @@
 use alloc::{format, string::String, vec::Vec};
 use casper_contract::contract_api::runtime::{self, call_contract};
 use casper_contract::unwrap_or_revert::UnwrapOrRevert;
-use casper_types::{
-    runtime_args, ApiError, ContractHash, ContractPackageHash, Key, RuntimeArgs, URef, U256, U512,
+use casper_types::{runtime_args, ContractHash, ContractPackageHash, Key, RuntimeArgs, URef, U256};
+use contract_utils::{
+    is_zero, key_to_contract_hash, u256_to_u512, ContractContext, ContractStorage, ZERO_HASH,
 };
-use contract_utils::{ContractContext, ContractStorage};
+use errors::FlashSwapperError;
 
 use crate::data::{self};
 
-/// Enum for FailureCode, It represents codes for different smart contract errors.
-#[repr(u16)]
-enum FailureCode {
-    /// 65,536 for (Requested pair is not available)
-    Zero = 0,
-    /// 65,537 for (Requested borrow token is not available)
-    One,
-    //  65,538 for (Requested pay token is not available)
-    Two,
-    //  65,539 for (_amount is too big)
-    Three,
-    /// 65,540 for (UniswapV2: OVERFLOW)
-    Four,
-    /// 65,541 for (UniswapV2: UNDERFLOW)
-    Five,
-}
-
-#[repr(u16)]
-pub enum Error {
-    UniswapV2ZeroAddress = 0,
-    UniswapV2PairExists = 1,
-    UniswapV2PermissionedPairAccess = 2,
-    UniswapV2InvalidContractAddress = 3,
-}
-
-impl From<Error> for ApiError {
-    fn from(error: Error) -> ApiError {
-        ApiError::User(error as u16)
-    }
-}
-
 pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
     fn init(
         &mut self,
@@ -50,12 +20,7 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         purse: URef,
     ) {
         data::set_wcspr(wcspr);
-        data::set_cspr(
-            Key::from_formatted_str(
-                "hash-0000000000000000000000000000000000000000000000000000000000000000",
-            )
-            .unwrap(),
-        );
+        data::set_cspr(ZERO_HASH);
         data::set_dai(dai);
         data::set_uniswap_v2_factory(uniswap_v2_factory);
         data::set_hash(contract_hash);
@@ -111,10 +76,10 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         // access control
         let permissioned_pair_address = data::get_permissioned_pair_address();
         if self.get_caller() != permissioned_pair_address {
-            runtime::revert(Error::UniswapV2PermissionedPairAccess);
+            runtime::revert(FlashSwapperError::UniswapV2PermissionedPairAccess);
         }
         if _sender != data::get_hash() {
-            runtime::revert(Error::UniswapV2InvalidContractAddress);
+            runtime::revert(FlashSwapperError::UniswapV2InvalidContractAddress);
         }
         let decoded_data_without_commas: Vec<&str> = _data.split(',').collect();
         let _token_borrow_string = format!("{}{}", "hash-", decoded_data_without_commas[1]);
@@ -175,12 +140,8 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         if _token_borrow != wcspr {
             other_token = wcspr;
         }
-        let uniswap_v2_factory_hash_add_array = match uniswap_v2_factory {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
         let uniswap_v2_factory_hash_add: ContractHash =
-            ContractHash::new(uniswap_v2_factory_hash_add_array);
+            key_to_contract_hash(uniswap_v2_factory).unwrap_or_revert();
         let permissioned_pair_address: Key = call_contract(
             uniswap_v2_factory_hash_add,
             "get_pair",
@@ -188,20 +149,11 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         );
         data::set_permissioned_pair_address(permissioned_pair_address);
         let pair_address: Key = data::get_permissioned_pair_address();
-        // in before 0 address was hash-0000000000000000000000000000000000000000000000000000000000000000
-        if pair_address
-            == Key::from_formatted_str(
-                "hash-0000000000000000000000000000000000000000000000000000000000000000",
-            )
-            .unwrap()
-        {
-            runtime::revert(Error::UniswapV2ZeroAddress);
+        if is_zero(&pair_address) {
+            runtime::revert(FlashSwapperError::UniswapV2ZeroAddress);
         }
-        let pair_address_hash_add_array = match pair_address {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let pair_address_hash_add = ContractHash::new(pair_address_hash_add_array);
+        let pair_address_hash_add: ContractHash =
+            key_to_contract_hash(pair_address).unwrap_or_revert();
         let token0: Key = call_contract(pair_address_hash_add, "token0", RuntimeArgs::new());
         let token1: Key = call_contract(pair_address_hash_add, "token1", RuntimeArgs::new());
         let amount0_out: U256;
@@ -216,11 +168,8 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         } else {
             amount1_out = 0.into();
         }
-        let _token_borrow_hash_add_array = match _token_borrow {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let _token_borrow_hash_add: ContractHash = ContractHash::new(_token_borrow_hash_add_array);
+        let _token_borrow_hash_add: ContractHash =
+            key_to_contract_hash(_token_borrow).unwrap_or_revert();
         let _token_borrow_str: String = _token_borrow_hash_add.to_formatted_string();
         let _token_borrow_vec: Vec<&str> = _token_borrow_str.split('-').collect();
         let _token_borrow_hash: &str = _token_borrow_vec[1];
@@ -262,18 +211,14 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         _user_data: String,
     ) {
         let wcspr: Key = data::get_wcspr();
-        let wcspr_hash_add_array = match wcspr {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let wcspr_hash_add: ContractHash = ContractHash::new(wcspr_hash_add_array);
+        let wcspr_hash_add: ContractHash = key_to_contract_hash(wcspr).unwrap_or_revert();
         let cspr: Key = data::get_cspr();
         if _is_borrowing_cspr {
             // call withdraw from WCSPR and transfer cspr to 'to'
             let res: Result<(), u32> = call_contract(
                 wcspr_hash_add,
                 "withdraw",
-                runtime_args! {"to_purse" => data::get_self_purse(), "amount" => U512::from(_amount.as_u128())},
+                runtime_args! {"to_purse" => data::get_self_purse(), "amount" => u256_to_u512(_amount)},
             );
             match res {
                 Ok(()) => (),
@@ -282,11 +227,11 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         }
         let fee: U256 = U256::from((_amount * 3) / 997)
             .checked_add(U256::from(1))
-            .ok_or(ApiError::User(FailureCode::Four as u16))
+            .ok_or(FlashSwapperError::UniswapV2OverFlow)
             .unwrap_or_revert();
         let amount_to_repay: U256 = _amount
             .checked_add(fee)
-            .ok_or(ApiError::User(FailureCode::Four as u16))
+            .ok_or(FlashSwapperError::UniswapV2OverFlow)
             .unwrap_or_revert();
         let token_borrowed: Key;
         let token_to_repay: Key;
@@ -316,27 +261,14 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
             let res: Result<(), u32> = call_contract(
                 wcspr_hash_add,
                 "deposit",
-                runtime_args! { "purse" => caller_purse, "amount" => U512::from(amount_to_repay.as_u128())},
+                runtime_args! { "purse" => caller_purse, "amount" => u256_to_u512(amount_to_repay)},
             );
             match res {
                 Ok(()) => (),
                 Err(err) => runtime::revert(err),
             }
         }
-        let _token_borrow_hash_add_array = match _token_borrow {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let _token_borrow_hash_add: ContractHash = ContractHash::new(_token_borrow_hash_add_array);
-        let res: Result<(), u32> = call_contract(
-            _token_borrow_hash_add,
-            "transfer",
-            runtime_args! {"recipient"=>_pair_address , "amount" => amount_to_repay},
-        );
-        match res {
-            Ok(()) => (),
-            Err(err) => runtime::revert(err),
-        }
+        self.repay(_token_borrow, _pair_address, amount_to_repay);
     }
 
     /// @notice This function is used when either the _tokenBorrow or _tokenPay is wcspr or cspr
@@ -354,13 +286,8 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         user_data: String,
     ) {
         let uniswap_v2_factory_address: Key = data::get_uniswap_v2_factory();
-        //convert Key to ContractHash
-        let uniswap_v2_factory_address_hash_add_array = match uniswap_v2_factory_address {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
         let uniswap_v2_factory_contract_hash: ContractHash =
-            ContractHash::new(uniswap_v2_factory_address_hash_add_array);
+            key_to_contract_hash(uniswap_v2_factory_address).unwrap_or_revert();
         let token_borrow_token_pay_pair_address: Key = runtime::call_contract(
             uniswap_v2_factory_contract_hash,
             "get_pair",
@@ -368,18 +295,9 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         );
         data::set_permissioned_pair_address(token_borrow_token_pay_pair_address);
         let pair_address: Key = token_borrow_token_pay_pair_address; // gas efficiency
-        let address_0: Key = Key::from_formatted_str(
-            "hash-0000000000000000000000000000000000000000000000000000000000000000",
-        )
-        .unwrap();
-        if pair_address != address_0 {
-            //convert Key to ContractHash
-            let pair_address_hash_add_array = match pair_address {
-                Key::Hash(package) => package,
-                _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-            };
+        if !is_zero(&pair_address) {
             let pair_address_hash_add: ContractHash =
-                ContractHash::new(pair_address_hash_add_array);
+                key_to_contract_hash(pair_address).unwrap_or_revert();
             let token0: Key =
                 runtime::call_contract(pair_address_hash_add, "token0", runtime_args! {});
             let token1: Key =
@@ -396,20 +314,13 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
             } else {
                 amount1_out = 0.into();
             }
-            let _token_borrow_hash_add_array = match token_borrow {
-                Key::Hash(package) => package,
-                _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-            };
             let _token_borrow_hash_add: ContractHash =
-                ContractHash::new(_token_borrow_hash_add_array);
+                key_to_contract_hash(token_borrow).unwrap_or_revert();
             let _token_borrow_str: String = _token_borrow_hash_add.to_formatted_string();
             let _token_borrow_vec: Vec<&str> = _token_borrow_str.split('-').collect();
             let _token_borrow_hash: &str = _token_borrow_vec[1];
-            let _token_pay_hash_add_array = match token_pay {
-                Key::Hash(package) => package,
-                _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-            };
-            let _token_pay_hash_add: ContractHash = ContractHash::new(_token_pay_hash_add_array);
+            let _token_pay_hash_add: ContractHash =
+                key_to_contract_hash(token_pay).unwrap_or_revert();
             let _token_pay_str: String = _token_pay_hash_add.to_formatted_string();
             let _token_pay_vec: Vec<&str> = _token_pay_str.split('-').collect();
             let _token_pay_hash: &str = _token_pay_vec[1];
@@ -438,7 +349,7 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
             );
         } else {
             // requested pair is not available
-            runtime::revert(ApiError::User(FailureCode::Zero as u16));
+            runtime::revert(FlashSwapperError::UniswapV2RequestedPairNotAvailable);
         }
     }
 
@@ -457,18 +368,14 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
     ) {
         // unwrap wcspr if necessary
         let wcspr_address: Key = data::get_wcspr();
-        //convert Key to ContractHash
-        let wcspr_address_hash_add_array = match wcspr_address {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let wcspr_contract_hash: ContractHash = ContractHash::new(wcspr_address_hash_add_array);
+        let wcspr_contract_hash: ContractHash =
+            key_to_contract_hash(wcspr_address).unwrap_or_revert();
         if is_borrowing_cspr {
             // call withdraw from WCSPR and transfer cspr to 'to'
             let res: Result<(), u32> = call_contract(
                 wcspr_contract_hash,
                 "withdraw",
-                runtime_args! {"to_purse" => data::get_self_purse(), "amount" => U512::from(amount.as_u128())},
+                runtime_args! {"to_purse" => data::get_self_purse(), "amount" => u256_to_u512(amount)},
             );
             match res {
                 Ok(()) => (),
@@ -477,25 +384,15 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         }
         // compute the amount of _tokenPay that needs to be repaid
         let pair_address: Key = data::get_permissioned_pair_address(); // gas efficiency
-                                                                       //convert Key to ContractHash
-        let token_borrow_address_hash_add_array = match token_borrow {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
         let token_borrow_contract_hash: ContractHash =
-            ContractHash::new(token_borrow_address_hash_add_array);
+            key_to_contract_hash(token_borrow).unwrap_or_revert();
         let pair_balance_token_borrow: U256 = runtime::call_contract(
             token_borrow_contract_hash,
             "balance_of",
             runtime_args! {"owner" => pair_address},
         );
-        //convert Key to ContractHash
-        let token_pay_address_hash_add_array = match token_pay {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
         let token_pay_contract_hash: ContractHash =
-            ContractHash::new(token_pay_address_hash_add_array);
+            key_to_contract_hash(token_pay).unwrap_or_revert();
         let pair_balance_token_pay: U256 = runtime::call_contract(
             token_pay_contract_hash,
             "balance_of",
@@ -507,17 +404,11 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         let amount_to_repay: U256 = ((amount_1000 * pair_balance_token_pay * amount)
             / (amount_997 * pair_balance_token_borrow))
             .checked_add(amount_1)
-            .ok_or(ApiError::User(FailureCode::Four as u16))
+            .ok_or(FlashSwapperError::UniswapV2OverFlow)
             .unwrap_or_revert();
         // get the orignal tokens the user requested
-        let mut _token_borrowed: Key = Key::from_formatted_str(
-            "hash-0000000000000000000000000000000000000000000000000000000000000000",
-        )
-        .unwrap();
-        let mut _token_to_repay: Key = Key::from_formatted_str(
-            "hash-0000000000000000000000000000000000000000000000000000000000000000",
-        )
-        .unwrap();
+        let mut _token_borrowed: Key = ZERO_HASH;
+        let mut _token_to_repay: Key = ZERO_HASH;
         let cspr: Key = data::get_cspr();
         if is_borrowing_cspr == true {
             _token_borrowed = cspr;
@@ -544,22 +435,14 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
             let _deposit_result: Result<(), u32> = runtime::call_contract(
                 wcspr_contract_hash,
                 "deposit",
-                runtime_args! { "purse" => caller_purse, "amount" => U512::from(amount_to_repay.as_u128())},
+                runtime_args! { "purse" => caller_purse, "amount" => u256_to_u512(amount_to_repay)},
             );
             match _deposit_result {
                 Ok(()) => (),
                 Err(err) => runtime::revert(err),
             }
         }
-        let res: Result<(), u32> = runtime::call_contract(
-            token_pay_contract_hash,
-            "transfer",
-            runtime_args! {"recipient" => _pair_address, "amount" => amount_to_repay},
-        );
-        match res {
-            Ok(()) => (),
-            Err(err) => runtime::revert(err),
-        }
+        self.repay(token_pay, _pair_address, amount_to_repay);
     }
 
     /// @notice This function is used when neither the _tokenBorrow nor the _tokenPay is wcspr
@@ -578,24 +461,15 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         user_data: String,
     ) {
         let uniswap_v2_factory_address: Key = data::get_uniswap_v2_factory();
-        // convert Key to ContractHash
-        let uniswap_v2_factory_address_hash_add_array = match uniswap_v2_factory_address {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
         let uniswap_v2_factory_contract_hash: ContractHash =
-            ContractHash::new(uniswap_v2_factory_address_hash_add_array);
+            key_to_contract_hash(uniswap_v2_factory_address).unwrap_or_revert();
         let wcspr: Key = data::get_wcspr();
         let borrow_pair_address: Key = runtime::call_contract(
             uniswap_v2_factory_contract_hash,
             "get_pair",
             runtime_args! {"token0" => token_borrow, "token1" => wcspr},
         );
-        let address_0: Key = Key::from_formatted_str(
-            "hash-0000000000000000000000000000000000000000000000000000000000000000",
-        )
-        .unwrap();
-        if borrow_pair_address != address_0 {
+        if !is_zero(&borrow_pair_address) {
             let permissioned_pair_address: Key = runtime::call_contract(
                 uniswap_v2_factory_contract_hash,
                 "get_pair",
@@ -603,15 +477,10 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
             );
             data::set_permissioned_pair_address(permissioned_pair_address);
             let pay_pair_address: Key = permissioned_pair_address; // gas efficiency
-            if pay_pair_address != address_0 {
+            if !is_zero(&pay_pair_address) {
                 // STEP 1: Compute how much wcspr will be needed to get _amount of _tokenBorrow out of the _tokenBorrow/wcspr pool
-                //convert Key to ContractHash
-                let token_borrow_address_hash_add_array = match token_borrow {
-                    Key::Hash(package) => package,
-                    _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-                };
                 let token_borrow_contract_hash: ContractHash =
-                    ContractHash::new(token_borrow_address_hash_add_array);
+                    key_to_contract_hash(token_borrow).unwrap_or_revert();
                 let pair_balance_token_borrow_before: U256 = runtime::call_contract(
                     token_borrow_contract_hash,
                     "balance_of",
@@ -621,15 +490,10 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
                 if pair_balance_token_borrow_before >= amount {
                     let pair_balance_token_borrow_after: U256 = pair_balance_token_borrow_before
                         .checked_sub(amount)
-                        .ok_or(ApiError::User(FailureCode::Five as u16))
+                        .ok_or(FlashSwapperError::UniswapV2UnderFlow)
                         .unwrap_or_revert();
-                    //convert Key to ContractHash
-                    let wcspr_address_hash_add_array = match wcspr {
-                        Key::Hash(package) => package,
-                        _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-                    };
                     let wcspr_contract_hash: ContractHash =
-                        ContractHash::new(wcspr_address_hash_add_array);
+                        key_to_contract_hash(wcspr).unwrap_or_revert();
                     let pair_balance_wcspr: U256 = runtime::call_contract(
                         wcspr_contract_hash,
                         "balance_of",
@@ -653,15 +517,15 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
                     );
                 } else {
                     // _amount is too big
-                    runtime::revert(ApiError::User(FailureCode::Three as u16));
+                    runtime::revert(FlashSwapperError::UniswapV2AmountTooBig);
                 }
             } else {
                 // Requested pay token is not available
-                runtime::revert(ApiError::User(FailureCode::Two as u16));
+                runtime::revert(FlashSwapperError::UniswapV2PayTokenNotAvailable);
             }
         } else {
             // Requested borrow token is not available
-            runtime::revert(ApiError::User(FailureCode::One as u16));
+            runtime::revert(FlashSwapperError::UniswapV2BorrowTokenNotAvailable);
         }
     }
 
@@ -677,13 +541,8 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         amount_of_wcspr: U256,
         user_data: String,
     ) {
-        //convert Key to ContractHash
-        let pay_pair_address_hash_add_array = match pay_pair_address {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
         let pay_pair_contract_hash: ContractHash =
-            ContractHash::new(pay_pair_address_hash_add_array);
+            key_to_contract_hash(pay_pair_address).unwrap_or_revert();
         // Step 2: Flash-borrow _amountOfwcspr wcspr from the _tokenPay/wcspr pool
         let token0: Key =
             runtime::call_contract(pay_pair_contract_hash, "token0", runtime_args! {});
@@ -698,27 +557,17 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         if wcspr == token1 {
             amount1_out = amount_of_wcspr;
         }
-        let _token_borrow_hash_add_array = match token_borrow {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let _token_borrow_hash_add: ContractHash = ContractHash::new(_token_borrow_hash_add_array);
+        let _token_borrow_hash_add: ContractHash =
+            key_to_contract_hash(token_borrow).unwrap_or_revert();
         let _token_borrow_str: String = _token_borrow_hash_add.to_formatted_string();
         let _token_borrow_vec: Vec<&str> = _token_borrow_str.split('-').collect();
         let _token_borrow_hash: &str = _token_borrow_vec[1];
-        let _token_pay_hash_add_array = match token_pay {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let _token_pay_hash_add: ContractHash = ContractHash::new(_token_pay_hash_add_array);
+        let _token_pay_hash_add: ContractHash = key_to_contract_hash(token_pay).unwrap_or_revert();
         let _token_pay_str: String = _token_pay_hash_add.to_formatted_string();
         let _token_pay_vec: Vec<&str> = _token_pay_str.split('-').collect();
         let _token_pay_hash: &str = _token_pay_vec[1];
-        let _borrow_pair_hash_add_array = match borrow_pair_address {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let _borrow_pair_hash_add: ContractHash = ContractHash::new(_borrow_pair_hash_add_array);
+        let _borrow_pair_hash_add: ContractHash =
+            key_to_contract_hash(borrow_pair_address).unwrap_or_revert();
         let _borrow_pair_str: String = _borrow_pair_hash_add.to_formatted_string();
         let _borrow_pair_vec: Vec<&str> = _borrow_pair_str.split('-').collect();
         let _borrow_pair_hash: &str = _borrow_pair_vec[1];
@@ -766,13 +615,8 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         let borrow_pair_address: Key =
             Key::from_formatted_str(&borrow_pair_address_string).unwrap();
         let amount_of_wcspr: U256 = decoded_data_without_fullstop[1].parse().unwrap();
-        //convert Key to ContractHash
-        let borrow_pair_address_hash_add_array = match borrow_pair_address {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
         let borrow_pair_contract_hash: ContractHash =
-            ContractHash::new(borrow_pair_address_hash_add_array);
+            key_to_contract_hash(borrow_pair_address).unwrap_or_revert();
         // Step 3: Using a normal swap, trade that wcspr for _tokenBorrow
         let token0: Key =
             runtime::call_contract(borrow_pair_contract_hash, "token0", runtime_args! {});
@@ -792,12 +636,7 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         }
         // send our flash-borrowed wcspr to the pair
         let wcspr: Key = data::get_wcspr();
-        //convert Key to ContractHash
-        let wcspr_address_hash_add_array = match wcspr {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let wcspr_contract_hash: ContractHash = ContractHash::new(wcspr_address_hash_add_array);
+        let wcspr_contract_hash: ContractHash = key_to_contract_hash(wcspr).unwrap_or_revert();
         let res: Result<(), u32> = runtime::call_contract(
             wcspr_contract_hash,
             "transfer",
@@ -820,13 +659,8 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
             "balance_of",
             runtime_args! {"owner" => pay_pair_address},
         );
-        //convert Key to ContractHash
-        let token_pay_address_hash_add_array = match token_pay {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
         let token_pay_contract_hash: ContractHash =
-            ContractHash::new(token_pay_address_hash_add_array);
+            key_to_contract_hash(token_pay).unwrap_or_revert();
         let pair_balance_token_pay: U256 = runtime::call_contract(
             token_pay_contract_hash,
             "balance_of",
@@ -841,14 +675,44 @@ pub trait FLASHSWAPPER<Storage: ContractStorage>: ContractContext<Storage> {
         // Step 4: Do whatever the user wants (arb, liqudiation, etc)
         self.execute(token_borrow, amount, token_pay, amount_to_repay, user_data);
         // Step 5: Pay back the flash-borrow to the _tokenPay/wcspr pool
-        let res: Result<(), u32> = runtime::call_contract(
-            token_pay_contract_hash,
-            "transfer",
-            runtime_args! {"recipient" => pay_pair_address, "amount" => amount_to_repay},
+        self.repay(token_pay, pay_pair_address, amount_to_repay);
+    }
+
+    /// @notice Transfers `amount_to_repay` of `token` to `pair` and checks the balance of the pair
+    ///     before and after. Tokens that take a fee on transfer deliver less than what is sent,
+    ///     so the shortfall is sent again until the pair has received the full amount.
+    /// @dev Reverts if a transfer delivers nothing, as the loop would not end otherwise.
+    fn repay(&mut self, token: Key, pair: Key, amount_to_repay: U256) {
+        let token_contract_hash: ContractHash = key_to_contract_hash(token).unwrap_or_revert();
+        let pair_balance_before: U256 = runtime::call_contract(
+            token_contract_hash,
+            "balance_of",
+            runtime_args! {"owner" => pair},
         );
-        match res {
-            Ok(()) => (),
-            Err(err) => runtime::revert(err),
+        let mut received: U256 = 0.into();
+        while received < amount_to_repay {
+            let res: Result<(), u32> = runtime::call_contract(
+                token_contract_hash,
+                "transfer",
+                runtime_args! {"recipient" => pair, "amount" => amount_to_repay - received},
+            );
+            match res {
+                Ok(()) => (),
+                Err(err) => runtime::revert(err),
+            }
+            let pair_balance: U256 = runtime::call_contract(
+                token_contract_hash,
+                "balance_of",
+                runtime_args! {"owner" => pair},
+            );
+            let now_received: U256 = pair_balance
+                .checked_sub(pair_balance_before)
+                .ok_or(FlashSwapperError::UniswapV2UnderFlow)
+                .unwrap_or_revert();
+            if now_received <= received {
+                runtime::revert(FlashSwapperError::UniswapV2RepaymentNotReceived);
+            }
+            received = now_received;
         }
     }
 
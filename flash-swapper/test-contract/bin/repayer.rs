@@ -0,0 +1,121 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{collections::BTreeSet, format, vec};
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    runtime_args, CLTyped, ContractPackageHash, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Group, Key, Parameter, RuntimeArgs, URef, U256,
+};
+use contract_utils::{ContractContext, OnChainContractStorage};
+use flash_swapper::{self, FLASHSWAPPER};
+
+/// Runs the repayment step of the flash swapper on its own, as the flash swaps themselves
+/// cannot be set up in the test environment yet.
+#[derive(Default)]
+struct Repayer(OnChainContractStorage);
+
+impl ContractContext<OnChainContractStorage> for Repayer {
+    fn storage(&self) -> &OnChainContractStorage {
+        &self.0
+    }
+}
+
+impl FLASHSWAPPER<OnChainContractStorage> for Repayer {}
+
+#[no_mangle]
+fn constructor() {
+    let package_hash: ContractPackageHash = runtime::get_named_arg("package_hash");
+    flash_swapper::data::set_package_hash(package_hash);
+}
+
+#[no_mangle]
+fn repay() {
+    let token: Key = runtime::get_named_arg("token");
+    let pair: Key = runtime::get_named_arg("pair");
+    let amount_to_repay: U256 = runtime::get_named_arg("amount_to_repay");
+    Repayer::default().repay(token, pair, amount_to_repay);
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![Parameter::new(
+            "package_hash",
+            ContractPackageHash::cl_type(),
+        )],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "repay",
+        vec![
+            Parameter::new("token", Key::cl_type()),
+            Parameter::new("pair", Key::cl_type()),
+            Parameter::new("amount_to_repay", U256::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
+
+#[no_mangle]
+fn call() {
+    let contract_name: alloc::string::String = runtime::get_named_arg("contract_name");
+    let (package_hash, access_token) = storage::create_contract_package_at_hash();
+    let (contract_hash, _) =
+        storage::add_contract_version(package_hash, get_entry_points(), Default::default());
+
+    // Add the constructor group to the package hash with a single URef.
+    let constructor_access: URef =
+        storage::create_contract_user_group(package_hash, "constructor", 1, Default::default())
+            .unwrap_or_revert()
+            .pop()
+            .unwrap_or_revert();
+
+    // Call the constructor entry point
+    let _: () = runtime::call_versioned_contract(
+        package_hash,
+        None,
+        "constructor",
+        runtime_args! {"package_hash" => package_hash},
+    );
+
+    // Remove all URefs from the constructor group, so no one can call it for the second time.
+    let mut urefs = BTreeSet::new();
+    urefs.insert(constructor_access);
+    storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
+        .unwrap_or_revert();
+
+    // Store contract in the account's named keys.
+    runtime::put_key(
+        &format!("{}_package_hash", contract_name),
+        package_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_hash_wrapped", contract_name),
+        storage::new_uref(package_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash_wrapped", contract_name),
+        storage::new_uref(contract_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_access_token", contract_name),
+        access_token.into(),
+    );
+}
@@ -1,9 +1,9 @@
 use alloc::string::String;
 
-use casper_contract::contract_api::runtime;
-use casper_types::{runtime_args, ApiError, ContractHash, Key, RuntimeArgs, U256};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{runtime_args, ContractHash, Key, RuntimeArgs, U256};
 use contract_utils::set_key;
-use contract_utils::{ContractContext, ContractStorage};
+use contract_utils::{key_to_contract_hash, ContractContext, ContractStorage};
 
 use crate::data::{self};
 
@@ -14,12 +14,7 @@ pub trait TEST<Storage: ContractStorage>: ContractContext<Storage> {
     }
 
     fn mint_with_caller(&mut self, caller: Key, recipient: Key, amount: U256) {
-        let caller_hash_add_array = match caller {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-
-        let caller_hash_add = ContractHash::new(caller_hash_add_array);
+        let caller_hash_add: ContractHash = key_to_contract_hash(caller).unwrap_or_revert();
 
         let _ret: () = runtime::call_contract(
             caller_hash_add,
@@ -29,12 +24,7 @@ pub trait TEST<Storage: ContractStorage>: ContractContext<Storage> {
     }
 
     fn pair_mint(&mut self, caller: Key, recipient: Key, amount: U256) {
-        let caller_hash_add_array = match caller {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-
-        let caller_hash_add = ContractHash::new(caller_hash_add_array);
+        let caller_hash_add: ContractHash = key_to_contract_hash(caller).unwrap_or_revert();
 
         let _ret: () = runtime::call_contract(
             caller_hash_add,
@@ -44,12 +34,7 @@ pub trait TEST<Storage: ContractStorage>: ContractContext<Storage> {
     }
 
     fn balance(&mut self, token: Key, owner: Key) {
-        let token_hash_add_array = match token {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-
-        let token_hash_add = ContractHash::new(token_hash_add_array);
+        let token_hash_add: ContractHash = key_to_contract_hash(token).unwrap_or_revert();
 
         let balance: U256 = runtime::call_contract(
             token_hash_add,
@@ -60,24 +45,14 @@ pub trait TEST<Storage: ContractStorage>: ContractContext<Storage> {
     }
 
     fn token0(&mut self, pair: Key) {
-        let pair_hash_add_array = match pair {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-
-        let pair_hash_add = ContractHash::new(pair_hash_add_array);
+        let pair_hash_add: ContractHash = key_to_contract_hash(pair).unwrap_or_revert();
 
         let token0: Key = runtime::call_contract(pair_hash_add, "token0", runtime_args! {});
         set_key("token0", token0);
     }
 
     fn token1(&mut self, pair: Key) {
-        let pair_hash_add_array = match pair {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-
-        let pair_hash_add = ContractHash::new(pair_hash_add_array);
+        let pair_hash_add: ContractHash = key_to_contract_hash(pair).unwrap_or_revert();
 
         let token1: Key = runtime::call_contract(pair_hash_add, "token1", runtime_args! {});
         set_key("token1", token1);
@@ -86,11 +61,7 @@ pub trait TEST<Storage: ContractStorage>: ContractContext<Storage> {
 
     // FACTORY METHOD
     fn create_pair(&mut self, token_a: Key, token_b: Key, pair_hash: Key, factory_hash: Key) {
-        let factory_hash_add_array = match factory_hash {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let factory_hash_add = ContractHash::new(factory_hash_add_array);
+        let factory_hash_add: ContractHash = key_to_contract_hash(factory_hash).unwrap_or_revert();
         let _create_pair: () = runtime::call_contract(
             factory_hash_add,
             "create_pair",
@@ -103,20 +74,12 @@ pub trait TEST<Storage: ContractStorage>: ContractContext<Storage> {
     }
 
     fn sync(&mut self, pair_hash: Key) {
-        let pair_hash_add_array = match pair_hash {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let pair_hash_add = ContractHash::new(pair_hash_add_array);
+        let pair_hash_add: ContractHash = key_to_contract_hash(pair_hash).unwrap_or_revert();
         let _fee_to: () = runtime::call_contract(pair_hash_add, "sync", runtime_args! {});
     }
 
     fn set_fee_to(&mut self, fee_to: Key, factory_hash: Key) {
-        let factory_hash_add_array = match factory_hash {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let factory_hash_add = ContractHash::new(factory_hash_add_array);
+        let factory_hash_add: ContractHash = key_to_contract_hash(factory_hash).unwrap_or_revert();
         let _fee_to: () = runtime::call_contract(
             factory_hash_add,
             "set_fee_to",
@@ -0,0 +1,145 @@
+use blake2::{
+    digest::{Update, VariableOutput},
+    VarBlake2b,
+};
+use casper_types::{
+    bytesrepr::{Bytes, ToBytes},
+    runtime_args, Key, RuntimeArgs, U256,
+};
+use test_env::{Sender, TestContract, TestEnv};
+
+pub struct GOVERNORInstance(pub TestContract);
+
+impl GOVERNORInstance {
+    pub fn new<T: Into<Key>>(
+        env: &TestEnv,
+        contract_name: &str,
+        sender: Sender,
+        token: T,
+        voting_delay: u64,
+        voting_period: u64,
+        quorum_votes: U256,
+        proposal_threshold: U256,
+        timelock_delay: u64,
+    ) -> GOVERNORInstance {
+        GOVERNORInstance(TestContract::new(
+            env,
+            "governor.wasm",
+            contract_name,
+            sender,
+            runtime_args! {
+                "token" => token.into(),
+                "voting_delay" => voting_delay,
+                "voting_period" => voting_period,
+                "quorum_votes" => quorum_votes,
+                "proposal_threshold" => proposal_threshold,
+                "timelock_delay" => timelock_delay
+            },
+        ))
+    }
+
+    pub fn propose(
+        &self,
+        sender: Sender,
+        calls: Vec<(Key, String, Bytes)>,
+        description: &str,
+        time: u64,
+    ) {
+        self.0.call_contract_with_time(
+            sender,
+            "propose",
+            runtime_args! {
+                "calls" => calls,
+                "description" => description
+            },
+            time,
+        );
+    }
+
+    pub fn cast_vote(&self, sender: Sender, id: U256, support: bool, time: u64) {
+        self.0.call_contract_with_time(
+            sender,
+            "cast_vote",
+            runtime_args! {
+                "id" => id,
+                "support" => support
+            },
+            time,
+        );
+    }
+
+    pub fn queue(&self, sender: Sender, id: U256, time: u64) {
+        self.0
+            .call_contract_with_time(sender, "queue", runtime_args! {"id" => id}, time);
+    }
+
+    pub fn execute(&self, sender: Sender, id: U256, time: u64) {
+        self.0
+            .call_contract_with_time(sender, "execute", runtime_args! {"id" => id}, time);
+    }
+
+    pub fn cancel(&self, sender: Sender, id: U256, time: u64) {
+        self.0
+            .call_contract_with_time(sender, "cancel", runtime_args! {"id" => id}, time);
+    }
+
+    pub fn proposal_count(&self) -> U256 {
+        self.0.query_named_key(String::from("proposal_count"))
+    }
+
+    pub fn for_votes(&self, id: U256) -> U256 {
+        self.0
+            .query_dictionary("proposal_for_votes", id.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn against_votes(&self, id: U256) -> U256 {
+        self.0
+            .query_dictionary("proposal_against_votes", id.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn eta(&self, id: U256) -> u64 {
+        self.0
+            .query_dictionary("proposal_etas", id.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn executed(&self, id: U256) -> bool {
+        self.0
+            .query_dictionary("proposal_executed", id.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn canceled(&self, id: U256) -> bool {
+        self.0
+            .query_dictionary("proposal_canceled", id.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn receipt<T: Into<Key>>(&self, id: U256, voter: T) -> Option<(bool, U256)> {
+        self.0
+            .query_dictionary("receipts", receipt_key(id, &voter.into()))
+    }
+}
+
+pub fn call<T: Into<Key>>(
+    contract: T,
+    entry_point: &str,
+    args: RuntimeArgs,
+) -> (Key, String, Bytes) {
+    (
+        contract.into(),
+        String::from(entry_point),
+        Bytes::from(args.to_bytes().unwrap()),
+    )
+}
+
+pub fn receipt_key(id: U256, voter: &Key) -> String {
+    let mut hasher = VarBlake2b::new(32).unwrap();
+    hasher.update(id.to_bytes().unwrap());
+    hasher.update(voter.to_bytes().unwrap());
+    let mut ret = [0u8; 32];
+    hasher.finalize_variable(|hash| ret.clone_from_slice(hash));
+    hex::encode(ret)
+}
@@ -0,0 +1,307 @@
+use casper_engine_test_support::AccountHash;
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs, U256};
+use test_env::{Sender, TestContract, TestEnv};
+
+use crate::governor_instance::{call, GOVERNORInstance};
+
+const NAME_GOVERNOR: &str = "Governor";
+const VOTING_DELAY: u64 = 1000;
+const VOTING_PERIOD: u64 = 10000;
+const QUORUM_VOTES: u64 = 400;
+const PROPOSAL_THRESHOLD: u64 = 100;
+const TIMELOCK_DELAY: u64 = 5000;
+const GUARDIAN: u8 = 2;
+
+/// Times in milliseconds of the proposal made by `propose`.
+const PROPOSED_AT: u64 = 2000;
+const VOTING_START: u64 = PROPOSED_AT + VOTING_DELAY;
+const VOTING_END: u64 = VOTING_START + VOTING_PERIOD;
+
+/// A governance token with 1000 tokens, 700 of them delegated by the owner to itself
+/// and 300 by `alice` to itself, and a factory governed by the governor.
+struct Dao {
+    env: TestEnv,
+    owner: AccountHash,
+    alice: AccountHash,
+    token: TestContract,
+    factory: TestContract,
+    governor: GOVERNORInstance,
+}
+
+fn deploy() -> Dao {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let alice = env.next_user();
+    let token = TestContract::new(
+        &env,
+        "erc20-token.wasm",
+        "token",
+        Sender(owner),
+        runtime_args! {
+            "initial_supply" => U256::from(1000),
+            "name" => "Governance",
+            "symbol" => "gov",
+            "decimals" => 18u8
+        },
+    );
+    let governor = GOVERNORInstance::new(
+        &env,
+        NAME_GOVERNOR,
+        Sender(owner),
+        Key::Hash(token.contract_hash()),
+        VOTING_DELAY,
+        VOTING_PERIOD,
+        QUORUM_VOTES.into(),
+        PROPOSAL_THRESHOLD.into(),
+        TIMELOCK_DELAY,
+    );
+    let governor_package: ContractPackageHash = governor
+        .0
+        .query_named_key(String::from("contract_package_hash"));
+    let factory = TestContract::new(
+        &env,
+        "factory.wasm",
+        "factory",
+        Sender(owner),
+        runtime_args! {"fee_to_setter" => Key::from(governor_package)},
+    );
+    factory.call_contract(
+        Sender(owner),
+        "grant_role",
+        runtime_args! {"role" => GUARDIAN, "account" => Key::from(governor_package)},
+    );
+    token.call_contract_with_time(
+        Sender(owner),
+        "transfer",
+        runtime_args! {"recipient" => Key::from(alice), "amount" => U256::from(300)},
+        1000,
+    );
+    delegate(&token, owner, owner, 1000);
+    delegate(&token, alice, alice, 1000);
+    Dao {
+        env,
+        owner,
+        alice,
+        token,
+        factory,
+        governor,
+    }
+}
+
+fn delegate(token: &TestContract, sender: AccountHash, delegatee: AccountHash, time: u64) {
+    token.call_contract_with_time(
+        Sender(sender),
+        "delegate",
+        runtime_args! {"delegatee" => Key::from(delegatee)},
+        time,
+    );
+}
+
+/// The owner proposes to set `fee_to` of the factory and to pause it, as proposal 0.
+fn propose(dao: &Dao, fee_to: AccountHash) {
+    dao.governor.propose(
+        Sender(dao.owner),
+        vec![
+            call(
+                Key::Hash(dao.factory.contract_hash()),
+                "set_fee_to",
+                runtime_args! {"fee_to" => Key::from(fee_to)},
+            ),
+            call(
+                Key::Hash(dao.factory.contract_hash()),
+                "pause",
+                runtime_args! {},
+            ),
+        ],
+        "Turn the protocol fee on and pause the pairs",
+        PROPOSED_AT,
+    );
+}
+
+#[test]
+fn test_governor_propose_vote_queue_execute() {
+    let dao = deploy();
+    let fee_to = dao.env.next_user();
+    propose(&dao, fee_to);
+    assert_eq!(dao.governor.proposal_count(), 1.into());
+    let id: U256 = 0.into();
+    dao.governor
+        .cast_vote(Sender(dao.owner), id, true, VOTING_START + 1);
+    dao.governor
+        .cast_vote(Sender(dao.alice), id, false, VOTING_START + 1);
+    assert_eq!(dao.governor.for_votes(id), 700.into());
+    assert_eq!(dao.governor.against_votes(id), 300.into());
+    assert_eq!(
+        dao.governor.receipt(id, dao.alice),
+        Some((false, 300.into()))
+    );
+    dao.governor.queue(Sender(dao.alice), id, VOTING_END + 1);
+    let eta: u64 = VOTING_END + 1 + TIMELOCK_DELAY;
+    assert_eq!(dao.governor.eta(id), eta);
+    // Anyone can execute a queued proposal once its eta has passed.
+    let keeper = dao.env.next_user();
+    dao.governor.execute(Sender(keeper), id, eta);
+    assert!(dao.governor.executed(id));
+    let factory_fee_to: Key = dao.factory.query_named_key(String::from("fee_to"));
+    assert_eq!(factory_fee_to, Key::from(fee_to));
+    let paused: bool = dao.factory.query_named_key(String::from("paused"));
+    assert!(paused);
+}
+
+#[test]
+fn test_governor_votes_are_taken_when_voting_starts() {
+    let dao = deploy();
+    let fee_to = dao.env.next_user();
+    propose(&dao, fee_to);
+    let id: U256 = 0.into();
+    // Moving tokens once voting has started does not change the votes on the proposal.
+    dao.token.call_contract_with_time(
+        Sender(dao.owner),
+        "transfer",
+        runtime_args! {"recipient" => Key::from(dao.alice), "amount" => U256::from(500)},
+        VOTING_START + 1,
+    );
+    dao.governor
+        .cast_vote(Sender(dao.owner), id, true, VOTING_START + 2);
+    dao.governor
+        .cast_vote(Sender(dao.alice), id, true, VOTING_START + 2);
+    assert_eq!(dao.governor.for_votes(id), 1000.into());
+    assert_eq!(
+        dao.governor.receipt(id, dao.owner),
+        Some((true, 700.into()))
+    );
+}
+
+#[test]
+fn test_governor_cancel() {
+    let dao = deploy();
+    let fee_to = dao.env.next_user();
+    propose(&dao, fee_to);
+    let id: U256 = 0.into();
+    dao.governor.cancel(Sender(dao.owner), id, VOTING_START);
+    assert!(dao.governor.canceled(id));
+}
+
+#[test]
+#[should_panic]
+fn test_governor_vote_on_canceled_proposal() {
+    let dao = deploy();
+    let fee_to = dao.env.next_user();
+    propose(&dao, fee_to);
+    let id: U256 = 0.into();
+    dao.governor.cancel(Sender(dao.owner), id, VOTING_START);
+    dao.governor
+        .cast_vote(Sender(dao.owner), id, true, VOTING_START + 1);
+}
+
+#[test]
+#[should_panic]
+fn test_governor_cancel_by_other_than_proposer() {
+    let dao = deploy();
+    let fee_to = dao.env.next_user();
+    propose(&dao, fee_to);
+    dao.governor
+        .cancel(Sender(dao.alice), 0.into(), VOTING_START);
+}
+
+#[test]
+#[should_panic]
+fn test_governor_propose_below_threshold() {
+    let dao = deploy();
+    let user = dao.env.next_user();
+    dao.token.call_contract_with_time(
+        Sender(dao.owner),
+        "transfer",
+        runtime_args! {"recipient" => Key::from(user), "amount" => U256::from(PROPOSAL_THRESHOLD)},
+        1000,
+    );
+    delegate(&dao.token, user, user, 1000);
+    dao.governor.propose(
+        Sender(user),
+        vec![call(
+            Key::Hash(dao.factory.contract_hash()),
+            "pause",
+            runtime_args! {},
+        )],
+        "Pause the pairs",
+        PROPOSED_AT,
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_governor_vote_before_voting_starts() {
+    let dao = deploy();
+    let fee_to = dao.env.next_user();
+    propose(&dao, fee_to);
+    dao.governor
+        .cast_vote(Sender(dao.owner), 0.into(), true, VOTING_START);
+}
+
+#[test]
+#[should_panic]
+fn test_governor_vote_twice() {
+    let dao = deploy();
+    let fee_to = dao.env.next_user();
+    propose(&dao, fee_to);
+    dao.governor
+        .cast_vote(Sender(dao.alice), 0.into(), true, VOTING_START + 1);
+    dao.governor
+        .cast_vote(Sender(dao.alice), 0.into(), true, VOTING_START + 2);
+}
+
+#[test]
+#[should_panic]
+fn test_governor_queue_defeated_proposal() {
+    let dao = deploy();
+    let fee_to = dao.env.next_user();
+    propose(&dao, fee_to);
+    dao.governor
+        .cast_vote(Sender(dao.owner), 0.into(), false, VOTING_START + 1);
+    dao.governor
+        .cast_vote(Sender(dao.alice), 0.into(), true, VOTING_START + 1);
+    dao.governor
+        .queue(Sender(dao.owner), 0.into(), VOTING_END + 1);
+}
+
+#[test]
+#[should_panic]
+fn test_governor_queue_without_quorum() {
+    let dao = deploy();
+    let fee_to = dao.env.next_user();
+    propose(&dao, fee_to);
+    // 300 votes for and none against is still below the quorum of 400.
+    dao.governor
+        .cast_vote(Sender(dao.alice), 0.into(), true, VOTING_START + 1);
+    dao.governor
+        .queue(Sender(dao.owner), 0.into(), VOTING_END + 1);
+}
+
+#[test]
+#[should_panic]
+fn test_governor_execute_before_eta() {
+    let dao = deploy();
+    let fee_to = dao.env.next_user();
+    propose(&dao, fee_to);
+    dao.governor
+        .cast_vote(Sender(dao.owner), 0.into(), true, VOTING_START + 1);
+    dao.governor
+        .queue(Sender(dao.owner), 0.into(), VOTING_END + 1);
+    dao.governor
+        .execute(Sender(dao.owner), 0.into(), VOTING_END + TIMELOCK_DELAY);
+}
+
+#[test]
+#[should_panic]
+fn test_governor_execute_twice() {
+    let dao = deploy();
+    let fee_to = dao.env.next_user();
+    propose(&dao, fee_to);
+    dao.governor
+        .cast_vote(Sender(dao.owner), 0.into(), true, VOTING_START + 1);
+    dao.governor
+        .queue(Sender(dao.owner), 0.into(), VOTING_END + 1);
+    let eta: u64 = VOTING_END + 1 + TIMELOCK_DELAY;
+    dao.governor.execute(Sender(dao.owner), 0.into(), eta);
+    dao.governor.execute(Sender(dao.owner), 0.into(), eta + 1);
+}
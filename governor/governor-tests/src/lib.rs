@@ -0,0 +1,5 @@
+#[cfg(test)]
+pub mod governor_tests;
+
+#[cfg(test)]
+pub mod governor_instance;
@@ -0,0 +1,362 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{collections::BTreeSet, format, string::String, vec, vec::Vec};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    runtime_args, CLTyped, CLValue, ContractHash, ContractPackageHash, EntryPoint,
+    EntryPointAccess, EntryPointType, EntryPoints, Group, Key, Parameter, RuntimeArgs, URef, U256,
+};
+use contract_utils::{ContractContext, OnChainContractStorage};
+use governor::{
+    self,
+    data::{Call, Receipt},
+    GOVERNOR,
+};
+
+#[derive(Default)]
+struct Governor(OnChainContractStorage);
+
+impl ContractContext<OnChainContractStorage> for Governor {
+    fn storage(&self) -> &OnChainContractStorage {
+        &self.0
+    }
+}
+
+impl GOVERNOR<OnChainContractStorage> for Governor {}
+
+impl Governor {
+    fn constructor(
+        &mut self,
+        token: Key,
+        voting_delay: u64,
+        voting_period: u64,
+        quorum_votes: U256,
+        proposal_threshold: U256,
+        timelock_delay: u64,
+        contract_hash: ContractHash,
+        package_hash: ContractPackageHash,
+    ) {
+        GOVERNOR::init(
+            self,
+            token,
+            voting_delay,
+            voting_period,
+            quorum_votes,
+            proposal_threshold,
+            timelock_delay,
+            Key::from(contract_hash),
+            package_hash,
+        );
+    }
+}
+
+#[no_mangle]
+fn constructor() {
+    let token: Key = runtime::get_named_arg("token");
+    let voting_delay: u64 = runtime::get_named_arg("voting_delay");
+    let voting_period: u64 = runtime::get_named_arg("voting_period");
+    let quorum_votes: U256 = runtime::get_named_arg("quorum_votes");
+    let proposal_threshold: U256 = runtime::get_named_arg("proposal_threshold");
+    let timelock_delay: u64 = runtime::get_named_arg("timelock_delay");
+    let contract_hash: ContractHash = runtime::get_named_arg("contract_hash");
+    let package_hash: ContractPackageHash = runtime::get_named_arg("package_hash");
+    Governor::default().constructor(
+        token,
+        voting_delay,
+        voting_period,
+        quorum_votes,
+        proposal_threshold,
+        timelock_delay,
+        contract_hash,
+        package_hash,
+    );
+}
+
+/// This function is to propose calls to other contracts that are made by this contract once the proposal passes, it returns the id of the proposal
+///
+/// # Parameters
+///
+/// * `calls` - A list of tuples that each hold the Hash of a contract, the name of an entry point that returns nothing and the serialized runtime args of the call
+///
+/// * `description` - A String that holds the description of the proposal
+///
+
+#[no_mangle]
+fn propose() {
+    let calls: Vec<Call> = runtime::get_named_arg("calls");
+    let description: String = runtime::get_named_arg("description");
+    let ret: U256 = Governor::default().propose(calls, description);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to vote for or against an active proposal with the votes the caller had when voting started
+///
+/// # Parameters
+///
+/// * `id` - A U256 that holds the id of the proposal
+///
+/// * `support` - A bool that holds true to vote for the proposal and false to vote against it
+///
+
+#[no_mangle]
+fn cast_vote() {
+    let id: U256 = runtime::get_named_arg("id");
+    let support: bool = runtime::get_named_arg("support");
+    Governor::default().cast_vote(id, support);
+}
+
+/// This function is to queue a succeeded proposal, which can be executed once the timelock delay has passed
+///
+/// # Parameters
+///
+/// * `id` - A U256 that holds the id of the proposal
+///
+
+#[no_mangle]
+fn queue() {
+    let id: U256 = runtime::get_named_arg("id");
+    Governor::default().queue(id);
+}
+
+/// This function is to make the calls of a queued proposal whose eta has passed
+///
+/// # Parameters
+///
+/// * `id` - A U256 that holds the id of the proposal
+///
+
+#[no_mangle]
+fn execute() {
+    let id: U256 = runtime::get_named_arg("id");
+    Governor::default().execute(id);
+}
+
+/// This function is to cancel a proposal that has not been executed, which only its proposer can do
+///
+/// # Parameters
+///
+/// * `id` - A U256 that holds the id of the proposal
+///
+
+#[no_mangle]
+fn cancel() {
+    let id: U256 = runtime::get_named_arg("id");
+    Governor::default().cancel(id);
+}
+
+/// This function is to return the state of a proposal, 0 Pending, 1 Active, 2 Canceled, 3 Defeated, 4 Succeeded, 5 Queued, 6 Expired or 7 Executed
+///
+/// # Parameters
+///
+/// * `id` - A U256 that holds the id of the proposal
+///
+
+#[no_mangle]
+fn state() {
+    let id: U256 = runtime::get_named_arg("id");
+    let ret: u8 = Governor::default().state(id) as u8;
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return how a voter voted on a proposal and with how many votes, None if it has not voted
+///
+/// # Parameters
+///
+/// * `id` - A U256 that holds the id of the proposal
+///
+/// * `voter` - A Key that holds the account address or package hash of the voter
+///
+
+#[no_mangle]
+fn get_receipt() {
+    let id: U256 = runtime::get_named_arg("id");
+    let voter: Key = runtime::get_named_arg("voter");
+    let ret: Option<Receipt> = Governor::default().get_receipt(id, voter);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the number of proposals made
+///
+
+#[no_mangle]
+fn proposal_count() {
+    let ret: U256 = Governor::default().get_proposal_count();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to fetch a Contract Package Hash
+///
+
+#[no_mangle]
+fn package_hash() {
+    let ret: ContractPackageHash = Governor::default().get_package_hash();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new("token", Key::cl_type()),
+            Parameter::new("voting_delay", u64::cl_type()),
+            Parameter::new("voting_period", u64::cl_type()),
+            Parameter::new("quorum_votes", U256::cl_type()),
+            Parameter::new("proposal_threshold", U256::cl_type()),
+            Parameter::new("timelock_delay", u64::cl_type()),
+            Parameter::new("contract_hash", ContractHash::cl_type()),
+            Parameter::new("package_hash", ContractPackageHash::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "propose",
+        vec![
+            Parameter::new("calls", Vec::<Call>::cl_type()),
+            Parameter::new("description", String::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "cast_vote",
+        vec![
+            Parameter::new("id", U256::cl_type()),
+            Parameter::new("support", bool::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "queue",
+        vec![Parameter::new("id", U256::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "execute",
+        vec![Parameter::new("id", U256::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "cancel",
+        vec![Parameter::new("id", U256::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "state",
+        vec![Parameter::new("id", U256::cl_type())],
+        u8::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_receipt",
+        vec![
+            Parameter::new("id", U256::cl_type()),
+            Parameter::new("voter", Key::cl_type()),
+        ],
+        Option::<Receipt>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "proposal_count",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "package_hash",
+        vec![],
+        ContractPackageHash::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
+
+#[no_mangle]
+fn call() {
+    // Contract name must be same for all new versions of the contracts
+    let contract_name: alloc::string::String = runtime::get_named_arg("contract_name");
+
+    // Build new package with initial a first version of the contract.
+    let (package_hash, access_token) = storage::create_contract_package_at_hash();
+    let (contract_hash, _) =
+        storage::add_contract_version(package_hash, get_entry_points(), Default::default());
+
+    let token: Key = runtime::get_named_arg("token");
+    let voting_delay: u64 = runtime::get_named_arg("voting_delay");
+    let voting_period: u64 = runtime::get_named_arg("voting_period");
+    let quorum_votes: U256 = runtime::get_named_arg("quorum_votes");
+    let proposal_threshold: U256 = runtime::get_named_arg("proposal_threshold");
+    let timelock_delay: u64 = runtime::get_named_arg("timelock_delay");
+
+    // Prepare constructor args
+    let constructor_args = runtime_args! {
+        "token" => token,
+        "voting_delay" => voting_delay,
+        "voting_period" => voting_period,
+        "quorum_votes" => quorum_votes,
+        "proposal_threshold" => proposal_threshold,
+        "timelock_delay" => timelock_delay,
+        "contract_hash" => contract_hash,
+        "package_hash"=> package_hash
+    };
+
+    // Add the constructor group to the package hash with a single URef.
+    let constructor_access: URef =
+        storage::create_contract_user_group(package_hash, "constructor", 1, Default::default())
+            .unwrap_or_revert()
+            .pop()
+            .unwrap_or_revert();
+
+    // Call the constructor entry point
+    let _: () =
+        runtime::call_versioned_contract(package_hash, None, "constructor", constructor_args);
+
+    // Remove all URefs from the constructor group, so no one can call it for the second time.
+    let mut urefs = BTreeSet::new();
+    urefs.insert(constructor_access);
+    storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
+        .unwrap_or_revert();
+
+    // Store contract in the account's named keys.
+    runtime::put_key(
+        &format!("{}_package_hash", contract_name),
+        package_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_hash_wrapped", contract_name),
+        storage::new_uref(package_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash_wrapped", contract_name),
+        storage::new_uref(contract_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_access_token", contract_name),
+        access_token.into(),
+    );
+}
@@ -0,0 +1,271 @@
+use crate::alloc::string::ToString;
+use alloc::{string::String, vec::Vec};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    bytesrepr::{Bytes, ToBytes},
+    ContractPackageHash, Key, U256,
+};
+use contract_utils::{get_key, set_key, Dict, ZERO_ACCOUNT};
+
+pub const SELF_CONTRACT_HASH: &str = "self_contract_hash";
+pub const CONTRACT_PACKAGE_HASH: &str = "contract_package_hash";
+pub const TOKEN: &str = "token";
+pub const VOTING_DELAY: &str = "voting_delay";
+pub const VOTING_PERIOD: &str = "voting_period";
+pub const QUORUM_VOTES: &str = "quorum_votes";
+pub const PROPOSAL_THRESHOLD: &str = "proposal_threshold";
+pub const TIMELOCK_DELAY: &str = "timelock_delay";
+pub const PROPOSAL_COUNT: &str = "proposal_count";
+pub const PROPOSAL_PROPOSERS_DICT: &str = "proposal_proposers";
+pub const PROPOSAL_CALLS_DICT: &str = "proposal_calls";
+pub const PROPOSAL_STARTS_DICT: &str = "proposal_starts";
+pub const PROPOSAL_ENDS_DICT: &str = "proposal_ends";
+pub const PROPOSAL_ETAS_DICT: &str = "proposal_etas";
+pub const PROPOSAL_FOR_VOTES_DICT: &str = "proposal_for_votes";
+pub const PROPOSAL_AGAINST_VOTES_DICT: &str = "proposal_against_votes";
+pub const PROPOSAL_CANCELED_DICT: &str = "proposal_canceled";
+pub const PROPOSAL_EXECUTED_DICT: &str = "proposal_executed";
+pub const RECEIPTS_DICT: &str = "receipts";
+
+/// Time in milliseconds after its eta during which a queued proposal can be executed,
+/// after that it has expired.
+pub const GRACE_PERIOD: u64 = 14 * 24 * 60 * 60 * 1000;
+
+/// `(contract, entry_point, args)`: the Hash of a contract, the name of one of its entry
+/// points and the serialized runtime args to call it with, as in the multicall.
+pub type Call = (Key, String, Bytes);
+
+/// `(support, votes)`: how a voter voted on a proposal and with how many votes.
+pub type Receipt = (bool, U256);
+
+/// State of a proposal, as returned by the `state` entry point.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProposalState {
+    Pending = 0,
+    Active = 1,
+    Canceled = 2,
+    Defeated = 3,
+    Succeeded = 4,
+    Queued = 5,
+    Expired = 6,
+    Executed = 7,
+}
+
+/// Every proposal, indexed by proposal id. Proposal ids are given out in the order the
+/// proposals are made, starting from 0.
+pub struct Proposals {
+    proposers: Dict,
+    calls: Dict,
+    starts: Dict,
+    ends: Dict,
+    etas: Dict,
+    for_votes: Dict,
+    against_votes: Dict,
+    canceled: Dict,
+    executed: Dict,
+}
+
+impl Proposals {
+    pub fn instance() -> Proposals {
+        Proposals {
+            proposers: Dict::instance(PROPOSAL_PROPOSERS_DICT),
+            calls: Dict::instance(PROPOSAL_CALLS_DICT),
+            starts: Dict::instance(PROPOSAL_STARTS_DICT),
+            ends: Dict::instance(PROPOSAL_ENDS_DICT),
+            etas: Dict::instance(PROPOSAL_ETAS_DICT),
+            for_votes: Dict::instance(PROPOSAL_FOR_VOTES_DICT),
+            against_votes: Dict::instance(PROPOSAL_AGAINST_VOTES_DICT),
+            canceled: Dict::instance(PROPOSAL_CANCELED_DICT),
+            executed: Dict::instance(PROPOSAL_EXECUTED_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(PROPOSAL_PROPOSERS_DICT);
+        Dict::init(PROPOSAL_CALLS_DICT);
+        Dict::init(PROPOSAL_STARTS_DICT);
+        Dict::init(PROPOSAL_ENDS_DICT);
+        Dict::init(PROPOSAL_ETAS_DICT);
+        Dict::init(PROPOSAL_FOR_VOTES_DICT);
+        Dict::init(PROPOSAL_AGAINST_VOTES_DICT);
+        Dict::init(PROPOSAL_CANCELED_DICT);
+        Dict::init(PROPOSAL_EXECUTED_DICT);
+    }
+
+    pub fn add(&self, id: U256, proposer: Key, calls: Vec<Call>, start: u64, end: u64) {
+        let id: &str = &id.to_string();
+        self.proposers.set(id, proposer);
+        self.calls.set(id, calls);
+        self.starts.set(id, start);
+        self.ends.set(id, end);
+        self.etas.set(id, 0u64);
+        self.for_votes.set(id, U256::zero());
+        self.against_votes.set(id, U256::zero());
+        self.canceled.set(id, false);
+        self.executed.set(id, false);
+    }
+
+    pub fn proposer(&self, id: U256) -> Key {
+        self.proposers.get(&id.to_string()).unwrap_or(ZERO_ACCOUNT)
+    }
+
+    pub fn calls(&self, id: U256) -> Vec<Call> {
+        self.calls.get(&id.to_string()).unwrap_or_default()
+    }
+
+    pub fn start(&self, id: U256) -> u64 {
+        self.starts.get(&id.to_string()).unwrap_or_default()
+    }
+
+    pub fn end(&self, id: U256) -> u64 {
+        self.ends.get(&id.to_string()).unwrap_or_default()
+    }
+
+    /// Time in milliseconds from which a queued proposal can be executed, 0 until it is
+    /// queued.
+    pub fn eta(&self, id: U256) -> u64 {
+        self.etas.get(&id.to_string()).unwrap_or_default()
+    }
+
+    pub fn set_eta(&self, id: U256, eta: u64) {
+        self.etas.set(&id.to_string(), eta);
+    }
+
+    pub fn for_votes(&self, id: U256) -> U256 {
+        self.for_votes.get(&id.to_string()).unwrap_or_default()
+    }
+
+    pub fn set_for_votes(&self, id: U256, for_votes: U256) {
+        self.for_votes.set(&id.to_string(), for_votes);
+    }
+
+    pub fn against_votes(&self, id: U256) -> U256 {
+        self.against_votes.get(&id.to_string()).unwrap_or_default()
+    }
+
+    pub fn set_against_votes(&self, id: U256, against_votes: U256) {
+        self.against_votes.set(&id.to_string(), against_votes);
+    }
+
+    pub fn canceled(&self, id: U256) -> bool {
+        self.canceled.get(&id.to_string()).unwrap_or_default()
+    }
+
+    pub fn set_canceled(&self, id: U256) {
+        self.canceled.set(&id.to_string(), true);
+    }
+
+    pub fn executed(&self, id: U256) -> bool {
+        self.executed.get(&id.to_string()).unwrap_or_default()
+    }
+
+    pub fn set_executed(&self, id: U256) {
+        self.executed.set(&id.to_string(), true);
+    }
+}
+
+/// Vote of every voter on every proposal, indexed by the proposal id and the voter.
+pub struct Receipts {
+    dict: Dict,
+}
+
+impl Receipts {
+    pub fn instance() -> Receipts {
+        Receipts {
+            dict: Dict::instance(RECEIPTS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(RECEIPTS_DICT)
+    }
+
+    pub fn get(&self, id: U256, voter: &Key) -> Option<Receipt> {
+        self.dict.get(&receipt_key(id, voter))
+    }
+
+    pub fn set(&self, id: U256, voter: &Key, receipt: Receipt) {
+        self.dict.set(&receipt_key(id, voter), receipt);
+    }
+}
+
+/// Dictionary keys are limited in length, so a receipt is stored under the hash of the
+/// proposal id and the voter.
+fn receipt_key(id: U256, voter: &Key) -> String {
+    let mut bytes: Vec<u8> = id.to_bytes().unwrap_or_revert();
+    bytes.append(&mut voter.to_bytes().unwrap_or_revert());
+    hex::encode(runtime::blake2b(bytes))
+}
+
+pub fn set_hash(contract_hash: Key) {
+    set_key(SELF_CONTRACT_HASH, contract_hash);
+}
+
+pub fn get_hash() -> Key {
+    get_key(SELF_CONTRACT_HASH).unwrap_or_revert()
+}
+
+pub fn set_package_hash(package_hash: ContractPackageHash) {
+    set_key(CONTRACT_PACKAGE_HASH, package_hash);
+}
+
+pub fn get_package_hash() -> ContractPackageHash {
+    get_key(CONTRACT_PACKAGE_HASH).unwrap_or_revert()
+}
+
+pub fn set_token(token: Key) {
+    set_key(TOKEN, token);
+}
+
+pub fn get_token() -> Key {
+    get_key(TOKEN).unwrap_or_revert()
+}
+
+pub fn set_voting_delay(voting_delay: u64) {
+    set_key(VOTING_DELAY, voting_delay);
+}
+
+pub fn get_voting_delay() -> u64 {
+    get_key(VOTING_DELAY).unwrap_or_default()
+}
+
+pub fn set_voting_period(voting_period: u64) {
+    set_key(VOTING_PERIOD, voting_period);
+}
+
+pub fn get_voting_period() -> u64 {
+    get_key(VOTING_PERIOD).unwrap_or_default()
+}
+
+pub fn set_quorum_votes(quorum_votes: U256) {
+    set_key(QUORUM_VOTES, quorum_votes);
+}
+
+pub fn get_quorum_votes() -> U256 {
+    get_key(QUORUM_VOTES).unwrap_or_default()
+}
+
+pub fn set_proposal_threshold(proposal_threshold: U256) {
+    set_key(PROPOSAL_THRESHOLD, proposal_threshold);
+}
+
+pub fn get_proposal_threshold() -> U256 {
+    get_key(PROPOSAL_THRESHOLD).unwrap_or_default()
+}
+
+pub fn set_timelock_delay(timelock_delay: u64) {
+    set_key(TIMELOCK_DELAY, timelock_delay);
+}
+
+pub fn get_timelock_delay() -> u64 {
+    get_key(TIMELOCK_DELAY).unwrap_or_default()
+}
+
+pub fn set_proposal_count(proposal_count: U256) {
+    set_key(PROPOSAL_COUNT, proposal_count);
+}
+
+pub fn get_proposal_count() -> U256 {
+    get_key(PROPOSAL_COUNT).unwrap_or_default()
+}
@@ -0,0 +1,342 @@
+use crate::alloc::string::ToString;
+use crate::data::{self, Call, ProposalState, Proposals, Receipt, Receipts};
+use alloc::collections::BTreeMap;
+use alloc::{string::String, vec::Vec};
+use casper_contract::contract_api::runtime;
+use casper_contract::contract_api::storage;
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{
+    bytesrepr, runtime_args, ContractHash, ContractPackageHash, Key, RuntimeArgs, URef, U256,
+};
+use contract_utils::{key_to_contract_hash, ContractContext, ContractStorage};
+use errors::GovernorError;
+
+pub enum GOVERNOREvent {
+    ProposalCreated {
+        id: U256,
+        proposer: Key,
+        start: u64,
+        end: u64,
+        description: String,
+    },
+    VoteCast {
+        id: U256,
+        voter: Key,
+        support: bool,
+        votes: U256,
+    },
+    ProposalCanceled {
+        id: U256,
+    },
+    ProposalQueued {
+        id: U256,
+        eta: u64,
+    },
+    ProposalExecuted {
+        id: U256,
+    },
+}
+impl GOVERNOREvent {
+    pub fn type_name(&self) -> String {
+        match self {
+            GOVERNOREvent::ProposalCreated {
+                id: _,
+                proposer: _,
+                start: _,
+                end: _,
+                description: _,
+            } => "proposal_created",
+            GOVERNOREvent::VoteCast {
+                id: _,
+                voter: _,
+                support: _,
+                votes: _,
+            } => "vote_cast",
+            GOVERNOREvent::ProposalCanceled { id: _ } => "proposal_canceled",
+            GOVERNOREvent::ProposalQueued { id: _, eta: _ } => "proposal_queued",
+            GOVERNOREvent::ProposalExecuted { id: _ } => "proposal_executed",
+        }
+        .to_string()
+    }
+}
+
+/// Proposals to call entry points of other contracts, voted on with the votes delegated
+/// on `token` and executed through a timelock. Contracts that want to be governed make
+/// the package hash of this contract their admin, e.g. the `fee_to_setter` or a
+/// `Guardian` of the factory. All times are block times in milliseconds.
+pub trait GOVERNOR<Storage: ContractStorage>: ContractContext<Storage> {
+    fn init(
+        &mut self,
+        token: Key,
+        voting_delay: u64,
+        voting_period: u64,
+        quorum_votes: U256,
+        proposal_threshold: U256,
+        timelock_delay: u64,
+        contract_hash: Key,
+        package_hash: ContractPackageHash,
+    ) {
+        data::set_token(token);
+        data::set_voting_delay(voting_delay);
+        data::set_voting_period(voting_period);
+        data::set_quorum_votes(quorum_votes);
+        data::set_proposal_threshold(proposal_threshold);
+        data::set_timelock_delay(timelock_delay);
+        data::set_proposal_count(0.into());
+        data::set_hash(contract_hash);
+        data::set_package_hash(package_hash);
+        Proposals::init();
+        Receipts::init();
+    }
+
+    /// Proposes `calls` and returns the id of the proposal. The caller must have had
+    /// more than the proposal threshold of votes just before this block. Voting starts
+    /// after the voting delay and lasts for the voting period.
+    fn propose(&mut self, calls: Vec<Call>, description: String) -> U256 {
+        if calls.is_empty() {
+            runtime::revert(GovernorError::GovernorNoCalls);
+        }
+        for call in calls.iter() {
+            decode_call(call);
+        }
+        let proposer: Key = self.get_caller();
+        let now: u64 = runtime::get_blocktime().into();
+        if prior_votes(proposer, now.saturating_sub(1)) <= data::get_proposal_threshold() {
+            runtime::revert(GovernorError::GovernorBelowProposalThreshold);
+        }
+        let start: u64 = now
+            .checked_add(data::get_voting_delay())
+            .ok_or(GovernorError::GovernorOverFlow)
+            .unwrap_or_revert();
+        let end: u64 = start
+            .checked_add(data::get_voting_period())
+            .ok_or(GovernorError::GovernorOverFlow)
+            .unwrap_or_revert();
+        let id: U256 = data::get_proposal_count();
+        Proposals::instance().add(id, proposer, calls, start, end);
+        data::set_proposal_count(id + 1);
+        self.emit(&GOVERNOREvent::ProposalCreated {
+            id: id,
+            proposer: proposer,
+            start: start,
+            end: end,
+            description: description,
+        });
+        id
+    }
+
+    /// Votes for or against an active proposal with the votes the caller had when
+    /// voting started.
+    fn cast_vote(&mut self, id: U256, support: bool) {
+        if self.state(id) != ProposalState::Active {
+            runtime::revert(GovernorError::GovernorNotActive);
+        }
+        let voter: Key = self.get_caller();
+        let receipts: Receipts = Receipts::instance();
+        if receipts.get(id, &voter).is_some() {
+            runtime::revert(GovernorError::GovernorAlreadyVoted);
+        }
+        let proposals: Proposals = Proposals::instance();
+        let votes: U256 = prior_votes(voter, proposals.start(id));
+        if support {
+            let for_votes: U256 = proposals
+                .for_votes(id)
+                .checked_add(votes)
+                .ok_or(GovernorError::GovernorOverFlow)
+                .unwrap_or_revert();
+            proposals.set_for_votes(id, for_votes);
+        } else {
+            let against_votes: U256 = proposals
+                .against_votes(id)
+                .checked_add(votes)
+                .ok_or(GovernorError::GovernorOverFlow)
+                .unwrap_or_revert();
+            proposals.set_against_votes(id, against_votes);
+        }
+        receipts.set(id, &voter, (support, votes));
+        self.emit(&GOVERNOREvent::VoteCast {
+            id: id,
+            voter: voter,
+            support: support,
+            votes: votes,
+        });
+    }
+
+    /// Queues a succeeded proposal, it can be executed once the timelock delay has passed.
+    fn queue(&mut self, id: U256) {
+        if self.state(id) != ProposalState::Succeeded {
+            runtime::revert(GovernorError::GovernorNotSucceeded);
+        }
+        let now: u64 = runtime::get_blocktime().into();
+        let eta: u64 = now
+            .checked_add(data::get_timelock_delay())
+            .ok_or(GovernorError::GovernorOverFlow)
+            .unwrap_or_revert();
+        Proposals::instance().set_eta(id, eta);
+        self.emit(&GOVERNOREvent::ProposalQueued { id: id, eta: eta });
+    }
+
+    /// Makes the calls of a queued proposal whose eta has passed, in order. The deploy
+    /// reverts as a whole if any of them fails. Anyone can execute a proposal.
+    fn execute(&mut self, id: U256) {
+        if self.state(id) != ProposalState::Queued {
+            runtime::revert(GovernorError::GovernorNotQueued);
+        }
+        let proposals: Proposals = Proposals::instance();
+        let now: u64 = runtime::get_blocktime().into();
+        if now < proposals.eta(id) {
+            runtime::revert(GovernorError::GovernorTimelockNotElapsed);
+        }
+        proposals.set_executed(id);
+        for call in proposals.calls(id) {
+            let (contract_hash, args): (ContractHash, RuntimeArgs) = decode_call(&call);
+            // Only entry points that return nothing can be called, as for any other
+            // return type the call would fail to deserialize its result.
+            let _: () = runtime::call_contract(contract_hash, &call.1, args);
+        }
+        self.emit(&GOVERNOREvent::ProposalExecuted { id: id });
+    }
+
+    /// Cancels a proposal that has not been executed yet, only its proposer can.
+    fn cancel(&mut self, id: U256) {
+        let state: ProposalState = self.state(id);
+        let proposals: Proposals = Proposals::instance();
+        if self.get_caller() != proposals.proposer(id) {
+            runtime::revert(GovernorError::GovernorNotProposer);
+        }
+        if state == ProposalState::Canceled
+            || state == ProposalState::Executed
+            || state == ProposalState::Expired
+        {
+            runtime::revert(GovernorError::GovernorCannotCancel);
+        }
+        proposals.set_canceled(id);
+        self.emit(&GOVERNOREvent::ProposalCanceled { id: id });
+    }
+
+    fn state(&mut self, id: U256) -> ProposalState {
+        if id >= data::get_proposal_count() {
+            runtime::revert(GovernorError::GovernorInvalidProposal);
+        }
+        let proposals: Proposals = Proposals::instance();
+        let now: u64 = runtime::get_blocktime().into();
+        let for_votes: U256 = proposals.for_votes(id);
+        let eta: u64 = proposals.eta(id);
+        if proposals.canceled(id) {
+            ProposalState::Canceled
+        } else if now <= proposals.start(id) {
+            ProposalState::Pending
+        } else if now <= proposals.end(id) {
+            ProposalState::Active
+        } else if for_votes <= proposals.against_votes(id) || for_votes < data::get_quorum_votes() {
+            ProposalState::Defeated
+        } else if eta == 0 {
+            ProposalState::Succeeded
+        } else if proposals.executed(id) {
+            ProposalState::Executed
+        } else if now >= eta.saturating_add(data::GRACE_PERIOD) {
+            ProposalState::Expired
+        } else {
+            ProposalState::Queued
+        }
+    }
+
+    fn get_receipt(&mut self, id: U256, voter: Key) -> Option<Receipt> {
+        Receipts::instance().get(id, &voter)
+    }
+
+    fn get_proposal_count(&mut self) -> U256 {
+        data::get_proposal_count()
+    }
+
+    fn emit(&mut self, governor_event: &GOVERNOREvent) {
+        let mut events = Vec::new();
+        let package = data::get_package_hash();
+        match governor_event {
+            GOVERNOREvent::ProposalCreated {
+                id,
+                proposer,
+                start,
+                end,
+                description,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", governor_event.type_name());
+                event.insert("id", id.to_string());
+                event.insert("proposer", proposer.to_string());
+                event.insert("start", start.to_string());
+                event.insert("end", end.to_string());
+                event.insert("description", description.clone());
+                events.push(event);
+            }
+            GOVERNOREvent::VoteCast {
+                id,
+                voter,
+                support,
+                votes,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", governor_event.type_name());
+                event.insert("id", id.to_string());
+                event.insert("voter", voter.to_string());
+                event.insert("support", support.to_string());
+                event.insert("votes", votes.to_string());
+                events.push(event);
+            }
+            GOVERNOREvent::ProposalCanceled { id } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", governor_event.type_name());
+                event.insert("id", id.to_string());
+                events.push(event);
+            }
+            GOVERNOREvent::ProposalQueued { id, eta } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", governor_event.type_name());
+                event.insert("id", id.to_string());
+                event.insert("eta", eta.to_string());
+                events.push(event);
+            }
+            GOVERNOREvent::ProposalExecuted { id } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", governor_event.type_name());
+                event.insert("id", id.to_string());
+                events.push(event);
+            }
+        };
+
+        for event in events {
+            let _: URef = storage::new_uref(event);
+        }
+    }
+
+    fn get_package_hash(&mut self) -> ContractPackageHash {
+        data::get_package_hash()
+    }
+}
+
+/// Votes of `account` on the governance token at the block time `time`.
+fn prior_votes(account: Key, time: u64) -> U256 {
+    runtime::call_contract(
+        key_to_contract_hash(data::get_token()).unwrap_or_revert(),
+        "get_prior_votes",
+        runtime_args! {
+            "account" => account,
+            "time" => time
+        },
+    )
+}
+
+fn decode_call((contract, _, args): &Call) -> (ContractHash, RuntimeArgs) {
+    let contract_hash: ContractHash = key_to_contract_hash(*contract)
+        .map_err(|_| GovernorError::GovernorInvalidContract)
+        .unwrap_or_revert();
+    let args: RuntimeArgs = bytesrepr::deserialize(args.to_vec())
+        .map_err(|_| GovernorError::GovernorInvalidArgs)
+        .unwrap_or_revert();
+    (contract_hash, args)
+}
@@ -0,0 +1,31 @@
+use casper_types::{account::AccountHash, ApiError, ContractHash, ContractPackageHash, Key};
+
+/// `account-hash-0000…`, the key used for "no account", e.g. an unset `fee_to` or the
+/// `from`/`to` side of a mint or burn.
+pub const ZERO_ACCOUNT: Key = Key::Account(AccountHash::new([0u8; 32]));
+
+/// `hash-0000…`, the key used for "no contract", e.g. a pair that has not been created.
+pub const ZERO_HASH: Key = Key::Hash([0u8; 32]);
+
+/// Returns `true` if `key` is either of the zero keys.
+pub fn is_zero(key: &Key) -> bool {
+    *key == ZERO_ACCOUNT || *key == ZERO_HASH
+}
+
+/// Reads a `Key::Hash` as the hash of a contract.
+/// Returns `ApiError::UnexpectedKeyVariant` for any other variant.
+pub fn key_to_contract_hash(key: Key) -> Result<ContractHash, ApiError> {
+    match key {
+        Key::Hash(hash) => Ok(ContractHash::new(hash)),
+        _ => Err(ApiError::UnexpectedKeyVariant),
+    }
+}
+
+/// Reads a `Key::Hash` as the hash of a contract package.
+/// Returns `ApiError::UnexpectedKeyVariant` for any other variant.
+pub fn key_to_contract_package_hash(key: Key) -> Result<ContractPackageHash, ApiError> {
+    match key {
+        Key::Hash(hash) => Ok(ContractPackageHash::new(hash)),
+        _ => Err(ApiError::UnexpectedKeyVariant),
+    }
+}
@@ -0,0 +1,96 @@
+use casper_types::{runtime_args, Key, RuntimeArgs, U128, U256};
+use test_env::{Sender, TestContract, TestEnv};
+
+pub struct LENSInstance(pub TestContract);
+
+impl LENSInstance {
+    pub fn new(env: &TestEnv, contract_name: &str, sender: Sender) -> LENSInstance {
+        LENSInstance(TestContract::new(
+            env,
+            "lens.wasm",
+            contract_name,
+            sender,
+            runtime_args! {},
+        ))
+    }
+
+    pub fn get_reserves_batch(&self, sender: Sender, pairs: Vec<Key>) {
+        self.0.call_contract(
+            sender,
+            "get_reserves_batch",
+            runtime_args! {"pairs" => pairs},
+        );
+    }
+
+    pub fn balances_of<T: Into<Key>>(&self, sender: Sender, token: T, owners: Vec<Key>) {
+        self.0.call_contract(
+            sender,
+            "balances_of",
+            runtime_args! {
+                "token" => token.into(),
+                "owners" => owners
+            },
+        );
+    }
+
+    pub fn allowances_of<T: Into<Key>>(
+        &self,
+        sender: Sender,
+        token: T,
+        owners: Vec<Key>,
+        spender: T,
+    ) {
+        self.0.call_contract(
+            sender,
+            "allowances_of",
+            runtime_args! {
+                "token" => token.into(),
+                "owners" => owners,
+                "spender" => spender.into()
+            },
+        );
+    }
+
+    pub fn user_positions<T: Into<Key>>(&self, sender: Sender, user: T, pairs: Vec<Key>) {
+        self.0.call_contract(
+            sender,
+            "user_positions",
+            runtime_args! {
+                "user" => user.into(),
+                "pairs" => pairs
+            },
+        );
+    }
+
+    pub fn reserves_batch_result<T: Into<Key>>(&self, caller: T) -> Vec<(U128, U128, u64)> {
+        self.0
+            .query_dictionary("reserves_batch", key_to_str(&caller.into()))
+            .unwrap_or_default()
+    }
+
+    pub fn balances_of_result<T: Into<Key>>(&self, caller: T) -> Vec<U256> {
+        self.0
+            .query_dictionary("balances_of", key_to_str(&caller.into()))
+            .unwrap_or_default()
+    }
+
+    pub fn allowances_of_result<T: Into<Key>>(&self, caller: T) -> Vec<U256> {
+        self.0
+            .query_dictionary("allowances_of", key_to_str(&caller.into()))
+            .unwrap_or_default()
+    }
+
+    pub fn user_positions_result<T: Into<Key>>(&self, caller: T) -> Vec<(U256, U256, U256)> {
+        self.0
+            .query_dictionary("user_positions", key_to_str(&caller.into()))
+            .unwrap_or_default()
+    }
+}
+
+pub fn key_to_str(key: &Key) -> String {
+    match key {
+        Key::Account(account) => account.to_string(),
+        Key::Hash(package) => hex::encode(package),
+        _ => panic!("Unexpected key type"),
+    }
+}
@@ -0,0 +1,220 @@
+use casper_engine_test_support::AccountHash;
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs, U256};
+use test_env::{Sender, TestContract, TestEnv};
+
+use crate::lens_instance::LENSInstance;
+
+const NAME_LENS: &str = "Lens";
+
+/// `pair_a` of token0 and token1 with reserves of 10000/10000 and `pair_b` of token0 and
+/// token2 with 5000/20000, with all the liquidity of both minted to the owner.
+struct Pools {
+    pair_a: TestContract,
+    pair_b: TestContract,
+    token0: TestContract,
+}
+
+fn deploy() -> (TestEnv, LENSInstance, AccountHash, Pools) {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let factory = TestContract::new(
+        &env,
+        "factory.wasm",
+        "factory",
+        Sender(owner),
+        runtime_args! {"fee_to_setter" => Key::from(owner)},
+    );
+    // owner creates the pairs
+    factory.call_contract(
+        Sender(owner),
+        "set_white_list",
+        runtime_args! {"white_list" => Key::from(owner)},
+    );
+    let wcspr = TestContract::new(
+        &env,
+        "wcspr-token.wasm",
+        "wcspr",
+        Sender(owner),
+        runtime_args! {
+            "initial_supply" => U256::from(0),
+            "name" => "wcspr",
+            "symbol" => "wcspr",
+            "decimals" => 9u8
+        },
+    );
+    let flash_swapper = TestContract::new(
+        &env,
+        "flash-swapper.wasm",
+        "flash_swapper",
+        Sender(owner),
+        runtime_args! {
+            "wcspr" => Key::Hash(wcspr.contract_hash()),
+            "dai" => Key::Hash(wcspr.contract_hash()),
+            "uniswap_v2_factory" => Key::Hash(factory.contract_hash())
+        },
+    );
+    let token0 = deploy_token(&env, "token0", owner);
+    let token1 = deploy_token(&env, "token1", owner);
+    let token2 = deploy_token(&env, "token2", owner);
+    let deploy_pair = |name: &str,
+                       token_a: &TestContract,
+                       token_b: &TestContract,
+                       amount0: U256,
+                       amount1: U256|
+     -> TestContract {
+        let pair = TestContract::new(
+            &env,
+            "pair-token.wasm",
+            name,
+            Sender(owner),
+            runtime_args! {
+                "initial_supply" => U256::from(0),
+                "name" => name,
+                "symbol" => "lp",
+                "decimals" => 9u8,
+                "callee_contract_hash" => Key::Hash(flash_swapper.contract_hash()),
+                "factory_hash" => Key::Hash(factory.contract_hash())
+            },
+        );
+        factory.call_contract(
+            Sender(owner),
+            "create_pair",
+            runtime_args! {
+                "token_a" => Key::Hash(token_a.contract_hash()),
+                "token_b" => Key::Hash(token_b.contract_hash()),
+                "pair_hash" => Key::Hash(pair.contract_hash())
+            },
+        );
+        // the factory sorts the tokens, amount0 goes to the one it made token0
+        let (pair_token0, pair_token1) =
+            if Key::Hash(token_a.contract_hash()) < Key::Hash(token_b.contract_hash()) {
+                (token_a, token_b)
+            } else {
+                (token_b, token_a)
+            };
+        let pair_package: ContractPackageHash =
+            pair.query_named_key(String::from("self_package_hash"));
+        mint(pair_token0, owner, Key::from(pair_package), amount0);
+        mint(pair_token1, owner, Key::from(pair_package), amount1);
+        pair.call_contract(
+            Sender(owner),
+            "mint",
+            runtime_args! {"to" => Key::from(owner)},
+        );
+        pair
+    };
+    let pair_a = deploy_pair("pair_a", &token0, &token1, 10000.into(), 10000.into());
+    let pair_b = deploy_pair("pair_b", &token0, &token2, 5000.into(), 20000.into());
+    let lens = LENSInstance::new(&env, NAME_LENS, Sender(owner));
+    let pools = Pools {
+        pair_a,
+        pair_b,
+        token0,
+    };
+    (env, lens, owner, pools)
+}
+
+fn deploy_token(env: &TestEnv, name: &str, owner: AccountHash) -> TestContract {
+    TestContract::new(
+        env,
+        "erc20-token.wasm",
+        name,
+        Sender(owner),
+        runtime_args! {
+            "initial_supply" => U256::from(0),
+            "name" => name,
+            "symbol" => "tk",
+            "decimals" => 18u8
+        },
+    )
+}
+
+fn mint(token: &TestContract, owner: AccountHash, to: Key, amount: U256) {
+    token.call_contract(
+        Sender(owner),
+        "mint",
+        runtime_args! {"to" => to, "amount" => amount},
+    );
+}
+
+fn pairs(pools: &Pools) -> Vec<Key> {
+    vec![
+        Key::Hash(pools.pair_a.contract_hash()),
+        Key::Hash(pools.pair_b.contract_hash()),
+    ]
+}
+
+#[test]
+fn test_lens_get_reserves_batch() {
+    let (env, lens, _owner, pools) = deploy();
+    let user = env.next_user();
+    lens.get_reserves_batch(Sender(user), pairs(&pools));
+    assert_eq!(
+        lens.reserves_batch_result(user),
+        vec![
+            (10000.into(), 10000.into(), 0),
+            (5000.into(), 20000.into(), 0)
+        ]
+    );
+}
+
+#[test]
+fn test_lens_balances_of() {
+    let (env, lens, owner, pools) = deploy();
+    let user = env.next_user();
+    mint(&pools.token0, owner, Key::from(user), 250.into());
+    let pair_a_package: ContractPackageHash = pools
+        .pair_a
+        .query_named_key(String::from("self_package_hash"));
+    lens.balances_of(
+        Sender(user),
+        Key::Hash(pools.token0.contract_hash()),
+        vec![Key::from(owner), Key::from(user), Key::from(pair_a_package)],
+    );
+    assert_eq!(
+        lens.balances_of_result(user),
+        vec![0.into(), 250.into(), 10000.into()]
+    );
+}
+
+#[test]
+fn test_lens_allowances_of() {
+    let (env, lens, owner, pools) = deploy();
+    let user = env.next_user();
+    let spender = env.next_user();
+    pools.token0.call_contract(
+        Sender(user),
+        "approve",
+        runtime_args! {"spender" => Key::from(spender), "amount" => U256::from(100)},
+    );
+    lens.allowances_of(
+        Sender(user),
+        Key::Hash(pools.token0.contract_hash()),
+        vec![Key::from(owner), Key::from(user)],
+        Key::from(spender),
+    );
+    assert_eq!(lens.allowances_of_result(user), vec![0.into(), 100.into()]);
+}
+
+#[test]
+fn test_lens_user_positions() {
+    let (env, lens, owner, pools) = deploy();
+    let user = env.next_user();
+    lens.user_positions(Sender(owner), owner, pairs(&pools));
+    lens.user_positions(Sender(user), user, pairs(&pools));
+    // Both pairs minted 10000 liquidity, 1000 of which is locked for good.
+    assert_eq!(
+        lens.user_positions_result(owner),
+        vec![
+            (9000.into(), 9000.into(), 9000.into()),
+            (9000.into(), 4500.into(), 18000.into())
+        ]
+    );
+    assert_eq!(
+        lens.user_positions_result(user),
+        vec![
+            (0.into(), 0.into(), 0.into()),
+            (0.into(), 0.into(), 0.into())
+        ]
+    );
+}
@@ -0,0 +1,5 @@
+#[cfg(test)]
+pub mod lens_tests;
+
+#[cfg(test)]
+pub mod lens_instance;
@@ -0,0 +1,237 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{collections::BTreeSet, format, vec, vec::Vec};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    runtime_args, CLTyped, CLValue, ContractHash, ContractPackageHash, EntryPoint,
+    EntryPointAccess, EntryPointType, EntryPoints, Group, Key, Parameter, RuntimeArgs, URef, U256,
+};
+use contract_utils::{ContractContext, OnChainContractStorage};
+use lens::{
+    self,
+    data::{Position, Reserves},
+    LENS,
+};
+
+#[derive(Default)]
+struct Lens(OnChainContractStorage);
+
+impl ContractContext<OnChainContractStorage> for Lens {
+    fn storage(&self) -> &OnChainContractStorage {
+        &self.0
+    }
+}
+
+impl LENS<OnChainContractStorage> for Lens {}
+
+impl Lens {
+    fn constructor(&mut self, contract_hash: ContractHash, package_hash: ContractPackageHash) {
+        LENS::init(self, Key::from(contract_hash), package_hash);
+    }
+}
+
+#[no_mangle]
+fn constructor() {
+    let contract_hash: ContractHash = runtime::get_named_arg("contract_hash");
+    let package_hash: ContractPackageHash = runtime::get_named_arg("package_hash");
+    Lens::default().constructor(contract_hash, package_hash);
+}
+
+/// This function is to return the reserves of many pairs at once, it also stores them in the `reserves_batch` dictionary under the caller
+///
+/// # Parameters
+///
+/// * `pairs` - A list of Keys that hold the Hashes of the pairs
+///
+
+#[no_mangle]
+fn get_reserves_batch() {
+    let pairs: Vec<Key> = runtime::get_named_arg("pairs");
+    let ret: Vec<Reserves> = Lens::default().get_reserves_batch(pairs);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the balances of many owners on a token at once, it also stores them in the `balances_of` dictionary under the caller
+///
+/// # Parameters
+///
+/// * `token` - A Key that holds the Hash of the token
+///
+/// * `owners` - A list of Keys that hold the account addresses or package hashes of the owners
+///
+
+#[no_mangle]
+fn balances_of() {
+    let token: Key = runtime::get_named_arg("token");
+    let owners: Vec<Key> = runtime::get_named_arg("owners");
+    let ret: Vec<U256> = Lens::default().balances_of(token, owners);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the allowances of many owners to a spender on a token at once, it also stores them in the `allowances_of` dictionary under the caller
+///
+/// # Parameters
+///
+/// * `token` - A Key that holds the Hash of the token
+///
+/// * `owners` - A list of Keys that hold the account addresses or package hashes of the owners
+///
+/// * `spender` - A Key that holds the account address or package hash of the spender
+///
+
+#[no_mangle]
+fn allowances_of() {
+    let token: Key = runtime::get_named_arg("token");
+    let owners: Vec<Key> = runtime::get_named_arg("owners");
+    let spender: Key = runtime::get_named_arg("spender");
+    let ret: Vec<U256> = Lens::default().allowances_of(token, owners, spender);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the LP balance of a user in many pairs and the amounts of the tokens it is worth, it also stores them in the `user_positions` dictionary under the caller
+///
+/// # Parameters
+///
+/// * `user` - A Key that holds the account address or package hash of the user
+///
+/// * `pairs` - A list of Keys that hold the Hashes of the pairs
+///
+
+#[no_mangle]
+fn user_positions() {
+    let user: Key = runtime::get_named_arg("user");
+    let pairs: Vec<Key> = runtime::get_named_arg("pairs");
+    let ret: Vec<Position> = Lens::default().user_positions(user, pairs);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to fetch a Contract Package Hash
+///
+
+#[no_mangle]
+fn package_hash() {
+    let ret: ContractPackageHash = Lens::default().get_package_hash();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new("contract_hash", ContractHash::cl_type()),
+            Parameter::new("package_hash", ContractPackageHash::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_reserves_batch",
+        vec![Parameter::new("pairs", Vec::<Key>::cl_type())],
+        Vec::<Reserves>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "balances_of",
+        vec![
+            Parameter::new("token", Key::cl_type()),
+            Parameter::new("owners", Vec::<Key>::cl_type()),
+        ],
+        Vec::<U256>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "allowances_of",
+        vec![
+            Parameter::new("token", Key::cl_type()),
+            Parameter::new("owners", Vec::<Key>::cl_type()),
+            Parameter::new("spender", Key::cl_type()),
+        ],
+        Vec::<U256>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "user_positions",
+        vec![
+            Parameter::new("user", Key::cl_type()),
+            Parameter::new("pairs", Vec::<Key>::cl_type()),
+        ],
+        Vec::<Position>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "package_hash",
+        vec![],
+        ContractPackageHash::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
+
+#[no_mangle]
+fn call() {
+    // Contract name must be same for all new versions of the contracts
+    let contract_name: alloc::string::String = runtime::get_named_arg("contract_name");
+
+    // Build new package with initial a first version of the contract.
+    let (package_hash, access_token) = storage::create_contract_package_at_hash();
+    let (contract_hash, _) =
+        storage::add_contract_version(package_hash, get_entry_points(), Default::default());
+
+    // Prepare constructor args
+    let constructor_args = runtime_args! {
+        "contract_hash" => contract_hash,
+        "package_hash"=> package_hash
+    };
+
+    // Add the constructor group to the package hash with a single URef.
+    let constructor_access: URef =
+        storage::create_contract_user_group(package_hash, "constructor", 1, Default::default())
+            .unwrap_or_revert()
+            .pop()
+            .unwrap_or_revert();
+
+    // Call the constructor entry point
+    let _: () =
+        runtime::call_versioned_contract(package_hash, None, "constructor", constructor_args);
+
+    // Remove all URefs from the constructor group, so no one can call it for the second time.
+    let mut urefs = BTreeSet::new();
+    urefs.insert(constructor_access);
+    storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
+        .unwrap_or_revert();
+
+    // Store contract in the account's named keys.
+    runtime::put_key(
+        &format!("{}_package_hash", contract_name),
+        package_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_hash_wrapped", contract_name),
+        storage::new_uref(package_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash_wrapped", contract_name),
+        storage::new_uref(contract_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_access_token", contract_name),
+        access_token.into(),
+    );
+}
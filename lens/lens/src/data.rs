@@ -0,0 +1,77 @@
+use alloc::vec::Vec;
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{ContractPackageHash, Key, U128, U256};
+use contract_utils::{get_key, set_key, Dict};
+
+pub const SELF_CONTRACT_HASH: &str = "self_contract_hash";
+pub const CONTRACT_PACKAGE_HASH: &str = "contract_package_hash";
+pub const RESERVES_BATCH_DICT: &str = "reserves_batch";
+pub const BALANCES_OF_DICT: &str = "balances_of";
+pub const ALLOWANCES_OF_DICT: &str = "allowances_of";
+pub const USER_POSITIONS_DICT: &str = "user_positions";
+
+/// `(reserve0, reserve1, block_timestamp_last)` of a pair, as returned by `get_reserves`.
+pub type Reserves = (U128, U128, u64);
+
+/// `(liquidity, amount0, amount1)`: the LP balance of a user in a pair and the amounts of
+/// token0 and token1 it is worth at the current reserves.
+pub type Position = (U256, U256, U256);
+
+/// Result of the last call of each entry point, indexed by the caller, so that off-chain
+/// code can read what it asked for with a single dictionary query.
+pub struct Results {
+    reserves_batch: Dict,
+    balances_of: Dict,
+    allowances_of: Dict,
+    user_positions: Dict,
+}
+
+impl Results {
+    pub fn instance() -> Results {
+        Results {
+            reserves_batch: Dict::instance(RESERVES_BATCH_DICT),
+            balances_of: Dict::instance(BALANCES_OF_DICT),
+            allowances_of: Dict::instance(ALLOWANCES_OF_DICT),
+            user_positions: Dict::instance(USER_POSITIONS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(RESERVES_BATCH_DICT);
+        Dict::init(BALANCES_OF_DICT);
+        Dict::init(ALLOWANCES_OF_DICT);
+        Dict::init(USER_POSITIONS_DICT);
+    }
+
+    pub fn set_reserves_batch(&self, caller: &Key, reserves: Vec<Reserves>) {
+        self.reserves_batch.set_by_key(caller, reserves);
+    }
+
+    pub fn set_balances_of(&self, caller: &Key, balances: Vec<U256>) {
+        self.balances_of.set_by_key(caller, balances);
+    }
+
+    pub fn set_allowances_of(&self, caller: &Key, allowances: Vec<U256>) {
+        self.allowances_of.set_by_key(caller, allowances);
+    }
+
+    pub fn set_user_positions(&self, caller: &Key, positions: Vec<Position>) {
+        self.user_positions.set_by_key(caller, positions);
+    }
+}
+
+pub fn set_hash(contract_hash: Key) {
+    set_key(SELF_CONTRACT_HASH, contract_hash);
+}
+
+pub fn get_hash() -> Key {
+    get_key(SELF_CONTRACT_HASH).unwrap_or_revert()
+}
+
+pub fn set_package_hash(package_hash: ContractPackageHash) {
+    set_key(CONTRACT_PACKAGE_HASH, package_hash);
+}
+
+pub fn get_package_hash() -> ContractPackageHash {
+    get_key(CONTRACT_PACKAGE_HASH).unwrap_or_revert()
+}
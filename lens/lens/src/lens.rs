@@ -0,0 +1,106 @@
+use crate::data::{self, Position, Reserves, Results};
+use alloc::vec::Vec;
+use casper_contract::contract_api::runtime;
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{runtime_args, ContractHash, ContractPackageHash, Key, RuntimeArgs, U256};
+use contract_utils::{key_to_contract_hash, ContractContext, ContractStorage};
+use errors::LensError;
+
+/// Read only views over many pairs or accounts at once. Every entry point returns its
+/// result and also stores it for the caller, see `Results`.
+pub trait LENS<Storage: ContractStorage>: ContractContext<Storage> {
+    fn init(&mut self, contract_hash: Key, package_hash: ContractPackageHash) {
+        data::set_hash(contract_hash);
+        data::set_package_hash(package_hash);
+        Results::init();
+    }
+
+    /// Reserves of each of `pairs`, in the same order.
+    fn get_reserves_batch(&mut self, pairs: Vec<Key>) -> Vec<Reserves> {
+        let reserves: Vec<Reserves> = pairs.into_iter().map(reserves).collect();
+        Results::instance().set_reserves_batch(&self.get_caller(), reserves.clone());
+        reserves
+    }
+
+    /// Balance on `token` of each of `owners`, in the same order.
+    fn balances_of(&mut self, token: Key, owners: Vec<Key>) -> Vec<U256> {
+        let token_hash: ContractHash = key_to_contract_hash(token).unwrap_or_revert();
+        let balances: Vec<U256> = owners
+            .into_iter()
+            .map(|owner| balance_of(token_hash, owner))
+            .collect();
+        Results::instance().set_balances_of(&self.get_caller(), balances.clone());
+        balances
+    }
+
+    /// Allowance on `token` given to `spender` by each of `owners`, in the same order.
+    fn allowances_of(&mut self, token: Key, owners: Vec<Key>, spender: Key) -> Vec<U256> {
+        let token_hash: ContractHash = key_to_contract_hash(token).unwrap_or_revert();
+        let allowances: Vec<U256> = owners
+            .into_iter()
+            .map(|owner| {
+                runtime::call_contract(
+                    token_hash,
+                    "allowance",
+                    runtime_args! {
+                        "owner" => owner,
+                        "spender" => spender
+                    },
+                )
+            })
+            .collect();
+        Results::instance().set_allowances_of(&self.get_caller(), allowances.clone());
+        allowances
+    }
+
+    /// Position of `user` in each of `pairs`, in the same order. The amounts are the
+    /// share of the reserves that burning the whole LP balance would give, before the
+    /// protocol fee that `burn` may mint first.
+    fn user_positions(&mut self, user: Key, pairs: Vec<Key>) -> Vec<Position> {
+        let positions: Vec<Position> = pairs
+            .into_iter()
+            .map(|pair| {
+                let pair_hash: ContractHash = key_to_contract_hash(pair).unwrap_or_revert();
+                let liquidity: U256 = balance_of(pair_hash, user);
+                let total_supply: U256 =
+                    runtime::call_contract(pair_hash, "total_supply", runtime_args! {});
+                if liquidity.is_zero() || total_supply.is_zero() {
+                    return (liquidity, U256::zero(), U256::zero());
+                }
+                let (reserve0, reserve1, _): Reserves = reserves(pair);
+                (
+                    liquidity,
+                    share(liquidity, U256::from(reserve0.as_u128()), total_supply),
+                    share(liquidity, U256::from(reserve1.as_u128()), total_supply),
+                )
+            })
+            .collect();
+        Results::instance().set_user_positions(&self.get_caller(), positions.clone());
+        positions
+    }
+
+    fn get_package_hash(&mut self) -> ContractPackageHash {
+        data::get_package_hash()
+    }
+}
+
+fn reserves(pair: Key) -> Reserves {
+    runtime::call_contract(
+        key_to_contract_hash(pair).unwrap_or_revert(),
+        "get_reserves",
+        runtime_args! {},
+    )
+}
+
+fn balance_of(token_hash: ContractHash, owner: Key) -> U256 {
+    runtime::call_contract(token_hash, "balance_of", runtime_args! {"owner" => owner})
+}
+
+/// `liquidity * reserve / total_supply`, rounded down as in `burn`.
+fn share(liquidity: U256, reserve: U256, total_supply: U256) -> U256 {
+    liquidity
+        .checked_mul(reserve)
+        .ok_or(LensError::LensOverFlow)
+        .unwrap_or_revert()
+        / total_supply
+}
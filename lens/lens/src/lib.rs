@@ -0,0 +1,8 @@
+#![no_std]
+
+extern crate alloc;
+
+pub mod data;
+mod lens;
+
+pub use lens::LENS;
@@ -0,0 +1,344 @@
+//! Registry of the `ApiError::User` codes raised by the contracts in this repository.
+//!
+//! Every contract owns a range of 100 codes, so a code read from a failed deploy
+//! identifies both the contract and the error:
+//!
+//! Range | Contract
+//! ---|---
+//! 0 - 99 | shared by every contract (`contract-utils` and token transfer results)
+//! 100 - 199 | erc20
+//! 200 - 299 | wcspr
+//! 300 - 399 | factory
+//! 400 - 499 | pair
+//! 500 - 599 | flash swapper
+//! 600 - 699 | staking
+//! 700 - 799 | zap
+//! 800 - 899 | limit order
+//! 900 - 999 | multicall
+//! 1000 - 1099 | lens
+//! 1100 - 1199 | governor
+//!
+//! A deploy that reverts with `ApiError::User(n)` fails with code `65_536 + n`. Token
+//! entry points that return `Result<(), u32>` use the same `65_536 + n` value in `Err`.
+//! `describe`, `describe_api_error` and `describe_u32` map any of these back to an
+//! `ErrorInfo`.
+#![no_std]
+
+use casper_types::ApiError;
+
+/// Where an error code comes from and what it means.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ErrorInfo {
+    pub code: u16,
+    pub contract: &'static str,
+    pub name: &'static str,
+    pub message: &'static str,
+}
+
+macro_rules! errors {
+    (
+        $(#[$meta:meta])*
+        $enum_name:ident, $contract:literal, $start:literal..$end:literal {
+            $($variant:ident = $code:literal => $message:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[repr(u16)]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum $enum_name {
+            $(
+                #[doc = $message]
+                $variant = $code,
+            )*
+        }
+
+        impl $enum_name {
+            pub const CONTRACT: &'static str = $contract;
+            /// Codes of this contract are in `RANGE.0..RANGE.1`.
+            pub const RANGE: (u16, u16) = ($start, $end);
+            pub const ALL: &'static [$enum_name] = &[$($enum_name::$variant,)*];
+
+            pub fn from_code(code: u16) -> Option<$enum_name> {
+                Self::ALL.iter().copied().find(|error| *error as u16 == code)
+            }
+
+            pub fn info(self) -> ErrorInfo {
+                let (name, message) = match self {
+                    $($enum_name::$variant => (stringify!($variant), $message),)*
+                };
+                ErrorInfo {
+                    code: self as u16,
+                    contract: $contract,
+                    name: name,
+                    message: message,
+                }
+            }
+        }
+
+        impl From<$enum_name> for ApiError {
+            fn from(error: $enum_name) -> ApiError {
+                ApiError::User(error as u16)
+            }
+        }
+
+        impl From<$enum_name> for u32 {
+            fn from(error: $enum_name) -> u32 {
+                u32::from(ApiError::from(error))
+            }
+        }
+    };
+}
+
+errors! {
+    /// Errors shared by every contract.
+    CommonError, "common", 0..100 {
+        Abort = 1 => "a named key the contract needs is missing",
+        NotAdmin = 2 => "caller is not an admin",
+        InvalidAllowance = 3 => "allowance can not be changed to the requested amount",
+        SameSenderRecipient = 4 => "sender and recipient are the same",
+        SameOwnerSpender = 5 => "owner and spender are the same",
+        ZeroAmount = 6 => "amount is zero",
+        InsufficientBalance = 7 => "insufficient balance",
+    }
+}
+
+errors! {
+    Erc20Error, "erc20", 100..200 {
+        UniswapV2CoreERC20EXPIRED = 100 => "permit deadline has passed",
+        UniswapV2CoreERC20SignatureVerificatFailed = 101 => "permit signature verification failed",
+        UniswapV2CoreERC20OverFlow = 102 => "overflow",
+        UniswapV2CoreERC20UnderFlow1 = 103 => "allowance underflow in decrease_allowance",
+        UniswapV2CoreERC20UnderFlow2 = 104 => "allowance underflow in transfer_from",
+        UniswapV2CoreERC20UnderFlow3 = 105 => "balance underflow in burn",
+        UniswapV2CoreERC20UnderFlow4 = 106 => "total supply underflow in burn",
+        UniswapV2CoreERC20UnderFlow5 = 107 => "balance underflow in transfer",
+        UniswapV2CoreERC20UnderFlow6 = 108 => "underflow",
+        UniswapV2CoreERC20VotesNotYetDetermined = 109 => "prior votes are only known for times in the past",
+        UniswapV2CoreERC20InvalidSnapshotId = 110 => "snapshot id is zero or has not been taken yet",
+        UniswapV2CoreERC20SnapshotsDisabled = 111 => "token has not opted in to snapshots",
+    }
+}
+
+errors! {
+    WcsprError, "wcspr", 200..300 {
+        UniswapV2CoreWCSPROverFlow = 200 => "overflow",
+        UniswapV2CoreWCSPRUnderFlow = 201 => "underflow",
+        UniswapV2CoreWCSPRConversionOverFlow = 202 => "U512 amount does not fit in U256",
+        UniswapV2CoreWCSPRPurseTransferFailed = 203 => "purse transfer failed",
+        UniswapV2CoreWCSPRInsolvent = 204 => "purse balance is below total supply",
+        UniswapV2CoreWCSPREXPIRED = 205 => "permit deadline has passed",
+        UniswapV2CoreWCSPRSignatureVerificationFailed = 206 => "permit signature verification failed",
+    }
+}
+
+errors! {
+    FactoryError, "factory", 300..400 {
+        UniswapV2FactoryZeroAddress = 300 => "token is the zero key",
+        UniswapV2FactoryPairExists = 301 => "pair already exists",
+        UniswapV2Forbidden = 302 => "caller is not fee_to_setter",
+        UniswapV2FactoryIdenticalAddresses = 303 => "tokens are identical",
+        UniswapV2FactoryNotInWhiteList = 304 => "caller can not create pairs",
+        UniswapV2FactoryNotOwner = 305 => "caller is not the owner",
+        UniswapV2FactoryInvalidRole = 306 => "unknown role",
+        UniswapV2FactoryNotPending = 307 => "caller is not the pending account",
+        UniswapV2FactoryTransferDelayNotElapsed = 308 => "transfer delay has not elapsed",
+        UniswapV2FactoryNotGuardian = 309 => "caller is not a guardian",
+        UniswapV2FactoryInvalidToken = 310 => "token is not a contract hash",
+        UniswapV2FactoryTokenDenied = 311 => "token is on the denylist",
+        UniswapV2FactoryInvalidDecimals = 312 => "token has more than 36 decimals",
+    }
+}
+
+errors! {
+    PairError, "pair", 400..500 {
+        UniswapV2PairExpired = 400 => "UniswapV2: EXPIRED",
+        UniswapV2PairForbidden = 401 => "UniswapV2: FORBIDDEN",
+        UniswapV2PairSignatureVerificationFailed = 402 => "signature verification failed",
+        UniswapV2PairReserveOverFlow = 403 => "UniswapV2: OVERFLOW, balance does not fit in the reserves",
+        UniswapV2PairInsufficientOutputAmount = 404 => "UniswapV2: INSUFFICIENT_OUTPUT_AMOUNT",
+        UniswapV2PairInsufficientLiquidity = 405 => "UniswapV2: INSUFFICIENT_LIQUIDITY",
+        UniswapV2PairInvalidTo = 406 => "UniswapV2: INVALID_TO",
+        UniswapV2PairInsufficientInputAmount = 407 => "UniswapV2: INSUFFICIENT_INPUT_AMOUNT",
+        UniswapV2PairK = 408 => "UniswapV2: K",
+        UniswapV2PairInsufficientLiquidityMinted = 409 => "UniswapV2: INSUFFICIENT_LIQUIDITY_MINTED",
+        UniswapV2PairInsufficientLiquidityBurned = 410 => "UniswapV2: INSUFFICIENT_LIQUIDITY_BURNED",
+        UniswapV2PairOverFlow = 411 => "UniswapV2: OVERFLOW",
+        UniswapV2PairUnderFlow = 412 => "UniswapV2: UNDERFLOW",
+        UniswapV2PairDenominatorIsZero = 413 => "UniswapV2: DENOMINATOR IS ZERO",
+        UniswapV2PairLocked = 414 => "UniswapV2: LOCKED",
+        UniswapV2PairBalanceBelowReserve = 415 => "UniswapV2: UNDERFLOW, balance is below the reserve",
+        UniswapV2PairInsufficientInitialLiquidity = 416 => "UniswapV2: initial liquidity is below MINIMUM_LIQUIDITY",
+        UniswapV2PairUnsupportedToken = 417 => "UniswapV2: UNSUPPORTED_TOKEN",
+        UniswapV2PairFlashLoanCallbackFailed = 418 => "UniswapV2: FLASH_LOAN_CALLBACK_FAILED",
+        UniswapV2PairFlashLoanNotRepaid = 419 => "UniswapV2: FLASH_LOAN_NOT_REPAID",
+        UniswapV2PairPaused = 420 => "UniswapV2: PAUSED",
+        UniswapV2PairDeadlinePassed = 421 => "UniswapV2: deadline has passed",
+        UniswapV2PairAmount0OutBelowMinimum = 422 => "UniswapV2: amount0_out is below amount0_out_min",
+        UniswapV2PairAmount1OutBelowMinimum = 423 => "UniswapV2: amount1_out is below amount1_out_min",
+        UniswapV2PairAmount0BelowMinimum = 424 => "UniswapV2: burned amount0 is below amount0_min",
+        UniswapV2PairAmount1BelowMinimum = 425 => "UniswapV2: burned amount1 is below amount1_min",
+        UniswapV2PairInvalidAmp = 426 => "UniswapV2: amplification coefficient is zero or above MAX_AMP",
+        UniswapV2PairInvalidSnapshotId = 427 => "UniswapV2: snapshot id is zero or has not been taken yet",
+        UniswapV2PairSnapshotsDisabled = 428 => "UniswapV2: pair has not opted in to snapshots",
+        UniswapV2PairAlreadyInitialized = 429 => "UniswapV2: pair is already initialized",
+    }
+}
+
+errors! {
+    FlashSwapperError, "flash_swapper", 500..600 {
+        UniswapV2ZeroAddress = 500 => "pair is the zero key",
+        UniswapV2PairExists = 501 => "pair already exists",
+        UniswapV2PermissionedPairAccess = 502 => "caller is not the permissioned pair",
+        UniswapV2InvalidContractAddress = 503 => "contract hash is not valid",
+        UniswapV2RequestedPairNotAvailable = 504 => "requested pair is not available",
+        UniswapV2BorrowTokenNotAvailable = 505 => "requested borrow token is not available",
+        UniswapV2PayTokenNotAvailable = 506 => "requested pay token is not available",
+        UniswapV2AmountTooBig = 507 => "_amount is too big",
+        UniswapV2OverFlow = 508 => "UniswapV2: OVERFLOW",
+        UniswapV2UnderFlow = 509 => "UniswapV2: UNDERFLOW",
+        UniswapV2RepaymentNotReceived = 510 => "pair received nothing from a repayment transfer",
+    }
+}
+
+errors! {
+    StakingError, "staking", 600..700 {
+        StakingInvalidPool = 600 => "pool does not exist",
+        StakingPoolExists = 601 => "lp token already has a pool",
+        StakingInvalidLpToken = 602 => "lp token is not a contract hash or is the reward token",
+        StakingInsufficientStake = 603 => "amount is more than the staked amount",
+        StakingOverFlow = 604 => "overflow",
+        StakingUnderFlow = 605 => "underflow",
+    }
+}
+
+errors! {
+    ZapError, "zap", 700..800 {
+        ZapInvalidToken = 700 => "token is not one of the tokens of the pair",
+        ZapInsufficientLiquidity = 701 => "pair has no reserves to zap into",
+        ZapInsufficientLiquidityMinted = 702 => "liquidity minted is below the minimum",
+        ZapInsufficientOutputAmount = 703 => "amount out is below the minimum",
+        ZapOverFlow = 704 => "overflow",
+        ZapUnderFlow = 705 => "underflow",
+    }
+}
+
+errors! {
+    LimitOrderError, "limit_order", 800..900 {
+        LimitOrderInvalidOrder = 800 => "order does not exist or is no longer open",
+        LimitOrderInvalidToken = 801 => "token is not one of the tokens of the pair",
+        LimitOrderInvalidChunks = 802 => "chunks is zero or more than the amount in",
+        LimitOrderNotOwner = 803 => "caller is not the owner of the order",
+        LimitOrderTooEarly = 804 => "next chunk of the order can not be executed yet",
+        LimitOrderPriceNotReached = 805 => "reserves of the pair do not give the minimum amount out",
+        LimitOrderOverFlow = 806 => "overflow",
+        LimitOrderUnderFlow = 807 => "underflow",
+        LimitOrderAmountNotReceived = 808 => "less than amount in plus bounty arrived, fee on transfer tokens are not supported",
+    }
+}
+
+errors! {
+    MulticallError, "multicall", 900..1000 {
+        MulticallNoCalls = 900 => "list of calls is empty",
+        MulticallInvalidContract = 901 => "contract of a call is not a contract hash",
+        MulticallInvalidArgs = 902 => "args of a call are not serialized runtime args",
+    }
+}
+
+errors! {
+    LensError, "lens", 1000..1100 {
+        LensOverFlow = 1000 => "overflow",
+    }
+}
+
+errors! {
+    GovernorError, "governor", 1100..1200 {
+        GovernorBelowProposalThreshold = 1100 => "votes of the proposer are below the proposal threshold",
+        GovernorNoCalls = 1101 => "list of calls of the proposal is empty",
+        GovernorInvalidProposal = 1102 => "proposal does not exist",
+        GovernorNotActive = 1103 => "proposal is not open for voting",
+        GovernorAlreadyVoted = 1104 => "voter already voted on the proposal",
+        GovernorNotSucceeded = 1105 => "proposal can only be queued if it succeeded",
+        GovernorNotQueued = 1106 => "proposal can only be executed if it is queued",
+        GovernorTimelockNotElapsed = 1107 => "eta of the proposal has not been reached",
+        GovernorNotProposer = 1108 => "caller is not the proposer of the proposal",
+        GovernorCannotCancel = 1109 => "executed or expired proposal can not be canceled",
+        GovernorInvalidContract = 1110 => "contract of a call is not a contract hash",
+        GovernorInvalidArgs = 1111 => "args of a call are not serialized runtime args",
+        GovernorOverFlow = 1112 => "overflow",
+    }
+}
+
+/// Returns the error raised as `ApiError::User(code)`, or `None` if no contract uses `code`.
+pub fn describe(code: u16) -> Option<ErrorInfo> {
+    match code {
+        0..=99 => CommonError::from_code(code).map(CommonError::info),
+        100..=199 => Erc20Error::from_code(code).map(Erc20Error::info),
+        200..=299 => WcsprError::from_code(code).map(WcsprError::info),
+        300..=399 => FactoryError::from_code(code).map(FactoryError::info),
+        400..=499 => PairError::from_code(code).map(PairError::info),
+        500..=599 => FlashSwapperError::from_code(code).map(FlashSwapperError::info),
+        600..=699 => StakingError::from_code(code).map(StakingError::info),
+        700..=799 => ZapError::from_code(code).map(ZapError::info),
+        800..=899 => LimitOrderError::from_code(code).map(LimitOrderError::info),
+        900..=999 => MulticallError::from_code(code).map(MulticallError::info),
+        1000..=1099 => LensError::from_code(code).map(LensError::info),
+        1100..=1199 => GovernorError::from_code(code).map(GovernorError::info),
+        _ => None,
+    }
+}
+
+/// Same as `describe` for an `ApiError`, `None` unless it is an `ApiError::User`.
+pub fn describe_api_error(error: ApiError) -> Option<ErrorInfo> {
+    match error {
+        ApiError::User(code) => describe(code),
+        _ => None,
+    }
+}
+
+/// Same as `describe` for the value of a deploy error or of a `Result<(), u32>` returned
+/// by a token entry point.
+pub fn describe_u32(value: u32) -> Option<ErrorInfo> {
+    describe_api_error(ApiError::from(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // every variant has to be in the range of its contract and come back from `describe_u32`
+    macro_rules! assert_errors {
+        ($($enum_name:ident),*) => {
+            $(
+                for error in $enum_name::ALL {
+                    let info: ErrorInfo = error.info();
+                    let (start, end) = $enum_name::RANGE;
+                    assert!(
+                        start <= info.code && info.code < end,
+                        "{} is outside of the range of {}",
+                        info.name,
+                        $enum_name::CONTRACT
+                    );
+                    assert_eq!(describe_u32(u32::from(*error)), Some(info));
+                }
+            )*
+        };
+    }
+
+    #[test]
+    fn test_errors_in_range_and_described() {
+        assert_errors!(
+            CommonError,
+            Erc20Error,
+            WcsprError,
+            FactoryError,
+            PairError,
+            FlashSwapperError,
+            StakingError,
+            ZapError,
+            LimitOrderError,
+            MulticallError,
+            LensError,
+            GovernorError
+        );
+    }
+}
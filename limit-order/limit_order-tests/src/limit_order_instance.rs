@@ -0,0 +1,87 @@
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs, U256};
+use test_env::{Sender, TestContract, TestEnv};
+
+pub struct LIMITORDERInstance(pub TestContract);
+
+impl LIMITORDERInstance {
+    pub fn new(env: &TestEnv, contract_name: &str, sender: Sender) -> LIMITORDERInstance {
+        LIMITORDERInstance(TestContract::new(
+            env,
+            "limit-order.wasm",
+            contract_name,
+            sender,
+            runtime_args! {},
+        ))
+    }
+
+    pub fn place_order<T: Into<Key>>(
+        &self,
+        sender: Sender,
+        pair: T,
+        token_in: T,
+        amount_in: U256,
+        min_amount_out: U256,
+        bounty: U256,
+        chunks: U256,
+        interval: u64,
+    ) {
+        self.0.call_contract(
+            sender,
+            "place_order",
+            runtime_args! {
+                "pair" => pair.into(),
+                "token_in" => token_in.into(),
+                "amount_in" => amount_in,
+                "min_amount_out" => min_amount_out,
+                "bounty" => bounty,
+                "chunks" => chunks,
+                "interval" => interval,
+            },
+        );
+    }
+
+    pub fn cancel_order(&self, sender: Sender, id: U256) {
+        self.0
+            .call_contract(sender, "cancel_order", runtime_args! {"id" => id});
+    }
+
+    pub fn execute_order(&self, sender: Sender, id: U256) {
+        self.0
+            .call_contract(sender, "execute_order", runtime_args! {"id" => id});
+    }
+
+    pub fn order_count(&self) -> U256 {
+        self.0.query_named_key(String::from("order_count"))
+    }
+
+    pub fn owner(&self, id: U256) -> Key {
+        self.0
+            .query_dictionary("order_owners", id.to_string())
+            .unwrap()
+    }
+
+    pub fn remaining_amount_in(&self, id: U256) -> U256 {
+        self.0
+            .query_dictionary("order_remaining_amounts_in", id.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn remaining_chunks(&self, id: U256) -> U256 {
+        self.0
+            .query_dictionary("order_remaining_chunks", id.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn package_hash(&self) -> ContractPackageHash {
+        self.0
+            .query_named_key(String::from("contract_package_hash"))
+    }
+}
+
+pub fn key_to_str(key: &Key) -> String {
+    match key {
+        Key::Account(account) => account.to_string(),
+        Key::Hash(package) => hex::encode(package),
+        _ => panic!("Unexpected key type"),
+    }
+}
@@ -0,0 +1,419 @@
+use casper_engine_test_support::AccountHash;
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs, U128, U256};
+use test_env::{Sender, TestContract, TestEnv};
+
+use crate::limit_order_instance::{key_to_str, LIMITORDERInstance};
+
+const NAME_LIMIT_ORDER: &str = "LimitOrder";
+
+struct Pool {
+    pair: TestContract,
+    token0: TestContract,
+    token1: TestContract,
+}
+
+impl Pool {
+    fn key(&self) -> Key {
+        Key::Hash(self.pair.contract_hash())
+    }
+
+    fn token0_key(&self) -> Key {
+        Key::Hash(self.token0.contract_hash())
+    }
+
+    fn package_hash(&self) -> ContractPackageHash {
+        self.pair.query_named_key(String::from("self_package_hash"))
+    }
+
+    fn reserves(&self) -> (U128, U128) {
+        (
+            self.pair.query_named_key(String::from("reserve0")),
+            self.pair.query_named_key(String::from("reserve1")),
+        )
+    }
+}
+
+fn deploy() -> (TestEnv, LIMITORDERInstance, AccountHash, Pool) {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let token_a = deploy_token(&env, "token0", owner);
+    let token_b = deploy_token(&env, "token1", owner);
+    let pool = deploy_pool(&env, owner, token_a, token_b, None);
+    let limit_order = LIMITORDERInstance::new(&env, NAME_LIMIT_ORDER, Sender(owner));
+    (env, limit_order, owner, pool)
+}
+
+/// Deploys a factory and a pair of `token_a` and `token_b`, a stable pair when `amp` is
+/// given, and adds 10000 of each to it.
+fn deploy_pool(
+    env: &TestEnv,
+    owner: AccountHash,
+    token_a: TestContract,
+    token_b: TestContract,
+    amp: Option<U256>,
+) -> Pool {
+    let factory = TestContract::new(
+        env,
+        "factory.wasm",
+        "factory",
+        Sender(owner),
+        runtime_args! {"fee_to_setter" => Key::from(owner)},
+    );
+    let wcspr = TestContract::new(
+        env,
+        "wcspr-token.wasm",
+        "wcspr",
+        Sender(owner),
+        runtime_args! {
+            "initial_supply" => U256::from(0),
+            "name" => "wcspr",
+            "symbol" => "wcspr",
+            "decimals" => 9u8
+        },
+    );
+    let flash_swapper = TestContract::new(
+        env,
+        "flash-swapper.wasm",
+        "flash_swapper",
+        Sender(owner),
+        runtime_args! {
+            "wcspr" => Key::Hash(wcspr.contract_hash()),
+            "dai" => Key::Hash(wcspr.contract_hash()),
+            "uniswap_v2_factory" => Key::Hash(factory.contract_hash())
+        },
+    );
+    let pair = TestContract::new(
+        env,
+        "pair-token.wasm",
+        "pair",
+        Sender(owner),
+        runtime_args! {
+            "initial_supply" => U256::from(0),
+            "name" => "pair",
+            "symbol" => "lp",
+            "decimals" => 9u8,
+            "callee_contract_hash" => Key::Hash(flash_swapper.contract_hash()),
+            "factory_hash" => Key::Hash(factory.contract_hash())
+        },
+    );
+    // owner creates the pair
+    factory.call_contract(
+        Sender(owner),
+        "set_white_list",
+        runtime_args! {"white_list" => Key::from(owner)},
+    );
+    let token_a_key = Key::Hash(token_a.contract_hash());
+    let token_b_key = Key::Hash(token_b.contract_hash());
+    let pair_key = Key::Hash(pair.contract_hash());
+    match amp {
+        Some(amp) => factory.call_contract(
+            Sender(owner),
+            "create_stable_pair",
+            runtime_args! {
+                "token_a" => token_a_key,
+                "token_b" => token_b_key,
+                "pair_hash" => pair_key,
+                "amp" => amp
+            },
+        ),
+        None => factory.call_contract(
+            Sender(owner),
+            "create_pair",
+            runtime_args! {
+                "token_a" => token_a_key,
+                "token_b" => token_b_key,
+                "pair_hash" => pair_key
+            },
+        ),
+    }
+    // the factory sorts the tokens
+    let (token0, token1) =
+        if Key::Hash(token_a.contract_hash()) < Key::Hash(token_b.contract_hash()) {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        };
+    let pool = Pool {
+        pair,
+        token0,
+        token1,
+    };
+    let pair_package = Key::from(pool.package_hash());
+    mint(&pool.token0, owner, pair_package, 10000.into());
+    mint(&pool.token1, owner, pair_package, 10000.into());
+    pool.pair.call_contract(
+        Sender(owner),
+        "mint",
+        runtime_args! {"to" => Key::from(owner)},
+    );
+    pool
+}
+
+fn deploy_token(env: &TestEnv, name: &str, owner: AccountHash) -> TestContract {
+    TestContract::new(
+        env,
+        "erc20-token.wasm",
+        name,
+        Sender(owner),
+        runtime_args! {
+            "initial_supply" => U256::from(0),
+            "name" => name,
+            "symbol" => "tk",
+            "decimals" => 18u8
+        },
+    )
+}
+
+// takes 1% of every transfer, see erc20/test-contract
+fn deploy_taxed_token(env: &TestEnv, owner: AccountHash) -> TestContract {
+    TestContract::new(
+        env,
+        "erc20-taxed-token.wasm",
+        "taxed_token",
+        Sender(owner),
+        runtime_args! {
+            "initial_supply" => U256::from(0),
+            "name" => "taxed",
+            "symbol" => "tax",
+            "decimals" => 18u8
+        },
+    )
+}
+
+fn mint(token: &TestContract, owner: AccountHash, to: Key, amount: U256) {
+    token.call_contract(
+        Sender(owner),
+        "mint",
+        runtime_args! {"to" => to, "amount" => amount},
+    );
+}
+
+fn approve(token: &TestContract, owner: AccountHash, spender: Key, amount: U256) {
+    token.call_contract(
+        Sender(owner),
+        "approve",
+        runtime_args! {"spender" => spender, "amount" => amount},
+    );
+}
+
+fn balance_of(token: &TestContract, account: Key) -> U256 {
+    token
+        .query_dictionary("balances", key_to_str(&account))
+        .unwrap_or_default()
+}
+
+/// Places an order of a new user to sell 1000 token0 with a bounty of 10 token0.
+fn place_order(
+    env: &TestEnv,
+    limit_order: &LIMITORDERInstance,
+    owner: AccountHash,
+    pool: &Pool,
+    min_amount_out: U256,
+    chunks: U256,
+    interval: u64,
+) -> AccountHash {
+    let user = env.next_user();
+    mint(&pool.token0, owner, Key::from(user), 1010.into());
+    let spender = Key::from(limit_order.package_hash());
+    approve(&pool.token0, user, spender, 1010.into());
+    limit_order.place_order(
+        Sender(user),
+        pool.key(),
+        pool.token0_key(),
+        1000.into(),
+        min_amount_out,
+        10.into(),
+        chunks,
+        interval,
+    );
+    user
+}
+
+#[test]
+fn test_limit_order_deploy() {
+    let (_env, limit_order, _owner, _pool) = deploy();
+    assert_eq!(limit_order.order_count(), 0.into());
+}
+
+#[test]
+fn test_limit_order_place_order() {
+    let (env, limit_order, owner, pool) = deploy();
+    let user = place_order(&env, &limit_order, owner, &pool, 900.into(), 1.into(), 0);
+    assert_eq!(limit_order.order_count(), 1.into());
+    assert_eq!(limit_order.owner(0.into()), Key::from(user));
+    assert_eq!(limit_order.remaining_amount_in(0.into()), 1000.into());
+    assert_eq!(limit_order.remaining_chunks(0.into()), 1.into());
+    assert_eq!(balance_of(&pool.token0, Key::from(user)), 0.into());
+    assert_eq!(
+        balance_of(&pool.token0, Key::from(limit_order.package_hash())),
+        1010.into()
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_limit_order_place_order_with_zero_chunks() {
+    let (env, limit_order, owner, pool) = deploy();
+    place_order(&env, &limit_order, owner, &pool, 900.into(), 0.into(), 0);
+}
+
+#[test]
+#[should_panic(expected = "User(808)")]
+fn test_limit_order_place_order_with_taxed_token() {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let taxed = deploy_taxed_token(&env, owner);
+    let taxed_key = Key::Hash(taxed.contract_hash());
+    let token = deploy_token(&env, "token1", owner);
+    let pool = deploy_pool(&env, owner, taxed, token, None);
+    let limit_order = LIMITORDERInstance::new(&env, NAME_LIMIT_ORDER, Sender(owner));
+    let user = env.next_user();
+    let taxed = if taxed_key == pool.token0_key() {
+        &pool.token0
+    } else {
+        &pool.token1
+    };
+    let spender = Key::from(limit_order.package_hash());
+    mint(taxed, owner, Key::from(user), 1010.into());
+    approve(taxed, user, spender, 1010.into());
+    // 1% of the escrow is taken on the way, so the order is rejected
+    limit_order.place_order(
+        Sender(user),
+        pool.key(),
+        taxed_key,
+        1000.into(),
+        900.into(),
+        10.into(),
+        1.into(),
+        0,
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_limit_order_place_order_with_token_not_in_pair() {
+    let (env, limit_order, owner, pool) = deploy();
+    let user = env.next_user();
+    let token = deploy_token(&env, "token2", owner);
+    mint(&token, owner, Key::from(user), 1000.into());
+    approve(
+        &token,
+        user,
+        Key::from(limit_order.package_hash()),
+        1000.into(),
+    );
+    limit_order.place_order(
+        Sender(user),
+        pool.key(),
+        Key::Hash(token.contract_hash()),
+        1000.into(),
+        900.into(),
+        0.into(),
+        1.into(),
+        0,
+    );
+}
+
+#[test]
+fn test_limit_order_execute_order() {
+    let (env, limit_order, owner, pool) = deploy();
+    let user = place_order(&env, &limit_order, owner, &pool, 900.into(), 1.into(), 0);
+    let keeper = env.next_user();
+    limit_order.execute_order(Sender(keeper), 0.into());
+    // 1000 * 997 * 10000 / (10000 * 1000 + 1000 * 997) = 906
+    assert_eq!(balance_of(&pool.token1, Key::from(user)), 906.into());
+    assert_eq!(balance_of(&pool.token0, Key::from(keeper)), 10.into());
+    assert_eq!(pool.reserves(), (11000.into(), 9094.into()));
+    assert_eq!(limit_order.remaining_amount_in(0.into()), 0.into());
+    assert_eq!(limit_order.remaining_chunks(0.into()), 0.into());
+}
+
+#[test]
+fn test_limit_order_execute_order_on_stable_pair() {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let token_a = deploy_token(&env, "token0", owner);
+    let token_b = deploy_token(&env, "token1", owner);
+    let pool = deploy_pool(&env, owner, token_a, token_b, Some(100.into()));
+    let limit_order = LIMITORDERInstance::new(&env, NAME_LIMIT_ORDER, Sender(owner));
+    // x * y = k would give 906, the stable curve gives 996
+    let user = place_order(&env, &limit_order, owner, &pool, 990.into(), 1.into(), 0);
+    let keeper = env.next_user();
+    limit_order.execute_order(Sender(keeper), 0.into());
+    assert_eq!(balance_of(&pool.token1, Key::from(user)), 996.into());
+    assert_eq!(balance_of(&pool.token0, Key::from(keeper)), 10.into());
+    assert_eq!(pool.reserves(), (11000.into(), 9004.into()));
+}
+
+#[test]
+#[should_panic]
+fn test_limit_order_execute_order_before_price_is_reached() {
+    let (env, limit_order, owner, pool) = deploy();
+    place_order(&env, &limit_order, owner, &pool, 907.into(), 1.into(), 0);
+    let keeper = env.next_user();
+    limit_order.execute_order(Sender(keeper), 0.into());
+}
+
+#[test]
+#[should_panic]
+fn test_limit_order_execute_filled_order() {
+    let (env, limit_order, owner, pool) = deploy();
+    place_order(&env, &limit_order, owner, &pool, 900.into(), 1.into(), 0);
+    let keeper = env.next_user();
+    limit_order.execute_order(Sender(keeper), 0.into());
+    limit_order.execute_order(Sender(keeper), 0.into());
+}
+
+#[test]
+fn test_limit_order_execute_order_in_chunks() {
+    let (env, limit_order, owner, pool) = deploy();
+    let user = place_order(&env, &limit_order, owner, &pool, 800.into(), 2.into(), 0);
+    let keeper = env.next_user();
+    // 500 * 997 * 10000 / (10000 * 1000 + 500 * 997) = 474
+    limit_order.execute_order(Sender(keeper), 0.into());
+    assert_eq!(balance_of(&pool.token1, Key::from(user)), 474.into());
+    assert_eq!(balance_of(&pool.token0, Key::from(keeper)), 5.into());
+    assert_eq!(limit_order.remaining_amount_in(0.into()), 500.into());
+    assert_eq!(limit_order.remaining_chunks(0.into()), 1.into());
+    // 500 * 997 * 9526 / (10500 * 1000 + 500 * 997) = 431
+    limit_order.execute_order(Sender(keeper), 0.into());
+    assert_eq!(balance_of(&pool.token1, Key::from(user)), 905.into());
+    assert_eq!(balance_of(&pool.token0, Key::from(keeper)), 10.into());
+    assert_eq!(pool.reserves(), (11000.into(), 9095.into()));
+    assert_eq!(limit_order.remaining_chunks(0.into()), 0.into());
+}
+
+#[test]
+#[should_panic]
+fn test_limit_order_execute_chunk_before_interval() {
+    let (env, limit_order, owner, pool) = deploy();
+    place_order(&env, &limit_order, owner, &pool, 800.into(), 2.into(), 60);
+    let keeper = env.next_user();
+    limit_order.execute_order(Sender(keeper), 0.into());
+    limit_order.execute_order(Sender(keeper), 0.into());
+}
+
+#[test]
+fn test_limit_order_cancel_order() {
+    let (env, limit_order, owner, pool) = deploy();
+    let user = place_order(&env, &limit_order, owner, &pool, 800.into(), 2.into(), 0);
+    let keeper = env.next_user();
+    limit_order.execute_order(Sender(keeper), 0.into());
+    limit_order.cancel_order(Sender(user), 0.into());
+    assert_eq!(balance_of(&pool.token0, Key::from(user)), 505.into());
+    assert_eq!(limit_order.remaining_amount_in(0.into()), 0.into());
+    assert_eq!(limit_order.remaining_chunks(0.into()), 0.into());
+    assert_eq!(
+        balance_of(&pool.token0, Key::from(limit_order.package_hash())),
+        0.into()
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_limit_order_cancel_order_by_non_owner() {
+    let (env, limit_order, owner, pool) = deploy();
+    place_order(&env, &limit_order, owner, &pool, 900.into(), 1.into(), 0);
+    let user = env.next_user();
+    limit_order.cancel_order(Sender(user), 0.into());
+}
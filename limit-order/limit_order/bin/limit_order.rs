@@ -0,0 +1,260 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{collections::BTreeSet, format, vec};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    runtime_args, CLType, CLTyped, CLValue, ContractHash, ContractPackageHash, EntryPoint,
+    EntryPointAccess, EntryPointType, EntryPoints, Group, Key, Parameter, RuntimeArgs, URef, U256,
+};
+use contract_utils::{ContractContext, OnChainContractStorage};
+use limit_order::{self, LIMITORDER};
+
+#[derive(Default)]
+struct LimitOrder(OnChainContractStorage);
+
+impl ContractContext<OnChainContractStorage> for LimitOrder {
+    fn storage(&self) -> &OnChainContractStorage {
+        &self.0
+    }
+}
+
+impl LIMITORDER<OnChainContractStorage> for LimitOrder {}
+
+impl LimitOrder {
+    fn constructor(&mut self, contract_hash: ContractHash, package_hash: ContractPackageHash) {
+        LIMITORDER::init(self, Key::from(contract_hash), package_hash);
+    }
+}
+
+#[no_mangle]
+fn constructor() {
+    let contract_hash: ContractHash = runtime::get_named_arg("contract_hash");
+    let package_hash: ContractPackageHash = runtime::get_named_arg("package_hash");
+    LimitOrder::default().constructor(contract_hash, package_hash);
+}
+
+/// This function is to place an order that sells token_in on a pair once its reserves give the minimum amount out, the amount and the bounty are escrowed and it returns the id of the order
+///
+/// # Parameters
+///
+/// * `pair` - A Key that holds the Hash of the pair
+///
+/// * `token_in` - A Key that holds the Hash of the token to sell, which the caller must have approved for the package hash of this contract
+///
+/// * `amount_in` - A U256 that holds the amount of token_in to sell
+///
+/// * `min_amount_out` - A U256 that holds the minimum amount of the other token to receive for amount_in
+///
+/// * `bounty` - A U256 that holds the amount of token_in paid to the callers who execute the order
+///
+/// * `chunks` - A U256 that holds the number of parts the order is sold in
+///
+/// * `interval` - A u64 that holds the time in seconds between two parts
+///
+
+#[no_mangle]
+fn place_order() {
+    let pair: Key = runtime::get_named_arg("pair");
+    let token_in: Key = runtime::get_named_arg("token_in");
+    let amount_in: U256 = runtime::get_named_arg("amount_in");
+    let min_amount_out: U256 = runtime::get_named_arg("min_amount_out");
+    let bounty: U256 = runtime::get_named_arg("bounty");
+    let chunks: U256 = runtime::get_named_arg("chunks");
+    let interval: u64 = runtime::get_named_arg("interval");
+    let ret: U256 = LimitOrder::default().place_order(
+        pair,
+        token_in,
+        amount_in,
+        min_amount_out,
+        bounty,
+        chunks,
+        interval,
+    );
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to cancel an open order and return what is left of its escrow, which is only possible if the caller is the owner of the order
+///
+/// # Parameters
+///
+/// * `id` - A U256 that holds the id of the order
+///
+
+#[no_mangle]
+fn cancel_order() {
+    let id: U256 = runtime::get_named_arg("id");
+    LimitOrder::default().cancel_order(id);
+}
+
+/// This function is to sell the next part of an order, anyone can call it and gets the bounty of the part, it returns the amount sent to the owner of the order
+///
+/// # Parameters
+///
+/// * `id` - A U256 that holds the id of the order
+///
+
+#[no_mangle]
+fn execute_order() {
+    let id: U256 = runtime::get_named_arg("id");
+    let ret: U256 = LimitOrder::default().execute_order(id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return whether the next part of an order can be executed at the current block time
+///
+/// # Parameters
+///
+/// * `id` - A U256 that holds the id of the order
+///
+
+#[no_mangle]
+fn can_execute() {
+    let id: U256 = runtime::get_named_arg("id");
+    let ret: bool = LimitOrder::default().can_execute(id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the number of orders placed
+///
+
+#[no_mangle]
+fn order_count() {
+    let ret: U256 = LimitOrder::default().order_count();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to fetch a Contract Package Hash
+///
+
+#[no_mangle]
+fn package_hash() {
+    let ret: ContractPackageHash = LimitOrder::default().get_package_hash();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new("contract_hash", ContractHash::cl_type()),
+            Parameter::new("package_hash", ContractPackageHash::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "place_order",
+        vec![
+            Parameter::new("pair", Key::cl_type()),
+            Parameter::new("token_in", Key::cl_type()),
+            Parameter::new("amount_in", U256::cl_type()),
+            Parameter::new("min_amount_out", U256::cl_type()),
+            Parameter::new("bounty", U256::cl_type()),
+            Parameter::new("chunks", U256::cl_type()),
+            Parameter::new("interval", u64::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "cancel_order",
+        vec![Parameter::new("id", U256::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "execute_order",
+        vec![Parameter::new("id", U256::cl_type())],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "can_execute",
+        vec![Parameter::new("id", U256::cl_type())],
+        bool::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "order_count",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "package_hash",
+        vec![],
+        ContractPackageHash::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
+
+#[no_mangle]
+fn call() {
+    // Contract name must be same for all new versions of the contracts
+    let contract_name: alloc::string::String = runtime::get_named_arg("contract_name");
+
+    // Build new package with initial a first version of the contract.
+    let (package_hash, access_token) = storage::create_contract_package_at_hash();
+    let (contract_hash, _) =
+        storage::add_contract_version(package_hash, get_entry_points(), Default::default());
+
+    // Prepare constructor args
+    let constructor_args = runtime_args! {
+        "contract_hash" => contract_hash,
+        "package_hash"=> package_hash
+    };
+
+    // Add the constructor group to the package hash with a single URef.
+    let constructor_access: URef =
+        storage::create_contract_user_group(package_hash, "constructor", 1, Default::default())
+            .unwrap_or_revert()
+            .pop()
+            .unwrap_or_revert();
+
+    // Call the constructor entry point
+    let _: () =
+        runtime::call_versioned_contract(package_hash, None, "constructor", constructor_args);
+
+    // Remove all URefs from the constructor group, so no one can call it for the second time.
+    let mut urefs = BTreeSet::new();
+    urefs.insert(constructor_access);
+    storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
+        .unwrap_or_revert();
+
+    // Store contract in the account's named keys.
+    runtime::put_key(
+        &format!("{}_package_hash", contract_name),
+        package_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_hash_wrapped", contract_name),
+        storage::new_uref(package_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash_wrapped", contract_name),
+        storage::new_uref(contract_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_access_token", contract_name),
+        access_token.into(),
+    );
+}
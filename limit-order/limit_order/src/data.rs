@@ -0,0 +1,182 @@
+use crate::alloc::string::ToString;
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{ContractPackageHash, Key, U256};
+use contract_utils::{get_key, set_key, Dict, ZERO_HASH};
+
+pub const SELF_CONTRACT_HASH: &str = "self_contract_hash";
+pub const CONTRACT_PACKAGE_HASH: &str = "contract_package_hash";
+pub const ORDER_COUNT: &str = "order_count";
+pub const ORDER_OWNERS_DICT: &str = "order_owners";
+pub const ORDER_PAIRS_DICT: &str = "order_pairs";
+pub const ORDER_TOKENS_IN_DICT: &str = "order_tokens_in";
+pub const ORDER_AMOUNTS_IN_DICT: &str = "order_amounts_in";
+pub const ORDER_MIN_AMOUNTS_OUT_DICT: &str = "order_min_amounts_out";
+pub const ORDER_BOUNTIES_DICT: &str = "order_bounties";
+pub const ORDER_CHUNKS_DICT: &str = "order_chunks";
+pub const ORDER_INTERVALS_DICT: &str = "order_intervals";
+pub const ORDER_REMAINING_AMOUNTS_IN_DICT: &str = "order_remaining_amounts_in";
+pub const ORDER_REMAINING_BOUNTIES_DICT: &str = "order_remaining_bounties";
+pub const ORDER_REMAINING_CHUNKS_DICT: &str = "order_remaining_chunks";
+pub const ORDER_NEXT_EXECUTION_TIMES_DICT: &str = "order_next_execution_times";
+
+/// Terms of an order as it was placed. `amount_in` is sold in `chunks` equal parts, at
+/// least `interval` seconds apart, and each part must get its share of `min_amount_out`.
+/// `bounty` is paid out in the same parts to whoever executes them.
+pub struct Order {
+    pub owner: Key,
+    pub pair: Key,
+    pub token_in: Key,
+    pub amount_in: U256,
+    pub min_amount_out: U256,
+    pub bounty: U256,
+    pub chunks: U256,
+    pub interval: u64,
+}
+
+/// State of every order, indexed by order id. Order ids are given out in the order
+/// the orders are placed, starting from 0. An order is open while it has chunks left.
+pub struct Orders {
+    owners: Dict,
+    pairs: Dict,
+    tokens_in: Dict,
+    amounts_in: Dict,
+    min_amounts_out: Dict,
+    bounties: Dict,
+    chunks: Dict,
+    intervals: Dict,
+    remaining_amounts_in: Dict,
+    remaining_bounties: Dict,
+    remaining_chunks: Dict,
+    next_execution_times: Dict,
+}
+
+impl Orders {
+    pub fn instance() -> Orders {
+        Orders {
+            owners: Dict::instance(ORDER_OWNERS_DICT),
+            pairs: Dict::instance(ORDER_PAIRS_DICT),
+            tokens_in: Dict::instance(ORDER_TOKENS_IN_DICT),
+            amounts_in: Dict::instance(ORDER_AMOUNTS_IN_DICT),
+            min_amounts_out: Dict::instance(ORDER_MIN_AMOUNTS_OUT_DICT),
+            bounties: Dict::instance(ORDER_BOUNTIES_DICT),
+            chunks: Dict::instance(ORDER_CHUNKS_DICT),
+            intervals: Dict::instance(ORDER_INTERVALS_DICT),
+            remaining_amounts_in: Dict::instance(ORDER_REMAINING_AMOUNTS_IN_DICT),
+            remaining_bounties: Dict::instance(ORDER_REMAINING_BOUNTIES_DICT),
+            remaining_chunks: Dict::instance(ORDER_REMAINING_CHUNKS_DICT),
+            next_execution_times: Dict::instance(ORDER_NEXT_EXECUTION_TIMES_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(ORDER_OWNERS_DICT);
+        Dict::init(ORDER_PAIRS_DICT);
+        Dict::init(ORDER_TOKENS_IN_DICT);
+        Dict::init(ORDER_AMOUNTS_IN_DICT);
+        Dict::init(ORDER_MIN_AMOUNTS_OUT_DICT);
+        Dict::init(ORDER_BOUNTIES_DICT);
+        Dict::init(ORDER_CHUNKS_DICT);
+        Dict::init(ORDER_INTERVALS_DICT);
+        Dict::init(ORDER_REMAINING_AMOUNTS_IN_DICT);
+        Dict::init(ORDER_REMAINING_BOUNTIES_DICT);
+        Dict::init(ORDER_REMAINING_CHUNKS_DICT);
+        Dict::init(ORDER_NEXT_EXECUTION_TIMES_DICT);
+    }
+
+    pub fn add(&self, id: U256, order: &Order, next_execution_time: u64) {
+        let id: &str = &id.to_string();
+        self.owners.set(id, order.owner);
+        self.pairs.set(id, order.pair);
+        self.tokens_in.set(id, order.token_in);
+        self.amounts_in.set(id, order.amount_in);
+        self.min_amounts_out.set(id, order.min_amount_out);
+        self.bounties.set(id, order.bounty);
+        self.chunks.set(id, order.chunks);
+        self.intervals.set(id, order.interval);
+        self.remaining_amounts_in.set(id, order.amount_in);
+        self.remaining_bounties.set(id, order.bounty);
+        self.remaining_chunks.set(id, order.chunks);
+        self.next_execution_times.set(id, next_execution_time);
+    }
+
+    pub fn get(&self, id: U256) -> Order {
+        let id: &str = &id.to_string();
+        Order {
+            owner: self.owners.get(id).unwrap_or(ZERO_HASH),
+            pair: self.pairs.get(id).unwrap_or(ZERO_HASH),
+            token_in: self.tokens_in.get(id).unwrap_or(ZERO_HASH),
+            amount_in: self.amounts_in.get(id).unwrap_or_default(),
+            min_amount_out: self.min_amounts_out.get(id).unwrap_or_default(),
+            bounty: self.bounties.get(id).unwrap_or_default(),
+            chunks: self.chunks.get(id).unwrap_or_default(),
+            interval: self.intervals.get(id).unwrap_or_default(),
+        }
+    }
+
+    pub fn remaining_amount_in(&self, id: U256) -> U256 {
+        self.remaining_amounts_in
+            .get(&id.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn set_remaining_amount_in(&self, id: U256, remaining_amount_in: U256) {
+        self.remaining_amounts_in
+            .set(&id.to_string(), remaining_amount_in);
+    }
+
+    pub fn remaining_bounty(&self, id: U256) -> U256 {
+        self.remaining_bounties
+            .get(&id.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn set_remaining_bounty(&self, id: U256, remaining_bounty: U256) {
+        self.remaining_bounties
+            .set(&id.to_string(), remaining_bounty);
+    }
+
+    pub fn remaining_chunks(&self, id: U256) -> U256 {
+        self.remaining_chunks
+            .get(&id.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn set_remaining_chunks(&self, id: U256, remaining_chunks: U256) {
+        self.remaining_chunks.set(&id.to_string(), remaining_chunks);
+    }
+
+    pub fn next_execution_time(&self, id: U256) -> u64 {
+        self.next_execution_times
+            .get(&id.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn set_next_execution_time(&self, id: U256, next_execution_time: u64) {
+        self.next_execution_times
+            .set(&id.to_string(), next_execution_time);
+    }
+}
+
+pub fn set_hash(contract_hash: Key) {
+    set_key(SELF_CONTRACT_HASH, contract_hash);
+}
+
+pub fn get_hash() -> Key {
+    get_key(SELF_CONTRACT_HASH).unwrap_or_revert()
+}
+
+pub fn set_package_hash(package_hash: ContractPackageHash) {
+    set_key(CONTRACT_PACKAGE_HASH, package_hash);
+}
+
+pub fn get_package_hash() -> ContractPackageHash {
+    get_key(CONTRACT_PACKAGE_HASH).unwrap_or_revert()
+}
+
+pub fn set_order_count(order_count: U256) {
+    set_key(ORDER_COUNT, order_count);
+}
+
+pub fn get_order_count() -> U256 {
+    get_key(ORDER_COUNT).unwrap_or_default()
+}
@@ -0,0 +1,8 @@
+#![no_std]
+
+extern crate alloc;
+
+pub mod data;
+mod limit_order;
+
+pub use limit_order::LIMITORDER;
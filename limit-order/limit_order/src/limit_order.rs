@@ -0,0 +1,379 @@
+use crate::alloc::string::ToString;
+use crate::data::{self, Order, Orders};
+use alloc::collections::BTreeMap;
+use alloc::{string::String, vec::Vec};
+use casper_contract::contract_api::runtime;
+use casper_contract::contract_api::storage;
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{runtime_args, ContractHash, ContractPackageHash, Key, RuntimeArgs, URef, U256};
+use contract_utils::{key_to_contract_hash, ContractContext, ContractStorage};
+use errors::LimitOrderError;
+
+pub enum LIMITORDEREvent {
+    OrderPlaced {
+        id: U256,
+        owner: Key,
+        pair: Key,
+        token_in: Key,
+        amount_in: U256,
+        min_amount_out: U256,
+    },
+    OrderExecuted {
+        id: U256,
+        keeper: Key,
+        amount_in: U256,
+        amount_out: U256,
+        bounty: U256,
+    },
+    OrderCancelled {
+        id: U256,
+        amount_in: U256,
+        bounty: U256,
+    },
+}
+impl LIMITORDEREvent {
+    pub fn type_name(&self) -> String {
+        match self {
+            LIMITORDEREvent::OrderPlaced {
+                id: _,
+                owner: _,
+                pair: _,
+                token_in: _,
+                amount_in: _,
+                min_amount_out: _,
+            } => "order_placed",
+            LIMITORDEREvent::OrderExecuted {
+                id: _,
+                keeper: _,
+                amount_in: _,
+                amount_out: _,
+                bounty: _,
+            } => "order_executed",
+            LIMITORDEREvent::OrderCancelled {
+                id: _,
+                amount_in: _,
+                bounty: _,
+            } => "order_cancelled",
+        }
+        .to_string()
+    }
+}
+
+pub trait LIMITORDER<Storage: ContractStorage>: ContractContext<Storage> {
+    fn init(&mut self, contract_hash: Key, package_hash: ContractPackageHash) {
+        data::set_order_count(0.into());
+        data::set_hash(contract_hash);
+        data::set_package_hash(package_hash);
+        Orders::init();
+    }
+
+    /// Places an order to sell `amount_in` of `token_in` on `pair` for at least
+    /// `min_amount_out` of the other token, and returns its id. The order is sold in
+    /// `chunks` parts at least `interval` seconds apart, a single chunk makes it a plain
+    /// limit order. `amount_in` and `bounty` are escrowed, so the caller must have
+    /// approved both for this contract's package hash. Tokens that take a fee on
+    /// transfer are rejected, as the escrow would not cover the order.
+    fn place_order(
+        &mut self,
+        pair: Key,
+        token_in: Key,
+        amount_in: U256,
+        min_amount_out: U256,
+        bounty: U256,
+        chunks: U256,
+        interval: u64,
+    ) -> U256 {
+        if chunks.is_zero() || chunks > amount_in {
+            runtime::revert(LimitOrderError::LimitOrderInvalidChunks);
+        }
+        let pair_hash: ContractHash = key_to_contract_hash(pair).unwrap_or_revert();
+        let token0: Key = runtime::call_contract(pair_hash, "token0", runtime_args! {});
+        let token1: Key = runtime::call_contract(pair_hash, "token1", runtime_args! {});
+        if token_in != token0 && token_in != token1 {
+            runtime::revert(LimitOrderError::LimitOrderInvalidToken);
+        }
+        let owner: Key = self.get_caller();
+        let escrow: U256 = amount_in
+            .checked_add(bounty)
+            .ok_or(LimitOrderError::LimitOrderOverFlow)
+            .unwrap_or_revert();
+        let limit_order: Key = Key::from(data::get_package_hash());
+        let balance_before: U256 = token_balance(token_in, limit_order);
+        let ret: Result<(), u32> = runtime::call_contract(
+            key_to_contract_hash(token_in).unwrap_or_revert(),
+            "transfer_from",
+            runtime_args! {
+                "owner" => owner,
+                "recipient" => limit_order,
+                "amount" => escrow
+            },
+        );
+        ret.unwrap_or_revert();
+        let received: U256 = token_balance(token_in, limit_order)
+            .checked_sub(balance_before)
+            .ok_or(LimitOrderError::LimitOrderUnderFlow)
+            .unwrap_or_revert();
+        if received < escrow {
+            runtime::revert(LimitOrderError::LimitOrderAmountNotReceived);
+        }
+        let id: U256 = data::get_order_count();
+        Orders::instance().add(
+            id,
+            &Order {
+                owner: owner,
+                pair: pair,
+                token_in: token_in,
+                amount_in: amount_in,
+                min_amount_out: min_amount_out,
+                bounty: bounty,
+                chunks: chunks,
+                interval: interval,
+            },
+            runtime::get_blocktime().into(),
+        );
+        data::set_order_count(id + 1);
+        self.emit(&LIMITORDEREvent::OrderPlaced {
+            id: id,
+            owner: owner,
+            pair: pair,
+            token_in: token_in,
+            amount_in: amount_in,
+            min_amount_out: min_amount_out,
+        });
+        id
+    }
+
+    /// Returns what is left of the escrow of an open order to its owner.
+    fn cancel_order(&mut self, id: U256) {
+        self.assert_order_open(id);
+        let orders: Orders = Orders::instance();
+        let order: Order = orders.get(id);
+        if self.get_caller() != order.owner {
+            runtime::revert(LimitOrderError::LimitOrderNotOwner);
+        }
+        let amount_in: U256 = orders.remaining_amount_in(id);
+        let bounty: U256 = orders.remaining_bounty(id);
+        orders.set_remaining_amount_in(id, U256::zero());
+        orders.set_remaining_bounty(id, U256::zero());
+        orders.set_remaining_chunks(id, U256::zero());
+        transfer(
+            order.token_in,
+            order.owner,
+            amount_in
+                .checked_add(bounty)
+                .ok_or(LimitOrderError::LimitOrderOverFlow)
+                .unwrap_or_revert(),
+        );
+        self.emit(&LIMITORDEREvent::OrderCancelled {
+            id: id,
+            amount_in: amount_in,
+            bounty: bounty,
+        });
+    }
+
+    /// Sells the next chunk of an order through `swap` of its pair, the output goes to the
+    /// owner of the order and the bounty of the chunk to the caller. Anyone may call it,
+    /// it reverts unless the interval has passed and the reserves of the pair give at
+    /// least the chunk's share of `min_amount_out`. Returns the amount out.
+    fn execute_order(&mut self, id: U256) -> U256 {
+        self.assert_order_open(id);
+        let now: u64 = runtime::get_blocktime().into();
+        let orders: Orders = Orders::instance();
+        if now < orders.next_execution_time(id) {
+            runtime::revert(LimitOrderError::LimitOrderTooEarly);
+        }
+        let order: Order = orders.get(id);
+        let (amount_in, bounty, min_amount_out): (U256, U256, U256) = self.next_chunk(id);
+        let pair_hash: ContractHash = key_to_contract_hash(order.pair).unwrap_or_revert();
+        let (amount_out, token_in_is_token0): (U256, bool) =
+            quote_amount_out(pair_hash, order.token_in, amount_in);
+        if amount_out.is_zero() || amount_out < min_amount_out {
+            runtime::revert(LimitOrderError::LimitOrderPriceNotReached);
+        }
+
+        orders.set_remaining_amount_in(id, orders.remaining_amount_in(id) - amount_in);
+        orders.set_remaining_bounty(id, orders.remaining_bounty(id) - bounty);
+        orders.set_remaining_chunks(id, orders.remaining_chunks(id) - 1);
+        orders.set_next_execution_time(id, now.saturating_add(order.interval.saturating_mul(1000)));
+
+        let pair_package: ContractPackageHash =
+            runtime::call_contract(pair_hash, "package_hash", runtime_args! {});
+        transfer(order.token_in, Key::from(pair_package), amount_in);
+        let (amount0_out, amount1_out): (U256, U256) = if token_in_is_token0 {
+            (U256::zero(), amount_out)
+        } else {
+            (amount_out, U256::zero())
+        };
+        let _: () = runtime::call_contract(
+            pair_hash,
+            "swap",
+            runtime_args! {
+                "amount0_out" => amount0_out,
+                "amount1_out" => amount1_out,
+                "to" => order.owner,
+                "data" => String::new()
+            },
+        );
+        let keeper: Key = self.get_caller();
+        transfer(order.token_in, keeper, bounty);
+        self.emit(&LIMITORDEREvent::OrderExecuted {
+            id: id,
+            keeper: keeper,
+            amount_in: amount_in,
+            amount_out: amount_out,
+            bounty: bounty,
+        });
+        amount_out
+    }
+
+    /// Whether `execute_order` would go through at the current block time, for keepers
+    /// to check before they pay for a deploy.
+    fn can_execute(&mut self, id: U256) -> bool {
+        let orders: Orders = Orders::instance();
+        if id >= data::get_order_count() || orders.remaining_chunks(id).is_zero() {
+            return false;
+        }
+        let now: u64 = runtime::get_blocktime().into();
+        if now < orders.next_execution_time(id) {
+            return false;
+        }
+        let order: Order = orders.get(id);
+        let (amount_in, _bounty, min_amount_out): (U256, U256, U256) = self.next_chunk(id);
+        let (amount_out, _): (U256, bool) = quote_amount_out(
+            key_to_contract_hash(order.pair).unwrap_or_revert(),
+            order.token_in,
+            amount_in,
+        );
+        !amount_out.is_zero() && amount_out >= min_amount_out
+    }
+
+    /// `(amount_in, bounty, min_amount_out)` of the next chunk of an open order. The last
+    /// chunk takes whatever rounding left over, and the share of `min_amount_out` is
+    /// rounded up so that no chunk sells below the price of the order.
+    fn next_chunk(&mut self, id: U256) -> (U256, U256, U256) {
+        let orders: Orders = Orders::instance();
+        let order: Order = orders.get(id);
+        let (amount_in, bounty): (U256, U256) = if orders.remaining_chunks(id) == U256::one() {
+            (orders.remaining_amount_in(id), orders.remaining_bounty(id))
+        } else {
+            (order.amount_in / order.chunks, order.bounty / order.chunks)
+        };
+        let min_amount_out: U256 = order
+            .min_amount_out
+            .checked_mul(amount_in)
+            .and_then(|x| x.checked_add(order.amount_in - 1))
+            .ok_or(LimitOrderError::LimitOrderOverFlow)
+            .unwrap_or_revert()
+            / order.amount_in;
+        (amount_in, bounty, min_amount_out)
+    }
+
+    fn order_count(&mut self) -> U256 {
+        data::get_order_count()
+    }
+
+    fn assert_order_open(&mut self, id: U256) {
+        if id >= data::get_order_count() || Orders::instance().remaining_chunks(id).is_zero() {
+            runtime::revert(LimitOrderError::LimitOrderInvalidOrder);
+        }
+    }
+
+    fn emit(&mut self, limit_order_event: &LIMITORDEREvent) {
+        let mut events = Vec::new();
+        let package = data::get_package_hash();
+        match limit_order_event {
+            LIMITORDEREvent::OrderPlaced {
+                id,
+                owner,
+                pair,
+                token_in,
+                amount_in,
+                min_amount_out,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", limit_order_event.type_name());
+                event.insert("id", id.to_string());
+                event.insert("owner", owner.to_string());
+                event.insert("pair", pair.to_string());
+                event.insert("token_in", token_in.to_string());
+                event.insert("amount_in", amount_in.to_string());
+                event.insert("min_amount_out", min_amount_out.to_string());
+                events.push(event);
+            }
+            LIMITORDEREvent::OrderExecuted {
+                id,
+                keeper,
+                amount_in,
+                amount_out,
+                bounty,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", limit_order_event.type_name());
+                event.insert("id", id.to_string());
+                event.insert("keeper", keeper.to_string());
+                event.insert("amount_in", amount_in.to_string());
+                event.insert("amount_out", amount_out.to_string());
+                event.insert("bounty", bounty.to_string());
+                events.push(event);
+            }
+            LIMITORDEREvent::OrderCancelled {
+                id,
+                amount_in,
+                bounty,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", limit_order_event.type_name());
+                event.insert("id", id.to_string());
+                event.insert("amount_in", amount_in.to_string());
+                event.insert("bounty", bounty.to_string());
+                events.push(event);
+            }
+        };
+
+        for event in events {
+            let _: URef = storage::new_uref(event);
+        }
+    }
+
+    fn get_package_hash(&mut self) -> ContractPackageHash {
+        data::get_package_hash()
+    }
+}
+
+/// Output of the pair for `amount_in` of `token_in`, as quoted by `get_amount_out` of its
+/// latest version on the curve of the pair, and whether `token_in` is token0.
+fn quote_amount_out(pair_hash: ContractHash, token_in: Key, amount_in: U256) -> (U256, bool) {
+    let token0: Key = runtime::call_contract(pair_hash, "token0", runtime_args! {});
+    let pair_package: ContractPackageHash =
+        runtime::call_contract(pair_hash, "package_hash", runtime_args! {});
+    let amount_out: U256 = runtime::call_versioned_contract(
+        pair_package,
+        None,
+        "get_amount_out",
+        runtime_args! {"token_in" => token_in, "amount_in" => amount_in},
+    );
+    (amount_out, token_in == token0)
+}
+
+fn token_balance(token: Key, owner: Key) -> U256 {
+    runtime::call_contract(
+        key_to_contract_hash(token).unwrap_or_revert(),
+        "balance_of",
+        runtime_args! {"owner" => owner},
+    )
+}
+
+fn transfer(token: Key, recipient: Key, amount: U256) {
+    if amount.is_zero() {
+        return;
+    }
+    let ret: Result<(), u32> = runtime::call_contract(
+        key_to_contract_hash(token).unwrap_or_revert(),
+        "transfer",
+        runtime_args! {"recipient" => recipient, "amount" => amount},
+    );
+    ret.unwrap_or_revert();
+}
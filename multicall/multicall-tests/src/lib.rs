@@ -0,0 +1,5 @@
+#[cfg(test)]
+pub mod multicall_tests;
+
+#[cfg(test)]
+pub mod multicall_instance;
@@ -0,0 +1,51 @@
+use casper_engine_test_support::{AccountHash, Code};
+use casper_types::{
+    bytesrepr::{Bytes, ToBytes},
+    runtime_args, Key, RuntimeArgs,
+};
+use test_env::{Sender, TestEnv};
+
+/// Multicall is session code, so there is no contract to hold on to, every multicall
+/// sends the wasm again with its list of calls.
+pub struct MULTICALLInstance(pub TestEnv);
+
+impl MULTICALLInstance {
+    pub fn new(env: &TestEnv) -> MULTICALLInstance {
+        MULTICALLInstance(env.clone())
+    }
+
+    pub fn multicall(&self, sender: Sender, calls: Vec<(Key, String, Bytes)>) {
+        self.0.run(
+            sender,
+            Code::from("multicall.wasm"),
+            runtime_args! {"calls" => calls},
+        );
+    }
+
+    pub fn results(&self, account: AccountHash) -> Vec<Bytes> {
+        self.0
+            .query_account_named_key(account, &[String::from("multicall_results")])
+            .into_t()
+            .unwrap()
+    }
+}
+
+pub fn call<T: Into<Key>>(
+    contract: T,
+    entry_point: &str,
+    args: RuntimeArgs,
+) -> (Key, String, Bytes) {
+    (
+        contract.into(),
+        String::from(entry_point),
+        Bytes::from(args.to_bytes().unwrap()),
+    )
+}
+
+pub fn key_to_str(key: &Key) -> String {
+    match key {
+        Key::Account(account) => account.to_string(),
+        Key::Hash(package) => hex::encode(package),
+        _ => panic!("Unexpected key type"),
+    }
+}
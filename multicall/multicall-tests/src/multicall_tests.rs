@@ -0,0 +1,245 @@
+use casper_engine_test_support::AccountHash;
+use casper_types::{
+    bytesrepr::{self, Bytes, ToBytes},
+    runtime_args, ContractPackageHash, Key, RuntimeArgs, U128, U256,
+};
+use test_env::{Sender, TestContract, TestEnv};
+
+use crate::multicall_instance::{call, key_to_str, MULTICALLInstance};
+
+fn deploy() -> (TestEnv, MULTICALLInstance, AccountHash, TestContract) {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let token = deploy_token(&env, "token", owner);
+    let multicall = MULTICALLInstance::new(&env);
+    (env, multicall, owner, token)
+}
+
+fn deploy_token(env: &TestEnv, name: &str, owner: AccountHash) -> TestContract {
+    TestContract::new(
+        env,
+        "erc20-token.wasm",
+        name,
+        Sender(owner),
+        runtime_args! {
+            "initial_supply" => U256::from(0),
+            "name" => name,
+            "symbol" => "tk",
+            "decimals" => 18u8
+        },
+    )
+}
+
+fn deploy_pair(
+    env: &TestEnv,
+    owner: AccountHash,
+    token0: &TestContract,
+    token1: &TestContract,
+) -> TestContract {
+    let factory = TestContract::new(
+        env,
+        "factory.wasm",
+        "factory",
+        Sender(owner),
+        runtime_args! {"fee_to_setter" => Key::from(owner)},
+    );
+    let wcspr = TestContract::new(
+        env,
+        "wcspr-token.wasm",
+        "wcspr",
+        Sender(owner),
+        runtime_args! {
+            "initial_supply" => U256::from(0),
+            "name" => "wcspr",
+            "symbol" => "wcspr",
+            "decimals" => 9u8
+        },
+    );
+    let flash_swapper = TestContract::new(
+        env,
+        "flash-swapper.wasm",
+        "flash_swapper",
+        Sender(owner),
+        runtime_args! {
+            "wcspr" => Key::Hash(wcspr.contract_hash()),
+            "dai" => Key::Hash(wcspr.contract_hash()),
+            "uniswap_v2_factory" => Key::Hash(factory.contract_hash())
+        },
+    );
+    let pair = TestContract::new(
+        env,
+        "pair-token.wasm",
+        "pair",
+        Sender(owner),
+        runtime_args! {
+            "initial_supply" => U256::from(0),
+            "name" => "pair",
+            "symbol" => "lp",
+            "decimals" => 9u8,
+            "callee_contract_hash" => Key::Hash(flash_swapper.contract_hash()),
+            "factory_hash" => Key::Hash(factory.contract_hash())
+        },
+    );
+    // owner creates the pair
+    factory.call_contract(
+        Sender(owner),
+        "set_white_list",
+        runtime_args! {"white_list" => Key::from(owner)},
+    );
+    factory.call_contract(
+        Sender(owner),
+        "create_pair",
+        runtime_args! {
+            "token_a" => Key::Hash(token0.contract_hash()),
+            "token_b" => Key::Hash(token1.contract_hash()),
+            "pair_hash" => Key::Hash(pair.contract_hash())
+        },
+    );
+    pair
+}
+
+fn mint(token: &TestContract, owner: AccountHash, to: Key, amount: U256) {
+    token.call_contract(
+        Sender(owner),
+        "mint",
+        runtime_args! {"to" => to, "amount" => amount},
+    );
+}
+
+fn balance_of(token: &TestContract, account: Key) -> U256 {
+    token
+        .query_dictionary("balances", key_to_str(&account))
+        .unwrap_or_default()
+}
+
+fn ok() -> Bytes {
+    Bytes::from(Ok::<(), u32>(()).to_bytes().unwrap())
+}
+
+#[test]
+fn test_multicall_approve_and_transfer() {
+    let (env, multicall, owner, token) = deploy();
+    let user = env.next_user();
+    let spender = env.next_user();
+    let recipient = env.next_user();
+    mint(&token, owner, Key::from(user), 1000.into());
+    multicall.multicall(
+        Sender(user),
+        vec![
+            call(
+                Key::Hash(token.contract_hash()),
+                "approve",
+                runtime_args! {"spender" => Key::from(spender), "amount" => U256::from(100)},
+            ),
+            call(
+                Key::Hash(token.contract_hash()),
+                "transfer",
+                runtime_args! {"recipient" => Key::from(recipient), "amount" => U256::from(300)},
+            ),
+        ],
+    );
+    assert_eq!(balance_of(&token, Key::from(user)), 700.into());
+    assert_eq!(balance_of(&token, Key::from(recipient)), 300.into());
+    // approve returns nothing, transfer returns Ok(())
+    assert_eq!(multicall.results(user), vec![Bytes::from(vec![]), ok()]);
+    // The allowance was given by the user, not by the multicall.
+    token.call_contract(
+        Sender(spender),
+        "transfer_from",
+        runtime_args! {
+            "owner" => Key::from(user),
+            "recipient" => Key::from(spender),
+            "amount" => U256::from(100)
+        },
+    );
+    assert_eq!(balance_of(&token, Key::from(user)), 600.into());
+    assert_eq!(balance_of(&token, Key::from(spender)), 100.into());
+}
+
+#[test]
+fn test_multicall_add_liquidity() {
+    let (env, multicall, owner, token0) = deploy();
+    let token1 = deploy_token(&env, "token1", owner);
+    let pair = deploy_pair(&env, owner, &token0, &token1);
+    let pair_package: ContractPackageHash = pair.query_named_key(String::from("self_package_hash"));
+    let user = env.next_user();
+    mint(&token0, owner, Key::from(user), 10000.into());
+    mint(&token1, owner, Key::from(user), 10000.into());
+    multicall.multicall(
+        Sender(user),
+        vec![
+            call(
+                Key::Hash(token0.contract_hash()),
+                "transfer",
+                runtime_args! {"recipient" => Key::from(pair_package), "amount" => U256::from(10000)},
+            ),
+            call(
+                Key::Hash(token1.contract_hash()),
+                "transfer",
+                runtime_args! {"recipient" => Key::from(pair_package), "amount" => U256::from(10000)},
+            ),
+            call(
+                Key::Hash(pair.contract_hash()),
+                "mint",
+                runtime_args! {"to" => Key::from(user)},
+            ),
+        ],
+    );
+    let results: Vec<Bytes> = multicall.results(user);
+    assert_eq!(results.len(), 3);
+    // sqrt(10000 * 10000) minus the minimum liquidity of 1000
+    let liquidity: U256 = bytesrepr::deserialize(results[2].to_vec()).unwrap();
+    assert_eq!(liquidity, 9000.into());
+    assert_eq!(balance_of(&pair, Key::from(user)), 9000.into());
+    let reserve0: U128 = pair.query_named_key(String::from("reserve0"));
+    let reserve1: U128 = pair.query_named_key(String::from("reserve1"));
+    assert_eq!((reserve0, reserve1), (10000.into(), 10000.into()));
+}
+
+/// A transfer of 0 returns `Err` instead of reverting, which must revert the whole deploy.
+#[test]
+#[should_panic]
+fn test_multicall_reverts_on_transfer_error() {
+    let (env, multicall, owner, token) = deploy();
+    let user = env.next_user();
+    let recipient = env.next_user();
+    mint(&token, owner, Key::from(user), 1000.into());
+    multicall.multicall(
+        Sender(user),
+        vec![
+            call(
+                Key::Hash(token.contract_hash()),
+                "transfer",
+                runtime_args! {"recipient" => Key::from(recipient), "amount" => U256::from(300)},
+            ),
+            call(
+                Key::Hash(token.contract_hash()),
+                "transfer",
+                runtime_args! {"recipient" => Key::from(recipient), "amount" => U256::from(0)},
+            ),
+        ],
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_multicall_reverts_on_missing_entry_point() {
+    let (env, multicall, _owner, token) = deploy();
+    let user = env.next_user();
+    multicall.multicall(
+        Sender(user),
+        vec![call(
+            Key::Hash(token.contract_hash()),
+            "no_such_entry_point",
+            runtime_args! {},
+        )],
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_multicall_with_no_calls() {
+    let (env, multicall, _owner, _token) = deploy();
+    let user = env.next_user();
+    multicall.multicall(Sender(user), vec![]);
+}
@@ -0,0 +1,26 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use casper_contract::contract_api::runtime;
+use casper_types::bytesrepr::Bytes;
+use multicall::{self, data, Call};
+
+/// This is session code, it is not stored on chain but sent with every deploy, so the
+/// calls are made by the account of the deploy. The deploy reverts as a whole if any
+/// of the calls fails, and the results are stored under the `multicall_results` named
+/// key of the account.
+///
+/// # Parameters
+///
+/// * `calls` - A list of tuples that each hold the Hash of a contract, the name of an entry point and the serialized runtime args of the call
+///
+
+#[no_mangle]
+fn call() {
+    let calls: Vec<Call> = runtime::get_named_arg("calls");
+    let results: Vec<Bytes> = multicall::multicall(calls);
+    data::set_results(results);
+}
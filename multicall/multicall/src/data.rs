@@ -0,0 +1,14 @@
+use alloc::vec::Vec;
+use casper_types::bytesrepr::Bytes;
+use contract_utils::{get_key, set_key};
+
+pub const RESULTS: &str = "multicall_results";
+
+/// Stores the results of the last multicall under a named key of the account that ran it.
+pub fn set_results(results: Vec<Bytes>) {
+    set_key(RESULTS, results);
+}
+
+pub fn get_results() -> Vec<Bytes> {
+    get_key(RESULTS).unwrap_or_default()
+}
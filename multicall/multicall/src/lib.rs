@@ -0,0 +1,8 @@
+#![no_std]
+
+extern crate alloc;
+
+pub mod data;
+mod multicall;
+
+pub use multicall::{multicall, Call};
@@ -0,0 +1,75 @@
+use alloc::{string::String, vec, vec::Vec};
+use casper_contract::{contract_api::runtime, ext_ffi, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    api_error,
+    bytesrepr::{self, Bytes, ToBytes},
+    ApiError, ContractHash, Key, RuntimeArgs,
+};
+use contract_utils::key_to_contract_hash;
+use errors::MulticallError;
+
+/// A call to make: the hash of the contract, the entry point and the runtime args
+/// serialized with `ToBytes`.
+pub type Call = (Key, String, Bytes);
+
+/// Makes `calls` in order and returns the serialized return value of each of them,
+/// empty for entry points that return nothing.
+///
+/// Run from session code, every call is made with the account of the deploy as its
+/// caller, and a call that reverts reverts the whole deploy. Token entry points report
+/// failures as an `Err` of `Result<(), u32>` instead of reverting, so such a result also
+/// reverts the deploy, with the error of the token.
+pub fn multicall(calls: Vec<Call>) -> Vec<Bytes> {
+    if calls.is_empty() {
+        runtime::revert(MulticallError::MulticallNoCalls);
+    }
+    let mut results: Vec<Bytes> = Vec::new();
+    for (contract, entry_point, args) in calls {
+        let contract_hash: ContractHash = key_to_contract_hash(contract)
+            .map_err(|_| MulticallError::MulticallInvalidContract)
+            .unwrap_or_revert();
+        let args: RuntimeArgs = bytesrepr::deserialize(args.into())
+            .map_err(|_| MulticallError::MulticallInvalidArgs)
+            .unwrap_or_revert();
+        let result: Bytes = call_contract(contract_hash, &entry_point, args);
+        if let Ok(Err(error)) = bytesrepr::deserialize::<Result<(), u32>>(result.to_vec()) {
+            runtime::revert(ApiError::from(error));
+        }
+        results.push(result);
+    }
+    results
+}
+
+/// Same as `runtime::call_contract`, but returns the return value of the entry point
+/// without deserializing it, as its type differs from call to call.
+fn call_contract(contract_hash: ContractHash, entry_point: &str, args: RuntimeArgs) -> Bytes {
+    let contract_hash: Vec<u8> = contract_hash.to_bytes().unwrap_or_revert();
+    let entry_point: Vec<u8> = entry_point.to_bytes().unwrap_or_revert();
+    let args: Vec<u8> = args.to_bytes().unwrap_or_revert();
+    let mut result_size: usize = 0;
+    let ret: i32 = unsafe {
+        ext_ffi::casper_call_contract(
+            contract_hash.as_ptr(),
+            contract_hash.len(),
+            entry_point.as_ptr(),
+            entry_point.len(),
+            args.as_ptr(),
+            args.len(),
+            &mut result_size as *mut usize,
+        )
+    };
+    api_error::result_from(ret).unwrap_or_revert();
+    let mut result: Vec<u8> = vec![0u8; result_size];
+    if result_size > 0 {
+        let mut bytes_written: usize = 0;
+        let ret: i32 = unsafe {
+            ext_ffi::casper_read_host_buffer(
+                result.as_mut_ptr(),
+                result_size,
+                &mut bytes_written as *mut usize,
+            )
+        };
+        api_error::result_from(ret).unwrap_or_revert();
+    }
+    Bytes::from(result)
+}
@@ -219,6 +219,26 @@ impl PAIRInstance {
         );
     }
 
+    pub fn initialize_stable<T: Into<Key>>(
+        &self,
+        sender: Sender,
+        token0: T,
+        token1: T,
+        factory_hash: T,
+        amp: U256,
+    ) {
+        self.0.call_contract(
+            sender,
+            "initialize_stable",
+            runtime_args! {
+                "token0" => token0.into(),
+                "token1" => token1.into(),
+                "factory_hash" => factory_hash.into(),
+                "amp" => amp
+            },
+        );
+    }
+
     pub fn set_treasury_fee_percent(&self, sender: Sender, treasury_fee: U256) {
         self.0.call_contract(
             sender,
@@ -240,6 +260,15 @@ impl PAIRInstance {
         );
     }
 
+    pub fn enable_snapshots(&self, sender: Sender) {
+        self.0
+            .call_contract(sender, "enable_snapshots", runtime_args! {});
+    }
+
+    pub fn snapshot(&self, sender: Sender) {
+        self.0.call_contract(sender, "snapshot", runtime_args! {});
+    }
+
     pub fn sync(&self, sender: Sender) {
         self.0.call_contract(sender, "sync", runtime_args! {});
     }
@@ -275,6 +304,130 @@ impl PAIRInstance {
         );
     }
 
+    pub fn swap_with_limits<T: Into<Key>>(
+        &self,
+        sender: Sender,
+        amount0_out_min: U256,
+        amount1_out_min: U256,
+        to: T,
+        deadline: u64,
+    ) {
+        self.0.call_contract(
+            sender,
+            "swap_with_limits",
+            runtime_args! {
+                "amount0_out_min" => amount0_out_min,
+                "amount1_out_min" => amount1_out_min,
+                "to" => to.into(),
+                "deadline" => deadline,
+            },
+        );
+    }
+
+    pub fn swap_with_limits_with_time<T: Into<Key>>(
+        &self,
+        sender: Sender,
+        amount0_out_min: U256,
+        amount1_out_min: U256,
+        to: T,
+        deadline: u64,
+        time: u64,
+    ) {
+        self.0.call_contract_with_time(
+            sender,
+            "swap_with_limits",
+            runtime_args! {
+                "amount0_out_min" => amount0_out_min,
+                "amount1_out_min" => amount1_out_min,
+                "to" => to.into(),
+                "deadline" => deadline,
+            },
+            time,
+        );
+    }
+
+    pub fn swap_supporting_fee_on_transfer_tokens<T: Into<Key>>(
+        &self,
+        sender: Sender,
+        amount0_out_min: U256,
+        amount1_out_min: U256,
+        to: T,
+        deadline: u64,
+    ) {
+        self.0.call_contract(
+            sender,
+            "swap_supporting_fee_on_transfer_tokens",
+            runtime_args! {
+                "amount0_out_min" => amount0_out_min,
+                "amount1_out_min" => amount1_out_min,
+                "to" => to.into(),
+                "deadline" => deadline,
+            },
+        );
+    }
+
+    pub fn burn_supporting_fee_on_transfer_tokens<T: Into<Key>>(
+        &self,
+        sender: Sender,
+        to: T,
+        amount0_min: U256,
+        amount1_min: U256,
+        deadline: u64,
+    ) {
+        self.0.call_contract(
+            sender,
+            "burn_supporting_fee_on_transfer_tokens",
+            runtime_args! {
+                "to" => to.into(),
+                "amount0_min" => amount0_min,
+                "amount1_min" => amount1_min,
+                "deadline" => deadline,
+            },
+        );
+    }
+
+    pub fn burn_with_min<T: Into<Key>>(
+        &self,
+        sender: Sender,
+        to: T,
+        amount0_min: U256,
+        amount1_min: U256,
+        deadline: u64,
+    ) {
+        self.0.call_contract(
+            sender,
+            "burn_with_min",
+            runtime_args! {
+                "to" => to.into(),
+                "amount0_min" => amount0_min,
+                "amount1_min" => amount1_min,
+                "deadline" => deadline,
+            },
+        );
+    }
+
+    pub fn burn_with_min_with_time<T: Into<Key>>(
+        &self,
+        sender: Sender,
+        to: T,
+        amount0_min: U256,
+        amount1_min: U256,
+        deadline: u64,
+        time: u64,
+    ) {
+        self.0.call_contract_with_time(
+            sender,
+            "burn_with_min",
+            runtime_args! {
+                "to" => to.into(),
+                "amount0_min" => amount0_min,
+                "amount1_min" => amount1_min,
+                "deadline" => deadline,
+            },
+            time,
+        );
+    }
+
     pub fn swap<T: Into<Key>>(
         &self,
         sender: Sender,
@@ -295,6 +448,26 @@ impl PAIRInstance {
         );
     }
 
+    pub fn flash_loan<T: Into<Key>>(
+        &self,
+        sender: Sender,
+        receiver: T,
+        token: Key,
+        amount: U256,
+        data: &str,
+    ) {
+        self.0.call_contract(
+            sender,
+            "flash_loan",
+            runtime_args! {
+                "receiver" => receiver.into(),
+                "token" => token,
+                "amount" => amount,
+                "data" => data
+            },
+        );
+    }
+
     pub fn name(&self) -> String {
         self.0.query_named_key(String::from("name"))
     }
@@ -322,6 +495,22 @@ impl PAIRInstance {
         self.0.query_named_key(String::from("balance"))
     }
 
+    pub fn stable(&self) -> bool {
+        self.0.query_named_key(String::from("stable"))
+    }
+
+    pub fn amp(&self) -> U256 {
+        self.0.query_named_key(String::from("amp"))
+    }
+
+    pub fn token0_decimals(&self) -> u8 {
+        self.0.query_named_key(String::from("token0_decimals"))
+    }
+
+    pub fn token1_decimals(&self) -> u8 {
+        self.0.query_named_key(String::from("token1_decimals"))
+    }
+
     pub fn reserve0(&self) -> U128 {
         self.0.query_named_key(String::from("reserve0"))
     }
@@ -380,6 +569,31 @@ impl PAIRInstance {
         self.0.query_named_key(String::from("fee_to"))
     }
 
+    pub fn contract_version(&self) -> u32 {
+        self.0.query_named_key(String::from("contract_version"))
+    }
+
+    pub fn snapshots_enabled(&self) -> bool {
+        self.0.query_named_key(String::from("snapshots_enabled"))
+    }
+
+    pub fn current_snapshot_id(&self) -> U256 {
+        self.0.query_named_key(String::from("current_snapshot_id"))
+    }
+
+    /// Number of snapshots recorded for `owner`, the hex of an account or "total_supply".
+    pub fn snapshot_count(&self, owner: &str) -> u32 {
+        self.0
+            .query_dictionary("snapshot_counts", owner.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn snapshots(&self, owner: &str, index: u32) -> (U256, U256) {
+        self.0
+            .query_dictionary("snapshots", snapshot_key(owner, index))
+            .unwrap_or_default()
+    }
+
     pub fn self_contract_hash(&self) -> Key {
         self.0.query_named_key(String::from("self_contract_hash"))
     }
@@ -420,3 +634,12 @@ pub fn keys_to_str(key_a: &Key, key_b: &Key) -> String {
     hasher.finalize_variable(|hash| ret.clone_from_slice(hash));
     hex::encode(ret)
 }
+
+pub fn snapshot_key(owner: &str, index: u32) -> String {
+    let mut hasher = VarBlake2b::new(32).unwrap();
+    hasher.update(owner.to_bytes().unwrap());
+    hasher.update(index.to_bytes().unwrap());
+    let mut ret = [0u8; 32];
+    hasher.finalize_variable(|hash| ret.clone_from_slice(hash));
+    hex::encode(ret)
+}
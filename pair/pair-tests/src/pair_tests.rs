@@ -1,14 +1,16 @@
 use casper_engine_test_support::AccountHash;
-use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+use casper_types::{runtime_args, Key, RuntimeArgs, U128, U256};
 use test_env::{Sender, TestContract, TestEnv};
 
-use crate::pair_instance::PAIRInstance;
+use crate::pair_instance::{key_to_str, PAIRInstance};
 
 const NAME: &str = "ERC20";
 const SYMBOL: &str = "ERC";
 const DECIMALS: u8 = 8;
 const INIT_TOTAL_SUPPLY: u64 = 1000;
 const INIT_TOTAL_SUPPLY_ZERO: u64 = 0;
+const PAIR_CREATOR: u8 = 0;
+const GUARDIAN: u8 = 2;
 
 fn deploy_wcspr(env: &TestEnv) -> TestContract {
     // deploy wcspr contract
@@ -45,10 +47,70 @@ fn deploy_factory(env: &TestEnv, owner: AccountHash) -> TestContract {
             // contract_name is passed seperately, so we don't need to pass it here.
         },
     );
+    // owner creates the pairs
+    factory.call_contract(
+        Sender(owner_factory),
+        "grant_role",
+        runtime_args! {
+            "role" => PAIR_CREATOR,
+            "account" => Key::from(owner)
+        },
+    );
+    // owner is the guardian who can pause the pairs
+    factory.call_contract(
+        Sender(owner_factory),
+        "grant_role",
+        runtime_args! {
+            "role" => GUARDIAN,
+            "account" => Key::from(owner)
+        },
+    );
 
     factory
 }
 
+fn pause(factory: &TestContract, owner: AccountHash) {
+    factory.call_contract(Sender(owner), "pause", runtime_args! {});
+}
+
+fn unpause(factory: &TestContract, owner: AccountHash) {
+    factory.call_contract(Sender(owner), "unpause", runtime_args! {});
+}
+
+fn set_pair_paused(factory: &TestContract, owner: AccountHash, pair: Key, paused: bool) {
+    factory.call_contract(
+        Sender(owner),
+        "set_pair_paused",
+        runtime_args! {
+            "pair" => pair,
+            "paused" => paused
+        },
+    );
+}
+
+fn add_reserves(
+    proxy: &PAIRInstance,
+    token: &PAIRInstance,
+    owner: AccountHash,
+    token0: Key,
+    token1: Key,
+    amount: U256,
+) {
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        amount,
+    );
+    proxy.mint_with_caller(
+        Sender(owner),
+        token1,
+        Key::from(token.self_package_hash()),
+        amount,
+    );
+    token.sync(Sender(owner));
+}
+
 fn deploy() -> (
     TestEnv,
     PAIRInstance,
@@ -196,6 +258,98 @@ fn deploy_token1(env: &TestEnv) -> TestContract {
     token1_contract
 }
 
+fn deploy_usdc(env: &TestEnv) -> TestContract {
+    TestContract::new(
+        &env,
+        "erc20-token.wasm",
+        "usdc_contract",
+        Sender(env.next_user()),
+        runtime_args! {
+            "initial_supply" => U256::from(0),
+            "name" => "usdc",
+            "symbol" => "usdc",
+            "decimals" => 6u8
+        },
+    )
+}
+
+// takes 1% of every transfer, see erc20/test-contract
+fn deploy_taxed_token(env: &TestEnv) -> TestContract {
+    TestContract::new(
+        &env,
+        "erc20-taxed-token.wasm",
+        "taxed_token_contract",
+        Sender(env.next_user()),
+        runtime_args! {
+            "initial_supply" => U256::from(0),
+            "name" => "taxed",
+            "symbol" => "tax",
+            "decimals" => 18u8
+        },
+    )
+}
+
+/// Creates `pair` for `token_a` and `token_b` through the factory, which initializes it with the
+/// tokens sorted, and returns them as `(token0, token1)`.
+fn create_pair(
+    factory: &TestContract,
+    owner: AccountHash,
+    pair: &PAIRInstance,
+    token_a: Key,
+    token_b: Key,
+) -> (Key, Key) {
+    factory.call_contract(
+        Sender(owner),
+        "create_pair",
+        runtime_args! {
+            "token_a" => token_a,
+            "token_b" => token_b,
+            "pair_hash" => pair.self_contract_hash()
+        },
+    );
+    sort_tokens(token_a, token_b)
+}
+
+/// Same as `create_pair` for a stable pair with the amplification coefficient `amp`.
+fn create_stable_pair(
+    factory: &TestContract,
+    owner: AccountHash,
+    pair: &PAIRInstance,
+    token_a: Key,
+    token_b: Key,
+    amp: U256,
+) -> (Key, Key) {
+    factory.call_contract(
+        Sender(owner),
+        "create_stable_pair",
+        runtime_args! {
+            "token_a" => token_a,
+            "token_b" => token_b,
+            "pair_hash" => pair.self_contract_hash(),
+            "amp" => amp
+        },
+    );
+    sort_tokens(token_a, token_b)
+}
+
+fn sort_tokens(token_a: Key, token_b: Key) -> (Key, Key) {
+    if token_a < token_b {
+        (token_a, token_b)
+    } else {
+        (token_b, token_a)
+    }
+}
+
+/// Orders the amounts of `token` and of the other token of a pair by the side the factory
+/// sorted `token` to.
+fn by_side<T>(token: Key, token0: Key, token_amount: T, other_amount: T) -> (T, T) {
+    if token == token0 {
+        (token_amount, other_amount)
+    } else {
+        (other_amount, token_amount)
+    }
+}
+
 #[test]
 fn test_pair_deploy() {
     let (env, _proxy, _proxy2, token, owner, _factory_hash) = deploy();
@@ -295,16 +449,58 @@ fn test_pair_approve() {
 
 #[test]
 fn test_pair_initialize() {
-    let (env, _proxy, _proxy2, token, owner, factory_hash) = deploy();
+    let (env, _proxy, _proxy2, token, owner, factory) = deploy();
     let token0 = deploy_token0(&env);
     let token1 = deploy_token1(&env);
     let token0 = Key::Hash(token0.contract_hash());
     let token1 = Key::Hash(token1.contract_hash());
-    let factory_hash = Key::Hash(factory_hash.contract_hash());
-    token.initialize(Sender(owner), token0, token1, factory_hash);
+    let factory_hash = Key::Hash(factory.contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
     assert_eq!(token.factory_hash(), factory_hash);
     assert_eq!(token.token0(), token0);
     assert_eq!(token.token1(), token1);
+    assert_eq!(token.token0_decimals(), 18);
+    assert_eq!(token.token1_decimals(), 18);
+}
+
+#[test]
+fn test_pair_initialize_caches_token_decimals() {
+    let (env, _proxy, _proxy2, token, owner, factory) = deploy();
+    let usdc = Key::Hash(deploy_usdc(&env).contract_hash());
+    let other = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, _token1) = create_pair(&factory, owner, &token, usdc, other);
+    assert_eq!(
+        (token.token0_decimals(), token.token1_decimals()),
+        if token0 == usdc { (6, 18) } else { (18, 6) }
+    );
+    // the liquidity token keeps its own decimals
+    assert_eq!(token.decimals(), DECIMALS);
+}
+
+#[test]
+#[should_panic(expected = "User(401)")]
+fn test_pair_initialize_by_non_factory() {
+    let (env, _proxy, _proxy2, token, owner, factory) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    token.initialize(
+        Sender(owner),
+        token0,
+        token1,
+        Key::Hash(factory.contract_hash()),
+    );
+}
+
+#[test]
+#[should_panic(expected = "User(429)")]
+fn test_pair_initialize_twice() {
+    let (env, _proxy, _proxy2, token, owner, factory) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let token2 = Key::Hash(deploy_taxed_token(&env).contract_hash());
+    create_pair(&factory, owner, &token, token0, token1);
+    // the factory passes the pair's own check, but the pair already has its tokens
+    create_pair(&factory, owner, &token, token0, token2);
 }
 
 #[test]
@@ -326,17 +522,17 @@ fn test_pair_set_treasury_fee_percent() {
 
 #[test]
 fn test_pair_skim() {
-    let (env, proxy, _proxy2, token, owner, factory_hash) = deploy();
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
     let user = env.next_user();
     let token0 = deploy_token0(&env);
     let token1 = deploy_token1(&env);
     let token0 = Key::Hash(token0.contract_hash());
     let token1 = Key::Hash(token1.contract_hash());
-    let factory_hash = Key::Hash(factory_hash.contract_hash());
+    let factory_hash = Key::Hash(factory.contract_hash());
     let amount0: U256 = 1000.into();
     let amount1: U256 = 1000.into();
 
-    token.initialize(Sender(owner), token0, token1, factory_hash);
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
     assert_eq!(token.token0(), token0);
     assert_eq!(token.token1(), token1);
     assert_eq!(token.factory_hash(), factory_hash);
@@ -358,17 +554,17 @@ fn test_pair_skim() {
 
 #[test]
 fn test_pair_mint() {
-    let (env, proxy, _proxy2, token, owner, factory_hash) = deploy1();
+    let (env, proxy, _proxy2, token, owner, factory) = deploy1();
     let user = env.next_user();
     let token0 = deploy_token0(&env);
     let token1 = deploy_token1(&env);
     let token0 = Key::Hash(token0.contract_hash());
     let token1 = Key::Hash(token1.contract_hash());
-    let factory_hash = Key::Hash(factory_hash.contract_hash());
+    let factory_hash = Key::Hash(factory.contract_hash());
     let amount0: U256 = 30000.into();
     let amount1: U256 = 30000.into();
 
-    token.initialize(Sender(owner), token0, token1, factory_hash);
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
     assert_eq!(token.token0(), token0);
     assert_eq!(token.token1(), token1);
     assert_eq!(token.factory_hash(), factory_hash);
@@ -390,17 +586,17 @@ fn test_pair_mint() {
 
 #[test]
 fn test_pair_burn() {
-    let (env, proxy, _proxy2, token, owner, factory_hash) = deploy1();
+    let (env, proxy, _proxy2, token, owner, factory) = deploy1();
     let user = env.next_user();
     let token0 = deploy_token0(&env);
     let token1 = deploy_token1(&env);
     let token0 = Key::Hash(token0.contract_hash());
     let token1 = Key::Hash(token1.contract_hash());
-    let factory_hash = Key::Hash(factory_hash.contract_hash());
+    let factory_hash = Key::Hash(factory.contract_hash());
     let amount0: U256 = 30000.into();
     let amount1: U256 = 30000.into();
 
-    token.initialize(Sender(owner), token0, token1, factory_hash);
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
     assert_eq!(token.token0(), token0);
     assert_eq!(token.token1(), token1);
     assert_eq!(token.factory_hash(), factory_hash);
@@ -466,15 +662,15 @@ fn test_pair_burn() {
 
 #[test]
 fn test_pair_sync() {
-    let (env, proxy, _, token, owner, factory_hash) = deploy();
+    let (env, proxy, _, token, owner, factory) = deploy();
     let user = env.next_user();
     let token0 = deploy_token0(&env);
     let token1 = deploy_token1(&env);
     let token0 = Key::Hash(token0.contract_hash());
     let token1 = Key::Hash(token1.contract_hash());
-    let factory_hash = Key::Hash(factory_hash.contract_hash());
+    let factory_hash = Key::Hash(factory.contract_hash());
     let amount: U256 = 50.into();
-    token.initialize(Sender(owner), token0, token1, factory_hash);
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
     assert_eq!(token.factory_hash(), factory_hash);
     assert_eq!(token.token0(), token0);
     assert_eq!(token.token1(), token1);
@@ -500,13 +696,13 @@ fn test_pair_sync() {
 
 #[test]
 fn test_pair_swap() {
-    let (env, proxy, _proxy2, token, owner, factory_hash) = deploy();
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
     let user = env.next_user();
     let token0 = deploy_token0(&env);
     let token1 = deploy_token1(&env);
     let token0 = Key::Hash(token0.contract_hash());
     let token1 = Key::Hash(token1.contract_hash());
-    let factory_hash = Key::Hash(factory_hash.contract_hash());
+    let factory_hash = Key::Hash(factory.contract_hash());
     let amount0: U256 = 2000.into();
     let amount1: U256 = 2000.into();
     let amount: U256 = 1000.into();
@@ -514,7 +710,7 @@ fn test_pair_swap() {
     let amount3: U256 = 40.into();
     let data: &str = "";
 
-    token.initialize(Sender(owner), token0, token1, factory_hash);
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
     assert_eq!(token.token0(), token0);
     assert_eq!(token.token1(), token1);
     assert_eq!(token.factory_hash(), factory_hash);
@@ -550,6 +746,520 @@ fn test_pair_swap() {
     token.swap(Sender(owner), amount2, amount3, user, data);
 }
 
+#[test]
+fn test_pair_flash_loan() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
+    let token0 = deploy_token0(&env);
+    let token1 = deploy_token1(&env);
+    let token0 = Key::Hash(token0.contract_hash());
+    let token1 = Key::Hash(token1.contract_hash());
+    let receiver = proxy.package_hash_result();
+    let reserve: U256 = 2000.into();
+    let amount: U256 = 1000.into();
+    // fee = (1000 * 3) / 997 + 1
+    let fee: U256 = 4.into();
+
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        reserve,
+    );
+    proxy.mint_with_caller(
+        Sender(owner),
+        token1,
+        Key::from(token.self_package_hash()),
+        reserve,
+    );
+    token.sync(Sender(owner));
+    // the receiver needs its own funds to pay the fee
+    proxy.mint_with_caller(Sender(owner), token0, Key::from(receiver), fee);
+
+    token.flash_loan(Sender(owner), receiver, token0, amount, "flash");
+    assert_eq!(token.reserve0(), 2004.into());
+    assert_eq!(token.reserve1(), 2000.into());
+}
+
+#[test]
+#[should_panic]
+fn test_pair_flash_loan_not_repaid() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
+    let token0 = deploy_token0(&env);
+    let token1 = deploy_token1(&env);
+    let token0 = Key::Hash(token0.contract_hash());
+    let token1 = Key::Hash(token1.contract_hash());
+    let receiver = proxy.package_hash_result();
+    let reserve: U256 = 2000.into();
+
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        reserve,
+    );
+    proxy.mint_with_caller(
+        Sender(owner),
+        token1,
+        Key::from(token.self_package_hash()),
+        reserve,
+    );
+    token.sync(Sender(owner));
+    // the receiver holds nothing to cover the fee
+    token.flash_loan(Sender(owner), receiver, token1, 1000.into(), "flash");
+}
+
+#[test]
+#[should_panic(expected = "User(414)")]
+fn test_pair_flash_loan_reentrant_swap() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let receiver = proxy.package_hash_result();
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    add_reserves(&proxy, &token, owner, token0, token1, 2000.into());
+    proxy.mint_with_caller(Sender(owner), token0, Key::from(receiver), 4.into());
+    // the receiver calls swap on the pair from its callback, which the lock rejects
+    token.flash_loan(Sender(owner), receiver, token0, 1000.into(), "swap");
+}
+
+#[test]
+#[should_panic(expected = "User(420)")]
+fn test_pair_swap_paused() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
+    let user = env.next_user();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    add_reserves(&proxy, &token, owner, token0, token1, 2000.into());
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        1000.into(),
+    );
+    pause(&factory, owner);
+    token.swap(Sender(owner), 0.into(), 40.into(), user, "");
+}
+
+#[test]
+#[should_panic(expected = "User(420)")]
+fn test_pair_mint_paused() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy1();
+    let user = env.next_user();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        30000.into(),
+    );
+    proxy.mint_with_caller(
+        Sender(owner),
+        token1,
+        Key::from(token.self_package_hash()),
+        30000.into(),
+    );
+    pause(&factory, owner);
+    token.mint_no_ret(Sender(owner), user);
+}
+
+#[test]
+#[should_panic(expected = "User(420)")]
+fn test_pair_skim_paused() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
+    let user = env.next_user();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        1000.into(),
+    );
+    pause(&factory, owner);
+    token.skim(Sender(owner), user);
+}
+
+#[test]
+#[should_panic(expected = "User(420)")]
+fn test_pair_flash_loan_paused() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let receiver = proxy.package_hash_result();
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    add_reserves(&proxy, &token, owner, token0, token1, 2000.into());
+    proxy.mint_with_caller(Sender(owner), token0, Key::from(receiver), 4.into());
+    pause(&factory, owner);
+    token.flash_loan(Sender(owner), receiver, token0, 1000.into(), "flash");
+}
+
+#[test]
+fn test_pair_burn_while_paused() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy1();
+    let user = env.next_user();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        30000.into(),
+    );
+    proxy.mint_with_caller(
+        Sender(owner),
+        token1,
+        Key::from(token.self_package_hash()),
+        30000.into(),
+    );
+    token.mint_no_ret(Sender(owner), Key::from(token.self_package_hash()));
+    pause(&factory, owner);
+    // liquidity providers can always exit
+    token.burn_no_ret(Sender(owner), user);
+    assert_eq!(token.total_supply(), 1000.into());
+    assert_eq!(token.reserve0(), 1000.into());
+}
+
+#[test]
+#[should_panic(expected = "User(420)")]
+fn test_pair_swap_pair_paused() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
+    let user = env.next_user();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    add_reserves(&proxy, &token, owner, token0, token1, 2000.into());
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        1000.into(),
+    );
+    set_pair_paused(&factory, owner, token.self_contract_hash(), true);
+    token.swap(Sender(owner), 0.into(), 40.into(), user, "");
+}
+
+#[test]
+fn test_pair_swap_after_unpause() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
+    let user = env.next_user();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    add_reserves(&proxy, &token, owner, token0, token1, 2000.into());
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        1000.into(),
+    );
+    pause(&factory, owner);
+    unpause(&factory, owner);
+    token.swap(Sender(owner), 0.into(), 40.into(), user, "");
+    assert_eq!(token.reserve0(), 3000.into());
+    assert_eq!(token.reserve1(), 1960.into());
+}
+
+#[test]
+fn test_pair_swap_with_limits() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
+    let user = env.next_user();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    add_reserves(&proxy, &token, owner, token0, token1, 2000.into());
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        1000.into(),
+    );
+    // 1000 * 997 * 2000 / (2000 * 1000 + 1000 * 997) = 665
+    token.swap_with_limits(Sender(owner), 0.into(), 665.into(), user, u64::MAX);
+    assert_eq!(token.reserve0(), 3000.into());
+    assert_eq!(token.reserve1(), 1335.into());
+}
+
+#[test]
+#[should_panic(expected = "User(423)")]
+fn test_pair_swap_with_limits_below_minimum() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
+    let user = env.next_user();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    add_reserves(&proxy, &token, owner, token0, token1, 2000.into());
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        1000.into(),
+    );
+    token.swap_with_limits(Sender(owner), 0.into(), 666.into(), user, u64::MAX);
+}
+
+/// Swaps 1000 token0 into a pair with 2000 of each token at block time `time`, in milliseconds,
+/// with a `deadline` in seconds.
+fn swap_with_limits_at(deadline: u64, time: u64) {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
+    let user = env.next_user();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    add_reserves(&proxy, &token, owner, token0, token1, 2000.into());
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        1000.into(),
+    );
+    token.swap_with_limits_with_time(Sender(owner), 0.into(), 665.into(), user, deadline, time);
+    assert_eq!(token.reserve1(), 1335.into());
+}
+
+#[test]
+fn test_pair_swap_with_limits_at_deadline() {
+    swap_with_limits_at(100, 100_000);
+}
+
+#[test]
+#[should_panic(expected = "User(421)")]
+fn test_pair_swap_with_limits_after_deadline() {
+    swap_with_limits_at(100, 100_001);
+}
+
+#[test]
+fn test_pair_initialize_stable() {
+    let (env, _proxy, _proxy2, token, owner, factory) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_stable_pair(&factory, owner, &token, token0, token1, 100.into());
+    assert_eq!(token.token0(), token0);
+    assert_eq!(token.token1(), token1);
+    assert!(token.stable());
+    assert_eq!(token.amp(), 100.into());
+}
+
+#[test]
+#[should_panic(expected = "User(426)")]
+fn test_pair_initialize_stable_with_zero_amp() {
+    let (env, _proxy, _proxy2, token, owner, factory) = deploy();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    create_stable_pair(&factory, owner, &token, token0, token1, 0.into());
+}
+
+#[test]
+fn test_pair_stable_swap_with_limits() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
+    let user = env.next_user();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_stable_pair(&factory, owner, &token, token0, token1, 100.into());
+    add_reserves(&proxy, &token, owner, token0, token1, 2000.into());
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        100.into(),
+    );
+    // x * y = k would give 94 for the same reserves
+    token.swap_with_limits(Sender(owner), 0.into(), 98.into(), user, u64::MAX);
+    assert_eq!(token.reserve0(), 2100.into());
+    assert_eq!(token.reserve1(), 1902.into());
+}
+
+#[test]
+fn test_pair_stable_swap_with_different_decimals() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
+    let user = env.next_user();
+    let usdc = Key::Hash(deploy_usdc(&env).contract_hash());
+    let other = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, _token1) = create_stable_pair(&factory, owner, &token, usdc, other, 100.into());
+    let pair = Key::from(token.self_package_hash());
+    proxy.mint_with_caller(Sender(owner), usdc, pair, U256::exp10(6) * 2000);
+    proxy.mint_with_caller(Sender(owner), other, pair, U256::exp10(18) * 2000);
+    token.sync(Sender(owner));
+    proxy.mint_with_caller(Sender(owner), usdc, pair, U256::exp10(6) * 100);
+    // 100 usdc buy close to 100 of the other token less the fee, not the ~48 the curve
+    // gives on the raw balances
+    let amount_out: U256 = U256::from_dec_str("99675218598796505339").unwrap();
+    let (amount0_out_min, amount1_out_min) = by_side(usdc, token0, 0.into(), amount_out);
+    token.swap_with_limits(
+        Sender(owner),
+        amount0_out_min,
+        amount1_out_min,
+        user,
+        u64::MAX,
+    );
+    assert_eq!(
+        (token.reserve0(), token.reserve1()),
+        by_side(
+            usdc,
+            token0,
+            U128::exp10(6) * 2100,
+            U128::from_dec_str("1900324781401203494661").unwrap()
+        )
+    );
+}
+
+#[test]
+#[should_panic(expected = "User(408)")]
+fn test_pair_stable_swap_breaking_invariant() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
+    let user = env.next_user();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_stable_pair(&factory, owner, &token, token0, token1, 100.into());
+    add_reserves(&proxy, &token, owner, token0, token1, 2000.into());
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        100.into(),
+    );
+    token.swap(Sender(owner), 0.into(), 99.into(), user, "");
+}
+
+#[test]
+fn test_pair_burn_with_min() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy1();
+    let user = env.next_user();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        30000.into(),
+    );
+    proxy.mint_with_caller(
+        Sender(owner),
+        token1,
+        Key::from(token.self_package_hash()),
+        30000.into(),
+    );
+    token.mint_no_ret(Sender(owner), Key::from(token.self_package_hash()));
+    token.burn_with_min(Sender(owner), user, 29000.into(), 29000.into(), u64::MAX);
+    assert_eq!(token.total_supply(), 1000.into());
+    assert_eq!(token.reserve0(), 1000.into());
+    assert_eq!(token.reserve1(), 1000.into());
+}
+
+#[test]
+#[should_panic(expected = "User(424)")]
+fn test_pair_burn_with_min_below_minimum() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy1();
+    let user = env.next_user();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        30000.into(),
+    );
+    proxy.mint_with_caller(
+        Sender(owner),
+        token1,
+        Key::from(token.self_package_hash()),
+        30000.into(),
+    );
+    token.mint_no_ret(Sender(owner), Key::from(token.self_package_hash()));
+    token.burn_with_min(Sender(owner), user, 29001.into(), 29000.into(), u64::MAX);
+}
+
+#[test]
+#[should_panic(expected = "User(421)")]
+fn test_pair_burn_with_min_after_deadline() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy1();
+    let user = env.next_user();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        30000.into(),
+    );
+    proxy.mint_with_caller(
+        Sender(owner),
+        token1,
+        Key::from(token.self_package_hash()),
+        30000.into(),
+    );
+    token.mint_no_ret(Sender(owner), Key::from(token.self_package_hash()));
+    // the deadline is in seconds and the block time in milliseconds
+    token.burn_with_min_with_time(
+        Sender(owner),
+        user,
+        29000.into(),
+        29000.into(),
+        100,
+        100_001,
+    );
+}
+
+#[test]
+fn test_pair_upgrade_with_liquidity() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy1();
+    let user = env.next_user();
+    let token0 = Key::Hash(deploy_token0(&env).contract_hash());
+    let token1 = Key::Hash(deploy_token1(&env).contract_hash());
+    let factory_hash = Key::Hash(factory.contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, token0, token1);
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        30000.into(),
+    );
+    proxy.mint_with_caller(
+        Sender(owner),
+        token1,
+        Key::from(token.self_package_hash()),
+        30000.into(),
+    );
+    token.mint_no_ret(Sender(owner), Key::from(token.self_package_hash()));
+    assert_eq!(token.total_supply(), 30000.into());
+    assert_eq!(token.contract_version(), 3);
+
+    // deploying again under the same contract name adds a new version to the package
+    let upgraded = PAIRInstance::instance(PAIRInstance::new(
+        &env,
+        NAME,
+        Sender(owner),
+        NAME,
+        SYMBOL,
+        DECIMALS,
+        INIT_TOTAL_SUPPLY_ZERO.into(),
+        token.callee_contract_hash(),
+        factory_hash,
+    ));
+    assert_eq!(upgraded.contract_version(), 3);
+    assert_eq!(upgraded.total_supply(), 30000.into());
+    assert_eq!(upgraded.reserve0(), 30000.into());
+    assert_eq!(upgraded.reserve1(), 30000.into());
+    assert_eq!(upgraded.token0(), token0);
+    assert_eq!(upgraded.token1(), token1);
+    assert_eq!(upgraded.factory_hash(), factory_hash);
+
+    // the liquidity can be withdrawn through the new version
+    upgraded.burn_no_ret(Sender(owner), user);
+    assert_eq!(upgraded.total_supply(), 1000.into());
+    assert_eq!(upgraded.reserve0(), 1000.into());
+    assert_eq!(upgraded.reserve1(), 1000.into());
+}
+
 #[test]
 fn test_pair_transfer_from() {
     let (env, proxy, proxy2, token, owner, _factory_hash) = deploy();
@@ -633,3 +1343,183 @@ fn test_calling_construction() {
         Key::Hash(factory_hash.contract_hash()),
     );
 }
+
+#[test]
+fn test_pair_swap_supporting_fee_on_transfer_tokens() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
+    let user = env.next_user();
+    let taxed_token = deploy_taxed_token(&env);
+    let taxed = Key::Hash(taxed_token.contract_hash());
+    let other = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, taxed, other);
+    add_reserves(&proxy, &token, owner, token0, token1, 20000.into());
+    proxy.mint_with_caller(
+        Sender(owner),
+        other,
+        Key::from(token.self_package_hash()),
+        1000.into(),
+    );
+    // the pair sends 949 of the taxed token, of which 9 are taken by the token
+    let (amount0_out_min, amount1_out_min) = by_side(taxed, token0, 940.into(), 0.into());
+    token.swap_supporting_fee_on_transfer_tokens(
+        Sender(owner),
+        amount0_out_min,
+        amount1_out_min,
+        user,
+        u64::MAX,
+    );
+    assert_eq!(
+        PAIRInstance::instance(taxed_token).balance_of(user),
+        940.into()
+    );
+    assert_eq!(
+        (token.reserve0(), token.reserve1()),
+        by_side(taxed, token0, 19051.into(), 21000.into())
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_pair_swap_supporting_fee_on_transfer_tokens_below_minimum() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy();
+    let user = env.next_user();
+    let taxed = Key::Hash(deploy_taxed_token(&env).contract_hash());
+    let other = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, taxed, other);
+    add_reserves(&proxy, &token, owner, token0, token1, 20000.into());
+    proxy.mint_with_caller(
+        Sender(owner),
+        other,
+        Key::from(token.self_package_hash()),
+        1000.into(),
+    );
+    // swap_with_limits would accept 949, but only 940 arrive
+    let (amount0_out_min, amount1_out_min) = by_side(taxed, token0, 949.into(), 0.into());
+    token.swap_supporting_fee_on_transfer_tokens(
+        Sender(owner),
+        amount0_out_min,
+        amount1_out_min,
+        user,
+        u64::MAX,
+    );
+}
+
+#[test]
+fn test_pair_burn_supporting_fee_on_transfer_tokens() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy1();
+    let user = env.next_user();
+    let taxed_token = deploy_taxed_token(&env);
+    let taxed = Key::Hash(taxed_token.contract_hash());
+    let other = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, taxed, other);
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        30000.into(),
+    );
+    proxy.mint_with_caller(
+        Sender(owner),
+        token1,
+        Key::from(token.self_package_hash()),
+        30000.into(),
+    );
+    token.mint_no_ret(Sender(owner), Key::from(token.self_package_hash()));
+    let (amount0_min, amount1_min) = by_side(taxed, token0, 28710.into(), 29000.into());
+    token.burn_supporting_fee_on_transfer_tokens(
+        Sender(owner),
+        user,
+        amount0_min,
+        amount1_min,
+        u64::MAX,
+    );
+    assert_eq!(
+        PAIRInstance::instance(taxed_token).balance_of(user),
+        28710.into()
+    );
+    assert_eq!(token.reserve0(), 1000.into());
+    assert_eq!(token.reserve1(), 1000.into());
+}
+
+#[test]
+#[should_panic]
+fn test_pair_burn_supporting_fee_on_transfer_tokens_below_minimum() {
+    let (env, proxy, _proxy2, token, owner, factory) = deploy1();
+    let user = env.next_user();
+    let taxed = Key::Hash(deploy_taxed_token(&env).contract_hash());
+    let other = Key::Hash(deploy_token1(&env).contract_hash());
+    let (token0, token1) = create_pair(&factory, owner, &token, taxed, other);
+    proxy.mint_with_caller(
+        Sender(owner),
+        token0,
+        Key::from(token.self_package_hash()),
+        30000.into(),
+    );
+    proxy.mint_with_caller(
+        Sender(owner),
+        token1,
+        Key::from(token.self_package_hash()),
+        30000.into(),
+    );
+    token.mint_no_ret(Sender(owner), Key::from(token.self_package_hash()));
+    // burn_with_min would accept 29000 as the pair sends that much
+    token.burn_supporting_fee_on_transfer_tokens(
+        Sender(owner),
+        user,
+        29000.into(),
+        29000.into(),
+        u64::MAX,
+    );
+}
+
+#[test]
+fn test_pair_snapshot() {
+    let (env, _proxy, _proxy2, token, owner, _factory_hash) = deploy();
+    let user = env.next_user();
+    // owner is the fee_to_setter of the factory
+    token.enable_snapshots(Sender(owner));
+    assert!(token.snapshots_enabled());
+    token.snapshot(Sender(owner));
+    assert_eq!(token.current_snapshot_id(), 1.into());
+    token.erc20_mint(Sender(owner), user, 500.into());
+    token.transfer(Sender(owner), user, 100.into());
+    token.snapshot(Sender(owner));
+    token.transfer(Sender(owner), user, 100.into());
+    assert_eq!(token.current_snapshot_id(), 2.into());
+
+    // only the value before the first change after each snapshot is recorded
+    let owner_str: String = key_to_str(&Key::from(owner));
+    let user_str: String = key_to_str(&Key::from(user));
+    assert_eq!(token.snapshot_count(&owner_str), 2);
+    assert_eq!(token.snapshots(&owner_str, 0), (1.into(), 1000.into()));
+    assert_eq!(token.snapshots(&owner_str, 1), (2.into(), 900.into()));
+    assert_eq!(token.snapshot_count(&user_str), 2);
+    assert_eq!(token.snapshots(&user_str, 0), (1.into(), 0.into()));
+    assert_eq!(token.snapshots(&user_str, 1), (2.into(), 600.into()));
+    assert_eq!(token.snapshot_count("total_supply"), 1);
+    assert_eq!(token.snapshots("total_supply", 0), (1.into(), 1000.into()));
+}
+
+#[test]
+#[should_panic]
+fn test_pair_enable_snapshots_by_other_than_fee_to_setter() {
+    let (env, _proxy, _proxy2, token, _owner, _factory_hash) = deploy();
+    let user = env.next_user();
+    token.enable_snapshots(Sender(user));
+}
+
+#[test]
+#[should_panic]
+fn test_pair_snapshot_by_other_than_fee_to_setter() {
+    let (env, _proxy, _proxy2, token, owner, _factory_hash) = deploy();
+    let user = env.next_user();
+    token.enable_snapshots(Sender(owner));
+    token.snapshot(Sender(user));
+}
+
+#[test]
+#[should_panic]
+fn test_pair_snapshot_before_enabled() {
+    let (_env, _proxy, _proxy2, token, owner, _factory_hash) = deploy();
+    token.snapshot(Sender(owner));
+}
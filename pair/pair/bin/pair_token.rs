@@ -196,6 +196,107 @@ fn swap() {
     Pair::default().swap(amount0_out, amount1_out, to, data);
 }
 
+/// This function is to swap the tokens sent to the pair before the call, it reverts if the deadline has passed or if an output is below its minimum
+///
+/// # Parameters
+///
+/// * `amount0_out_min` - A U256 that holds the minimum amount of token0 to receive
+///
+/// * `amount1_out_min` - A U256 that holds the minimum amount of token1 to receive
+///
+/// * `to` - A Key that holds the account address or package hash that receives the output
+///
+/// * `deadline` - A u64 that holds the time in seconds after which the swap reverts
+///
+
+#[no_mangle]
+fn swap_with_limits() {
+    let amount0_out_min: U256 = runtime::get_named_arg("amount0_out_min");
+    let amount1_out_min: U256 = runtime::get_named_arg("amount1_out_min");
+    let to: Key = runtime::get_named_arg("to");
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let (amount0_out, amount1_out): (U256, U256) =
+        Pair::default().swap_with_limits(amount0_out_min, amount1_out_min, to, deadline);
+    runtime::ret(CLValue::from_t((amount0_out, amount1_out)).unwrap_or_revert());
+}
+
+/// This function is to swap like swap_with_limits, but checks the minimums against the amounts received by `to`, for tokens that take a fee on transfer
+///
+/// # Parameters
+///
+/// * `amount0_out_min` - A U256 that holds the minimum amount of token0 `to` has to receive
+///
+/// * `amount1_out_min` - A U256 that holds the minimum amount of token1 `to` has to receive
+///
+/// * `to` - A Key that holds the account address or package hash that receives the output
+///
+/// * `deadline` - A u64 that holds the time in seconds after which the swap reverts
+///
+
+#[no_mangle]
+fn swap_supporting_fee_on_transfer_tokens() {
+    let amount0_out_min: U256 = runtime::get_named_arg("amount0_out_min");
+    let amount1_out_min: U256 = runtime::get_named_arg("amount1_out_min");
+    let to: Key = runtime::get_named_arg("to");
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let (amount0_out, amount1_out): (U256, U256) = Pair::default()
+        .swap_supporting_fee_on_transfer_tokens(amount0_out_min, amount1_out_min, to, deadline);
+    runtime::ret(CLValue::from_t((amount0_out, amount1_out)).unwrap_or_revert());
+}
+
+/// This function is to lend token0 or token1 to a receiver contract that implements on_flash_loan
+///
+/// # Parameters
+///
+/// * `receiver` - A Key that holds the package hash of the receiver contract
+///
+/// * `token` - A Key that holds the hash of the token to borrow
+///
+/// * `amount` - A U256 that holds the amount to borrow
+///
+/// * `data` - A String that is passed on to the receiver
+///
+
+#[no_mangle]
+fn flash_loan() {
+    let receiver: Key = runtime::get_named_arg("receiver");
+    let token: Key = runtime::get_named_arg("token");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let data: String = runtime::get_named_arg("data");
+    Pair::default().flash_loan(receiver, token, amount, data);
+}
+
+/// This function is to get the maximum amount of a token that can be flash borrowed
+///
+/// # Parameters
+///
+/// * `token` - A Key that holds the hash of the token
+///
+
+#[no_mangle]
+fn max_flash_loan() {
+    let token: Key = runtime::get_named_arg("token");
+    let ret: U256 = Pair::default().max_flash_loan(token);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to get the fee charged for flash borrowing an amount of a token
+///
+/// # Parameters
+///
+/// * `token` - A Key that holds the hash of the token
+///
+/// * `amount` - A U256 that holds the amount to borrow
+///
+
+#[no_mangle]
+fn flash_fee() {
+    let token: Key = runtime::get_named_arg("token");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let ret: U256 = Pair::default().flash_fee(token, amount);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 /// This function is to get meta transaction signer and verify if it is equal
 /// to the signer public key or not then call approve.
 ///
@@ -356,6 +457,58 @@ fn burn_no_ret() {
     let (_amount0, _amount1): (U256, U256) = Pair::default().burn_helper(to);
 }
 
+/// This function is to burn the liquidity sent to the pair, it reverts if the deadline has passed or if an amount is below its minimum
+///
+/// # Parameters
+///
+/// * `to` - A Key that holds the account address or package hash that receives token0 and token1
+///
+/// * `amount0_min` - A U256 that holds the minimum amount of token0 to receive
+///
+/// * `amount1_min` - A U256 that holds the minimum amount of token1 to receive
+///
+/// * `deadline` - A u64 that holds the time in seconds after which the burn reverts
+///
+
+#[no_mangle]
+fn burn_with_min() {
+    let to: Key = runtime::get_named_arg("to");
+    let amount0_min: U256 = runtime::get_named_arg("amount0_min");
+    let amount1_min: U256 = runtime::get_named_arg("amount1_min");
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let (amount0, amount1): (U256, U256) =
+        Pair::default().burn_with_min(to, amount0_min, amount1_min, deadline);
+    runtime::ret(CLValue::from_t((amount0, amount1)).unwrap_or_revert());
+}
+
+/// This function is to burn like burn_with_min, but checks the minimums against the amounts received by `to`, for tokens that take a fee on transfer
+///
+/// # Parameters
+///
+/// * `to` - A Key that holds the account address or package hash that receives token0 and token1
+///
+/// * `amount0_min` - A U256 that holds the minimum amount of token0 `to` has to receive
+///
+/// * `amount1_min` - A U256 that holds the minimum amount of token1 `to` has to receive
+///
+/// * `deadline` - A u64 that holds the time in seconds after which the burn reverts
+///
+
+#[no_mangle]
+fn burn_supporting_fee_on_transfer_tokens() {
+    let to: Key = runtime::get_named_arg("to");
+    let amount0_min: U256 = runtime::get_named_arg("amount0_min");
+    let amount1_min: U256 = runtime::get_named_arg("amount1_min");
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let (amount0, amount1): (U256, U256) = Pair::default().burn_supporting_fee_on_transfer_tokens(
+        to,
+        amount0_min,
+        amount1_min,
+        deadline,
+    );
+    runtime::ret(CLValue::from_t((amount0, amount1)).unwrap_or_revert());
+}
+
 /// This function is to get a balance of a owner provided by user
 ///
 /// # Parameters
@@ -461,6 +614,105 @@ pub extern "C" fn initialize() {
     Pair::default().initialize(token0, token1, factory_hash);
 }
 
+/// This method will be called once by the factory at time of create_stable_pair() method
+///
+/// This function is to Initialize a stable Pair Contract with Token0, Token1 and the amplification coefficient of the StableSwap invariant
+///
+/// # Parameters
+///
+/// * `amp` - A U256 that holds the amplification coefficient, between 1 and MAX_AMP
+///
+
+#[no_mangle]
+pub extern "C" fn initialize_stable() {
+    let token0: Key = runtime::get_named_arg("token0");
+    let token1: Key = runtime::get_named_arg("token1");
+    let factory_hash: Key = runtime::get_named_arg("factory_hash");
+    let amp: U256 = runtime::get_named_arg("amp");
+
+    Pair::default().initialize_stable(token0, token1, factory_hash, amp);
+}
+
+/// This function is to return whether the pair trades on the StableSwap invariant or on x * y = k
+///
+
+#[no_mangle]
+fn stable() {
+    let ret: bool = Pair::default().is_stable();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the amplification coefficient of a stable pair, zero for other pairs
+///
+
+#[no_mangle]
+fn amp() {
+    let ret: U256 = Pair::default().get_amp();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the Decimals of the liquidity token
+///
+
+#[no_mangle]
+fn decimals() {
+    let ret: u8 = Pair::default().decimals();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the Decimals of Token0, read from the token at time of initialize
+///
+
+#[no_mangle]
+fn token0_decimals() {
+    let ret: u8 = Pair::default().get_token0_decimals();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the Decimals of Token1, read from the token at time of initialize
+///
+
+#[no_mangle]
+fn token1_decimals() {
+    let ret: u8 = Pair::default().get_token1_decimals();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the price of Token0 in Token1 from the reserves, normalized to 18 decimals
+///
+
+#[no_mangle]
+fn price0() {
+    let ret: U256 = Pair::default().get_price0();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the price of Token1 in Token0 from the reserves, normalized to 18 decimals
+///
+
+#[no_mangle]
+fn price1() {
+    let ret: U256 = Pair::default().get_price1();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the amount of the other token that swap gives for an amount of a token at the current reserves
+///
+/// # Parameters
+///
+/// * `token_in` - A Key that holds the hash of the token going in, token0 or token1
+///
+/// * `amount_in` - A U256 that holds the amount going in
+///
+
+#[no_mangle]
+fn get_amount_out() {
+    let token_in: Key = runtime::get_named_arg("token_in");
+    let amount_in: U256 = runtime::get_named_arg("amount_in");
+    let ret: U256 = Pair::default().get_token_amount_out(token_in, amount_in);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 /// This function is to set a treasury_fee
 ///
 /// # Parameters
@@ -483,6 +735,102 @@ fn package_hash() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
+/// This function is to bring the storage of an upgraded pair up to the current version, it is called by the upgrade deploy
+///
+
+#[no_mangle]
+fn migrate() {
+    Pair::default().migrate();
+}
+
+/// This function is to return the version of the pair contract
+///
+
+#[no_mangle]
+fn contract_version() {
+    let ret: u32 = Pair::default().get_contract_version();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to point the pair to a new version of its factory which is only possible if the caller is the factory
+///
+/// # Parameters
+///
+/// * `factory_hash` - A Key that holds the Hash of the new factory contract version
+///
+
+#[no_mangle]
+fn update_factory_hash() {
+    let factory_hash: Key = runtime::get_named_arg("factory_hash");
+    Pair::default().update_factory_hash(factory_hash);
+}
+
+/// This function is to opt the LP token in to snapshots of balances and total supply which is only possible if the caller is the fee_to_setter of the factory
+///
+
+#[no_mangle]
+fn enable_snapshots() {
+    Pair::default().enable_snapshots();
+}
+
+/// This function is to return whether the LP token has opted in to snapshots
+///
+
+#[no_mangle]
+fn snapshots_enabled() {
+    let ret: bool = Pair::default().snapshots_enabled();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to take a snapshot of every LP balance and of the total supply once the LP token has opted in which is only possible if the caller is the fee_to_setter of the factory, it returns the id of the snapshot
+///
+
+#[no_mangle]
+fn snapshot() {
+    let ret: U256 = Pair::default().snapshot();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the id of the last snapshot taken, 0 if none has been taken
+///
+
+#[no_mangle]
+fn current_snapshot_id() {
+    let ret: U256 = Pair::default().get_current_snapshot_id();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the LP balance of an owner at a snapshot
+///
+/// # Parameters
+///
+/// * `owner` - A Key that holds the account address of the user
+///
+/// * `id` - A U256 that holds the id of the snapshot, which must have been taken
+///
+
+#[no_mangle]
+fn balance_of_at() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let id: U256 = runtime::get_named_arg("id");
+    let ret: U256 = Pair::default().balance_of_at(owner, id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the LP total supply at a snapshot
+///
+/// # Parameters
+///
+/// * `id` - A U256 that holds the id of the snapshot, which must have been taken
+///
+
+#[no_mangle]
+fn total_supply_at() {
+    let id: U256 = runtime::get_named_arg("id");
+    let ret: U256 = Pair::default().total_supply_at(id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 fn get_entry_points() -> EntryPoints {
     let mut entry_points = EntryPoints::new();
     entry_points.add_entry_point(EntryPoint::new(
@@ -551,6 +899,59 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "swap_with_limits",
+        vec![
+            Parameter::new("amount0_out_min", U256::cl_type()),
+            Parameter::new("amount1_out_min", U256::cl_type()),
+            Parameter::new("to", Key::cl_type()),
+            Parameter::new("deadline", u64::cl_type()),
+        ],
+        CLType::Tuple2([Box::new(CLType::U256), Box::new(CLType::U256)]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "swap_supporting_fee_on_transfer_tokens",
+        vec![
+            Parameter::new("amount0_out_min", U256::cl_type()),
+            Parameter::new("amount1_out_min", U256::cl_type()),
+            Parameter::new("to", Key::cl_type()),
+            Parameter::new("deadline", u64::cl_type()),
+        ],
+        CLType::Tuple2([Box::new(CLType::U256), Box::new(CLType::U256)]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "flash_loan",
+        vec![
+            Parameter::new("receiver", Key::cl_type()),
+            Parameter::new("token", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+            Parameter::new("data", String::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "max_flash_loan",
+        vec![Parameter::new("token", Key::cl_type())],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "flash_fee",
+        vec![
+            Parameter::new("token", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "skim",
         vec![Parameter::new("to", Key::cl_type())],
@@ -667,6 +1068,30 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "burn_with_min",
+        vec![
+            Parameter::new("to", Key::cl_type()),
+            Parameter::new("amount0_min", U256::cl_type()),
+            Parameter::new("amount1_min", U256::cl_type()),
+            Parameter::new("deadline", u64::cl_type()),
+        ],
+        CLType::Tuple2([Box::new(CLType::U256), Box::new(CLType::U256)]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "burn_supporting_fee_on_transfer_tokens",
+        vec![
+            Parameter::new("to", Key::cl_type()),
+            Parameter::new("amount0_min", U256::cl_type()),
+            Parameter::new("amount1_min", U256::cl_type()),
+            Parameter::new("deadline", u64::cl_type()),
+        ],
+        CLType::Tuple2([Box::new(CLType::U256), Box::new(CLType::U256)]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "burn_no_ret",
         vec![Parameter::new("to", Key::cl_type())],
@@ -702,6 +1127,27 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "migrate",
+        vec![],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "contract_version",
+        vec![],
+        CLType::U32,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "update_factory_hash",
+        vec![Parameter::new("factory_hash", Key::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "package_hash",
         vec![],
@@ -721,6 +1167,77 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "initialize_stable",
+        vec![
+            Parameter::new("token0", Key::cl_type()),
+            Parameter::new("token1", Key::cl_type()),
+            Parameter::new("factory_hash", Key::cl_type()),
+            Parameter::new("amp", U256::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "stable",
+        vec![],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "amp",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "decimals",
+        vec![],
+        u8::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "token0_decimals",
+        vec![],
+        u8::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "token1_decimals",
+        vec![],
+        u8::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "price0",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "price1",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_amount_out",
+        vec![
+            Parameter::new("token_in", Key::cl_type()),
+            Parameter::new("amount_in", U256::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "get_reserves",
         vec![],
@@ -742,6 +1259,51 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "enable_snapshots",
+        vec![],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "snapshots_enabled",
+        vec![],
+        bool::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "snapshot",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "current_snapshot_id",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "balance_of_at",
+        vec![
+            Parameter::new("owner", Key::cl_type()),
+            Parameter::new("id", U256::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "total_supply_at",
+        vec![Parameter::new("id", U256::cl_type())],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points
 }
 
@@ -863,9 +1425,15 @@ fn call() {
                                                             .unwrap()
                                                             .into();
 
+        // The named keys of the previous version, and with them all of the state, are carried over to the new version.
         let (contract_hash, _): (ContractHash, _) =
         storage::add_contract_version(package_hash, get_entry_points(), Default::default());
 
+        // Bring the storage up to the version of the new code
+        let _: () = runtime::call_contract(contract_hash, "migrate", runtime_args! {});
+
+        // The previous version stays enabled, as the factory, routers and other contracts hold its contract hash
+
         // update contract hash
         runtime::put_key(
             &format!("{}_contract_hash", contract_name),
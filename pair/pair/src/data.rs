@@ -1,8 +1,8 @@
-use alloc::string::String;
+use alloc::{string::String, vec::Vec};
 
-use casper_contract::unwrap_or_revert::UnwrapOrRevert;
-use casper_types::{ContractPackageHash, Key, U128, U256};
-use contract_utils::{get_key, set_key, Dict};
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{bytesrepr::ToBytes, ContractPackageHash, Key, U128, U256};
+use contract_utils::{get_key, key_to_str, set_key, Dict};
 
 pub const BALANCES_DICT: &str = "balances";
 pub const NONCES_DICT: &str = "nonces";
@@ -16,6 +16,7 @@ pub const SELF_PACKAGE_HASH: &str = "self_package_hash";
 pub const DOMAIN_SEPARATOR: &str = "domain_separator";
 pub const PERMIT_TYPE_HASH: &str = "permit_type_hash";
 pub const FACTORY: &str = "factory_hash";
+pub const FACTORY_PACKAGE_HASH: &str = "factory_package_hash";
 pub const RESERVE0: &str = "reserve0";
 pub const RESERVE1: &str = "reserve1";
 pub const BLOCK_TIMESTAMP_LAST: &str = "block_timestamp_last";
@@ -31,6 +32,18 @@ pub const AMOUNT0: &str = "amount0";
 pub const AMOUNT1: &str = "amount1";
 pub const CALLEE_CONTRACT_HASH: &str = "callee_contract_hash";
 pub const LOCK: &str = "lock";
+pub const CONTRACT_VERSION: &str = "contract_version";
+pub const STABLE: &str = "stable";
+pub const AMP: &str = "amp";
+pub const TOKEN0_DECIMALS: &str = "token0_decimals";
+pub const TOKEN1_DECIMALS: &str = "token1_decimals";
+pub const SNAPSHOTS_ENABLED: &str = "snapshots_enabled";
+pub const CURRENT_SNAPSHOT_ID: &str = "current_snapshot_id";
+pub const SNAPSHOT_COUNTS_DICT: &str = "snapshot_counts";
+pub const SNAPSHOTS_DICT: &str = "snapshots";
+
+/// Message hashed with keccak256 that on_flash_loan must return on success (ERC-3156).
+pub const FLASH_LOAN_CALLBACK_SUCCESS: &str = "ERC3156FlashBorrower.onFlashLoan";
 
 pub struct Balances {
     dict: Dict,
@@ -104,6 +117,66 @@ impl Allowances {
     }
 }
 
+/// `(id, value)`: the value of an LP balance or of the total supply when the snapshot
+/// `id` was taken.
+pub type Snapshot = (U256, U256);
+
+/// LP balances and total supply at the snapshots that were taken, kept the same way as
+/// by the snapshot extension of the ERC20 crate. The dictionaries only exist once the
+/// pair has opted in to snapshots.
+pub struct Snapshots {
+    counts: Dict,
+    snapshots: Dict,
+}
+
+impl Snapshots {
+    pub fn instance() -> Snapshots {
+        Snapshots {
+            counts: Dict::instance(SNAPSHOT_COUNTS_DICT),
+            snapshots: Dict::instance(SNAPSHOTS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(SNAPSHOT_COUNTS_DICT);
+        Dict::init(SNAPSHOTS_DICT);
+    }
+
+    /// Owner under which the snapshots of the total supply are kept.
+    pub fn total_supply_owner() -> String {
+        String::from(TOTAL_SUPPLY)
+    }
+
+    /// Owner under which the snapshots of the LP balance of `account` are kept.
+    pub fn account_owner(account: &Key) -> String {
+        key_to_str(account)
+    }
+
+    pub fn len(&self, owner: &str) -> u32 {
+        self.counts.get(owner).unwrap_or_default()
+    }
+
+    pub fn get(&self, owner: &str, index: u32) -> Snapshot {
+        self.snapshots
+            .get(&snapshot_key(owner, index))
+            .unwrap_or_default()
+    }
+
+    pub fn push(&self, owner: &str, snapshot: Snapshot) {
+        let len: u32 = self.len(owner);
+        self.snapshots.set(&snapshot_key(owner, len), snapshot);
+        self.counts.set(owner, len + 1);
+    }
+}
+
+/// Dictionary keys are limited in length, so a snapshot is stored under the hash of its
+/// owner and index.
+fn snapshot_key(owner: &str, index: u32) -> String {
+    let mut bytes: Vec<u8> = owner.to_bytes().unwrap_or_revert();
+    bytes.append(&mut index.to_bytes().unwrap_or_revert());
+    hex::encode(runtime::blake2b(bytes))
+}
+
 pub fn name() -> String {
     get_key(NAME).unwrap_or_revert()
 }
@@ -159,6 +232,14 @@ pub fn get_factory_hash() -> Key {
     get_key(FACTORY).unwrap_or_revert()
 }
 
+pub fn set_factory_package_hash(factory_package_hash: ContractPackageHash) {
+    set_key(FACTORY_PACKAGE_HASH, factory_package_hash);
+}
+
+pub fn get_factory_package_hash() -> Option<ContractPackageHash> {
+    get_key(FACTORY_PACKAGE_HASH)
+}
+
 pub fn set_token0(token0: Key) {
     set_key(TOKEN0, token0);
 }
@@ -294,3 +375,64 @@ pub fn set_lock(lock: u64) {
 pub fn get_lock() -> u64 {
     get_key(LOCK).unwrap_or_revert()
 }
+
+pub fn set_contract_version(contract_version: u32) {
+    set_key(CONTRACT_VERSION, contract_version);
+}
+
+pub fn get_contract_version() -> u32 {
+    get_key(CONTRACT_VERSION).unwrap_or_default()
+}
+
+pub fn set_stable(stable: bool) {
+    set_key(STABLE, stable);
+}
+
+pub fn get_stable() -> bool {
+    get_key(STABLE).unwrap_or_default()
+}
+
+pub fn set_amp(amp: U256) {
+    set_key(AMP, amp);
+}
+
+pub fn get_amp() -> U256 {
+    get_key(AMP).unwrap_or_default()
+}
+
+pub fn is_initialized() -> bool {
+    get_key::<Key>(TOKEN0).is_some()
+}
+
+pub fn set_token0_decimals(token0_decimals: u8) {
+    set_key(TOKEN0_DECIMALS, token0_decimals);
+}
+
+pub fn get_token0_decimals() -> u8 {
+    get_key(TOKEN0_DECIMALS).unwrap_or_revert()
+}
+
+pub fn set_token1_decimals(token1_decimals: u8) {
+    set_key(TOKEN1_DECIMALS, token1_decimals);
+}
+
+pub fn get_token1_decimals() -> u8 {
+    get_key(TOKEN1_DECIMALS).unwrap_or_revert()
+}
+
+pub fn set_snapshots_enabled(snapshots_enabled: bool) {
+    set_key(SNAPSHOTS_ENABLED, snapshots_enabled);
+}
+
+pub fn get_snapshots_enabled() -> bool {
+    get_key(SNAPSHOTS_ENABLED).unwrap_or_default()
+}
+
+pub fn set_current_snapshot_id(current_snapshot_id: U256) {
+    set_key(CURRENT_SNAPSHOT_ID, current_snapshot_id);
+}
+
+/// Id of the last snapshot taken, 0 before the first one.
+pub fn get_current_snapshot_id() -> U256 {
+    get_key(CURRENT_SNAPSHOT_ID).unwrap_or_default()
+}
@@ -1,7 +1,7 @@
 use alloc::{format, string::String, vec::Vec};
 use casper_contract::unwrap_or_revert::UnwrapOrRevert;
 
-use crate::data::{self, Allowances, Balances, Nonces};
+use crate::data::{self, Allowances, Balances, Nonces, Snapshot, Snapshots};
 
 use casper_contract::contract_api::runtime;
 use casper_contract::contract_api::storage;
@@ -10,11 +10,14 @@ use crate::alloc::string::ToString;
 use alloc::collections::BTreeMap;
 use casper_types::system::mint::Error as MintError;
 use casper_types::{
-    runtime_args, ApiError, BlockTime, ContractHash, ContractPackageHash, Key, RuntimeArgs, URef,
-    U128, U256,
+    runtime_args, BlockTime, ContractHash, ContractPackageHash, Key, RuntimeArgs, URef, U128, U256,
+};
+use contract_utils::{
+    is_zero, key_to_contract_hash, key_to_contract_package_hash, set_key, ContractContext,
+    ContractStorage, ZERO_ACCOUNT,
 };
-use contract_utils::{set_key, ContractContext, ContractStorage};
 use cryptoxide::ed25519;
+use errors::{CommonError, PairError};
 use renvm_sig::hash_message;
 use renvm_sig::keccak256;
 
@@ -58,6 +61,18 @@ pub enum PAIREvent {
         reserve1: U128,
         pair: Key,
     },
+    FlashLoan {
+        initiator: Key,
+        receiver: Key,
+        token: Key,
+        amount: U256,
+        fee: U256,
+        pair: Key,
+    },
+    Snapshot {
+        id: U256,
+        pair: Key,
+    },
 }
 
 impl PAIREvent {
@@ -102,49 +117,29 @@ impl PAIREvent {
                 reserve1: _,
                 pair: _,
             } => "sync",
+            PAIREvent::FlashLoan {
+                initiator: _,
+                receiver: _,
+                token: _,
+                amount: _,
+                fee: _,
+                pair: _,
+            } => "flash_loan",
+            PAIREvent::Snapshot { id: _, pair: _ } => "snapshot",
         }
         .to_string()
     }
 }
 
-/// Enum for FailureCode, It represents codes for different smart contract errors.
-#[repr(u16)]
-pub enum FailureCode {
-    /// 65,536 for (UniswapV2: EXPIRED)
-    Twelve = 12,
-    /// 65,537 for (UniswapV2: FORBIDDEN)
-    Thirteen,
-    /// 65,538 for (signature verification failed)
-    Fourteen,
-    /// 65,539 for (UniswapV2: OVERFLOW)
-    Fifteen,
-    /// 65,540 for (UniswapV2: INSUFFICIENT_OUTPUT_AMOUNT)
-    Sixteen,
-    /// 65,541 for (UniswapV2: INSUFFICIENT_LIQUIDITY)
-    Seventeen,
-    /// 65,542 for (UniswapV2: INVALID_TO)
-    Eighteen,
-    /// 65,543 for (UniswapV2: INSUFFICIENT_INPUT_AMOUNT)
-    Ninteen,
-    /// 65,544 for (UniswapV2: K)
-    Twenty,
-    /// 65,545 for (UniswapV2: INSUFFICIENT_LIQUIDITY_MINTED)
-    TwentyOne,
-    /// 65,546 for (UniswapV2: INSUFFICIENT_LIQUIDITY_BURNED)
-    TwentyTwo,
-    /// 65,547 for (UniswapV2: OVERFLOW)
-    TwentyThree,
-    /// 65,548 for (UniswapV2: UNDERFLOW)
-    TwentyFour,
-    /// 65,549 for (UniswapV2: DENOMINATOR IS ZERO)
-    TwentyFive,
-    /// 65,550 for (UniswapV2: LOCKED)
-    TwentySix,
-    /// 65,551 for (UniswapV2: UNDERFLOW)
-    TwentySeven,
-    /// 65,552 for (UniswapV2: OVERFLOW)
-    TwentyEight,
-}
+/// Version of the pair code, stored in `contract_version` by the constructor and by `migrate`.
+pub const VERSION: u32 = 3;
+
+/// Largest amplification coefficient a stable pair can be initialized with.
+pub const MAX_AMP: u64 = 1_000_000;
+
+/// Decimals of the prices returned by `get_price0` and `get_price1`, and of the
+/// amounts returned by `normalize_amount`.
+pub const PRICE_DECIMALS: u8 = 18;
 
 pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
     fn init(
@@ -186,6 +181,7 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
         data::set_minimum_liquidity(minimum_liquidity);
         data::set_callee_contract_hash(callee_contract_hash);
         data::set_lock(lock);
+        data::set_contract_version(VERSION);
         Nonces::init();
         let nonces = Nonces::instance();
         nonces.set(&Key::from(self.get_caller()), U256::from(0));
@@ -229,14 +225,14 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
 
         let new_allowance: U256 = spender_allowance
             .checked_add(amount)
-            .ok_or(ApiError::User(FailureCode::Twelve as u16))
+            .ok_or(PairError::UniswapV2PairOverFlow)
             .unwrap_or_revert();
 
         if new_allowance <= owner_balance && owner != spender {
             self._approve(owner, spender, new_allowance);
             return Ok(());
         } else {
-            return Err(4);
+            return Err(CommonError::InvalidAllowance.into());
         }
     }
 
@@ -249,14 +245,14 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
 
         let new_allowance: U256 = spender_allowance
             .checked_sub(amount)
-            .ok_or(ApiError::User(FailureCode::Thirteen as u16))
+            .ok_or(PairError::UniswapV2PairUnderFlow)
             .unwrap_or_revert();
 
         if new_allowance >= 0.into() && new_allowance < spender_allowance && owner != spender {
             self._approve(owner, spender, new_allowance);
             return Ok(());
         } else {
-            return Err(4);
+            return Err(CommonError::InvalidAllowance.into());
         }
     }
 
@@ -267,7 +263,7 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
             let spender_allowance: U256 = allowances.get(&owner, &self.get_caller());
             let new_allowance: U256 = spender_allowance
                 .checked_sub(amount)
-                .ok_or(ApiError::User(FailureCode::Thirteen as u16))
+                .ok_or(PairError::UniswapV2PairUnderFlow)
                 .unwrap_or_revert();
             if new_allowance >= 0.into()
                 && new_allowance < spender_allowance
@@ -276,7 +272,7 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
                 self._approve(owner, self.get_caller(), new_allowance);
                 return Ok(());
             } else {
-                return Err(4);
+                return Err(CommonError::InvalidAllowance.into());
             }
         }
         ret
@@ -287,10 +283,11 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
     }
 
     fn skim(&mut self, to: Key) {
+        self.assert_not_paused();
         let lock = data::get_lock();
         if lock != 0 {
             //UniswapV2: Locked
-            runtime::revert(ApiError::User(FailureCode::TwentySix as u16));
+            runtime::revert(PairError::UniswapV2PairLocked);
         }
         data::set_lock(1);
         let token0: Key = self.get_token0();
@@ -298,18 +295,8 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
         let reserve0: U128 = data::get_reserve0();
         let reserve1: U128 = data::get_reserve1();
         let pair_address: Key = Key::from(data::get_package_hash());
-        //convert Key to ContractHash
-        let token0_hash_add_array = match token0 {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let token0_contract_hash = ContractHash::new(token0_hash_add_array);
-        //convert Key to ContractHash
-        let token1_hash_add_array = match token1 {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let token1_contract_hash = ContractHash::new(token1_hash_add_array);
+        let token0_contract_hash: ContractHash = key_to_contract_hash(token0).unwrap_or_revert();
+        let token1_contract_hash: ContractHash = key_to_contract_hash(token1).unwrap_or_revert();
         let balance0: U256 = runtime::call_contract(
             token0_contract_hash,
             "balance_of",
@@ -350,7 +337,7 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
         let lock = data::get_lock();
         if lock != 0 {
             //UniswapV2: Locked
-            runtime::revert(ApiError::User(FailureCode::TwentySix as u16));
+            runtime::revert(PairError::UniswapV2PairLocked);
         }
         data::set_lock(1);
         let token0: Key = self.get_token0();
@@ -358,18 +345,8 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
         let reserve0: U128 = data::get_reserve0();
         let reserve1: U128 = data::get_reserve1();
         let pair_address: Key = Key::from(data::get_package_hash());
-        //convert Key to ContractHash
-        let token0_hash_add_array = match token0 {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let token0_contract_hash = ContractHash::new(token0_hash_add_array);
-        //convert Key to ContractHash
-        let token1_hash_add_array = match token1 {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let token1_contract_hash = ContractHash::new(token1_hash_add_array);
+        let token0_contract_hash: ContractHash = key_to_contract_hash(token0).unwrap_or_revert();
+        let token1_contract_hash: ContractHash = key_to_contract_hash(token1).unwrap_or_revert();
         let balance0: U256 = runtime::call_contract(
             token0_contract_hash,
             "balance_of",
@@ -385,6 +362,13 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
     }
 
     fn swap(&mut self, amount0_out: U256, amount1_out: U256, to: Key, data: String) {
+        self.assert_not_paused();
+        let lock = data::get_lock();
+        if lock != 0 {
+            //UniswapV2: Locked
+            runtime::revert(PairError::UniswapV2PairLocked);
+        }
+        data::set_lock(1);
         let pair_address: Key = Key::from(data::get_package_hash());
         let zero: U256 = 0.into();
         if amount0_out > zero || amount1_out > zero {
@@ -396,15 +380,8 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
                 let token1: Key = self.get_token1();
                 if to != token0 && to != token1 {
                     if amount0_out > zero {
-                        //convert Key to ContractHash
-                        // let token0_hash_add_array = match token0 {
-                        //     Key::Hash(package) => package,
-                        //     _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-                        // };
-                        // let token0_contract_hash = ContractHash::new(token0_hash_add_array);
                         let ret: Result<(), u32> = runtime::call_contract(
-                            // token0_contract_hash,
-                            token0.into_hash().unwrap_or_revert().into(),
+                            key_to_contract_hash(token0).unwrap_or_revert(),
                             "transfer",
                             runtime_args! {
                                 "recipient" => to,
@@ -417,12 +394,8 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
                         }
                     }
                     if amount1_out > zero {
-                        //convert Key to ContractHash
-                        let token1_hash_add_array = match token1 {
-                            Key::Hash(package) => package,
-                            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-                        };
-                        let token1_contract_hash = ContractHash::new(token1_hash_add_array);
+                        let token1_contract_hash: ContractHash =
+                            key_to_contract_hash(token1).unwrap_or_revert();
                         let _ret: Result<(), u32> = runtime::call_contract(
                             token1_contract_hash,
                             "transfer",
@@ -435,14 +408,8 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
                     }
                     if data.len() > 0 {
                         let uniswap_v2_callee_address: Key = to;
-                        //convert Key to ContractHash
-                        let uniswap_v2_callee_address_hash_add_array =
-                            match uniswap_v2_callee_address {
-                                Key::Hash(package) => package,
-                                _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-                            };
-                        let uniswap_v2_callee_contract_hash =
-                            ContractHash::new(uniswap_v2_callee_address_hash_add_array);
+                        let uniswap_v2_callee_contract_hash: ContractHash =
+                            key_to_contract_hash(uniswap_v2_callee_address).unwrap_or_revert();
 
                         let _result: () = runtime::call_contract(
                             uniswap_v2_callee_contract_hash,
@@ -450,18 +417,10 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
                             runtime_args! {"sender" => data::get_callee_contract_hash(),"amount0" => amount0_out,"amount1" => amount1_out,"data" => data},
                         );
                     }
-                    //convert Key to ContractHash
-                    let token0_hash_add_array = match token0 {
-                        Key::Hash(package) => package,
-                        _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-                    };
-                    let token0_contract_hash = ContractHash::new(token0_hash_add_array);
-                    //convert Key to ContractHash
-                    let token1_hash_add_array = match token1 {
-                        Key::Hash(package) => package,
-                        _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-                    };
-                    let token1_contract_hash = ContractHash::new(token1_hash_add_array);
+                    let token0_contract_hash: ContractHash =
+                        key_to_contract_hash(token0).unwrap_or_revert();
+                    let token1_contract_hash: ContractHash =
+                        key_to_contract_hash(token1).unwrap_or_revert();
                     let balance0: U256 = runtime::call_contract(
                         token0_contract_hash,
                         "balance_of",
@@ -491,9 +450,16 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
                         let reserve0_conversion: U256 = U256::from(reserve0.as_u128());
                         let reserve1_conversion: U256 = U256::from(reserve1.as_u128());
                         let reserve_multiply: U256 = (1000 ^ 2).into();
-                        if (balance0_adjusted * balance1_adjusted)
-                            >= (reserve0_conversion * reserve1_conversion * reserve_multiply)
-                        {
+                        let k_holds: bool = if data::get_stable() {
+                            self.get_stable_d(
+                                balance0_adjusted / amount_1000,
+                                balance1_adjusted / amount_1000,
+                            ) >= self.get_stable_d(reserve0_conversion, reserve1_conversion)
+                        } else {
+                            (balance0_adjusted * balance1_adjusted)
+                                >= (reserve0_conversion * reserve1_conversion * reserve_multiply)
+                        };
+                        if k_holds {
                             self.update(balance0, balance1, reserve0, reserve1);
                             let eventpair: Key = Key::from(data::get_hash());
                             self.emit(&PAIREvent::Swap {
@@ -508,24 +474,333 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
                             });
                         } else {
                             //UniswapV2: K
-                            runtime::revert(ApiError::User(FailureCode::Twenty as u16));
+                            runtime::revert(PairError::UniswapV2PairK);
                         }
                     } else {
                         //UniswapV2: INSUFFICIENT_INPUT_AMOUNT
-                        runtime::revert(ApiError::User(FailureCode::Ninteen as u16));
+                        runtime::revert(PairError::UniswapV2PairInsufficientInputAmount);
                     }
                 } else {
                     //UniswapV2: INVALID_TO
-                    runtime::revert(ApiError::User(FailureCode::Eighteen as u16));
+                    runtime::revert(PairError::UniswapV2PairInvalidTo);
                 }
             } else {
                 //UniswapV2: INSUFFICIENT_LIQUIDITY
-                runtime::revert(ApiError::User(FailureCode::Seventeen as u16));
+                runtime::revert(PairError::UniswapV2PairInsufficientLiquidity);
             }
         } else {
             //UniswapV2: INSUFFICIENT_OUTPUT_AMOUNT
-            runtime::revert(ApiError::User(FailureCode::Sixteen as u16));
+            runtime::revert(PairError::UniswapV2PairInsufficientOutputAmount);
+        }
+        data::set_lock(0);
+    }
+
+    /// Swaps the tokens sent to the pair before the call for the other token, the output is
+    /// computed from the reserves with the 0.3% fee. Reverts if `deadline` (in seconds) has
+    /// passed or if an output is below its minimum.
+    fn swap_with_limits(
+        &mut self,
+        amount0_out_min: U256,
+        amount1_out_min: U256,
+        to: Key,
+        deadline: u64,
+    ) -> (U256, U256) {
+        self.assert_deadline(deadline);
+        let pair_address: Key = Key::from(data::get_package_hash());
+        let (reserve0, reserve1, _block_timestamp_last) = self.get_reserves();
+        let reserve0: U256 = U256::from(reserve0.as_u128());
+        let reserve1: U256 = U256::from(reserve1.as_u128());
+        let balance0: U256 = runtime::call_contract(
+            key_to_contract_hash(self.get_token0()).unwrap_or_revert(),
+            "balance_of",
+            runtime_args! {"owner" => pair_address},
+        );
+        let balance1: U256 = runtime::call_contract(
+            key_to_contract_hash(self.get_token1()).unwrap_or_revert(),
+            "balance_of",
+            runtime_args! {"owner" => pair_address},
+        );
+        let token0_decimals: u8 = data::get_token0_decimals();
+        let token1_decimals: u8 = data::get_token1_decimals();
+        let amount0_out: U256 = self.get_amount_out(
+            balance1.saturating_sub(reserve1),
+            (reserve1, token1_decimals),
+            (reserve0, token0_decimals),
+        );
+        let amount1_out: U256 = self.get_amount_out(
+            balance0.saturating_sub(reserve0),
+            (reserve0, token0_decimals),
+            (reserve1, token1_decimals),
+        );
+        if amount0_out < amount0_out_min {
+            runtime::revert(PairError::UniswapV2PairAmount0OutBelowMinimum);
+        }
+        if amount1_out < amount1_out_min {
+            runtime::revert(PairError::UniswapV2PairAmount1OutBelowMinimum);
         }
+        self.swap(amount0_out, amount1_out, to, String::new());
+        (amount0_out, amount1_out)
+    }
+
+    /// Same as `swap_with_limits` for tokens that take a fee on transfer: the minimums are
+    /// checked against what `to` received, measured from its balances, instead of against
+    /// what the pair sent. Returns the amounts received.
+    fn swap_supporting_fee_on_transfer_tokens(
+        &mut self,
+        amount0_out_min: U256,
+        amount1_out_min: U256,
+        to: Key,
+        deadline: u64,
+    ) -> (U256, U256) {
+        let token0: Key = self.get_token0();
+        let token1: Key = self.get_token1();
+        let balance0_before: U256 = token_balance(token0, to);
+        let balance1_before: U256 = token_balance(token1, to);
+        self.swap_with_limits(0.into(), 0.into(), to, deadline);
+        let amount0_received: U256 = token_balance(token0, to).saturating_sub(balance0_before);
+        let amount1_received: U256 = token_balance(token1, to).saturating_sub(balance1_before);
+        if amount0_received < amount0_out_min {
+            runtime::revert(PairError::UniswapV2PairAmount0OutBelowMinimum);
+        }
+        if amount1_received < amount1_out_min {
+            runtime::revert(PairError::UniswapV2PairAmount1OutBelowMinimum);
+        }
+        (amount0_received, amount1_received)
+    }
+
+    /// Output for `amount_in` given the reserve and the decimals of the input and the output
+    /// token. Only stable pairs use the decimals.
+    fn get_amount_out(
+        &mut self,
+        amount_in: U256,
+        (reserve_in, decimals_in): (U256, u8),
+        (reserve_out, decimals_out): (U256, u8),
+    ) -> U256 {
+        if amount_in.is_zero() {
+            return 0.into();
+        }
+        if reserve_in.is_zero() || reserve_out.is_zero() {
+            //UniswapV2: INSUFFICIENT_LIQUIDITY
+            runtime::revert(PairError::UniswapV2PairInsufficientLiquidity);
+        }
+        if data::get_stable() {
+            return self.get_stable_amount_out(
+                amount_in,
+                (reserve_in, decimals_in),
+                (reserve_out, decimals_out),
+            );
+        }
+        let amount_in_with_fee: U256 = amount_in * U256::from(997);
+        let numerator: U256 = amount_in_with_fee * reserve_out;
+        let denominator: U256 = (reserve_in * U256::from(1000)) + amount_in_with_fee;
+        numerator / denominator
+    }
+
+    /// Output of the pair for `amount_in` of `token_in` at the current reserves, on the curve
+    /// of the pair and after the fee, which is the most `swap` gives for it. Zero while the
+    /// pair holds no reserves.
+    fn get_token_amount_out(&mut self, token_in: Key, amount_in: U256) -> U256 {
+        let (reserve0, reserve1, _) = self.get_reserves();
+        let token0: (U256, u8) = (U256::from(reserve0.as_u128()), data::get_token0_decimals());
+        let token1: (U256, u8) = (U256::from(reserve1.as_u128()), data::get_token1_decimals());
+        let (token_in, token_out): ((U256, u8), (U256, u8)) = if token_in == self.get_token0() {
+            (token0, token1)
+        } else if token_in == self.get_token1() {
+            (token1, token0)
+        } else {
+            //UniswapV2: UNSUPPORTED_TOKEN
+            runtime::revert(PairError::UniswapV2PairUnsupportedToken);
+        };
+        if token_in.0.is_zero() || token_out.0.is_zero() {
+            return 0.into();
+        }
+        self.get_amount_out(amount_in, token_in, token_out)
+    }
+
+    /// Output of a stable pair for `amount_in` after the 0.3% fee. The curve is solved on
+    /// amounts normalized with `normalize_amount` and the output is scaled back to the
+    /// decimals of the output token. `get_y` only converges to within one unit, so the
+    /// output is lowered until it passes the same invariant check as `swap`.
+    fn get_stable_amount_out(
+        &mut self,
+        amount_in: U256,
+        (reserve_in, decimals_in): (U256, u8),
+        (reserve_out, decimals_out): (U256, u8),
+    ) -> U256 {
+        let d: U256 = self.get_d(
+            normalize_amount(reserve_in, decimals_in),
+            normalize_amount(reserve_out, decimals_out),
+        );
+        let x: U256 = normalize_amount(
+            reserve_in + (amount_in * U256::from(997)) / U256::from(1000),
+            decimals_in,
+        );
+        let y: U256 = self.get_y(x, d);
+        let mut amount_out: U256 = denormalize_amount(
+            normalize_amount(reserve_out, decimals_out).saturating_sub(y + 1),
+            decimals_out,
+        );
+        // one normalized unit is several units of a token with more than PRICE_DECIMALS
+        let step: U256 = denormalize_amount(1.into(), decimals_out).max(1.into());
+        while amount_out > 0.into()
+            && self.get_d(x, normalize_amount(reserve_out - amount_out, decimals_out)) < d
+        {
+            amount_out = amount_out.saturating_sub(step);
+        }
+        amount_out
+    }
+
+    /// This function is to get the maximum amount of `token` that can be borrowed through flash_loan,
+    /// which is the current reserve of that token or zero if the pair does not hold it.
+    ///
+    /// # Parameters
+    ///
+    /// * `token` - A Key that holds the hash of the token to borrow
+    ///
+
+    fn max_flash_loan(&mut self, token: Key) -> U256 {
+        let (reserve0, reserve1, _block_timestamp_last) = self.get_reserves();
+        if token == self.get_token0() {
+            U256::from(reserve0.as_u128())
+        } else if token == self.get_token1() {
+            U256::from(reserve1.as_u128())
+        } else {
+            0.into()
+        }
+    }
+
+    /// This function is to get the fee charged for borrowing `amount` of `token` through flash_loan.
+    /// The fee matches the 0.3% charged on the input side of swap.
+    ///
+    /// # Parameters
+    ///
+    /// * `token` - A Key that holds the hash of the token to borrow
+    ///
+    /// * `amount` - A U256 that holds the amount to borrow
+    ///
+
+    fn flash_fee(&mut self, token: Key, amount: U256) -> U256 {
+        if token != self.get_token0() && token != self.get_token1() {
+            //UniswapV2: UNSUPPORTED_TOKEN
+            runtime::revert(PairError::UniswapV2PairUnsupportedToken);
+        }
+        (checked_mul(amount, 3.into()) / 997) + 1
+    }
+
+    /// This function is to lend `amount` of `token` to `receiver` and call `on_flash_loan` on it.
+    /// The receiver has to transfer `amount` plus flash_fee back to the pair before the callback returns
+    /// and the callback has to return the keccak256 hash of "ERC3156FlashBorrower.onFlashLoan".
+    ///
+    /// # Parameters
+    ///
+    /// * `receiver` - A Key that holds the package hash of the contract implementing on_flash_loan
+    ///
+    /// * `token` - A Key that holds the hash of the token to borrow, it must be token0 or token1
+    ///
+    /// * `amount` - A U256 that holds the amount to borrow
+    ///
+    /// * `data` - A String that is passed on to the receiver as it is
+    ///
+
+    fn flash_loan(&mut self, receiver: Key, token: Key, amount: U256, data: String) {
+        self.assert_not_paused();
+        let lock = data::get_lock();
+        if lock != 0 {
+            //UniswapV2: Locked
+            runtime::revert(PairError::UniswapV2PairLocked);
+        }
+        data::set_lock(1);
+        let token0: Key = self.get_token0();
+        let token1: Key = self.get_token1();
+        if token != token0 && token != token1 {
+            //UniswapV2: UNSUPPORTED_TOKEN
+            runtime::revert(PairError::UniswapV2PairUnsupportedToken);
+        }
+        if amount == 0.into() {
+            //UniswapV2: INSUFFICIENT_OUTPUT_AMOUNT
+            runtime::revert(PairError::UniswapV2PairInsufficientOutputAmount);
+        }
+        if amount > self.max_flash_loan(token) {
+            //UniswapV2: INSUFFICIENT_LIQUIDITY
+            runtime::revert(PairError::UniswapV2PairInsufficientLiquidity);
+        }
+        if receiver == token0 || receiver == token1 {
+            //UniswapV2: INVALID_TO
+            runtime::revert(PairError::UniswapV2PairInvalidTo);
+        }
+        let fee: U256 = self.flash_fee(token, amount);
+        let pair_address: Key = Key::from(data::get_package_hash());
+        let token_contract_hash: ContractHash = key_to_contract_hash(token).unwrap_or_revert();
+        let receiver_package_hash: ContractPackageHash =
+            key_to_contract_package_hash(receiver).unwrap_or_revert();
+        let balance_before: U256 = runtime::call_contract(
+            token_contract_hash,
+            "balance_of",
+            runtime_args! {"owner" => pair_address},
+        );
+        let ret: Result<(), u32> = runtime::call_contract(
+            token_contract_hash,
+            "transfer",
+            runtime_args! {"recipient" => receiver,"amount" => amount},
+        );
+        match ret {
+            Ok(()) => {}
+            Err(e) => runtime::revert(e),
+        }
+        let initiator: Key = self.get_caller();
+        let callback_result: String = runtime::call_versioned_contract(
+            receiver_package_hash,
+            None,
+            "on_flash_loan",
+            runtime_args! {
+                "initiator" => initiator,
+                "token" => token,
+                "amount" => amount,
+                "fee" => fee,
+                "data" => data
+            },
+        );
+        let callback_success: String =
+            hex::encode(keccak256(data::FLASH_LOAN_CALLBACK_SUCCESS.as_bytes()));
+        if callback_result != callback_success {
+            //UniswapV2: FLASH_LOAN_CALLBACK_FAILED
+            runtime::revert(PairError::UniswapV2PairFlashLoanCallbackFailed);
+        }
+        let balance_after: U256 = runtime::call_contract(
+            token_contract_hash,
+            "balance_of",
+            runtime_args! {"owner" => pair_address},
+        );
+        let repayment: U256 = balance_before
+            .checked_add(fee)
+            .ok_or(PairError::UniswapV2PairOverFlow)
+            .unwrap_or_revert();
+        if balance_after < repayment {
+            //UniswapV2: FLASH_LOAN_NOT_REPAID
+            runtime::revert(PairError::UniswapV2PairFlashLoanNotRepaid);
+        }
+        let (reserve0, reserve1, _block_timestamp_last) = self.get_reserves();
+        let balance0: U256 = runtime::call_contract(
+            key_to_contract_hash(token0).unwrap_or_revert(),
+            "balance_of",
+            runtime_args! {"owner" => pair_address},
+        );
+        let balance1: U256 = runtime::call_contract(
+            key_to_contract_hash(token1).unwrap_or_revert(),
+            "balance_of",
+            runtime_args! {"owner" => pair_address},
+        );
+        self.update(balance0, balance1, reserve0, reserve1);
+        let eventpair: Key = Key::from(data::get_hash());
+        self.emit(&PAIREvent::FlashLoan {
+            initiator: initiator,
+            receiver: receiver,
+            token: token,
+            amount: amount,
+            fee: fee,
+            pair: eventpair,
+        });
+        data::set_lock(0);
     }
 
     /// This function is to get signer and verify if it is equal
@@ -627,37 +902,34 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
                 });
             } else {
                 //signature verification failed
-                runtime::revert(ApiError::User(FailureCode::Fourteen as u16));
+                runtime::revert(PairError::UniswapV2PairSignatureVerificationFailed);
             }
         } else {
             //deadline is equal to or greater than blocktime
-            runtime::revert(ApiError::User(FailureCode::Twelve as u16));
+            runtime::revert(PairError::UniswapV2PairExpired);
         }
     }
 
     fn mint(&mut self, recipient: Key, amount: U256) {
+        self.update_snapshots(ZERO_ACCOUNT, recipient);
         let balances = Balances::instance();
         let balance = balances.get(&recipient);
         balances.set(
             &recipient,
             balance
                 .checked_add(amount)
-                .ok_or(ApiError::User(FailureCode::TwentyThree as u16))
+                .ok_or(PairError::UniswapV2PairOverFlow)
                 .unwrap_or_revert(),
         );
         data::set_total_supply(
             self.total_supply()
                 .checked_add(amount)
-                .ok_or(ApiError::User(FailureCode::TwentyThree as u16))
+                .ok_or(PairError::UniswapV2PairOverFlow)
                 .unwrap_or_revert(),
         );
-        let address_0: Key = Key::from_formatted_str(
-            "account-hash-0000000000000000000000000000000000000000000000000000000000000000",
-        )
-        .unwrap();
         let eventpair: Key = Key::from(data::get_hash());
         self.emit(&PAIREvent::Transfer {
-            from: address_0,
+            from: ZERO_ACCOUNT,
             to: recipient,
             value: amount,
             pair: eventpair,
@@ -668,27 +940,24 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
         let balances = Balances::instance();
         let balance = balances.get(&recipient);
         if balance >= amount {
+            self.update_snapshots(recipient, ZERO_ACCOUNT);
             balances.set(
                 &recipient,
                 balance
                     .checked_sub(amount)
-                    .ok_or(ApiError::User(FailureCode::TwentyFour as u16))
+                    .ok_or(PairError::UniswapV2PairUnderFlow)
                     .unwrap_or_revert(),
             );
             data::set_total_supply(
                 self.total_supply()
                     .checked_sub(amount)
-                    .ok_or(ApiError::User(FailureCode::TwentyFour as u16))
+                    .ok_or(PairError::UniswapV2PairUnderFlow)
                     .unwrap_or_revert(),
             );
-            let address_0: Key = Key::from_formatted_str(
-                "account-hash-0000000000000000000000000000000000000000000000000000000000000000",
-            )
-            .unwrap();
             let eventpair: Key = Key::from(data::get_hash());
             self.emit(&PAIREvent::Transfer {
                 from: recipient,
-                to: address_0,
+                to: ZERO_ACCOUNT,
                 value: amount,
                 pair: eventpair,
             });
@@ -705,13 +974,14 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
 
     fn make_transfer(&mut self, sender: Key, recipient: Key, amount: U256) -> Result<(), u32> {
         if sender == recipient {
-            return Err(4); // Same sender recipient error
+            return Err(CommonError::SameSenderRecipient.into());
         }
 
         if amount.is_zero() {
-            return Err(5); // Amount to transfer is 0
+            return Err(CommonError::ZeroAmount.into());
         }
 
+        self.update_snapshots(sender, recipient);
         let balances: Balances = Balances::instance();
         let sender_balance: U256 = balances.get(&sender);
         let recipient_balance: U256 = balances.get(&recipient);
@@ -719,14 +989,14 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
             &sender,
             sender_balance
                 .checked_sub(amount)
-                .ok_or(ApiError::User(FailureCode::TwentyFour as u16))
+                .ok_or(PairError::UniswapV2PairUnderFlow)
                 .unwrap_or_revert(),
         );
         balances.set(
             &recipient,
             recipient_balance
                 .checked_add(amount)
-                .ok_or(ApiError::User(FailureCode::TwentyThree as u16))
+                .ok_or(PairError::UniswapV2PairOverFlow)
                 .unwrap_or_revert(),
         );
         let eventpair: Key = Key::from(data::get_hash());
@@ -782,26 +1052,43 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
         data::get_factory_hash()
     }
 
+    /// Package of the factory, cached on first use. The factory is called through it,
+    /// so the pair keeps reaching the latest factory once an upgrade disables the
+    /// version in `factory_hash`.
+    fn factory_package_hash(&mut self) -> ContractPackageHash {
+        match data::get_factory_package_hash() {
+            Some(factory_package_hash) => factory_package_hash,
+            None => {
+                let factory_package_hash: ContractPackageHash = runtime::call_contract(
+                    key_to_contract_hash(self.get_factory_hash()).unwrap_or_revert(),
+                    "package_hash",
+                    runtime_args! {},
+                );
+                data::set_factory_package_hash(factory_package_hash);
+                factory_package_hash
+            }
+        }
+    }
+
     fn get_package_hash(&mut self) -> ContractPackageHash {
         data::get_package_hash()
     }
 
     fn mint_helper(&mut self, to: Key) -> U256 {
+        self.assert_not_paused();
+        let lock = data::get_lock();
+        if lock != 0 {
+            //UniswapV2: Locked
+            runtime::revert(PairError::UniswapV2PairLocked);
+        }
+        data::set_lock(1);
         let (reserve0, reserve1, _block_timestamp_last) = self.get_reserves(); // gas savings
         let token0: Key = data::get_token0();
         let token1: Key = data::get_token1();
         let pair_contract_hash1: Key = Key::from(data::get_package_hash());
         let pair_contract_hash2: Key = Key::from(data::get_package_hash());
-        let token0_hash_add_array = match token0 {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let token0_hash_add = ContractHash::new(token0_hash_add_array);
-        let token1_hash_add_array = match token1 {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let token1_hash_add = ContractHash::new(token1_hash_add_array);
+        let token0_hash_add: ContractHash = key_to_contract_hash(token0).unwrap_or_revert();
+        let token1_hash_add: ContractHash = key_to_contract_hash(token1).unwrap_or_revert();
         let balance0: U256 = runtime::call_contract(
             token0_hash_add,
             "balance_of",
@@ -814,11 +1101,11 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
         );
         let amount0: U256 = balance0
             .checked_sub(U256::from(reserve0.as_u128()))
-            .ok_or(ApiError::User(FailureCode::TwentySeven as u16))
+            .ok_or(PairError::UniswapV2PairBalanceBelowReserve)
             .unwrap_or_revert();
         let amount1: U256 = balance1
             .checked_sub(U256::from(reserve1.as_u128()))
-            .ok_or(ApiError::User(FailureCode::TwentySeven as u16))
+            .ok_or(PairError::UniswapV2PairBalanceBelowReserve)
             .unwrap_or_revert();
         let fee_on: bool = self.mint_fee(reserve0, reserve1);
         let total_supply: U256 = self.total_supply(); // gas savings, must be defined here since totalSupply can update in mint_fee
@@ -826,15 +1113,9 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
         let mut liquidity: U256 = 0.into();
         if total_supply == 0.into() {
             liquidity = self.sqrt(amount0 * amount1).checked_sub(U256::from(minimum_liquidity.as_u128()))
-            .ok_or(ApiError::User(FailureCode::TwentyEight as u16))
+            .ok_or(PairError::UniswapV2PairInsufficientInitialLiquidity)
             .unwrap_or_revert();
-            self.mint(
-                Key::from_formatted_str(
-                    "account-hash-0000000000000000000000000000000000000000000000000000000000000000",
-                )
-                .unwrap(),
-                minimum_liquidity,
-            );
+            self.mint(ZERO_ACCOUNT, minimum_liquidity);
         } else {
             let x: U256 = (amount0 * total_supply) / U256::from(reserve0.as_u128());
             let y: U256 = (amount1 * total_supply) / U256::from(reserve1.as_u128());
@@ -844,7 +1125,7 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
             self.mint(to, liquidity);
             self.update(balance0, balance1, reserve0, reserve1);
             if fee_on {
-                let k_last: U256 = U256::from((reserve0 * reserve1).as_u128()); // reserve0 and reserve1 are up-to-date
+                let k_last: U256 = self.k(reserve0, reserve1); // reserve0 and reserve1 are up-to-date
                 data::set_k_last(k_last);
             }
             data::set_liquidity(liquidity); // return liquidity
@@ -855,27 +1136,26 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
                 amount1: amount1,
                 pair: eventpair,
             });
+            data::set_lock(0);
             liquidity // return liquidity
         } else {
             //UniswapV2: INSUFFICIENT_LIQUIDITY_MINTED
-            runtime::revert(ApiError::User(FailureCode::TwentyOne as u16));
+            runtime::revert(PairError::UniswapV2PairInsufficientLiquidityMinted);
         }
     }
 
     fn burn_helper(&mut self, to: Key) -> (U256, U256) {
+        let lock = data::get_lock();
+        if lock != 0 {
+            //UniswapV2: Locked
+            runtime::revert(PairError::UniswapV2PairLocked);
+        }
+        data::set_lock(1);
         let (reserve0, reserve1, _block_timestamp_last) = self.get_reserves(); // gas savings
         let token0: Key = data::get_token0();
         let token1: Key = data::get_token1();
-        let token0_hash_add_array = match token0 {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let token0_hash_add = ContractHash::new(token0_hash_add_array);
-        let token1_hash_add_array = match token1 {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let token1_hash_add = ContractHash::new(token1_hash_add_array);
+        let token0_hash_add: ContractHash = key_to_contract_hash(token0).unwrap_or_revert();
+        let token1_hash_add: ContractHash = key_to_contract_hash(token1).unwrap_or_revert();
         let balance0: U256 = runtime::call_contract(
             token0_hash_add,
             "balance_of",
@@ -914,16 +1194,8 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
                 Err(e) => runtime::revert(e),
             }
 
-            let token0_hash_add_array = match token0 {
-                Key::Hash(package) => package,
-                _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-            };
-            let token0_hash_add = ContractHash::new(token0_hash_add_array);
-            let token1_hash_add_array = match token1 {
-                Key::Hash(package) => package,
-                _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-            };
-            let token1_hash_add = ContractHash::new(token1_hash_add_array);
+            let token0_hash_add: ContractHash = key_to_contract_hash(token0).unwrap_or_revert();
+            let token1_hash_add: ContractHash = key_to_contract_hash(token1).unwrap_or_revert();
             let balance0: U256 = runtime::call_contract(
                 token0_hash_add,
                 "balance_of",
@@ -936,7 +1208,7 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
             );
             self.update(balance0, balance1, reserve0, reserve1);
             if fee_on {
-                let k_last: U256 = U256::from((reserve0 * reserve1).as_u128()); // reserve0 and reserve1 are up-to-date
+                let k_last: U256 = self.k(reserve0, reserve1); // reserve0 and reserve1 are up-to-date
                 data::set_k_last(k_last);
             }
             data::set_amount0(amount0);
@@ -949,36 +1221,124 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
                 to: to,
                 pair: eventpair,
             });
+            data::set_lock(0);
             (amount0, amount1)
         } else {
             //UniswapV2: INSUFFICIENT_LIQUIDITY_BURNED
-            runtime::revert(ApiError::User(FailureCode::TwentyTwo as u16));
+            runtime::revert(PairError::UniswapV2PairInsufficientLiquidityBurned);
+        }
+    }
+
+    /// Same as `burn_helper`, but reverts if `deadline` (in seconds) has passed or if an
+    /// amount sent to `to` is below its minimum.
+    fn burn_with_min(
+        &mut self,
+        to: Key,
+        amount0_min: U256,
+        amount1_min: U256,
+        deadline: u64,
+    ) -> (U256, U256) {
+        self.assert_deadline(deadline);
+        let (amount0, amount1): (U256, U256) = self.burn_helper(to);
+        if amount0 < amount0_min {
+            runtime::revert(PairError::UniswapV2PairAmount0BelowMinimum);
+        }
+        if amount1 < amount1_min {
+            runtime::revert(PairError::UniswapV2PairAmount1BelowMinimum);
+        }
+        (amount0, amount1)
+    }
+
+    /// Same as `burn_with_min` for tokens that take a fee on transfer: the minimums are
+    /// checked against what `to` received, measured from its balances. Returns the
+    /// amounts received.
+    fn burn_supporting_fee_on_transfer_tokens(
+        &mut self,
+        to: Key,
+        amount0_min: U256,
+        amount1_min: U256,
+        deadline: u64,
+    ) -> (U256, U256) {
+        self.assert_deadline(deadline);
+        let token0: Key = data::get_token0();
+        let token1: Key = data::get_token1();
+        let balance0_before: U256 = token_balance(token0, to);
+        let balance1_before: U256 = token_balance(token1, to);
+        self.burn_helper(to);
+        let amount0_received: U256 = token_balance(token0, to).saturating_sub(balance0_before);
+        let amount1_received: U256 = token_balance(token1, to).saturating_sub(balance1_before);
+        if amount0_received < amount0_min {
+            runtime::revert(PairError::UniswapV2PairAmount0BelowMinimum);
+        }
+        if amount1_received < amount1_min {
+            runtime::revert(PairError::UniswapV2PairAmount1BelowMinimum);
+        }
+        (amount0_received, amount1_received)
+    }
+
+    // deadlines are in seconds, like the one of permit
+    fn assert_deadline(&mut self, deadline: u64) {
+        if BlockTime::new(deadline.saturating_mul(1000)) < runtime::get_blocktime() {
+            runtime::revert(PairError::UniswapV2PairDeadlinePassed);
+        }
+    }
+
+    // the factory can pause every pair at once or this pair on its own, burn is never paused
+    fn assert_not_paused(&mut self) {
+        let paused: bool = runtime::call_versioned_contract(
+            self.factory_package_hash(),
+            None,
+            "is_pair_paused",
+            runtime_args! {"pair" => data::get_hash()},
+        );
+        if paused {
+            //UniswapV2: PAUSED
+            runtime::revert(PairError::UniswapV2PairPaused);
+        }
+    }
+
+    // the fee_to_setter of the factory, e.g. a governor, manages the snapshots
+    fn assert_caller_is_fee_to_setter(&mut self) {
+        let fee_to_setter: Key = runtime::call_versioned_contract(
+            self.factory_package_hash(),
+            None,
+            "fee_to_setter",
+            runtime_args! {},
+        );
+        if self.get_caller() != fee_to_setter {
+            //(UniswapV2: FORBIDDEN)
+            runtime::revert(PairError::UniswapV2PairForbidden);
+        }
+    }
+
+    /// x * y for a constant product pair and D^2 for a stable pair, so that sqrt(k) only
+    /// grows with the fees in both cases.
+    fn k(&mut self, reserve0: U128, reserve1: U128) -> U256 {
+        if data::get_stable() {
+            let d: U256 = self.get_stable_d(
+                U256::from(reserve0.as_u128()),
+                U256::from(reserve1.as_u128()),
+            );
+            checked_mul(d, d)
+        } else {
+            U256::from((reserve0 * reserve1).as_u128())
         }
     }
 
     // if fee is on, mint liquidity equivalent to 1/6th of the growth in sqrt(k)
     fn mint_fee(&mut self, reserve0: U128, reserve1: U128) -> bool {
-        let factory_hash: Key = self.get_factory_hash();
-        let factory_hash_add_array = match factory_hash {
-            Key::Hash(package) => package,
-            _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-        };
-        let factory_hash_add = ContractHash::new(factory_hash_add_array);
-        let fee_to: Key = runtime::call_contract(factory_hash_add, "fee_to", runtime_args! {});
-        let mut fee_on: bool = false;
-        if fee_to
-            != Key::from_formatted_str(
-                "account-hash-0000000000000000000000000000000000000000000000000000000000000000",
-            )
-            .unwrap()
-        {
-            fee_on = true;
-        }
+        let fee_to: Key = runtime::call_versioned_contract(
+            self.factory_package_hash(),
+            None,
+            "fee_to",
+            runtime_args! {},
+        );
+        let fee_on: bool = !is_zero(&fee_to);
         let k_last: U256 = data::get_k_last(); // gas savings
         let treasury_fee: U256 = data::get_treasury_fee();
         if fee_on {
             if k_last != 0.into() {
-                let mul_val: U256 = U256::from((reserve1 * reserve0).as_u128());
+                let mul_val: U256 = self.k(reserve0, reserve1);
                 let root_k: U256 = self.sqrt(mul_val);
                 let root_k_last: U256 = self.sqrt(k_last);
                 if root_k > root_k_last {
@@ -992,7 +1352,7 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
                         }
                     } else {
                         //UniswapV2: DENOMINATOR IS ZERO
-                        runtime::revert(ApiError::User(FailureCode::TwentyFive as u16));
+                        runtime::revert(PairError::UniswapV2PairDenominatorIsZero);
                     }
                 }
             }
@@ -1002,15 +1362,198 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
         return fee_on;
     }
 
+    /// Brings the storage of an upgraded pair up to `VERSION`. Named keys and
+    /// dictionaries are carried over to the new contract version, so this only
+    /// has to set up what the new code expects in addition. It never goes back,
+    /// so anyone may call it.
+    fn migrate(&mut self) {
+        if data::get_contract_version() < VERSION {
+            // pairs initialized before version 2 have no cached token decimals
+            if data::get_contract_version() < 2 && data::is_initialized() {
+                self.cache_token_decimals();
+            }
+            // before version 3 the factory was called by contract hash, cache its package
+            if data::get_contract_version() < 3 {
+                self.factory_package_hash();
+            }
+            data::set_contract_version(VERSION);
+        }
+    }
+
+    fn get_contract_version(&mut self) -> u32 {
+        data::get_contract_version()
+    }
+
+    /// Points the pair to a new version of its factory, only the factory package can call it.
+    fn update_factory_hash(&mut self, factory_hash: Key) {
+        if self.get_caller() != Key::from(self.factory_package_hash()) {
+            //(UniswapV2: FORBIDDEN)
+            runtime::revert(PairError::UniswapV2PairForbidden);
+        }
+        data::set_factory_hash(factory_hash);
+    }
+
+    /// Opts the LP token in to snapshots of balances and total supply, which cannot be
+    /// undone. Only the `fee_to_setter` of the factory can call it, e.g. a governor.
+    fn enable_snapshots(&mut self) {
+        self.assert_caller_is_fee_to_setter();
+        if !data::get_snapshots_enabled() {
+            Snapshots::init();
+            data::set_snapshots_enabled(true);
+        }
+    }
+
+    fn snapshots_enabled(&mut self) -> bool {
+        data::get_snapshots_enabled()
+    }
+
+    /// Takes a snapshot of every LP balance and of the total supply and returns its id,
+    /// ids start from 1. Only the `fee_to_setter` of the factory can take one, once the
+    /// pair has opted in, so snapshots cannot be taken to grow the storage of the pair.
+    fn snapshot(&mut self) -> U256 {
+        self.assert_caller_is_fee_to_setter();
+        if !data::get_snapshots_enabled() {
+            runtime::revert(PairError::UniswapV2PairSnapshotsDisabled);
+        }
+        let id: U256 = data::get_current_snapshot_id()
+            .checked_add(1.into())
+            .ok_or(PairError::UniswapV2PairOverFlow)
+            .unwrap_or_revert();
+        data::set_current_snapshot_id(id);
+        self.emit(&PAIREvent::Snapshot {
+            id: id,
+            pair: data::get_hash(),
+        });
+        id
+    }
+
+    fn get_current_snapshot_id(&mut self) -> U256 {
+        data::get_current_snapshot_id()
+    }
+
+    fn balance_of_at(&mut self, owner: Key, id: U256) -> U256 {
+        match snapshot_value_at(&Snapshots::account_owner(&owner), id) {
+            Some(value) => value,
+            None => self.balance_of(owner),
+        }
+    }
+
+    fn total_supply_at(&mut self, id: U256) -> U256 {
+        match snapshot_value_at(&Snapshots::total_supply_owner(), id) {
+            Some(value) => value,
+            None => self.total_supply(),
+        }
+    }
+
+    /// Records the LP balances of `from` and `to`, and the total supply for a mint or a
+    /// burn, as they were at the current snapshot if they have not changed since it.
+    /// It is called before they change and does nothing before the first snapshot.
+    fn update_snapshots(&mut self, from: Key, to: Key) {
+        let id: U256 = data::get_current_snapshot_id();
+        if id.is_zero() {
+            return;
+        }
+        let snapshots: Snapshots = Snapshots::instance();
+        if from == ZERO_ACCOUNT || to == ZERO_ACCOUNT {
+            update_snapshot(
+                &snapshots,
+                &Snapshots::total_supply_owner(),
+                id,
+                data::total_supply(),
+            );
+        }
+        let balances: Balances = Balances::instance();
+        for account in [from, to].iter() {
+            if *account != ZERO_ACCOUNT {
+                update_snapshot(
+                    &snapshots,
+                    &Snapshots::account_owner(account),
+                    id,
+                    balances.get(account),
+                );
+            }
+        }
+    }
+
+    /// Sets the tokens of the pair. Only the factory package can call it, from
+    /// `create_pair` or `create_stable_pair`, and only once.
     fn initialize(&mut self, token0: Key, token1: Key, factory_hash: Key) {
-        let factory_hash_getter: Key = self.get_factory_hash();
-        if factory_hash == factory_hash_getter {
-            data::set_token0(token0);
-            data::set_token1(token1);
-        } else {
+        if factory_hash != self.get_factory_hash()
+            || self.get_caller() != Key::from(self.factory_package_hash())
+        {
             //(UniswapV2: FORBIDDEN)
-            runtime::revert(ApiError::User(FailureCode::Thirteen as u16));
+            runtime::revert(PairError::UniswapV2PairForbidden);
+        }
+        if data::is_initialized() {
+            runtime::revert(PairError::UniswapV2PairAlreadyInitialized);
+        }
+        data::set_token0(token0);
+        data::set_token1(token1);
+        self.cache_token_decimals();
+    }
+
+    /// Same as `initialize` for a pair that trades on the StableSwap invariant with the
+    /// amplification coefficient `amp` instead of x * y = k.
+    fn initialize_stable(&mut self, token0: Key, token1: Key, factory_hash: Key, amp: U256) {
+        if amp == 0.into() || amp > MAX_AMP.into() {
+            runtime::revert(PairError::UniswapV2PairInvalidAmp);
         }
+        self.initialize(token0, token1, factory_hash);
+        data::set_stable(true);
+        data::set_amp(amp);
+    }
+
+    fn is_stable(&mut self) -> bool {
+        data::get_stable()
+    }
+
+    fn get_amp(&mut self) -> U256 {
+        data::get_amp()
+    }
+
+    /// Reads the decimals of both tokens once, so that prices can be normalized
+    /// without calling the token contracts again. Casper cannot catch a failed call, so
+    /// a token without a `decimals` entry point reverts the whole deploy: `create_pair`
+    /// rejects such tokens before `initialize`, and the upgrade of a pair created
+    /// before version 2 with one fails, leaving the previous version enabled.
+    fn cache_token_decimals(&mut self) {
+        data::set_token0_decimals(token_decimals(data::get_token0()));
+        data::set_token1_decimals(token_decimals(data::get_token1()));
+    }
+
+    fn decimals(&mut self) -> u8 {
+        data::decimals()
+    }
+
+    fn get_token0_decimals(&mut self) -> u8 {
+        data::get_token0_decimals()
+    }
+
+    fn get_token1_decimals(&mut self) -> u8 {
+        data::get_token1_decimals()
+    }
+
+    /// Price of token0 in token1 from the current reserves, with `PRICE_DECIMALS`
+    /// decimals whatever the decimals of the tokens are.
+    fn get_price0(&mut self) -> U256 {
+        let (reserve0, reserve1, _) = self.get_reserves();
+        normalized_price(
+            U256::from(reserve0.as_u128()),
+            data::get_token0_decimals(),
+            U256::from(reserve1.as_u128()),
+            data::get_token1_decimals(),
+        )
+    }
+
+    /// Price of token1 in token0, see `get_price0`.
+    fn get_price1(&mut self) -> U256 {
+        let (reserve0, reserve1, _) = self.get_reserves();
+        normalized_price(
+            U256::from(reserve1.as_u128()),
+            data::get_token1_decimals(),
+            U256::from(reserve0.as_u128()),
+            data::get_token0_decimals(),
+        )
     }
 
     fn get_reserves(&mut self) -> (U128, U128, u64) {
@@ -1043,6 +1586,64 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
         }
     }
 
+    /// StableSwap invariant D of a pair holding `x` and `y`, found with Newton's method:
+    /// `Ann * (x + y) + D = Ann * D + D^3 / (4 * x * y)` with `Ann = amp * n^n = amp * 4`
+    /// for the two tokens, as in Curve.
+    fn get_d(&mut self, x: U256, y: U256) -> U256 {
+        if x.is_zero() || y.is_zero() {
+            return 0.into();
+        }
+        let two: U256 = 2.into();
+        let ann: U256 = checked_mul(data::get_amp(), 4.into());
+        let s: U256 = checked_add(x, y);
+        let mut d: U256 = s;
+        for _ in 0..255 {
+            let mut d_p: U256 = d;
+            d_p = checked_mul(d_p, d) / checked_mul(x, two);
+            d_p = checked_mul(d_p, d) / checked_mul(y, two);
+            let d_prev: U256 = d;
+            d = checked_mul(checked_add(checked_mul(ann, s), checked_mul(d_p, two)), d)
+                / checked_add(checked_mul(ann - 1, d), checked_mul(U256::from(3), d_p));
+            if d.max(d_prev) - d.min(d_prev) <= 1.into() {
+                break;
+            }
+        }
+        d
+    }
+
+    /// D of a stable pair holding `balance0` of token0 and `balance1` of token1, with both
+    /// normalized first so that one token trades close to one of the other whatever
+    /// their decimals are.
+    fn get_stable_d(&mut self, balance0: U256, balance1: U256) -> U256 {
+        self.get_d(
+            normalize_amount(balance0, data::get_token0_decimals()),
+            normalize_amount(balance1, data::get_token1_decimals()),
+        )
+    }
+
+    /// Balance of the other token that keeps the invariant at `d` when the pair holds `x`,
+    /// with the same `Ann` as `get_d`.
+    fn get_y(&mut self, x: U256, d: U256) -> U256 {
+        let two: U256 = 2.into();
+        let ann: U256 = checked_mul(data::get_amp(), 4.into());
+        let mut c: U256 = checked_mul(d, d) / checked_mul(x, two);
+        c = checked_mul(c, d) / checked_mul(ann, two);
+        let b: U256 = checked_add(x, d / ann);
+        let mut y: U256 = d;
+        for _ in 0..255 {
+            let y_prev: U256 = y;
+            y = checked_add(checked_mul(y, y), c)
+                / checked_add(checked_mul(y, two), b)
+                    .checked_sub(d)
+                    .ok_or(PairError::UniswapV2PairUnderFlow)
+                    .unwrap_or_revert();
+            if y.max(y_prev) - y.min(y_prev) <= 1.into() {
+                break;
+            }
+        }
+        y
+    }
+
     /// encode a U128 as a U256
     fn encode(&mut self, y: U128) -> U256 {
         let q128: U256 = (2 ^ 128).into();
@@ -1104,7 +1705,7 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
             });
         } else {
             //UniswapV2: OVERFLOW
-            runtime::revert(ApiError::User(FailureCode::Fifteen as u16));
+            runtime::revert(PairError::UniswapV2PairReserveOverFlow);
         }
     }
     fn emit(&mut self, pair_event: &PAIREvent) {
@@ -1207,9 +1808,139 @@ pub trait PAIR<Storage: ContractStorage>: ContractContext<Storage> {
                 event.insert("pair", pair.to_string());
                 events.push(event);
             }
+            PAIREvent::FlashLoan {
+                initiator,
+                receiver,
+                token,
+                amount,
+                fee,
+                pair,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", pair_event.type_name());
+                event.insert("initiator", initiator.to_string());
+                event.insert("receiver", receiver.to_string());
+                event.insert("token", token.to_string());
+                event.insert("amount", amount.to_string());
+                event.insert("fee", fee.to_string());
+                event.insert("pair", pair.to_string());
+                events.push(event);
+            }
+            PAIREvent::Snapshot { id, pair } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", pair_event.type_name());
+                event.insert("id", id.to_string());
+                event.insert("pair", pair.to_string());
+                events.push(event);
+            }
         };
         for event in events {
             let _: URef = storage::new_uref(event);
         }
     }
 }
+
+fn checked_mul(a: U256, b: U256) -> U256 {
+    a.checked_mul(b)
+        .ok_or(PairError::UniswapV2PairOverFlow)
+        .unwrap_or_revert()
+}
+
+fn checked_add(a: U256, b: U256) -> U256 {
+    a.checked_add(b)
+        .ok_or(PairError::UniswapV2PairOverFlow)
+        .unwrap_or_revert()
+}
+
+/// Value of `owner` at the snapshot `id`, None if it has not changed since then and the
+/// current value applies.
+fn snapshot_value_at(owner: &str, id: U256) -> Option<U256> {
+    if id.is_zero() || id > data::get_current_snapshot_id() {
+        runtime::revert(PairError::UniswapV2PairInvalidSnapshotId);
+    }
+    let snapshots: Snapshots = Snapshots::instance();
+    let len: u32 = snapshots.len(owner);
+    // Binary search for the first snapshot at or after `id`.
+    let mut lower: u32 = 0;
+    let mut upper: u32 = len;
+    while lower < upper {
+        let center: u32 = lower + (upper - lower) / 2;
+        if snapshots.get(owner, center).0 < id {
+            lower = center + 1;
+        } else {
+            upper = center;
+        }
+    }
+    if lower == len {
+        None
+    } else {
+        Some(snapshots.get(owner, lower).1)
+    }
+}
+
+fn update_snapshot(snapshots: &Snapshots, owner: &str, id: U256, value: U256) {
+    let len: u32 = snapshots.len(owner);
+    let last: Snapshot = match len {
+        0 => (U256::zero(), U256::zero()),
+        len => snapshots.get(owner, len - 1),
+    };
+    if last.0 < id {
+        snapshots.push(owner, (id, value));
+    }
+}
+
+fn token_balance(token: Key, owner: Key) -> U256 {
+    runtime::call_contract(
+        key_to_contract_hash(token).unwrap_or_revert(),
+        "balance_of",
+        runtime_args! {"owner" => owner},
+    )
+}
+
+fn token_decimals(token: Key) -> u8 {
+    runtime::call_contract(
+        key_to_contract_hash(token).unwrap_or_revert(),
+        "decimals",
+        runtime_args! {},
+    )
+}
+
+/// Scales `amount` of a token with `decimals` decimals to `PRICE_DECIMALS` decimals,
+/// rounding down when the token has more. The factory only creates pairs of tokens with
+/// at most 36 decimals, for which the powers of ten fit in a U256.
+pub fn normalize_amount(amount: U256, decimals: u8) -> U256 {
+    if decimals <= PRICE_DECIMALS {
+        checked_mul(amount, U256::exp10((PRICE_DECIMALS - decimals).into()))
+    } else {
+        amount / U256::exp10((decimals - PRICE_DECIMALS).into())
+    }
+}
+
+/// Scales `amount` with `PRICE_DECIMALS` decimals back to a token with `decimals`
+/// decimals, rounding down when the token has fewer.
+fn denormalize_amount(amount: U256, decimals: u8) -> U256 {
+    if decimals <= PRICE_DECIMALS {
+        amount / U256::exp10((PRICE_DECIMALS - decimals).into())
+    } else {
+        checked_mul(amount, U256::exp10((decimals - PRICE_DECIMALS).into()))
+    }
+}
+
+/// Amount of the quote token paid for one base token, both reserves normalized first.
+fn normalized_price(
+    reserve_base: U256,
+    decimals_base: u8,
+    reserve_quote: U256,
+    decimals_quote: u8,
+) -> U256 {
+    let base: U256 = normalize_amount(reserve_base, decimals_base);
+    if base == 0.into() {
+        runtime::revert(PairError::UniswapV2PairInsufficientLiquidity);
+    }
+    checked_mul(
+        normalize_amount(reserve_quote, decimals_quote),
+        U256::exp10(PRICE_DECIMALS.into()),
+    ) / base
+}
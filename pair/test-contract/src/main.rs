@@ -2,7 +2,7 @@
 #![no_std]
 
 extern crate alloc;
-use alloc::{collections::BTreeSet, format, vec};
+use alloc::{collections::BTreeSet, format, string::String, vec};
 
 use casper_contract::{
     contract_api::{runtime, storage},
@@ -10,10 +10,16 @@ use casper_contract::{
 };
 use casper_types::{
     contracts::{ContractHash, ContractPackageHash},
-    runtime_args, ApiError, CLTyped, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints,
-    Group, Key, Parameter, RuntimeArgs, URef, U256,
+    runtime_args,
+    system::CallStackElement,
+    ApiError, CLTyped, CLValue, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Group,
+    Key, Parameter, RuntimeArgs, URef, U256,
 };
 
+// keccak256("ERC3156FlashBorrower.onFlashLoan")
+const FLASH_LOAN_CALLBACK_SUCCESS: &str =
+    "439148f0bbc682ca079e46d6e2c2f0c1e3b820f1a291b069d8882abf8cf18dd9";
+
 pub mod mappings;
 
 #[no_mangle]
@@ -175,6 +181,54 @@ fn approve() {
     let _ret: () = runtime::call_contract(pair_address, "approve", args);
 }
 
+// Flash loan receiver, pays back the borrowed amount plus fee to the lending pair.
+// With data "swap" it first calls swap on the lending pair, which has to be locked.
+#[no_mangle]
+fn on_flash_loan() {
+    let token: Key = runtime::get_named_arg("token");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let fee: U256 = runtime::get_named_arg("fee");
+    let data: String = runtime::get_named_arg("data");
+    let call_stack = runtime::get_call_stack();
+    let lender: ContractPackageHash = match call_stack.get(call_stack.len() - 2) {
+        Some(CallStackElement::StoredContract {
+            contract_package_hash,
+            contract_hash: _,
+        }) => *contract_package_hash,
+        _ => runtime::revert(ApiError::InvalidCallerInfoRequest),
+    };
+    if data == "swap" {
+        let self_package: ContractPackageHash = mappings::get_key(&mappings::self_package_key());
+        let _: () = runtime::call_versioned_contract(
+            lender,
+            None,
+            "swap",
+            runtime_args! {
+                "amount0_out" => U256::from(1),
+                "amount1_out" => U256::from(0),
+                "to" => Key::from(self_package),
+                "data" => String::new()
+            },
+        );
+    }
+    let lender: Key = Key::from(lender);
+    let token_hash_add_array = match token {
+        Key::Hash(package) => package,
+        _ => runtime::revert(ApiError::UnexpectedKeyVariant),
+    };
+    let token_hash_add = ContractHash::new(token_hash_add_array);
+    let ret: Result<(), u32> = runtime::call_contract(
+        token_hash_add,
+        "transfer",
+        runtime_args! {"recipient" => lender, "amount" => amount + fee},
+    );
+    match ret {
+        Ok(()) => {}
+        Err(e) => runtime::revert(e),
+    }
+    runtime::ret(CLValue::from_t(String::from(FLASH_LOAN_CALLBACK_SUCCESS)).unwrap_or_revert());
+}
+
 fn get_entry_points() -> EntryPoints {
     let mut entry_points = EntryPoints::new();
     entry_points.add_entry_point(EntryPoint::new(
@@ -268,6 +322,19 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "on_flash_loan",
+        vec![
+            Parameter::new("initiator", Key::cl_type()),
+            Parameter::new("token", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+            Parameter::new("fee", U256::cl_type()),
+            Parameter::new("data", String::cl_type()),
+        ],
+        String::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "approve",
         vec![
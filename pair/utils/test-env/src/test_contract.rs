@@ -55,7 +55,19 @@ impl TestContract {
     }
 
     pub fn call_contract(&self, sender: Sender, entry_point: &str, session_args: RuntimeArgs) {
+        self.call_contract_with_time(sender, entry_point, session_args, 0);
+    }
+
+    /// Same as `call_contract` with the block time of the deploy set to `time`, in milliseconds.
+    pub fn call_contract_with_time(
+        &self,
+        sender: Sender,
+        entry_point: &str,
+        session_args: RuntimeArgs,
+        time: u64,
+    ) {
         let session_code = Code::Hash(self.contract_hash(), String::from(entry_point));
-        self.env.run(sender, session_code, session_args);
+        self.env
+            .run_with_time(sender, session_code, session_args, time);
     }
 }
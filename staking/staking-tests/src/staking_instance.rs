@@ -0,0 +1,198 @@
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs, U256};
+use test_env::{Sender, TestContract, TestEnv};
+
+pub struct STAKINGInstance(pub TestContract);
+
+impl STAKINGInstance {
+    pub fn new<T: Into<Key>>(
+        env: &TestEnv,
+        contract_name: &str,
+        sender: Sender,
+        reward_token: T,
+        reward_per_second: U256,
+    ) -> STAKINGInstance {
+        STAKINGInstance(TestContract::new(
+            env,
+            "staking.wasm",
+            contract_name,
+            sender,
+            runtime_args! {
+                "reward_token" => reward_token.into(),
+                "reward_per_second" => reward_per_second,
+            },
+        ))
+    }
+
+    pub fn proxy(env: &TestEnv, staking: Key, sender: Sender) -> STAKINGInstance {
+        STAKINGInstance(TestContract::new(
+            env,
+            "staking-test.wasm",
+            "proxy_test",
+            sender,
+            runtime_args! {
+                "staking" => staking
+            },
+        ))
+    }
+
+    pub fn add_pool<T: Into<Key>>(&self, sender: Sender, lp_token: T, alloc_point: U256) {
+        self.0.call_contract(
+            sender,
+            "add_pool",
+            runtime_args! {
+                "lp_token" => lp_token.into(),
+                "alloc_point" => alloc_point,
+            },
+        );
+    }
+
+    pub fn set_alloc(&self, sender: Sender, pid: U256, alloc_point: U256) {
+        self.0.call_contract(
+            sender,
+            "set_alloc",
+            runtime_args! {
+                "pid" => pid,
+                "alloc_point" => alloc_point,
+            },
+        );
+    }
+
+    pub fn set_alloc_with_time(&self, sender: Sender, pid: U256, alloc_point: U256, time: u64) {
+        self.0.call_contract_with_time(
+            sender,
+            "set_alloc",
+            runtime_args! {
+                "pid" => pid,
+                "alloc_point" => alloc_point,
+            },
+            time,
+        );
+    }
+
+    pub fn deposit(&self, sender: Sender, pid: U256, amount: U256) {
+        self.0.call_contract(
+            sender,
+            "deposit",
+            runtime_args! {
+                "pid" => pid,
+                "amount" => amount,
+            },
+        );
+    }
+
+    pub fn deposit_with_time(&self, sender: Sender, pid: U256, amount: U256, time: u64) {
+        self.0.call_contract_with_time(
+            sender,
+            "deposit",
+            runtime_args! {
+                "pid" => pid,
+                "amount" => amount,
+            },
+            time,
+        );
+    }
+
+    pub fn withdraw(&self, sender: Sender, pid: U256, amount: U256) {
+        self.0.call_contract(
+            sender,
+            "withdraw",
+            runtime_args! {
+                "pid" => pid,
+                "amount" => amount,
+            },
+        );
+    }
+
+    pub fn harvest(&self, sender: Sender, pid: U256) {
+        self.0
+            .call_contract(sender, "harvest", runtime_args! {"pid" => pid});
+    }
+
+    pub fn harvest_with_time(&self, sender: Sender, pid: U256, time: u64) {
+        self.0
+            .call_contract_with_time(sender, "harvest", runtime_args! {"pid" => pid}, time);
+    }
+
+    pub fn pending_reward<T: Into<Key>>(&self, sender: Sender, pid: U256, user: T, time: u64) {
+        self.0.call_contract_with_time(
+            sender,
+            "pending_reward",
+            runtime_args! {
+                "pid" => pid,
+                "user" => user.into(),
+            },
+            time,
+        );
+    }
+
+    pub fn pending_reward_result(&self) -> U256 {
+        self.0.query_named_key(String::from("pending_reward"))
+    }
+
+    pub fn emergency_withdraw(&self, sender: Sender, pid: U256) {
+        self.0
+            .call_contract(sender, "emergency_withdraw", runtime_args! {"pid" => pid});
+    }
+
+    pub fn add_admin<T: Into<Key>>(&self, sender: Sender, address: T) {
+        self.0.call_contract(
+            sender,
+            "add_admin",
+            runtime_args! {
+                "address" => address.into(),
+            },
+        );
+    }
+
+    pub fn pool_length(&self) -> U256 {
+        self.0.query_named_key(String::from("pool_length"))
+    }
+
+    pub fn total_alloc_point(&self) -> U256 {
+        self.0.query_named_key(String::from("total_alloc_point"))
+    }
+
+    pub fn reward_token(&self) -> Key {
+        self.0.query_named_key(String::from("reward_token"))
+    }
+
+    pub fn lp_token(&self, pid: U256) -> Key {
+        self.0
+            .query_dictionary("pool_lp_tokens", pid.to_string())
+            .unwrap()
+    }
+
+    pub fn alloc_point(&self, pid: U256) -> U256 {
+        self.0
+            .query_dictionary("pool_alloc_points", pid.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn total_staked(&self, pid: U256) -> U256 {
+        self.0
+            .query_dictionary("pool_total_staked", pid.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn staked_amount<T: Into<Key>>(&self, pid: U256, user: T) -> U256 {
+        self.0
+            .query_dictionary(
+                "user_amounts",
+                format!("{}_{}", pid, key_to_str(&user.into())),
+            )
+            .unwrap_or_default()
+    }
+
+    pub fn package_hash(&self) -> ContractPackageHash {
+        self.0
+            .query_named_key(String::from("contract_package_hash"))
+    }
+}
+
+pub fn key_to_str(key: &Key) -> String {
+    match key {
+        Key::Account(account) => account.to_string(),
+        Key::Hash(package) => hex::encode(package),
+        _ => panic!("Unexpected key type"),
+    }
+}
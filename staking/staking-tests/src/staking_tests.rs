@@ -0,0 +1,311 @@
+use casper_engine_test_support::AccountHash;
+use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+use test_env::{Sender, TestContract, TestEnv};
+
+use crate::staking_instance::{key_to_str, STAKINGInstance};
+
+const NAME_STAKING: &str = "Staking";
+
+fn deploy() -> (TestEnv, STAKINGInstance, AccountHash, TestContract, TestContract) {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let reward_token = deploy_token(&env, "reward_token", owner);
+    let lp_token = deploy_token(&env, "lp_token", owner);
+    let staking = STAKINGInstance::new(
+        &env,
+        NAME_STAKING,
+        Sender(owner),
+        Key::Hash(reward_token.contract_hash()),
+        100.into(),
+    );
+    (env, staking, owner, reward_token, lp_token)
+}
+
+fn deploy_token(env: &TestEnv, name: &str, owner: AccountHash) -> TestContract {
+    let decimals: u8 = 18;
+    let init_total_supply: U256 = 0.into();
+    TestContract::new(
+        env,
+        "erc20-token.wasm",
+        name,
+        Sender(owner),
+        runtime_args! {
+        "initial_supply" => init_total_supply,
+        "name" => name,
+        "symbol" => "tk",
+        "decimals" => decimals
+        },
+    )
+}
+
+fn mint(token: &TestContract, owner: AccountHash, to: AccountHash, amount: U256) {
+    token.call_contract(
+        Sender(owner),
+        "mint",
+        runtime_args! {"to" => Key::from(to), "amount" => amount},
+    );
+}
+
+fn approve(token: &TestContract, owner: AccountHash, spender: Key, amount: U256) {
+    token.call_contract(
+        Sender(owner),
+        "approve",
+        runtime_args! {"spender" => spender, "amount" => amount},
+    );
+}
+
+fn balance_of(token: &TestContract, account: Key) -> U256 {
+    token
+        .query_dictionary("balances", key_to_str(&account))
+        .unwrap_or_default()
+}
+
+fn stake(
+    env: &TestEnv,
+    staking: &STAKINGInstance,
+    owner: AccountHash,
+    lp_token: &TestContract,
+    amount: U256,
+) -> AccountHash {
+    let user = env.next_user();
+    staking.add_pool(Sender(owner), Key::Hash(lp_token.contract_hash()), 100.into());
+    mint(lp_token, owner, user, amount);
+    approve(lp_token, user, Key::from(staking.package_hash()), amount);
+    staking.deposit(Sender(user), 0.into(), amount);
+    user
+}
+
+/// Mints `amount` of the reward token to the staking contract, which pays the rewards out
+/// of its balance.
+fn fund_rewards(
+    reward_token: &TestContract,
+    owner: AccountHash,
+    staking: &STAKINGInstance,
+    amount: U256,
+) {
+    reward_token.call_contract(
+        Sender(owner),
+        "mint",
+        runtime_args! {"to" => Key::from(staking.package_hash()), "amount" => amount},
+    );
+}
+
+/// Lets a new user stake `amount` in pool `pid` at block time `time`, in milliseconds.
+fn stake_at(
+    env: &TestEnv,
+    staking: &STAKINGInstance,
+    owner: AccountHash,
+    lp_token: &TestContract,
+    pid: U256,
+    amount: U256,
+    time: u64,
+) -> AccountHash {
+    let user = env.next_user();
+    mint(lp_token, owner, user, amount);
+    approve(lp_token, user, Key::from(staking.package_hash()), amount);
+    staking.deposit_with_time(Sender(user), pid, amount, time);
+    user
+}
+
+fn pending_reward(
+    proxy: &STAKINGInstance,
+    owner: AccountHash,
+    pid: U256,
+    user: AccountHash,
+    time: u64,
+) -> U256 {
+    proxy.pending_reward(Sender(owner), pid, Key::from(user), time);
+    proxy.pending_reward_result()
+}
+
+#[test]
+fn test_staking_deploy() {
+    let (_env, staking, _owner, reward_token, _lp_token) = deploy();
+    assert_eq!(staking.pool_length(), 0.into());
+    assert_eq!(staking.total_alloc_point(), 0.into());
+    assert_eq!(
+        staking.reward_token(),
+        Key::Hash(reward_token.contract_hash())
+    );
+}
+
+#[test]
+fn test_staking_add_pool() {
+    let (_env, staking, owner, _reward_token, lp_token) = deploy();
+    staking.add_pool(Sender(owner), Key::Hash(lp_token.contract_hash()), 100.into());
+    assert_eq!(staking.pool_length(), 1.into());
+    assert_eq!(staking.total_alloc_point(), 100.into());
+    assert_eq!(staking.lp_token(0.into()), Key::Hash(lp_token.contract_hash()));
+    assert_eq!(staking.alloc_point(0.into()), 100.into());
+}
+
+#[test]
+#[should_panic]
+fn test_staking_add_pool_by_non_admin() {
+    let (env, staking, _owner, _reward_token, lp_token) = deploy();
+    let user = env.next_user();
+    staking.add_pool(Sender(user), Key::Hash(lp_token.contract_hash()), 100.into());
+}
+
+#[test]
+#[should_panic]
+fn test_staking_add_pool_twice() {
+    let (_env, staking, owner, _reward_token, lp_token) = deploy();
+    staking.add_pool(Sender(owner), Key::Hash(lp_token.contract_hash()), 100.into());
+    staking.add_pool(Sender(owner), Key::Hash(lp_token.contract_hash()), 100.into());
+}
+
+#[test]
+#[should_panic]
+fn test_staking_add_pool_for_reward_token() {
+    let (_env, staking, owner, reward_token, _lp_token) = deploy();
+    staking.add_pool(
+        Sender(owner),
+        Key::Hash(reward_token.contract_hash()),
+        100.into(),
+    );
+}
+
+#[test]
+fn test_staking_set_alloc() {
+    let (env, staking, owner, _reward_token, lp_token) = deploy();
+    let lp_token2 = deploy_token(&env, "lp_token2", owner);
+    staking.add_pool(Sender(owner), Key::Hash(lp_token.contract_hash()), 100.into());
+    staking.add_pool(Sender(owner), Key::Hash(lp_token2.contract_hash()), 50.into());
+    assert_eq!(staking.total_alloc_point(), 150.into());
+    staking.set_alloc(Sender(owner), 0.into(), 300.into());
+    assert_eq!(staking.alloc_point(0.into()), 300.into());
+    assert_eq!(staking.total_alloc_point(), 350.into());
+}
+
+#[test]
+#[should_panic]
+fn test_staking_set_alloc_for_unknown_pool() {
+    let (_env, staking, owner, _reward_token, _lp_token) = deploy();
+    staking.set_alloc(Sender(owner), 0.into(), 300.into());
+}
+
+#[test]
+fn test_staking_deposit_and_withdraw() {
+    let (env, staking, owner, _reward_token, lp_token) = deploy();
+    let user = stake(&env, &staking, owner, &lp_token, 500.into());
+    assert_eq!(staking.staked_amount(0.into(), user), 500.into());
+    assert_eq!(staking.total_staked(0.into()), 500.into());
+    assert_eq!(
+        balance_of(&lp_token, Key::from(staking.package_hash())),
+        500.into()
+    );
+    staking.withdraw(Sender(user), 0.into(), 200.into());
+    assert_eq!(staking.staked_amount(0.into(), user), 300.into());
+    assert_eq!(staking.total_staked(0.into()), 300.into());
+    assert_eq!(balance_of(&lp_token, Key::from(user)), 200.into());
+    staking.harvest(Sender(user), 0.into());
+    assert_eq!(staking.staked_amount(0.into(), user), 300.into());
+}
+
+#[test]
+#[should_panic]
+fn test_staking_withdraw_more_than_staked() {
+    let (env, staking, owner, _reward_token, lp_token) = deploy();
+    let user = stake(&env, &staking, owner, &lp_token, 500.into());
+    staking.withdraw(Sender(user), 0.into(), 501.into());
+}
+
+#[test]
+#[should_panic]
+fn test_staking_deposit_without_approval() {
+    let (env, staking, owner, _reward_token, lp_token) = deploy();
+    let user = env.next_user();
+    staking.add_pool(Sender(owner), Key::Hash(lp_token.contract_hash()), 100.into());
+    mint(&lp_token, owner, user, 500.into());
+    staking.deposit(Sender(user), 0.into(), 500.into());
+}
+
+#[test]
+fn test_staking_emergency_withdraw() {
+    let (env, staking, owner, _reward_token, lp_token) = deploy();
+    let user = stake(&env, &staking, owner, &lp_token, 500.into());
+    staking.emergency_withdraw(Sender(user), 0.into());
+    assert_eq!(staking.staked_amount(0.into(), user), 0.into());
+    assert_eq!(staking.total_staked(0.into()), 0.into());
+    assert_eq!(balance_of(&lp_token, Key::from(user)), 500.into());
+}
+
+// the staking contract pays 100 reward tokens per second over all pools
+
+#[test]
+fn test_staking_rewards_of_one_staker() {
+    let (env, staking, owner, reward_token, lp_token) = deploy();
+    let proxy = STAKINGInstance::proxy(&env, Key::Hash(staking.0.contract_hash()), Sender(owner));
+    fund_rewards(&reward_token, owner, &staking, 1_000_000.into());
+    staking.add_pool(Sender(owner), Key::Hash(lp_token.contract_hash()), 100.into());
+    let user = stake_at(&env, &staking, owner, &lp_token, 0.into(), 500.into(), 0);
+
+    assert_eq!(pending_reward(&proxy, owner, 0.into(), user, 0), 0.into());
+    assert_eq!(pending_reward(&proxy, owner, 0.into(), user, 5_000), 500.into());
+    assert_eq!(pending_reward(&proxy, owner, 0.into(), user, 10_000), 1000.into());
+    staking.harvest_with_time(Sender(user), 0.into(), 10_000);
+    assert_eq!(balance_of(&reward_token, Key::from(user)), 1000.into());
+    assert_eq!(pending_reward(&proxy, owner, 0.into(), user, 10_000), 0.into());
+}
+
+#[test]
+fn test_staking_rewards_of_stakers_joining_at_different_times() {
+    let (env, staking, owner, reward_token, lp_token) = deploy();
+    let proxy = STAKINGInstance::proxy(&env, Key::Hash(staking.0.contract_hash()), Sender(owner));
+    fund_rewards(&reward_token, owner, &staking, 1_000_000.into());
+    staking.add_pool(Sender(owner), Key::Hash(lp_token.contract_hash()), 100.into());
+    let user1 = stake_at(&env, &staking, owner, &lp_token, 0.into(), 100.into(), 0);
+    // user1 has the first 1000 to themselves, then gets 100 / 400 of the next 1000
+    let user2 = stake_at(&env, &staking, owner, &lp_token, 0.into(), 300.into(), 10_000);
+
+    assert_eq!(pending_reward(&proxy, owner, 0.into(), user1, 20_000), 1250.into());
+    assert_eq!(pending_reward(&proxy, owner, 0.into(), user2, 20_000), 750.into());
+    staking.harvest_with_time(Sender(user1), 0.into(), 20_000);
+    staking.harvest_with_time(Sender(user2), 0.into(), 20_000);
+    assert_eq!(balance_of(&reward_token, Key::from(user1)), 1250.into());
+    assert_eq!(balance_of(&reward_token, Key::from(user2)), 750.into());
+}
+
+#[test]
+fn test_staking_rewards_after_set_alloc() {
+    let (env, staking, owner, reward_token, lp_token) = deploy();
+    let proxy = STAKINGInstance::proxy(&env, Key::Hash(staking.0.contract_hash()), Sender(owner));
+    let lp_token2 = deploy_token(&env, "lp_token2", owner);
+    fund_rewards(&reward_token, owner, &staking, 1_000_000.into());
+    staking.add_pool(Sender(owner), Key::Hash(lp_token.contract_hash()), 100.into());
+    staking.add_pool(Sender(owner), Key::Hash(lp_token2.contract_hash()), 100.into());
+    let user = stake_at(&env, &staking, owner, &lp_token, 0.into(), 100.into(), 0);
+
+    // half of the rewards until the change, then 300 / 400 of them
+    assert_eq!(pending_reward(&proxy, owner, 0.into(), user, 10_000), 500.into());
+    staking.set_alloc_with_time(Sender(owner), 0.into(), 300.into(), 10_000);
+    assert_eq!(pending_reward(&proxy, owner, 0.into(), user, 10_000), 500.into());
+    assert_eq!(pending_reward(&proxy, owner, 0.into(), user, 20_000), 1250.into());
+    staking.harvest_with_time(Sender(user), 0.into(), 20_000);
+    assert_eq!(balance_of(&reward_token, Key::from(user)), 1250.into());
+}
+
+#[test]
+fn test_staking_rewards_owed_when_the_reward_balance_runs_short() {
+    let (env, staking, owner, reward_token, lp_token) = deploy();
+    let proxy = STAKINGInstance::proxy(&env, Key::Hash(staking.0.contract_hash()), Sender(owner));
+    fund_rewards(&reward_token, owner, &staking, 600.into());
+    staking.add_pool(Sender(owner), Key::Hash(lp_token.contract_hash()), 100.into());
+    let user = stake_at(&env, &staking, owner, &lp_token, 0.into(), 500.into(), 0);
+
+    // 1000 accrued, the 400 that the balance can not cover are kept for later
+    staking.harvest_with_time(Sender(user), 0.into(), 10_000);
+    assert_eq!(balance_of(&reward_token, Key::from(user)), 600.into());
+    assert_eq!(
+        balance_of(&reward_token, Key::from(staking.package_hash())),
+        0.into()
+    );
+    assert_eq!(pending_reward(&proxy, owner, 0.into(), user, 10_000), 400.into());
+    assert_eq!(pending_reward(&proxy, owner, 0.into(), user, 15_000), 900.into());
+
+    fund_rewards(&reward_token, owner, &staking, 1_000_000.into());
+    staking.harvest_with_time(Sender(user), 0.into(), 15_000);
+    assert_eq!(balance_of(&reward_token, Key::from(user)), 1500.into());
+    assert_eq!(pending_reward(&proxy, owner, 0.into(), user, 15_000), 0.into());
+}
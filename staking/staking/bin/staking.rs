@@ -0,0 +1,473 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{collections::BTreeSet, format, vec};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    runtime_args, CLType, CLTyped, CLValue, ContractHash, ContractPackageHash, EntryPoint,
+    EntryPointAccess, EntryPointType, EntryPoints, Group, Key, Parameter, RuntimeArgs, URef, U256,
+};
+use contract_utils::{AdminControl, ContractContext, OnChainContractStorage};
+use staking::{self, STAKING};
+
+#[derive(Default)]
+struct Staking(OnChainContractStorage);
+
+impl ContractContext<OnChainContractStorage> for Staking {
+    fn storage(&self) -> &OnChainContractStorage {
+        &self.0
+    }
+}
+
+impl STAKING<OnChainContractStorage> for Staking {}
+
+impl AdminControl<OnChainContractStorage> for Staking {}
+
+impl Staking {
+    fn constructor(
+        &mut self,
+        reward_token: Key,
+        reward_per_second: U256,
+        contract_hash: ContractHash,
+        package_hash: ContractPackageHash,
+    ) {
+        STAKING::init(
+            self,
+            reward_token,
+            reward_per_second,
+            Key::from(contract_hash),
+            package_hash,
+        );
+    }
+}
+
+#[no_mangle]
+fn constructor() {
+    let reward_token: Key = runtime::get_named_arg("reward_token");
+    let reward_per_second: U256 = runtime::get_named_arg("reward_per_second");
+    let contract_hash: ContractHash = runtime::get_named_arg("contract_hash");
+    let package_hash: ContractPackageHash = runtime::get_named_arg("package_hash");
+    Staking::default().constructor(reward_token, reward_per_second, contract_hash, package_hash);
+}
+
+/// This function is to add a pool for an LP token which is only possible if the caller is an admin, it returns the id of the new pool
+///
+/// # Parameters
+///
+/// * `lp_token` - A Key that holds the Hash of the LP token
+///
+/// * `alloc_point` - A U256 that holds the share of the rewards that the pool gets
+///
+
+#[no_mangle]
+fn add_pool() {
+    let lp_token: Key = runtime::get_named_arg("lp_token");
+    let alloc_point: U256 = runtime::get_named_arg("alloc_point");
+    let ret: U256 = Staking::default().add_pool(lp_token, alloc_point);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to change the share of the rewards that a pool gets which is only possible if the caller is an admin
+///
+/// # Parameters
+///
+/// * `pid` - A U256 that holds the id of the pool
+///
+/// * `alloc_point` - A U256 that holds the new share of the rewards
+///
+
+#[no_mangle]
+fn set_alloc() {
+    let pid: U256 = runtime::get_named_arg("pid");
+    let alloc_point: U256 = runtime::get_named_arg("alloc_point");
+    Staking::default().set_alloc(pid, alloc_point);
+}
+
+/// This function is to stake LP tokens in a pool, the caller must have approved the amount for the package hash of this contract
+///
+/// # Parameters
+///
+/// * `pid` - A U256 that holds the id of the pool
+///
+/// * `amount` - A U256 that holds the amount of LP tokens to stake
+///
+
+#[no_mangle]
+fn deposit() {
+    let pid: U256 = runtime::get_named_arg("pid");
+    let amount: U256 = runtime::get_named_arg("amount");
+    Staking::default().deposit(pid, amount);
+}
+
+/// This function is to unstake LP tokens from a pool, the rewards earned so far are harvested
+///
+/// # Parameters
+///
+/// * `pid` - A U256 that holds the id of the pool
+///
+/// * `amount` - A U256 that holds the amount of LP tokens to unstake
+///
+
+#[no_mangle]
+fn withdraw() {
+    let pid: U256 = runtime::get_named_arg("pid");
+    let amount: U256 = runtime::get_named_arg("amount");
+    Staking::default().withdraw(pid, amount);
+}
+
+/// This function is to pay the caller the rewards earned in a pool
+///
+/// # Parameters
+///
+/// * `pid` - A U256 that holds the id of the pool
+///
+
+#[no_mangle]
+fn harvest() {
+    let pid: U256 = runtime::get_named_arg("pid");
+    Staking::default().harvest(pid);
+}
+
+/// This function is to unstake all LP tokens from a pool and give up the rewards earned in it
+///
+/// # Parameters
+///
+/// * `pid` - A U256 that holds the id of the pool
+///
+
+#[no_mangle]
+fn emergency_withdraw() {
+    let pid: U256 = runtime::get_named_arg("pid");
+    Staking::default().emergency_withdraw(pid);
+}
+
+/// This function is to accrue the rewards of a pool up to the current block time
+///
+/// # Parameters
+///
+/// * `pid` - A U256 that holds the id of the pool
+///
+
+#[no_mangle]
+fn update_pool() {
+    let pid: U256 = runtime::get_named_arg("pid");
+    Staking::default().update_pool(pid);
+}
+
+/// This function is to return the rewards that a user would get from harvesting a pool
+///
+/// # Parameters
+///
+/// * `pid` - A U256 that holds the id of the pool
+///
+/// * `user` - A Key that holds the Account Hash of the user
+///
+
+#[no_mangle]
+fn pending_reward() {
+    let pid: U256 = runtime::get_named_arg("pid");
+    let user: Key = runtime::get_named_arg("user");
+    let ret: U256 = Staking::default().pending_reward(pid, user);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the amount of LP tokens that a user has staked in a pool
+///
+/// # Parameters
+///
+/// * `pid` - A U256 that holds the id of the pool
+///
+/// * `user` - A Key that holds the Account Hash of the user
+///
+
+#[no_mangle]
+fn staked_amount() {
+    let pid: U256 = runtime::get_named_arg("pid");
+    let user: Key = runtime::get_named_arg("user");
+    let ret: U256 = Staking::default().staked_amount(pid, user);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the number of pools
+///
+
+#[no_mangle]
+fn pool_length() {
+    let ret: U256 = Staking::default().pool_length();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the sum of the allocation points of all pools
+///
+
+#[no_mangle]
+fn total_alloc_point() {
+    let ret: U256 = Staking::default().total_alloc_point();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the Hash of the reward token
+///
+
+#[no_mangle]
+fn reward_token() {
+    let ret: Key = Staking::default().reward_token();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the amount of reward tokens given out per second over all pools
+///
+
+#[no_mangle]
+fn reward_per_second() {
+    let ret: U256 = Staking::default().reward_per_second();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to add an admin which is only possible if the caller is an admin
+///
+/// # Parameters
+///
+/// * `address` - A Key that holds the Account Hash of the new admin
+///
+
+#[no_mangle]
+fn add_admin() {
+    let address: Key = runtime::get_named_arg("address");
+    Staking::default().add_admin(address);
+}
+
+/// This function is to remove an admin which is only possible if the caller is an admin
+///
+/// # Parameters
+///
+/// * `address` - A Key that holds the Account Hash of the admin to remove
+///
+
+#[no_mangle]
+fn disable_admin() {
+    let address: Key = runtime::get_named_arg("address");
+    Staking::default().disable_admin(address);
+}
+
+/// This function is to fetch a Contract Package Hash
+///
+
+#[no_mangle]
+fn package_hash() {
+    let ret: ContractPackageHash = Staking::default().get_package_hash();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new("reward_token", Key::cl_type()),
+            Parameter::new("reward_per_second", U256::cl_type()),
+            Parameter::new("contract_hash", ContractHash::cl_type()),
+            Parameter::new("package_hash", ContractPackageHash::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "add_pool",
+        vec![
+            Parameter::new("lp_token", Key::cl_type()),
+            Parameter::new("alloc_point", U256::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_alloc",
+        vec![
+            Parameter::new("pid", U256::cl_type()),
+            Parameter::new("alloc_point", U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "deposit",
+        vec![
+            Parameter::new("pid", U256::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "withdraw",
+        vec![
+            Parameter::new("pid", U256::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "harvest",
+        vec![Parameter::new("pid", U256::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "emergency_withdraw",
+        vec![Parameter::new("pid", U256::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "update_pool",
+        vec![Parameter::new("pid", U256::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "pending_reward",
+        vec![
+            Parameter::new("pid", U256::cl_type()),
+            Parameter::new("user", Key::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "staked_amount",
+        vec![
+            Parameter::new("pid", U256::cl_type()),
+            Parameter::new("user", Key::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "pool_length",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "total_alloc_point",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "reward_token",
+        vec![],
+        Key::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "reward_per_second",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "add_admin",
+        vec![Parameter::new("address", Key::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "disable_admin",
+        vec![Parameter::new("address", Key::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "package_hash",
+        vec![],
+        ContractPackageHash::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
+
+#[no_mangle]
+fn call() {
+    // Contract name must be same for all new versions of the contracts
+    let contract_name: alloc::string::String = runtime::get_named_arg("contract_name");
+
+    // Build new package with initial a first version of the contract.
+    let (package_hash, access_token) = storage::create_contract_package_at_hash();
+    let (contract_hash, _) =
+        storage::add_contract_version(package_hash, get_entry_points(), Default::default());
+
+    let reward_token: Key = runtime::get_named_arg("reward_token");
+    let reward_per_second: U256 = runtime::get_named_arg("reward_per_second");
+
+    // Prepare constructor args
+    let constructor_args = runtime_args! {
+        "reward_token" => reward_token,
+        "reward_per_second" => reward_per_second,
+        "contract_hash" => contract_hash,
+        "package_hash"=> package_hash
+    };
+
+    // Add the constructor group to the package hash with a single URef.
+    let constructor_access: URef =
+        storage::create_contract_user_group(package_hash, "constructor", 1, Default::default())
+            .unwrap_or_revert()
+            .pop()
+            .unwrap_or_revert();
+
+    // Call the constructor entry point
+    let _: () =
+        runtime::call_versioned_contract(package_hash, None, "constructor", constructor_args);
+
+    // Remove all URefs from the constructor group, so no one can call it for the second time.
+    let mut urefs = BTreeSet::new();
+    urefs.insert(constructor_access);
+    storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
+        .unwrap_or_revert();
+
+    // Store contract in the account's named keys.
+    runtime::put_key(
+        &format!("{}_package_hash", contract_name),
+        package_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_hash_wrapped", contract_name),
+        storage::new_uref(package_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash_wrapped", contract_name),
+        storage::new_uref(contract_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_access_token", contract_name),
+        access_token.into(),
+    );
+}
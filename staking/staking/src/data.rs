@@ -0,0 +1,218 @@
+use crate::alloc::string::ToString;
+use alloc::{format, string::String};
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{ContractPackageHash, Key, U256};
+use contract_utils::{get_key, key_to_str, set_key, Dict, ZERO_HASH};
+
+pub const SELF_CONTRACT_HASH: &str = "self_contract_hash";
+pub const CONTRACT_PACKAGE_HASH: &str = "contract_package_hash";
+pub const REWARD_TOKEN: &str = "reward_token";
+pub const REWARD_PER_SECOND: &str = "reward_per_second";
+pub const TOTAL_ALLOC_POINT: &str = "total_alloc_point";
+pub const POOL_LENGTH: &str = "pool_length";
+pub const POOL_LP_TOKENS_DICT: &str = "pool_lp_tokens";
+pub const POOL_ALLOC_POINTS_DICT: &str = "pool_alloc_points";
+pub const POOL_LAST_REWARD_TIMES_DICT: &str = "pool_last_reward_times";
+pub const POOL_ACC_REWARD_PER_SHARE_DICT: &str = "pool_acc_reward_per_share";
+pub const POOL_TOTAL_STAKED_DICT: &str = "pool_total_staked";
+pub const LP_TOKEN_POOLS_DICT: &str = "lp_token_pools";
+pub const USER_AMOUNTS_DICT: &str = "user_amounts";
+pub const USER_REWARD_DEBTS_DICT: &str = "user_reward_debts";
+pub const USER_REWARDS_OWED_DICT: &str = "user_rewards_owed";
+
+/// `acc_reward_per_share` is scaled by this factor so that small rewards spread over
+/// a large stake are not rounded down to zero.
+pub const ACC_REWARD_PRECISION: u64 = 1_000_000_000_000;
+
+/// State of every pool, indexed by pool id. Pool ids are given out in the order
+/// the pools are added, starting from 0.
+pub struct Pools {
+    lp_tokens: Dict,
+    alloc_points: Dict,
+    last_reward_times: Dict,
+    acc_reward_per_share: Dict,
+    total_staked: Dict,
+    lp_token_pools: Dict,
+}
+
+impl Pools {
+    pub fn instance() -> Pools {
+        Pools {
+            lp_tokens: Dict::instance(POOL_LP_TOKENS_DICT),
+            alloc_points: Dict::instance(POOL_ALLOC_POINTS_DICT),
+            last_reward_times: Dict::instance(POOL_LAST_REWARD_TIMES_DICT),
+            acc_reward_per_share: Dict::instance(POOL_ACC_REWARD_PER_SHARE_DICT),
+            total_staked: Dict::instance(POOL_TOTAL_STAKED_DICT),
+            lp_token_pools: Dict::instance(LP_TOKEN_POOLS_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(POOL_LP_TOKENS_DICT);
+        Dict::init(POOL_ALLOC_POINTS_DICT);
+        Dict::init(POOL_LAST_REWARD_TIMES_DICT);
+        Dict::init(POOL_ACC_REWARD_PER_SHARE_DICT);
+        Dict::init(POOL_TOTAL_STAKED_DICT);
+        Dict::init(LP_TOKEN_POOLS_DICT);
+    }
+
+    pub fn lp_token(&self, pid: U256) -> Key {
+        self.lp_tokens.get(&pid.to_string()).unwrap_or(ZERO_HASH)
+    }
+
+    pub fn has_lp_token(&self, lp_token: &Key) -> bool {
+        self.lp_token_pools.get_by_key(lp_token).unwrap_or_default()
+    }
+
+    pub fn add(&self, pid: U256, lp_token: Key, alloc_point: U256, last_reward_time: u64) {
+        self.lp_tokens.set(&pid.to_string(), lp_token);
+        self.lp_token_pools.set_by_key(&lp_token, true);
+        self.set_alloc_point(pid, alloc_point);
+        self.set_last_reward_time(pid, last_reward_time);
+        self.set_acc_reward_per_share(pid, U256::zero());
+        self.set_total_staked(pid, U256::zero());
+    }
+
+    pub fn alloc_point(&self, pid: U256) -> U256 {
+        self.alloc_points.get(&pid.to_string()).unwrap_or_default()
+    }
+
+    pub fn set_alloc_point(&self, pid: U256, alloc_point: U256) {
+        self.alloc_points.set(&pid.to_string(), alloc_point);
+    }
+
+    pub fn last_reward_time(&self, pid: U256) -> u64 {
+        self.last_reward_times
+            .get(&pid.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn set_last_reward_time(&self, pid: U256, last_reward_time: u64) {
+        self.last_reward_times
+            .set(&pid.to_string(), last_reward_time);
+    }
+
+    pub fn acc_reward_per_share(&self, pid: U256) -> U256 {
+        self.acc_reward_per_share
+            .get(&pid.to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn set_acc_reward_per_share(&self, pid: U256, acc_reward_per_share: U256) {
+        self.acc_reward_per_share
+            .set(&pid.to_string(), acc_reward_per_share);
+    }
+
+    pub fn total_staked(&self, pid: U256) -> U256 {
+        self.total_staked.get(&pid.to_string()).unwrap_or_default()
+    }
+
+    pub fn set_total_staked(&self, pid: U256, total_staked: U256) {
+        self.total_staked.set(&pid.to_string(), total_staked);
+    }
+}
+
+/// Stake of every user in every pool. `reward_debt` is the part of
+/// `amount * acc_reward_per_share` that was already accounted for, and
+/// `rewards_owed` is what was earned but could not be paid out yet.
+pub struct Stakes {
+    amounts: Dict,
+    reward_debts: Dict,
+    rewards_owed: Dict,
+}
+
+impl Stakes {
+    pub fn instance() -> Stakes {
+        Stakes {
+            amounts: Dict::instance(USER_AMOUNTS_DICT),
+            reward_debts: Dict::instance(USER_REWARD_DEBTS_DICT),
+            rewards_owed: Dict::instance(USER_REWARDS_OWED_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(USER_AMOUNTS_DICT);
+        Dict::init(USER_REWARD_DEBTS_DICT);
+        Dict::init(USER_REWARDS_OWED_DICT);
+    }
+
+    pub fn amount(&self, pid: U256, user: &Key) -> U256 {
+        self.amounts.get(&stake_key(pid, user)).unwrap_or_default()
+    }
+
+    pub fn set_amount(&self, pid: U256, user: &Key, amount: U256) {
+        self.amounts.set(&stake_key(pid, user), amount);
+    }
+
+    pub fn reward_debt(&self, pid: U256, user: &Key) -> U256 {
+        self.reward_debts
+            .get(&stake_key(pid, user))
+            .unwrap_or_default()
+    }
+
+    pub fn set_reward_debt(&self, pid: U256, user: &Key, reward_debt: U256) {
+        self.reward_debts.set(&stake_key(pid, user), reward_debt);
+    }
+
+    pub fn rewards_owed(&self, pid: U256, user: &Key) -> U256 {
+        self.rewards_owed
+            .get(&stake_key(pid, user))
+            .unwrap_or_default()
+    }
+
+    pub fn set_rewards_owed(&self, pid: U256, user: &Key, rewards_owed: U256) {
+        self.rewards_owed.set(&stake_key(pid, user), rewards_owed);
+    }
+}
+
+fn stake_key(pid: U256, user: &Key) -> String {
+    format!("{}_{}", pid, key_to_str(user))
+}
+
+pub fn set_hash(contract_hash: Key) {
+    set_key(SELF_CONTRACT_HASH, contract_hash);
+}
+
+pub fn get_hash() -> Key {
+    get_key(SELF_CONTRACT_HASH).unwrap_or_revert()
+}
+
+pub fn set_package_hash(package_hash: ContractPackageHash) {
+    set_key(CONTRACT_PACKAGE_HASH, package_hash);
+}
+
+pub fn get_package_hash() -> ContractPackageHash {
+    get_key(CONTRACT_PACKAGE_HASH).unwrap_or_revert()
+}
+
+pub fn set_reward_token(reward_token: Key) {
+    set_key(REWARD_TOKEN, reward_token);
+}
+
+pub fn get_reward_token() -> Key {
+    get_key(REWARD_TOKEN).unwrap_or_revert()
+}
+
+pub fn set_reward_per_second(reward_per_second: U256) {
+    set_key(REWARD_PER_SECOND, reward_per_second);
+}
+
+pub fn get_reward_per_second() -> U256 {
+    get_key(REWARD_PER_SECOND).unwrap_or_default()
+}
+
+pub fn set_total_alloc_point(total_alloc_point: U256) {
+    set_key(TOTAL_ALLOC_POINT, total_alloc_point);
+}
+
+pub fn get_total_alloc_point() -> U256 {
+    get_key(TOTAL_ALLOC_POINT).unwrap_or_default()
+}
+
+pub fn set_pool_length(pool_length: U256) {
+    set_key(POOL_LENGTH, pool_length);
+}
+
+pub fn get_pool_length() -> U256 {
+    get_key(POOL_LENGTH).unwrap_or_default()
+}
@@ -0,0 +1,471 @@
+use crate::alloc::string::ToString;
+use crate::data::{self, Pools, Stakes, ACC_REWARD_PRECISION};
+use alloc::collections::BTreeMap;
+use alloc::{string::String, vec::Vec};
+use casper_contract::contract_api::runtime;
+use casper_contract::contract_api::storage;
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{
+    runtime_args, ContractHash, ContractPackageHash, Key, RuntimeArgs, URef, U256,
+};
+use contract_utils::{key_to_contract_hash, AdminControl, ContractContext, ContractStorage};
+use errors::StakingError;
+
+pub enum STAKINGEvent {
+    PoolAdded {
+        pid: U256,
+        lp_token: Key,
+        alloc_point: U256,
+    },
+    PoolAllocUpdated {
+        pid: U256,
+        alloc_point: U256,
+    },
+    Deposit {
+        user: Key,
+        pid: U256,
+        amount: U256,
+    },
+    Withdraw {
+        user: Key,
+        pid: U256,
+        amount: U256,
+    },
+    Harvest {
+        user: Key,
+        pid: U256,
+        amount: U256,
+    },
+    EmergencyWithdraw {
+        user: Key,
+        pid: U256,
+        amount: U256,
+    },
+}
+impl STAKINGEvent {
+    pub fn type_name(&self) -> String {
+        match self {
+            STAKINGEvent::PoolAdded {
+                pid: _,
+                lp_token: _,
+                alloc_point: _,
+            } => "pool_added",
+            STAKINGEvent::PoolAllocUpdated {
+                pid: _,
+                alloc_point: _,
+            } => "pool_alloc_updated",
+            STAKINGEvent::Deposit {
+                user: _,
+                pid: _,
+                amount: _,
+            } => "deposit",
+            STAKINGEvent::Withdraw {
+                user: _,
+                pid: _,
+                amount: _,
+            } => "withdraw",
+            STAKINGEvent::Harvest {
+                user: _,
+                pid: _,
+                amount: _,
+            } => "harvest",
+            STAKINGEvent::EmergencyWithdraw {
+                user: _,
+                pid: _,
+                amount: _,
+            } => "emergency_withdraw",
+        }
+        .to_string()
+    }
+}
+
+pub trait STAKING<Storage: ContractStorage>:
+    ContractContext<Storage> + AdminControl<Storage>
+{
+    fn init(
+        &mut self,
+        reward_token: Key,
+        reward_per_second: U256,
+        contract_hash: Key,
+        package_hash: ContractPackageHash,
+    ) {
+        data::set_reward_token(reward_token);
+        data::set_reward_per_second(reward_per_second);
+        data::set_total_alloc_point(0.into());
+        data::set_pool_length(0.into());
+        data::set_hash(contract_hash);
+        data::set_package_hash(package_hash);
+        Pools::init();
+        Stakes::init();
+        AdminControl::init(self);
+        self.add_admin_without_checked(self.get_caller());
+    }
+
+    /// Adds a pool for `lp_token` and returns its id. Every pool is updated first, as
+    /// the new allocation changes the share of the rewards that the other pools get.
+    fn add_pool(&mut self, lp_token: Key, alloc_point: U256) -> U256 {
+        self.assert_caller_is_admin();
+        key_to_contract_hash(lp_token).unwrap_or_revert_with(StakingError::StakingInvalidLpToken);
+        if lp_token == data::get_reward_token() {
+            runtime::revert(StakingError::StakingInvalidLpToken);
+        }
+        let pools: Pools = Pools::instance();
+        if pools.has_lp_token(&lp_token) {
+            runtime::revert(StakingError::StakingPoolExists);
+        }
+        self.mass_update_pools();
+        let pid: U256 = data::get_pool_length();
+        pools.add(pid, lp_token, alloc_point, runtime::get_blocktime().into());
+        data::set_total_alloc_point(
+            data::get_total_alloc_point()
+                .checked_add(alloc_point)
+                .ok_or(StakingError::StakingOverFlow)
+                .unwrap_or_revert(),
+        );
+        data::set_pool_length(pid + 1);
+        self.emit(&STAKINGEvent::PoolAdded {
+            pid: pid,
+            lp_token: lp_token,
+            alloc_point: alloc_point,
+        });
+        pid
+    }
+
+    fn set_alloc(&mut self, pid: U256, alloc_point: U256) {
+        self.assert_caller_is_admin();
+        self.assert_pool_exists(pid);
+        self.mass_update_pools();
+        let pools: Pools = Pools::instance();
+        data::set_total_alloc_point(
+            data::get_total_alloc_point()
+                .checked_sub(pools.alloc_point(pid))
+                .ok_or(StakingError::StakingUnderFlow)
+                .unwrap_or_revert()
+                .checked_add(alloc_point)
+                .ok_or(StakingError::StakingOverFlow)
+                .unwrap_or_revert(),
+        );
+        pools.set_alloc_point(pid, alloc_point);
+        self.emit(&STAKINGEvent::PoolAllocUpdated {
+            pid: pid,
+            alloc_point: alloc_point,
+        });
+    }
+
+    /// Stakes `amount` of the pool's LP token, which the caller must have approved for
+    /// this contract's package hash. Rewards earned so far are harvested first.
+    fn deposit(&mut self, pid: U256, amount: U256) {
+        self.assert_pool_exists(pid);
+        self.update_pool(pid);
+        let user: Key = self.get_caller();
+        let pools: Pools = Pools::instance();
+        let stakes: Stakes = Stakes::instance();
+        self.settle_rewards(pid, user);
+        let staked: U256 = stakes
+            .amount(pid, &user)
+            .checked_add(amount)
+            .ok_or(StakingError::StakingOverFlow)
+            .unwrap_or_revert();
+        stakes.set_amount(pid, &user, staked);
+        stakes.set_reward_debt(pid, &user, reward_debt(staked, pools.acc_reward_per_share(pid)));
+        pools.set_total_staked(
+            pid,
+            pools
+                .total_staked(pid)
+                .checked_add(amount)
+                .ok_or(StakingError::StakingOverFlow)
+                .unwrap_or_revert(),
+        );
+        if amount > U256::zero() {
+            let ret: Result<(), u32> = runtime::call_contract(
+                key_to_contract_hash(pools.lp_token(pid)).unwrap_or_revert(),
+                "transfer_from",
+                runtime_args! {
+                    "owner" => user,
+                    "recipient" => Key::from(data::get_package_hash()),
+                    "amount" => amount
+                },
+            );
+            ret.unwrap_or_revert();
+        }
+        self.emit(&STAKINGEvent::Deposit {
+            user: user,
+            pid: pid,
+            amount: amount,
+        });
+    }
+
+    /// Unstakes `amount` of the pool's LP token. Rewards earned so far are harvested first.
+    fn withdraw(&mut self, pid: U256, amount: U256) {
+        self.assert_pool_exists(pid);
+        let user: Key = self.get_caller();
+        let pools: Pools = Pools::instance();
+        let stakes: Stakes = Stakes::instance();
+        let staked: U256 = stakes
+            .amount(pid, &user)
+            .checked_sub(amount)
+            .ok_or(StakingError::StakingInsufficientStake)
+            .unwrap_or_revert();
+        self.update_pool(pid);
+        self.settle_rewards(pid, user);
+        stakes.set_amount(pid, &user, staked);
+        stakes.set_reward_debt(pid, &user, reward_debt(staked, pools.acc_reward_per_share(pid)));
+        pools.set_total_staked(
+            pid,
+            pools
+                .total_staked(pid)
+                .checked_sub(amount)
+                .ok_or(StakingError::StakingUnderFlow)
+                .unwrap_or_revert(),
+        );
+        self.transfer_lp_token(pid, user, amount);
+        self.emit(&STAKINGEvent::Withdraw {
+            user: user,
+            pid: pid,
+            amount: amount,
+        });
+    }
+
+    fn harvest(&mut self, pid: U256) {
+        self.assert_pool_exists(pid);
+        self.update_pool(pid);
+        let user: Key = self.get_caller();
+        self.settle_rewards(pid, user);
+        let stakes: Stakes = Stakes::instance();
+        stakes.set_reward_debt(
+            pid,
+            &user,
+            reward_debt(
+                stakes.amount(pid, &user),
+                Pools::instance().acc_reward_per_share(pid),
+            ),
+        );
+    }
+
+    /// Returns the whole stake without touching the reward accounting, for when paying
+    /// out rewards keeps reverting. Rewards earned in the pool are given up.
+    fn emergency_withdraw(&mut self, pid: U256) {
+        self.assert_pool_exists(pid);
+        let user: Key = self.get_caller();
+        let pools: Pools = Pools::instance();
+        let stakes: Stakes = Stakes::instance();
+        let amount: U256 = stakes.amount(pid, &user);
+        stakes.set_amount(pid, &user, U256::zero());
+        stakes.set_reward_debt(pid, &user, U256::zero());
+        stakes.set_rewards_owed(pid, &user, U256::zero());
+        pools.set_total_staked(
+            pid,
+            pools
+                .total_staked(pid)
+                .checked_sub(amount)
+                .ok_or(StakingError::StakingUnderFlow)
+                .unwrap_or_revert(),
+        );
+        self.transfer_lp_token(pid, user, amount);
+        self.emit(&STAKINGEvent::EmergencyWithdraw {
+            user: user,
+            pid: pid,
+            amount: amount,
+        });
+    }
+
+    /// Accrues the rewards of `pid` up to the current block time.
+    fn update_pool(&mut self, pid: U256) {
+        self.assert_pool_exists(pid);
+        let now: u64 = runtime::get_blocktime().into();
+        let pools: Pools = Pools::instance();
+        if now <= pools.last_reward_time(pid) {
+            return;
+        }
+        pools.set_acc_reward_per_share(pid, self.acc_reward_per_share_at(pid, now));
+        pools.set_last_reward_time(pid, now);
+    }
+
+    fn mass_update_pools(&mut self) {
+        let pool_length: U256 = data::get_pool_length();
+        let mut pid: U256 = U256::zero();
+        while pid < pool_length {
+            self.update_pool(pid);
+            pid += U256::one();
+        }
+    }
+
+    /// Rewards `user` would get from `harvest` on `pid` at the current block time.
+    fn pending_reward(&mut self, pid: U256, user: Key) -> U256 {
+        self.assert_pool_exists(pid);
+        let now: u64 = runtime::get_blocktime().into();
+        let acc_reward_per_share: U256 = self.acc_reward_per_share_at(pid, now);
+        self.accrued_rewards(pid, user, acc_reward_per_share)
+    }
+
+    fn staked_amount(&mut self, pid: U256, user: Key) -> U256 {
+        Stakes::instance().amount(pid, &user)
+    }
+
+    fn pool_length(&mut self) -> U256 {
+        data::get_pool_length()
+    }
+
+    fn total_alloc_point(&mut self) -> U256 {
+        data::get_total_alloc_point()
+    }
+
+    fn reward_token(&mut self) -> Key {
+        data::get_reward_token()
+    }
+
+    fn reward_per_second(&mut self) -> U256 {
+        data::get_reward_per_second()
+    }
+
+    /// `acc_reward_per_share` of `pid` as it would be after accruing up to `now`. Block
+    /// time is in milliseconds while the reward rate is per second.
+    fn acc_reward_per_share_at(&mut self, pid: U256, now: u64) -> U256 {
+        let pools: Pools = Pools::instance();
+        let acc_reward_per_share: U256 = pools.acc_reward_per_share(pid);
+        let last_reward_time: u64 = pools.last_reward_time(pid);
+        let total_staked: U256 = pools.total_staked(pid);
+        let total_alloc_point: U256 = data::get_total_alloc_point();
+        if now <= last_reward_time
+            || total_staked == U256::zero()
+            || total_alloc_point == U256::zero()
+        {
+            return acc_reward_per_share;
+        }
+        let reward: U256 = data::get_reward_per_second()
+            .checked_mul(U256::from(now - last_reward_time))
+            .and_then(|reward| reward.checked_mul(pools.alloc_point(pid)))
+            .ok_or(StakingError::StakingOverFlow)
+            .unwrap_or_revert()
+            / total_alloc_point
+            / 1000;
+        acc_reward_per_share
+            .checked_add(
+                reward
+                    .checked_mul(ACC_REWARD_PRECISION.into())
+                    .ok_or(StakingError::StakingOverFlow)
+                    .unwrap_or_revert()
+                    / total_staked,
+            )
+            .ok_or(StakingError::StakingOverFlow)
+            .unwrap_or_revert()
+    }
+
+    fn accrued_rewards(&mut self, pid: U256, user: Key, acc_reward_per_share: U256) -> U256 {
+        let stakes: Stakes = Stakes::instance();
+        reward_debt(stakes.amount(pid, &user), acc_reward_per_share)
+            .checked_sub(stakes.reward_debt(pid, &user))
+            .ok_or(StakingError::StakingUnderFlow)
+            .unwrap_or_revert()
+            .checked_add(stakes.rewards_owed(pid, &user))
+            .ok_or(StakingError::StakingOverFlow)
+            .unwrap_or_revert()
+    }
+
+    /// Pays `user` the rewards accrued in `pid`, which must have just been updated. The
+    /// part that the reward balance of this contract can not cover is kept in
+    /// `rewards_owed` and paid by a later call. The caller resets `reward_debt`.
+    fn settle_rewards(&mut self, pid: U256, user: Key) {
+        let accrued: U256 =
+            self.accrued_rewards(pid, user, Pools::instance().acc_reward_per_share(pid));
+        if accrued == U256::zero() {
+            return;
+        }
+        let reward_token_hash: ContractHash =
+            key_to_contract_hash(data::get_reward_token()).unwrap_or_revert();
+        let balance: U256 = runtime::call_contract(
+            reward_token_hash,
+            "balance_of",
+            runtime_args! {"owner" => Key::from(data::get_package_hash())},
+        );
+        let paid: U256 = core::cmp::min(accrued, balance);
+        Stakes::instance().set_rewards_owed(pid, &user, accrued - paid);
+        if paid > U256::zero() {
+            let ret: Result<(), u32> = runtime::call_contract(
+                reward_token_hash,
+                "transfer",
+                runtime_args! {"recipient" => user, "amount" => paid},
+            );
+            ret.unwrap_or_revert();
+            self.emit(&STAKINGEvent::Harvest {
+                user: user,
+                pid: pid,
+                amount: paid,
+            });
+        }
+    }
+
+    fn transfer_lp_token(&mut self, pid: U256, to: Key, amount: U256) {
+        if amount == U256::zero() {
+            return;
+        }
+        let ret: Result<(), u32> = runtime::call_contract(
+            key_to_contract_hash(Pools::instance().lp_token(pid)).unwrap_or_revert(),
+            "transfer",
+            runtime_args! {"recipient" => to, "amount" => amount},
+        );
+        ret.unwrap_or_revert();
+    }
+
+    fn assert_pool_exists(&mut self, pid: U256) {
+        if pid >= data::get_pool_length() {
+            runtime::revert(StakingError::StakingInvalidPool);
+        }
+    }
+
+    fn emit(&mut self, staking_event: &STAKINGEvent) {
+        let mut events = Vec::new();
+        let package = data::get_package_hash();
+        match staking_event {
+            STAKINGEvent::PoolAdded {
+                pid,
+                lp_token,
+                alloc_point,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", staking_event.type_name());
+                event.insert("pid", pid.to_string());
+                event.insert("lp_token", lp_token.to_string());
+                event.insert("alloc_point", alloc_point.to_string());
+                events.push(event);
+            }
+            STAKINGEvent::PoolAllocUpdated { pid, alloc_point } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", staking_event.type_name());
+                event.insert("pid", pid.to_string());
+                event.insert("alloc_point", alloc_point.to_string());
+                events.push(event);
+            }
+            STAKINGEvent::Deposit { user, pid, amount }
+            | STAKINGEvent::Withdraw { user, pid, amount }
+            | STAKINGEvent::Harvest { user, pid, amount }
+            | STAKINGEvent::EmergencyWithdraw { user, pid, amount } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", staking_event.type_name());
+                event.insert("user", user.to_string());
+                event.insert("pid", pid.to_string());
+                event.insert("amount", amount.to_string());
+                events.push(event);
+            }
+        };
+
+        for event in events {
+            let _: URef = storage::new_uref(event);
+        }
+    }
+
+    fn get_package_hash(&mut self) -> ContractPackageHash {
+        data::get_package_hash()
+    }
+}
+
+fn reward_debt(amount: U256, acc_reward_per_share: U256) -> U256 {
+    amount
+        .checked_mul(acc_reward_per_share)
+        .ok_or(StakingError::StakingOverFlow)
+        .unwrap_or_revert()
+        / U256::from(ACC_REWARD_PRECISION)
+}
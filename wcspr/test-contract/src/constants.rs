@@ -28,6 +28,8 @@ pub const TRANSFER_TEST_RESULT_KEY_NAME: &str = "transfer_test_result";
 pub const TRANSFER_FROM_TEST_RESULT_KEY_NAME: &str = "transfer_from_test_result";
 pub const ALLOWANCE_KEY_NAME: &str = "allowance";
 pub const BALANCE_OF_KEY_NAME: &str = "balance_of";
+pub const SKIM_TEST_RESULT_KEY_NAME: &str = "skim_test_result";
+pub const BACKING_RATIO_KEY_NAME: &str = "backing_ratio";
 
 /// Name of `name` entry point.
 pub const NAME_ENTRY_POINT_NAME: &str = "name";
@@ -51,6 +53,18 @@ pub const TOTAL_SUPPLY_ENTRY_POINT_NAME: &str = "total_supply";
 pub const DEPOSIT_ENTRY_POINT_NAME: &str = "deposit";
 /// Name of `withdraw` entry point.
 pub const WITHDRAW_ENTRY_POINT_NAME: &str = "withdraw";
+/// Name of `deposit_to` entry point.
+pub const DEPOSIT_TO_ENTRY_POINT_NAME: &str = "deposit_to";
+/// Name of `withdraw_from` entry point.
+pub const WITHDRAW_FROM_ENTRY_POINT_NAME: &str = "withdraw_from";
+/// Name of `skim` entry point.
+pub const SKIM_ENTRY_POINT_NAME: &str = "skim";
+/// Name of `purse` entry point.
+pub const PURSE_ENTRY_POINT_NAME: &str = "purse";
+/// Name of `send_cspr` entry point.
+pub const SEND_CSPR_ENTRY_POINT_NAME: &str = "send_cspr";
+/// Name of `backing_ratio` entry point.
+pub const BACKING_RATIO_ENTRY_POINT_NAME: &str = "backing_ratio";
 
 /// Name of `address` runtime argument.
 pub const ADDRESS_RUNTIME_ARG_NAME: &str = "address";
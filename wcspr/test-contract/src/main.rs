@@ -79,6 +79,110 @@ fn withdraw() {
     set_key(WITHDRAW_TEST_RESULT_KEY_NAME, ret);
 }
 
+#[no_mangle]
+fn deposit_to() {
+    let recipient: Key = runtime::get_named_arg("recipient");
+    let amount: U512 = runtime::get_named_arg("amount");
+    let purse: URef = runtime::get_named_arg("purse");
+    let ret: Result<(), u32> = runtime::call_contract(
+        get_key(&WCSPR_HASH_KEY_NAME),
+        DEPOSIT_TO_ENTRY_POINT_NAME,
+        runtime_args! {
+            RECIPIENT_RUNTIME_ARG_NAME => recipient,
+            PURSE_RUNTIME_ARG_NAME => purse,
+            AMOUNT_RUNTIME_ARG_NAME => amount
+        },
+    );
+
+    set_key(DEPOSIT_TEST_RESULT_KEY_NAME, ret);
+}
+
+#[no_mangle]
+fn deposit_to_session() {
+    let recipient: Key = runtime::get_named_arg("recipient");
+    let amount: U512 = runtime::get_named_arg("amount");
+    let proxy_hash: Key = runtime::get_named_arg("proxy_hash");
+    let purse: URef = account::get_main_purse();
+
+    let () = runtime::call_contract(
+        _create_hash_from_key(proxy_hash),
+        DEPOSIT_TO_ENTRY_POINT_NAME,
+        runtime_args! {
+            RECIPIENT_RUNTIME_ARG_NAME => recipient,
+            PURSE_RUNTIME_ARG_NAME => purse,
+            AMOUNT_RUNTIME_ARG_NAME => amount
+        },
+    );
+}
+
+#[no_mangle]
+fn withdraw_from() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let to: URef = system::create_purse();
+    let amount: U512 = runtime::get_named_arg("amount");
+    let wcspr_hash: ContractHash = get_key(&WCSPR_HASH_KEY_NAME);
+    let ret: Result<(), u32> = runtime::call_contract(
+        wcspr_hash,
+        WITHDRAW_FROM_ENTRY_POINT_NAME,
+        runtime_args! {
+            OWNER_RUNTIME_ARG_NAME => owner,
+            TO_PURSE_RUNTIME_ARG_NAME => to,
+            AMOUNT_RUNTIME_ARG_NAME => amount
+        },
+    );
+    set_key(WITHDRAW_TEST_RESULT_KEY_NAME, ret);
+}
+
+#[no_mangle]
+fn skim() {
+    let to: URef = system::create_purse();
+    let wcspr_hash: ContractHash = get_key(&WCSPR_HASH_KEY_NAME);
+    let ret: Result<(), u32> = runtime::call_contract(
+        wcspr_hash,
+        SKIM_ENTRY_POINT_NAME,
+        runtime_args! {
+            TO_PURSE_RUNTIME_ARG_NAME => to
+        },
+    );
+    set_key(SKIM_TEST_RESULT_KEY_NAME, ret);
+}
+
+#[no_mangle]
+fn send_cspr() {
+    let amount: U512 = runtime::get_named_arg("amount");
+    let purse: URef = runtime::get_named_arg("purse");
+    let wcspr_purse: URef = runtime::call_contract(
+        get_key(&WCSPR_HASH_KEY_NAME),
+        PURSE_ENTRY_POINT_NAME,
+        runtime_args! {},
+    );
+    system::transfer_from_purse_to_purse(purse, wcspr_purse, amount, None).unwrap_or_revert();
+}
+
+#[no_mangle]
+fn send_cspr_session() {
+    let amount: U512 = runtime::get_named_arg("amount");
+    let proxy_hash: Key = runtime::get_named_arg("proxy_hash");
+    let purse: URef = account::get_main_purse();
+
+    let () = runtime::call_contract(
+        _create_hash_from_key(proxy_hash),
+        SEND_CSPR_ENTRY_POINT_NAME,
+        runtime_args! {
+            PURSE_RUNTIME_ARG_NAME=> purse,
+            AMOUNT_RUNTIME_ARG_NAME=> amount
+        },
+    );
+}
+
+#[no_mangle]
+fn backing_ratio() {
+    let wcspr_hash: ContractHash = get_key(&WCSPR_HASH_KEY_NAME);
+    let ret: U256 =
+        runtime::call_contract(wcspr_hash, BACKING_RATIO_ENTRY_POINT_NAME, runtime_args! {});
+    set_key(BACKING_RATIO_KEY_NAME, ret);
+}
+
 #[no_mangle]
 fn transfer() {
     let recipient: Key = runtime::get_named_arg(RECIPIENT_RUNTIME_ARG_NAME);
@@ -231,6 +335,72 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Session,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "deposit_to",
+        vec![
+            Parameter::new("recipient", Key::cl_type()),
+            Parameter::new("amount", U512::cl_type()),
+            Parameter::new("purse", URef::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "deposit_to_session",
+        vec![
+            Parameter::new("recipient", Key::cl_type()),
+            Parameter::new("amount", U512::cl_type()),
+            Parameter::new("proxy_hash", Key::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Session,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "withdraw_from",
+        vec![
+            Parameter::new("owner", Key::cl_type()),
+            Parameter::new("amount", U512::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "skim",
+        vec![],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "send_cspr",
+        vec![
+            Parameter::new("amount", U512::cl_type()),
+            Parameter::new("purse", URef::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "send_cspr_session",
+        vec![
+            Parameter::new("amount", U512::cl_type()),
+            Parameter::new("proxy_hash", Key::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Session,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "backing_ratio",
+        vec![],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "allowance",
         vec![
@@ -2,7 +2,8 @@ use casper_contract::{
     contract_api::{runtime, storage},
     unwrap_or_revert::UnwrapOrRevert,
 };
-use casper_types::{ApiError, Key};
+use casper_types::Key;
+use errors::CommonError;
 
 use crate::{ContractContext, ContractStorage, Dict};
 
@@ -30,7 +31,7 @@ pub trait AdminControl<Storage: ContractStorage>: ContractContext<Storage> {
     fn assert_caller_is_admin(&self) {
         let caller = self.get_caller();
         if !Admins::instance().is_admin(&caller) {
-            runtime::revert(ApiError::User(20));
+            runtime::revert(CommonError::NotAdmin);
         }
     }
 }
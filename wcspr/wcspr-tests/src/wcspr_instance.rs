@@ -134,6 +134,36 @@ impl WCSPRInstance {
             .unwrap_or_default()
     }
 
+    pub fn nonce<T: Into<Key>>(&self, account: T) -> U256 {
+        self.0
+            .query_dictionary("nonces", key_to_str(&account.into()))
+            .unwrap_or_default()
+    }
+
+    pub fn permit<T: Into<Key>>(
+        &self,
+        sender: Sender,
+        public_key: &str,
+        signature: &str,
+        owner: T,
+        spender: T,
+        value: U256,
+        deadline: u64,
+    ) {
+        self.0.call_contract(
+            sender,
+            "permit",
+            runtime_args! {
+                "public" => public_key,
+                "signature" => signature,
+                "owner" => owner.into(),
+                "spender" => spender.into(),
+                "value" => value,
+                "deadline" => deadline
+            },
+        );
+    }
+
     pub fn allowance<T: Into<Key>>(&self, owner: T, spender: T) -> U256 {
         let owner: Key = owner.into();
         let spender: Key = spender.into();
@@ -175,6 +205,54 @@ impl WCSPRInstance {
         );
     }
 
+    pub fn deposit_to<T: Into<Key>>(
+        &self,
+        sender: Sender,
+        recipient: T,
+        amount: U512,
+        proxy: Key,
+    ) {
+        self.0.call_contract(
+            sender,
+            "deposit_to_session",
+            runtime_args! {
+                "recipient"=>recipient.into(),
+                "amount"=>amount,
+                "proxy_hash"=>proxy
+            },
+        );
+    }
+
+    pub fn withdraw_from<T: Into<Key>>(&self, sender: Sender, owner: T, amount: U512) {
+        self.0.call_contract(
+            sender,
+            "withdraw_from",
+            runtime_args! {
+                "owner"=>owner.into(),
+                "amount"=>amount
+            },
+        );
+    }
+
+    pub fn skim(&self, sender: Sender) {
+        self.0.call_contract(sender, "skim", runtime_args! {});
+    }
+
+    pub fn send_cspr(&self, sender: Sender, amount: U512, proxy: Key) {
+        self.0.call_contract(
+            sender,
+            "send_cspr_session",
+            runtime_args! {
+                "amount"=>amount,
+                "proxy_hash"=>proxy
+            },
+        );
+    }
+
+    pub fn backing_ratio(&self, sender: Sender) {
+        self.0.call_contract(sender, "backing_ratio", runtime_args! {});
+    }
+
     // pub fn deposit(&self, sender: Sender, amount:U512, purse: URef) {
     //     self.0.call_contract(sender,"deposit", runtime_args!{
     //         "amount"=>amount,
@@ -190,6 +268,18 @@ impl WCSPRInstance {
         self.0.query_named_key(String::from("symbol"))
     }
 
+    pub fn domain_separator(&self) -> String {
+        self.0.query_named_key(String::from("domain_separator"))
+    }
+
+    pub fn permit_type_hash(&self) -> String {
+        self.0.query_named_key(String::from("permit_type_hash"))
+    }
+
+    pub fn total_supply(&self) -> U256 {
+        self.0.query_named_key(String::from("total_supply"))
+    }
+
     // Result methods
     pub fn transfer_result(&self) -> Result<(), u32> {
         self.0
@@ -223,6 +313,14 @@ impl WCSPRInstance {
         self.0
             .query_named_key(WITHDRAW_TEST_RESULT_KEY_NAME.to_string())
     }
+    pub fn skim_result(&self) -> Result<(), u32> {
+        self.0.query_named_key(SKIM_TEST_RESULT_KEY_NAME.to_string())
+    }
+
+    pub fn backing_ratio_result(&self) -> U256 {
+        self.0.query_named_key(BACKING_RATIO_KEY_NAME.to_string())
+    }
+
     pub fn allowance_res(&self) -> U256 {
         self.0.query_named_key("allowance".to_string())
     }
@@ -1,11 +1,15 @@
 use crate::wcspr_instance::WCSPRInstance;
 use casper_engine_test_support::AccountHash;
 use casper_types::{Key, U256, U512};
+use cryptoxide::ed25519;
+use errors::CommonError;
+use renvm_sig::{hash_message, keccak256};
 use test_env::{Sender, TestContract, TestEnv};
 
 const NAME: &str = "Wrapped_Casper";
 const SYMBOL: &str = "WCSPR";
 const DECIMALS: u8 = 10;
+const BACKING_RATIO_PRECISION: u64 = 1_000_000_000;
 pub const DEPOSIT_TEST_RESULT_KEY_NAME: &str = "deposit_test_result";
 pub const WITHDRAW_TEST_RESULT_KEY_NAME: &str = "withdraw_test_result";
 pub const TRANSFER_TEST_RESULT_KEY_NAME: &str = "transfer_test_result";
@@ -122,6 +126,169 @@ fn test_wcspr_withdraw() {
     assert_eq!(token.balance_of(proxy_package_hash), new_proxy_balance);
 }
 
+#[test]
+fn test_wcspr_deposit_above_u128() {
+    let (_env, token, proxy, _, owner) = deploy();
+    let proxy_contract_hash = proxy.contract_hash_result();
+    let package_hash = proxy.package_hash_result();
+    // one more than u128::MAX used to be truncated to zero
+    let amount: U512 = U512::from(u128::MAX) + 1;
+
+    proxy.deposit(Sender(owner), amount, Key::from(proxy_contract_hash));
+    let res: Result<(), u32> = proxy.deposit_result();
+
+    assert_eq!(res, Err(CommonError::InsufficientBalance.into()));
+    assert_eq!(token.balance_of(package_hash), 0.into());
+}
+
+#[test]
+fn test_wcspr_withdraw_above_u128() {
+    let (_env, token, proxy, _, owner) = deploy();
+    let proxy_contract_hash = proxy.contract_hash_result();
+    let package_hash = proxy.package_hash_result();
+
+    proxy.deposit(Sender(owner), 10.into(), Key::from(proxy_contract_hash));
+    proxy.withdraw(Sender(owner), U512::from(u128::MAX) + 1);
+    let res: Result<(), u32> = proxy.withdraw_result();
+
+    assert_eq!(res, Err(CommonError::InsufficientBalance.into()));
+    assert_eq!(token.balance_of(package_hash), 10.into());
+}
+
+#[test]
+#[should_panic]
+fn test_wcspr_withdraw_above_u256() {
+    let (_env, _token, proxy, _, owner) = deploy();
+    let proxy_contract_hash = proxy.contract_hash_result();
+
+    proxy.deposit(Sender(owner), 10.into(), Key::from(proxy_contract_hash));
+    // 2^256 does not fit in a token balance
+    proxy.withdraw(Sender(owner), U512::one() << 256);
+}
+
+#[test]
+fn test_wcspr_deposit_to() {
+    let (env, token, proxy, _, owner) = deploy();
+    let proxy_contract_hash = proxy.contract_hash_result();
+    let package_hash = proxy.package_hash_result();
+    let user = env.next_user();
+    let amount: U512 = 200.into();
+
+    proxy.deposit_to(Sender(owner), user, amount, Key::from(proxy_contract_hash));
+    let res: Result<(), u32> = proxy.deposit_result();
+
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(token.balance_of(user), U256::from(200));
+    assert_eq!(token.balance_of(package_hash), 0.into());
+}
+
+#[test]
+fn test_wcspr_withdraw_from() {
+    let (_env, token, proxy, proxy2, owner) = deploy();
+    let proxy_contract_hash = proxy.contract_hash_result();
+    let package_hash = proxy.package_hash_result();
+    let package_hash2 = proxy2.package_hash_result();
+    let deposit_amount = 50;
+    let allowance: U256 = 10.into();
+
+    // proxy wraps cspr on behalf of proxy2
+    proxy.deposit_to(
+        Sender(owner),
+        package_hash2,
+        deposit_amount.into(),
+        Key::from(proxy_contract_hash),
+    );
+    assert_eq!(token.balance_of(package_hash2), deposit_amount.into());
+
+    proxy2.approve(Sender(owner), package_hash, allowance);
+    proxy.withdraw_from(Sender(owner), package_hash2, U512::from(10));
+    let res: Result<(), u32> = proxy.withdraw_result();
+
+    assert_eq!(res.is_ok(), true);
+    assert_eq!(
+        token.balance_of(package_hash2),
+        U256::from(deposit_amount - 10)
+    );
+    assert_eq!(
+        token.allowance(Key::from(package_hash2), Key::from(package_hash)),
+        0.into()
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_wcspr_withdraw_from_without_allowance() {
+    let (_env, _token, proxy, proxy2, owner) = deploy();
+    let proxy_contract_hash = proxy.contract_hash_result();
+    let package_hash2 = proxy2.package_hash_result();
+
+    proxy.deposit_to(
+        Sender(owner),
+        package_hash2,
+        50.into(),
+        Key::from(proxy_contract_hash),
+    );
+    proxy.withdraw_from(Sender(owner), package_hash2, U512::from(10));
+}
+
+#[test]
+fn test_wcspr_backing_ratio() {
+    let (_env, _token, proxy, _, owner) = deploy();
+    let proxy_contract_hash = proxy.contract_hash_result();
+
+    // nothing minted yet counts as fully backed
+    proxy.backing_ratio(Sender(owner));
+    assert_eq!(proxy.backing_ratio_result(), BACKING_RATIO_PRECISION.into());
+
+    proxy.deposit(Sender(owner), 200.into(), Key::from(proxy_contract_hash));
+    proxy.withdraw(Sender(owner), 50.into());
+    proxy.backing_ratio(Sender(owner));
+    assert_eq!(proxy.backing_ratio_result(), BACKING_RATIO_PRECISION.into());
+}
+
+#[test]
+fn test_wcspr_skim_without_excess() {
+    let (_env, token, proxy, _, owner) = deploy();
+    let proxy_contract_hash = proxy.contract_hash_result();
+    let package_hash = proxy.package_hash_result();
+
+    proxy.deposit(Sender(owner), 200.into(), Key::from(proxy_contract_hash));
+    proxy.skim(Sender(owner));
+    let res: Result<(), u32> = proxy.skim_result();
+
+    assert_eq!(res, Err(CommonError::ZeroAmount.into()));
+    assert_eq!(token.balance_of(package_hash), 200.into());
+}
+
+#[test]
+fn test_wcspr_skim() {
+    let (_env, token, proxy, _, owner) = deploy();
+    let proxy_contract_hash = proxy.contract_hash_result();
+    let package_hash = proxy.package_hash_result();
+
+    proxy.deposit(Sender(owner), 200.into(), Key::from(proxy_contract_hash));
+    // cspr sent straight to the contract purse mints nothing
+    proxy.send_cspr(Sender(owner), 50.into(), Key::from(proxy_contract_hash));
+    proxy.backing_ratio(Sender(owner));
+    assert_eq!(
+        proxy.backing_ratio_result(),
+        U256::from(BACKING_RATIO_PRECISION) * 250 / 200
+    );
+
+    proxy.skim(Sender(owner));
+    let res: Result<(), u32> = proxy.skim_result();
+    assert_eq!(res, Ok(()));
+    proxy.backing_ratio(Sender(owner));
+    assert_eq!(proxy.backing_ratio_result(), BACKING_RATIO_PRECISION.into());
+    assert_eq!(token.balance_of(package_hash), 200.into());
+    assert_eq!(token.total_supply(), 200.into());
+
+    // the excess is gone, the holders can still withdraw everything
+    proxy.withdraw(Sender(owner), 200.into());
+    assert_eq!(proxy.withdraw_result(), Ok(()));
+    assert_eq!(token.total_supply(), 0.into());
+}
+
 #[test]
 fn test_wcspr_transfer() {
     let (env, token, proxy, _, owner) = deploy();
@@ -183,6 +350,77 @@ fn test_wcspr_approve() {
     assert_eq!(token.allowance(user, owner), 0.into());
 }
 
+#[test]
+fn test_wcspr_nonce() {
+    let (env, token, _proxy, _, owner) = deploy();
+    let user = env.next_user();
+    assert_eq!(token.nonce(owner), 0.into());
+    assert_eq!(token.nonce(user), 0.into());
+}
+
+fn bytes_to_str(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| byte.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+#[test]
+fn test_wcspr_permit() {
+    let (env, token, _proxy, _, owner) = deploy();
+    let user = env.next_user();
+    // the owner is the first test account, created from the secret key [9; 32]
+    let (secret_key, public_key) = ed25519::keypair(&[9u8; 32]);
+    let value: U256 = 10.into();
+    let deadline: u64 = 4_102_444_800;
+    let nonce: U256 = token.nonce(owner);
+
+    let data: String = format!(
+        "{}{}{}{}{}{}",
+        token.permit_type_hash(),
+        Key::from(owner),
+        Key::from(user),
+        value,
+        nonce,
+        deadline
+    );
+    let hash: String = hex::encode(keccak256(data.as_bytes()));
+    let digest: [u8; 32] = hash_message(format!("{}{}", token.domain_separator(), hash));
+    let signature = ed25519::signature(&digest, &secret_key);
+
+    token.permit(
+        Sender(owner),
+        &bytes_to_str(&public_key),
+        &bytes_to_str(&signature),
+        owner,
+        user,
+        value,
+        deadline,
+    );
+    assert_eq!(token.allowance(owner, user), value);
+    assert_eq!(token.nonce(owner), nonce + 1);
+}
+
+#[test]
+#[should_panic]
+fn test_wcspr_permit_invalid_signature() {
+    let (env, token, _proxy, _, owner) = deploy();
+    let user = env.next_user();
+    let public_key: String = vec!["1"; 32].join(",");
+    let signature: String = vec!["2"; 64].join(",");
+    let deadline: u64 = 4_102_444_800;
+    token.permit(
+        Sender(owner),
+        &public_key,
+        &signature,
+        owner,
+        user,
+        10.into(),
+        deadline,
+    );
+}
+
 #[test]
 fn test_wcspr_transfer_from() {
     let (env, token, proxy, proxy2, owner) = deploy();
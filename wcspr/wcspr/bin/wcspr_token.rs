@@ -32,6 +32,8 @@ impl Token {
         name: String,
         symbol: String,
         decimals: u8,
+        domain_separator: String,
+        permit_type_hash: String,
         contract_hash: ContractHash,
         package_hash: ContractPackageHash,
         purse: URef,
@@ -41,6 +43,8 @@ impl Token {
             name,
             symbol,
             decimals,
+            domain_separator,
+            permit_type_hash,
             Key::from(contract_hash),
             package_hash,
             purse,
@@ -53,10 +57,21 @@ fn constructor() {
     let name: String = runtime::get_named_arg("name");
     let symbol: String = runtime::get_named_arg("symbol");
     let decimals: u8 = runtime::get_named_arg("decimals");
+    let domain_separator: String = runtime::get_named_arg("domain_separator");
+    let permit_type_hash: String = runtime::get_named_arg("permit_type_hash");
     let contract_hash: ContractHash = runtime::get_named_arg("contract_hash");
     let package_hash: ContractPackageHash = runtime::get_named_arg("package_hash");
     let purse: URef = runtime::get_named_arg("purse");
-    Token::default().constructor(name, symbol, decimals, contract_hash, package_hash, purse);
+    Token::default().constructor(
+        name,
+        symbol,
+        decimals,
+        domain_separator,
+        permit_type_hash,
+        contract_hash,
+        package_hash,
+        purse,
+    );
 }
 
 /// This function is to transfer tokens against the address that user provided
@@ -167,6 +182,26 @@ fn deposit_no_return() {
     let _ret = Token::default().deposit(amount, purse);
 }
 
+/// This function is to deposit token against the recipient that user provided, so a router can wrap cspr for its users
+///
+/// # Parameters
+///
+/// * `recipient` - A Key that holds the account address of the user who receives the minted tokens
+///
+/// * `purse` - A URef that holds the purse to take cspr from
+///
+/// * `amount` - A U512 that holds the amount for deposit
+///
+
+#[no_mangle]
+fn deposit_to() {
+    let recipient: Key = runtime::get_named_arg("recipient");
+    let purse: URef = runtime::get_named_arg("purse");
+    let amount: U512 = runtime::get_named_arg("amount");
+    let ret = Token::default().deposit_to(recipient, purse, amount);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 /// This function is to withdraw token against the address that user provided
 ///
 /// # Parameters
@@ -184,6 +219,51 @@ fn withdraw() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
+/// This function is to withdraw token of an owner that has approved the caller before
+///
+/// # Parameters
+///
+/// * `owner` - A Key that holds the account address of the user whose tokens are burnt
+///
+/// * `to_purse` - A URef that holds the purse which receives the cspr
+///
+/// * `amount` - A U512 that holds the amount for withdraw
+///
+
+#[no_mangle]
+fn withdraw_from() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let to: URef = runtime::get_named_arg("to_purse");
+    let amount: U512 = runtime::get_named_arg("amount");
+    let ret = Token::default().withdraw_from(owner, to, amount);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to send the cspr held above total supply to the purse that user provided,
+/// anyone can call it since that cspr backs no token
+///
+/// # Parameters
+///
+/// * `to_purse` - A URef that holds the purse which receives the excess cspr
+///
+
+#[no_mangle]
+fn skim() {
+    let to: URef = runtime::get_named_arg("to_purse");
+    let ret = Token::default().skim(to);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the Backing Ratio of contract purse balance to total supply,
+/// 1,000,000,000 means the tokens are backed 1:1
+///
+
+#[no_mangle]
+fn backing_ratio() {
+    let ret: U256 = Token::default().backing_ratio();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 /// This function is to return the Balance of owner against the address that user provided
 ///
 /// # Parameters
@@ -198,6 +278,49 @@ fn balance_of() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
+/// This function is to return the Nonce of owner against the address that user provided
+///
+/// # Parameters
+///
+/// * `owner` - A Key that holds the account address of the user against which user wants to get nonce
+///
+
+#[no_mangle]
+fn nonce() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let ret: U256 = Token::default().nonce(owner);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to get signer and verify if it is equal
+/// to the owner or not then call approve, so that the owner does not have to pay for the approval
+///
+/// # Parameters
+///
+/// * `public` - A string slice that holds the public key of the meta transaction signer,  Subscriber have to get it from running cryptoxide project externally.
+///
+/// * `signature` - A string slice that holds the signature of the meta transaction,  Subscriber have to get it from running cryptoxide project externally.
+///
+/// * `owner` - A Key that holds the account address of the owner
+///
+/// * `spender` - A Key that holds the account address of the spender
+///
+/// * `value` - A U256 that holds the value
+///
+/// * `deadline` - A u64 that holds the deadline limit
+///
+
+#[no_mangle]
+fn permit() {
+    let public_key: String = runtime::get_named_arg("public");
+    let signature: String = runtime::get_named_arg("signature");
+    let owner: Key = runtime::get_named_arg("owner");
+    let spender: Key = runtime::get_named_arg("spender");
+    let value: U256 = runtime::get_named_arg("value");
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    Token::default().permit(public_key, signature, owner, spender, value, deadline);
+}
+
 /// This function is to return the Name of contract
 ///
 
@@ -216,6 +339,15 @@ fn symbol() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
 }
 
+/// This function is to return the Decimals of contract
+///
+
+#[no_mangle]
+fn decimals() {
+    let ret: u8 = Token::default().decimals();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
 /// This function is to return the Package Hash of contract
 ///
 
@@ -293,6 +425,8 @@ fn get_entry_points() -> EntryPoints {
             Parameter::new("name", String::cl_type()),
             Parameter::new("symbol", String::cl_type()),
             Parameter::new("decimals", u8::cl_type()),
+            Parameter::new("domain_separator", String::cl_type()),
+            Parameter::new("permit_type_hash", String::cl_type()),
             Parameter::new("contract_hash", ContractHash::cl_type()),
             Parameter::new("package_hash", ContractPackageHash::cl_type()),
             Parameter::new("purse", URef::cl_type()),
@@ -345,6 +479,27 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "nonce",
+        vec![Parameter::new("owner", Key::cl_type())],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "permit",
+        vec![
+            Parameter::new("public", String::cl_type()),
+            Parameter::new("signature", String::cl_type()),
+            Parameter::new("owner", Key::cl_type()),
+            Parameter::new("spender", Key::cl_type()),
+            Parameter::new("value", U256::cl_type()),
+            Parameter::new("deadline", u64::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "allowance",
         vec![
@@ -394,6 +549,51 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "deposit_to",
+        vec![
+            Parameter::new("recipient", Key::cl_type()),
+            Parameter::new("purse", URef::cl_type()),
+            Parameter::new("amount", U512::cl_type()),
+        ],
+        CLType::Result {
+            ok: Box::new(CLType::Unit),
+            err: Box::new(CLType::U32),
+        },
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "withdraw_from",
+        vec![
+            Parameter::new("owner", Key::cl_type()),
+            Parameter::new("to_purse", URef::cl_type()),
+            Parameter::new("amount", U512::cl_type()),
+        ],
+        CLType::Result {
+            ok: Box::new(CLType::Unit),
+            err: Box::new(CLType::U32),
+        },
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "skim",
+        vec![Parameter::new("to_purse", URef::cl_type())],
+        CLType::Result {
+            ok: Box::new(CLType::Unit),
+            err: Box::new(CLType::U32),
+        },
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "backing_ratio",
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "name",
         vec![],
@@ -408,6 +608,13 @@ fn get_entry_points() -> EntryPoints {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "decimals",
+        vec![],
+        u8::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
     entry_points.add_entry_point(EntryPoint::new(
         "purse",
         vec![],
@@ -469,12 +676,16 @@ fn call() {
         let symbol: String = runtime::get_named_arg("symbol");
         let decimals: u8 = runtime::get_named_arg("decimals");
         let purse: URef = system::create_purse();
+        let (domain_separator, permit_type_hash) =
+            Token::default().get_permit_type_and_domain_separator(&name, contract_hash);
 
         // Prepare constructor args
         let constructor_args = runtime_args! {
             "name" => name,
             "symbol" => symbol,
             "decimals" => decimals,
+            "domain_separator" => domain_separator,
+            "permit_type_hash" => permit_type_hash,
             "contract_hash" => contract_hash,
             "package_hash"=> package_hash,
             "purse" => purse
@@ -2,8 +2,9 @@ use crate::alloc::string::ToString;
 use alloc::string::String;
 use casper_contract::contract_api::runtime;
 use casper_contract::unwrap_or_revert::UnwrapOrRevert;
-use casper_types::{ApiError, ContractPackageHash, Key, URef, U256, U512};
+use casper_types::{ContractPackageHash, Key, URef, U256, U512};
 use contract_utils::{get_key, set_key, Dict};
+use errors::CommonError;
 //use casper_contract::{value::account::PurseId ,contract_api::{runtime,system}, unwrap_or_revert::UnwrapOrRevert};
 
 
@@ -25,11 +26,20 @@ pub enum WcsprEvents {
     },
 
     Deposit {
+        sender: Key,
+        recipient: Key,
         src_purse: URef,
         amount: U512
     },
 
     Withdraw {
+        sender: Key,
+        owner: Key,
+        recipient_purse: URef,
+        amount: U512
+    },
+
+    Skim {
         recipient_purse: URef,
         amount: U512
     }
@@ -51,14 +61,23 @@ impl WcsprEvents {
             } => "erc20_transfer",
 
             WcsprEvents::Deposit {
+                sender: _,
+                recipient: _,
                 src_purse: _,
                 amount: _
             } => "deposit",
             
             WcsprEvents::Withdraw {
+                sender: _,
+                owner: _,
                 recipient_purse: _,
                 amount: _
-            } => "withdraw"
+            } => "withdraw",
+
+            WcsprEvents::Skim {
+                recipient_purse: _,
+                amount: _
+            } => "skim"
         }.to_string()
     }
 }
@@ -68,6 +87,7 @@ impl WcsprEvents {
 
 pub const BALANCES_DICT: &str = "balances";
 pub const ALLOWANCES_DICT: &str = "allowances";
+pub const NONCES_DICT: &str = "nonces";
 pub const NAME: &str = "name";
 pub const SYMBOL: &str = "symbol";
 pub const SELF_CONTRACT_HASH: &str = "self_contract_hash";
@@ -75,13 +95,10 @@ pub const SELF_PURSE: &str = "self_purse";
 pub const DECIMALS: &str = "decimals";
 pub const CONTRACT_PACKAGE_HASH: &str = "contract_package_hash";
 pub const TOTAL_SUPPLY: &str = "total_supply";
+pub const DOMAIN_SEPARATOR: &str = "domain_separator";
+pub const PERMIT_TYPE_HASH: &str = "permit_type_hash";
 
 
-#[repr(u16)]
-pub enum ErrorCodes {
-    Abort = 35,
-}
-
 pub struct Balances {
     dict: Dict,
 }
@@ -106,6 +123,30 @@ impl Balances {
     }
 }
 
+pub struct Nonces {
+    dict: Dict,
+}
+
+impl Nonces {
+    pub fn instance() -> Nonces {
+        Nonces {
+            dict: Dict::instance(NONCES_DICT),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(NONCES_DICT)
+    }
+
+    pub fn get(&self, owner: &Key) -> U256 {
+        self.dict.get_by_key(owner).unwrap_or_default()
+    }
+
+    pub fn set(&self, owner: &Key, value: U256) {
+        self.dict.set_by_key(owner, value);
+    }
+}
+
 pub struct Allowances {
     dict: Dict,
 }
@@ -171,7 +212,7 @@ pub fn get_self_purse() -> URef {
 
     match destination_purse_key.as_uref() {
         Some(uref) => *uref,
-        None => runtime::revert(ApiError::User(ErrorCodes::Abort as u16)),
+        None => runtime::revert(CommonError::Abort),
     }
 }
 
@@ -189,4 +230,20 @@ pub fn set_totalsupply(value: U256) {
 
 pub fn get_totalsupply() -> U256 {
     get_key(TOTAL_SUPPLY).unwrap_or_revert()
-}
\ No newline at end of file
+}
+
+pub fn set_domain_separator(domain_separator: String) {
+    set_key(DOMAIN_SEPARATOR, domain_separator);
+}
+
+pub fn get_domain_separator() -> String {
+    get_key(DOMAIN_SEPARATOR).unwrap_or_revert()
+}
+
+pub fn set_permit_type_hash(permit_type_hash: String) {
+    set_key(PERMIT_TYPE_HASH, permit_type_hash);
+}
+
+pub fn get_permit_type_hash() -> String {
+    get_key(PERMIT_TYPE_HASH).unwrap_or_revert()
+}
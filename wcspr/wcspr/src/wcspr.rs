@@ -1,32 +1,31 @@
 use crate::alloc::string::ToString;
-use crate::data::{self, Allowances, Balances, WcsprEvents};
-use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use crate::data::{self, Allowances, Balances, Nonces, WcsprEvents};
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
 use casper_contract::{
-    contract_api::{storage, system},
+    contract_api::{runtime, storage, system},
     unwrap_or_revert::UnwrapOrRevert,
 };
-use casper_types::{ApiError, ContractPackageHash, Key, URef, U256, U512};
-use contract_utils::{ContractContext, ContractStorage};
-
-#[repr(u16)]
-pub enum Error {
-    /// 65,540 for UniswapV2CoreWCSPROverFlow
-    UniswapV2CoreWCSPROverFlow = 4,
-    /// 65,541 for UniswapV2CoreWCSPRUnderFlow
-    UniswapV2CoreWCSPRUnderFlow = 5,
-}
+use casper_types::{
+    account::AccountHash, BlockTime, ContractHash, ContractPackageHash, Key, PublicKey, URef, U256,
+    U512,
+};
+use contract_utils::{u256_to_u512, u512_to_u256, ContractContext, ContractStorage};
+use cryptoxide::ed25519;
+use errors::{CommonError, WcsprError};
+use hex::encode;
+use renvm_sig::{hash_message, keccak256};
+
+/// Precision of `backing_ratio`, a ratio equal to this value means 1:1 backing.
+pub const BACKING_RATIO_PRECISION: u64 = 1_000_000_000;
 
-impl From<Error> for ApiError {
-    fn from(error: Error) -> ApiError {
-        ApiError::User(error as u16)
-    }
-}
 pub trait WCSPR<Storage: ContractStorage>: ContractContext<Storage> {
     fn init(
         &mut self,
         name: String,
         symbol: String,
         decimals: u8,
+        domain_separator: String,
+        permit_type_hash: String,
         contract_hash: Key,
         package_hash: ContractPackageHash,
         purse: URef,
@@ -35,11 +34,14 @@ pub trait WCSPR<Storage: ContractStorage>: ContractContext<Storage> {
         data::set_symbol(symbol);
         data::set_hash(contract_hash);
         data::set_decimals(decimals);
+        data::set_domain_separator(domain_separator);
+        data::set_permit_type_hash(permit_type_hash);
         data::set_package_hash(package_hash);
         data::set_self_purse(purse);
 
         Balances::init();
         Allowances::init();
+        Nonces::init();
         data::set_totalsupply(0.into());
     }
 
@@ -47,6 +49,10 @@ pub trait WCSPR<Storage: ContractStorage>: ContractContext<Storage> {
         Balances::instance().get(&owner)
     }
 
+    fn nonce(&mut self, owner: Key) -> U256 {
+        Nonces::instance().get(&owner)
+    }
+
     fn transfer(&mut self, recipient: Key, amount: U256) -> Result<(), u32> {
         self.make_transfer(self.get_caller(), recipient, amount)
     }
@@ -78,14 +84,14 @@ pub trait WCSPR<Storage: ContractStorage>: ContractContext<Storage> {
 
         let new_allowance: U256 = spender_allowance
             .checked_add(amount)
-            .ok_or(Error::UniswapV2CoreWCSPROverFlow)
+            .ok_or(WcsprError::UniswapV2CoreWCSPROverFlow)
             .unwrap_or_revert();
 
         if new_allowance <= owner_balance && owner != spender {
             self._approve(owner, spender, new_allowance);
             return Ok(());
         } else {
-            return Err(4);
+            return Err(CommonError::InvalidAllowance.into());
         }
     }
 
@@ -98,14 +104,14 @@ pub trait WCSPR<Storage: ContractStorage>: ContractContext<Storage> {
 
         let new_allowance: U256 = spender_allowance
             .checked_sub(amount)
-            .ok_or(Error::UniswapV2CoreWCSPRUnderFlow)
+            .ok_or(WcsprError::UniswapV2CoreWCSPRUnderFlow)
             .unwrap_or_revert();
 
         if new_allowance >= 0.into() && new_allowance < spender_allowance && owner != spender {
             self._approve(owner, spender, new_allowance);
             return Ok(());
         } else {
-            return Err(4);
+            return Err(CommonError::InvalidAllowance.into());
         }
     }
 
@@ -116,7 +122,7 @@ pub trait WCSPR<Storage: ContractStorage>: ContractContext<Storage> {
             let spender_allowance: U256 = allowances.get(&owner, &self.get_caller());
             let new_allowance: U256 = spender_allowance
                 .checked_sub(amount)
-                .ok_or(Error::UniswapV2CoreWCSPRUnderFlow)
+                .ok_or(WcsprError::UniswapV2CoreWCSPRUnderFlow)
                 .unwrap_or_revert();
             if new_allowance >= 0.into()
                 && new_allowance < spender_allowance
@@ -125,41 +131,163 @@ pub trait WCSPR<Storage: ContractStorage>: ContractContext<Storage> {
                 self._approve(owner, self.get_caller(), new_allowance);
                 return Ok(());
             } else {
-                return Err(4);
+                return Err(CommonError::InvalidAllowance.into());
             }
         }
 
         ret
     }
 
+    /// This function is to verify the signature of the meta transaction against the
+    /// public key and check that the public key belongs to the owner.
+    ///
+    /// # Parameters
+    ///
+    /// * `public_key` - A string slice that holds the public key of the meta transaction signer
+    ///
+    /// * `signature` - A string slice that holds the signature of the meta transaction
+    ///
+    /// * `digest` - A u8 array that holds the digest
+    ///
+    /// * `owner` - A Key that holds the account address of the signer
+    ///
+
+    fn ecrecover(
+        &mut self,
+        public_key: String,
+        signature: String,
+        digest: [u8; 32],
+        owner: Key,
+    ) -> bool {
+        let public_key_without_spaces: String = public_key.split_whitespace().collect();
+        let public_key_string: Vec<&str> = public_key_without_spaces.split(',').collect();
+        let mut public_key_vec: Vec<u8> = Vec::new();
+        let mut public_counter: usize = 0;
+        while public_counter < 32 {
+            public_key_vec.push(public_key_string[public_counter].parse::<u8>().unwrap());
+            public_counter = public_counter + 1;
+        }
+        let signature_without_spaces: String = signature.split_whitespace().collect();
+        let signature_string: Vec<&str> = signature_without_spaces.split(',').collect();
+        let mut signature_vec: Vec<u8> = Vec::new();
+        let mut signature_counter: usize = 0;
+        while signature_counter < 64 {
+            signature_vec.push(signature_string[signature_counter].parse::<u8>().unwrap());
+            signature_counter = signature_counter + 1;
+        }
+        let signer: PublicKey = PublicKey::ed25519_from_bytes(&public_key_vec).unwrap_or_revert();
+        if Key::from(AccountHash::from(&signer)) != owner {
+            return false;
+        }
+        ed25519::verify(&digest, &public_key_vec, &signature_vec)
+    }
+
+    /// This function is to get meta transaction signer and verify if it is equal
+    /// to the owner public key or not then call approve. The digest is built the
+    /// same way as for the erc20 and pair tokens, using the nonce of the owner.
+    ///
+    /// # Parameters
+    ///
+    /// * `public_key` - A string slice that holds the public key of the meta transaction signer,  Subscriber have to get it from running cryptoxide project externally.
+    ///
+    /// * `signature` - A string slice that holds the signature of the meta transaction,  Subscriber have to get it from running cryptoxide project externally.
+    ///
+    /// * `owner` - A Key that holds the account address of the owner
+    ///
+    /// * `spender` - A Key that holds the account address of the spender
+    ///
+    /// * `value` - A U256 that holds the value
+    ///
+    /// * `deadline` - A u64 that holds the deadline limit
+    ///
+
+    fn permit(
+        &mut self,
+        public_key: String,
+        signature: String,
+        owner: Key,
+        spender: Key,
+        value: U256,
+        deadline: u64,
+    ) {
+        let domain_separator: String = data::get_domain_separator();
+        let permit_type_hash: String = data::get_permit_type_hash();
+        let nonce: U256 = self.nonce(owner);
+        let deadline_into_blocktime: BlockTime = BlockTime::new(deadline * 1000);
+        let blocktime: BlockTime = runtime::get_blocktime();
+        if deadline_into_blocktime >= blocktime {
+            let data: String = format!(
+                "{}{}{}{}{}{}",
+                permit_type_hash, owner, spender, value, nonce, deadline
+            );
+            let hash: [u8; 32] = keccak256(data.as_bytes());
+            let hash_string: String = encode(hash);
+            let encode_packed: String = format!("{}{}", domain_separator, hash_string);
+            let digest: [u8; 32] = hash_message(encode_packed);
+            self.set_nonce(owner);
+            let result: bool = self.ecrecover(public_key, signature, digest, owner);
+            if result == true {
+                self._approve(owner, spender, value);
+            } else {
+                //signature verification failed
+                runtime::revert(WcsprError::UniswapV2CoreWCSPRSignatureVerificationFailed);
+            }
+        } else {
+            //deadline is equal to or greater than blocktime
+            runtime::revert(WcsprError::UniswapV2CoreWCSPREXPIRED);
+        }
+    }
+
+    fn set_nonce(&mut self, owner: Key) {
+        let nonces: Nonces = Nonces::instance();
+        let nonce: U256 = nonces.get(&owner);
+        nonces.set(
+            &owner,
+            nonce
+                .checked_add(U256::from(1))
+                .ok_or(WcsprError::UniswapV2CoreWCSPROverFlow)
+                .unwrap_or_revert(),
+        );
+    }
+
     fn deposit(&mut self, amount_to_transfer: U512, purse: URef) -> Result<(), u32> {
+        self.deposit_to(self.get_caller(), purse, amount_to_transfer)
+    }
+
+    fn deposit_to(
+        &mut self,
+        recipient: Key,
+        purse: URef,
+        amount_to_transfer: U512,
+    ) -> Result<(), u32> {
         let cspr_amount: U512 = system::get_purse_balance(purse).unwrap_or_revert(); // get amount of cspr from purse received
-        let _cspr_amount_u256: U256 = U256::from(cspr_amount.as_u128()); // convert amount to U256
-        let amount_to_transfer_u256: U256 = U256::from(amount_to_transfer.as_u128()); // convert amount_to_transfer to U256
+        let amount_to_transfer_u256: U256 = u512_to_u256(amount_to_transfer)
+            .ok_or(WcsprError::UniswapV2CoreWCSPRConversionOverFlow)
+            .unwrap_or_revert(); // convert amount_to_transfer to U256
         let contract_self_purse: URef = data::get_self_purse(); // get this contract's purse
 
         if amount_to_transfer.is_zero() {
-            return Err(5); // Amount to transfer is 0
+            return Err(CommonError::ZeroAmount.into());
         }
 
         if cspr_amount >= amount_to_transfer {
             // save received cspr
-            let _ = system::transfer_from_purse_to_purse(
+            system::transfer_from_purse_to_purse(
                 purse,
                 contract_self_purse,
                 amount_to_transfer,
                 None,
-            ); // transfers native cspr from source purse to destination purse
+            )
+            .unwrap_or_revert_with(WcsprError::UniswapV2CoreWCSPRPurseTransferFailed); // transfers native cspr from source purse to destination purse
 
-            // mint wcspr for the caller
-            let caller = self.get_caller();
+            // mint wcspr for the recipient
             let balances = Balances::instance();
-            let balance = balances.get(&caller);
+            let balance = balances.get(&recipient);
             balances.set(
-                &caller,
+                &recipient,
                 balance
                     .checked_add(amount_to_transfer_u256)
-                    .ok_or(Error::UniswapV2CoreWCSPROverFlow)
+                    .ok_or(WcsprError::UniswapV2CoreWCSPROverFlow)
                     .unwrap_or_revert(),
             );
 
@@ -167,30 +295,65 @@ pub trait WCSPR<Storage: ContractStorage>: ContractContext<Storage> {
             data::set_totalsupply(
                 data::get_totalsupply()
                     .checked_add(amount_to_transfer_u256)
-                    .ok_or(Error::UniswapV2CoreWCSPROverFlow)
+                    .ok_or(WcsprError::UniswapV2CoreWCSPROverFlow)
                     .unwrap_or_revert(),
             );
 
             self.emit(&WcsprEvents::Deposit {
+                sender: self.get_caller(),
+                recipient: recipient,
                 src_purse: purse,
                 amount: amount_to_transfer,
             });
+            self.assert_solvent();
         } else {
-            return Err(2); // insufficient balance
-                           // runtime::revert(MintError::InsufficientFunds);
+            // runtime::revert(MintError::InsufficientFunds);
+            return Err(CommonError::InsufficientBalance.into());
         }
 
         Ok(())
     }
 
     fn withdraw(&mut self, recipient_purse: URef, amount: U512) -> Result<(), u32> {
-        let caller = self.get_caller();
+        self._withdraw(self.get_caller(), recipient_purse, amount)
+    }
+
+    fn withdraw_from(
+        &mut self,
+        owner: Key,
+        recipient_purse: URef,
+        amount: U512,
+    ) -> Result<(), u32> {
+        let spender: Key = self.get_caller();
+        if owner == spender {
+            return Err(CommonError::SameOwnerSpender.into());
+        }
+        let allowances = Allowances::instance();
+        let spender_allowance: U256 = allowances.get(&owner, &spender);
+        let new_allowance: U256 = spender_allowance
+            .checked_sub(
+                u512_to_u256(amount)
+                    .ok_or(WcsprError::UniswapV2CoreWCSPRConversionOverFlow)
+                    .unwrap_or_revert(),
+            )
+            .ok_or(WcsprError::UniswapV2CoreWCSPRUnderFlow)
+            .unwrap_or_revert();
+        let ret: Result<(), u32> = self._withdraw(owner, recipient_purse, amount);
+        if ret.is_ok() {
+            self._approve(owner, spender, new_allowance);
+        }
+        ret
+    }
+
+    fn _withdraw(&mut self, owner: Key, recipient_purse: URef, amount: U512) -> Result<(), u32> {
         let balances = Balances::instance();
-        let balance = balances.get(&caller); // get balance of the caller
-        let cspr_amount_u256: U256 = U256::from(amount.as_u128()); // convert U512 to U256
+        let balance = balances.get(&owner); // get balance of the owner
+        let cspr_amount_u256: U256 = u512_to_u256(amount)
+            .ok_or(WcsprError::UniswapV2CoreWCSPRConversionOverFlow)
+            .unwrap_or_revert(); // convert U512 to U256
 
         if amount.is_zero() {
-            return Err(5); // Amount to transfer is 0
+            return Err(CommonError::ZeroAmount.into());
         }
 
         let contract_main_purse = data::get_self_purse();
@@ -205,13 +368,13 @@ pub trait WCSPR<Storage: ContractStorage>: ContractContext<Storage> {
                 amount,
                 None,
             )
-            .unwrap_or_revert();
+            .unwrap_or_revert_with(WcsprError::UniswapV2CoreWCSPRPurseTransferFailed);
 
             balances.set(
-                &caller,
+                &owner,
                 balance
                     .checked_sub(cspr_amount_u256)
-                    .ok_or(Error::UniswapV2CoreWCSPRUnderFlow)
+                    .ok_or(WcsprError::UniswapV2CoreWCSPRUnderFlow)
                     .unwrap_or_revert(),
             );
 
@@ -219,28 +382,86 @@ pub trait WCSPR<Storage: ContractStorage>: ContractContext<Storage> {
             data::set_totalsupply(
                 data::get_totalsupply()
                     .checked_sub(cspr_amount_u256)
-                    .ok_or(Error::UniswapV2CoreWCSPROverFlow)
+                    .ok_or(WcsprError::UniswapV2CoreWCSPROverFlow)
                     .unwrap_or_revert(),
             );
 
             self.emit(&WcsprEvents::Withdraw {
+                sender: self.get_caller(),
+                owner: owner,
                 recipient_purse: recipient_purse,
                 amount: amount,
             });
+            self.assert_solvent();
         } else {
-            return Err(2); // insufficient Balance
+            return Err(CommonError::InsufficientBalance.into());
         }
 
         Ok(())
     }
 
+    /// Returns the cspr held by the contract purse as a U256 token amount.
+    fn backing(&mut self) -> U256 {
+        let purse_balance: U512 =
+            system::get_purse_balance(data::get_self_purse()).unwrap_or_revert();
+        u512_to_u256(purse_balance)
+            .ok_or(WcsprError::UniswapV2CoreWCSPRConversionOverFlow)
+            .unwrap_or_revert()
+    }
+
+    /// Returns purse balance / total supply scaled by BACKING_RATIO_PRECISION,
+    /// so BACKING_RATIO_PRECISION means every token is backed by exactly one mote.
+    fn backing_ratio(&mut self) -> U256 {
+        let total_supply: U256 = data::get_totalsupply();
+        if total_supply.is_zero() {
+            return BACKING_RATIO_PRECISION.into();
+        }
+        self.backing()
+            .checked_mul(BACKING_RATIO_PRECISION.into())
+            .ok_or(WcsprError::UniswapV2CoreWCSPROverFlow)
+            .unwrap_or_revert()
+            / total_supply
+    }
+
+    /// Reverts if the contract purse holds less cspr than the tokens in circulation.
+    fn assert_solvent(&mut self) {
+        if self.backing() < data::get_totalsupply() {
+            runtime::revert(WcsprError::UniswapV2CoreWCSPRInsolvent);
+        }
+    }
+
+    /// Sends the cspr held by the contract purse above total supply (e.g. cspr sent
+    /// directly to the purse) to `to_purse`, which brings the backing ratio back to 1:1.
+    /// Anyone may call it and pick `to_purse`: the excess was never minted, so it belongs
+    /// to no holder and sending it away cannot leave any token unbacked.
+    fn skim(&mut self, to_purse: URef) -> Result<(), u32> {
+        let backing: U256 = self.backing();
+        let total_supply: U256 = data::get_totalsupply();
+        if backing < total_supply {
+            runtime::revert(WcsprError::UniswapV2CoreWCSPRInsolvent);
+        }
+        let excess: U256 = backing - total_supply;
+        if excess.is_zero() {
+            return Err(CommonError::ZeroAmount.into());
+        }
+        let amount: U512 = u256_to_u512(excess);
+        system::transfer_from_purse_to_purse(data::get_self_purse(), to_purse, amount, None)
+            .unwrap_or_revert_with(WcsprError::UniswapV2CoreWCSPRPurseTransferFailed);
+        self.emit(&WcsprEvents::Skim {
+            recipient_purse: to_purse,
+            amount: amount,
+        });
+        self.assert_solvent();
+        Ok(())
+    }
+
     fn make_transfer(&mut self, sender: Key, recipient: Key, amount: U256) -> Result<(), u32> {
         if sender == recipient {
-            return Err(4); // Same sender recipient error
+            return Err(CommonError::SameSenderRecipient.into());
         }
 
         if amount.is_zero() {
-            return Err(5); // Amount to transfer is 0
+            return Err(CommonError::ZeroAmount.into());
         }
 
         let balances: Balances = Balances::instance();
@@ -250,14 +471,14 @@ pub trait WCSPR<Storage: ContractStorage>: ContractContext<Storage> {
             &sender,
             sender_balance
                 .checked_sub(amount)
-                .ok_or(Error::UniswapV2CoreWCSPRUnderFlow)
+                .ok_or(WcsprError::UniswapV2CoreWCSPRUnderFlow)
                 .unwrap_or_revert(),
         );
         balances.set(
             &recipient,
             recipient_balance
                 .checked_add(amount)
-                .ok_or(Error::UniswapV2CoreWCSPROverFlow)
+                .ok_or(WcsprError::UniswapV2CoreWCSPROverFlow)
                 .unwrap_or_revert(),
         );
         self.emit(&WcsprEvents::Transfer {
@@ -276,6 +497,10 @@ pub trait WCSPR<Storage: ContractStorage>: ContractContext<Storage> {
         data::symbol()
     }
 
+    fn decimals(&mut self) -> u8 {
+        data::decimals()
+    }
+
     fn purse(&mut self) -> URef {
         data::get_self_purse()
     }
@@ -284,6 +509,33 @@ pub trait WCSPR<Storage: ContractStorage>: ContractContext<Storage> {
         data::get_package_hash()
     }
 
+    fn get_permit_type_and_domain_separator(
+        &mut self,
+        name: &str,
+        contract_hash: ContractHash,
+    ) -> (String, String) {
+        let eip_712_domain: &str =
+            "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+        let permit_type: &str =
+            "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)";
+        let chain_id: &str = "101";
+        let eip_domain_hash: [u8; 32] = keccak256(eip_712_domain.as_bytes()); // to take a byte hash of EIP712Domain
+        let name_hash: [u8; 32] = keccak256(name.as_bytes()); // to take a byte hash of name
+        let one_hash: [u8; 32] = keccak256("1".as_bytes()); // to take a byte hash of "1"
+        let eip_domain_hash: String = encode(eip_domain_hash); // to encode and convert eip_domain_hash into string
+        let name_hash: String = encode(name_hash); // to encode and convert name_hash into string
+        let one_hash: String = encode(one_hash); // to encode and convert one_hash into string
+        let concatenated_data: String = format!(
+            "{}{}{}{}{}",
+            eip_domain_hash, name_hash, one_hash, chain_id, contract_hash
+        ); //string contactination
+        let domain_separator: [u8; 32] = keccak256(concatenated_data.as_bytes()); //to take a byte hash of concatenated Data
+        let permit_type_hash: [u8; 32] = keccak256(permit_type.as_bytes()); // to take a byte hash of Permit Type
+        let domain_separator: String = encode(domain_separator);
+        let permit_type_hash: String = encode(permit_type_hash);
+        (domain_separator, permit_type_hash)
+    }
+
     // Events
     fn emit(&mut self, wcspr_event: &WcsprEvents) {
         let mut events = Vec::new();
@@ -314,16 +566,39 @@ pub trait WCSPR<Storage: ContractStorage>: ContractContext<Storage> {
                 events.push(event);
             }
 
-            WcsprEvents::Deposit { src_purse, amount } => {
+            WcsprEvents::Deposit {
+                sender,
+                recipient,
+                src_purse,
+                amount,
+            } => {
                 let mut event = BTreeMap::new();
                 event.insert("contract_package_hash", package.to_string());
                 event.insert("event_type", wcspr_event.type_name());
+                event.insert("sender", sender.to_string());
+                event.insert("recipient", recipient.to_string());
                 event.insert("source_purse", src_purse.to_string());
                 event.insert("amount", amount.to_string());
                 events.push(event);
             }
 
             WcsprEvents::Withdraw {
+                sender,
+                owner,
+                recipient_purse,
+                amount,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", wcspr_event.type_name());
+                event.insert("sender", sender.to_string());
+                event.insert("owner", owner.to_string());
+                event.insert("recipient_purse", recipient_purse.to_string());
+                event.insert("amount", amount.to_string());
+                events.push(event);
+            }
+
+            WcsprEvents::Skim {
                 recipient_purse,
                 amount,
             } => {
@@ -0,0 +1,28 @@
+#![no_main]
+#![no_std]
+
+use casper_contract::{
+    contract_api::{account, runtime},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{runtime_args, ContractHash, Key, RuntimeArgs, URef, U512};
+
+/// Wraps `amount` of CSPR from the main purse of the account running it into WCSPR for
+/// `recipient`, as only session code can hand out the main purse.
+#[no_mangle]
+fn call() {
+    let wcspr: Key = runtime::get_named_arg("wcspr");
+    let recipient: Key = runtime::get_named_arg("recipient");
+    let amount: U512 = runtime::get_named_arg("amount");
+    let purse: URef = account::get_main_purse();
+    let ret: Result<(), u32> = runtime::call_contract(
+        ContractHash::from(wcspr.into_hash().unwrap_or_revert()),
+        "deposit_to",
+        runtime_args! {
+            "recipient" => recipient,
+            "purse" => purse,
+            "amount" => amount
+        },
+    );
+    ret.unwrap_or_revert();
+}
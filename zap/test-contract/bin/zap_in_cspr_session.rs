@@ -0,0 +1,32 @@
+#![no_main]
+#![no_std]
+
+use casper_contract::{
+    contract_api::{account, runtime},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{runtime_args, ContractHash, Key, RuntimeArgs, URef, U256, U512};
+
+/// Calls `zap_in_cspr` of `zap` with the main purse of the account running it.
+#[no_mangle]
+fn call() {
+    let zap: Key = runtime::get_named_arg("zap");
+    let pair: Key = runtime::get_named_arg("pair");
+    let amount: U512 = runtime::get_named_arg("amount");
+    let min_liquidity: U256 = runtime::get_named_arg("min_liquidity");
+    let to: Key = runtime::get_named_arg("to");
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let purse: URef = account::get_main_purse();
+    let _: U256 = runtime::call_contract(
+        ContractHash::from(zap.into_hash().unwrap_or_revert()),
+        "zap_in_cspr",
+        runtime_args! {
+            "pair" => pair,
+            "purse" => purse,
+            "amount" => amount,
+            "min_liquidity" => min_liquidity,
+            "to" => to,
+            "deadline" => deadline
+        },
+    );
+}
@@ -0,0 +1,20 @@
+#![no_std]
+#![feature(once_cell)]
+
+extern crate alloc;
+
+mod admin_control;
+mod contract_context;
+mod contract_storage;
+mod conversions;
+mod data;
+mod keys;
+
+pub use admin_control::AdminControl;
+pub use contract_context::ContractContext;
+pub use contract_storage::{ContractStorage, OnChainContractStorage};
+pub use conversions::{u256_to_u512, u512_to_u256};
+pub use data::{get_key, key_to_str, set_key, Dict};
+pub use keys::{
+    is_zero, key_to_contract_hash, key_to_contract_package_hash, ZERO_ACCOUNT, ZERO_HASH,
+};
@@ -0,0 +1,5 @@
+#[cfg(test)]
+pub mod zap_tests;
+
+#[cfg(test)]
+pub mod zap_instance;
@@ -0,0 +1,88 @@
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs, U256};
+use test_env::{Sender, TestContract, TestEnv};
+
+pub struct ZAPInstance(pub TestContract);
+
+impl ZAPInstance {
+    pub fn new<T: Into<Key>>(
+        env: &TestEnv,
+        contract_name: &str,
+        sender: Sender,
+        wcspr: T,
+    ) -> ZAPInstance {
+        ZAPInstance(TestContract::new(
+            env,
+            "zap.wasm",
+            contract_name,
+            sender,
+            runtime_args! {
+                "wcspr" => wcspr.into(),
+            },
+        ))
+    }
+
+    pub fn zap_in<T: Into<Key>>(
+        &self,
+        sender: Sender,
+        pair: T,
+        token_in: T,
+        amount_in: U256,
+        min_liquidity: U256,
+        to: T,
+        deadline: u64,
+    ) {
+        self.0.call_contract(
+            sender,
+            "zap_in",
+            runtime_args! {
+                "pair" => pair.into(),
+                "token_in" => token_in.into(),
+                "amount_in" => amount_in,
+                "min_liquidity" => min_liquidity,
+                "to" => to.into(),
+                "deadline" => deadline,
+            },
+        );
+    }
+
+    pub fn zap_out<T: Into<Key>>(
+        &self,
+        sender: Sender,
+        pair: T,
+        liquidity: U256,
+        token_out: T,
+        amount_out_min: U256,
+        to: T,
+        deadline: u64,
+    ) {
+        self.0.call_contract(
+            sender,
+            "zap_out",
+            runtime_args! {
+                "pair" => pair.into(),
+                "liquidity" => liquidity,
+                "token_out" => token_out.into(),
+                "amount_out_min" => amount_out_min,
+                "to" => to.into(),
+                "deadline" => deadline,
+            },
+        );
+    }
+
+    pub fn wcspr(&self) -> Key {
+        self.0.query_named_key(String::from("wcspr"))
+    }
+
+    pub fn package_hash(&self) -> ContractPackageHash {
+        self.0
+            .query_named_key(String::from("contract_package_hash"))
+    }
+}
+
+pub fn key_to_str(key: &Key) -> String {
+    match key {
+        Key::Account(account) => account.to_string(),
+        Key::Hash(package) => hex::encode(package),
+        _ => panic!("Unexpected key type"),
+    }
+}
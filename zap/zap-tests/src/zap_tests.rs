@@ -0,0 +1,427 @@
+use casper_engine_test_support::{AccountHash, Code};
+use casper_types::{runtime_args, ContractPackageHash, Key, RuntimeArgs, U128, U256, U512};
+use test_env::{Sender, TestContract, TestEnv};
+
+use crate::zap_instance::{key_to_str, ZAPInstance};
+
+const NAME_ZAP: &str = "Zap";
+
+struct Pool {
+    pair: TestContract,
+    token0: TestContract,
+    token1: TestContract,
+}
+
+impl Pool {
+    fn key(&self) -> Key {
+        Key::Hash(self.pair.contract_hash())
+    }
+
+    fn token0_key(&self) -> Key {
+        Key::Hash(self.token0.contract_hash())
+    }
+
+    fn token1_key(&self) -> Key {
+        Key::Hash(self.token1.contract_hash())
+    }
+
+    fn package_hash(&self) -> ContractPackageHash {
+        self.pair.query_named_key(String::from("self_package_hash"))
+    }
+
+    fn reserves(&self) -> (U128, U128) {
+        (
+            self.pair.query_named_key(String::from("reserve0")),
+            self.pair.query_named_key(String::from("reserve1")),
+        )
+    }
+
+    /// `token` and the other token of the pair.
+    fn tokens(&self, token: Key) -> (&TestContract, &TestContract) {
+        if token == self.token0_key() {
+            (&self.token0, &self.token1)
+        } else {
+            (&self.token1, &self.token0)
+        }
+    }
+
+    /// Reserves of `token` and of the other token of the pair.
+    fn reserves_of(&self, token: Key) -> (U128, U128) {
+        let (reserve0, reserve1) = self.reserves();
+        if token == self.token0_key() {
+            (reserve0, reserve1)
+        } else {
+            (reserve1, reserve0)
+        }
+    }
+}
+
+fn deploy() -> (TestEnv, ZAPInstance, AccountHash, Pool, Pool) {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let factory = TestContract::new(
+        &env,
+        "factory.wasm",
+        "factory",
+        Sender(owner),
+        runtime_args! {"fee_to_setter" => Key::from(owner)},
+    );
+    let wcspr = TestContract::new(
+        &env,
+        "wcspr-token.wasm",
+        "wcspr",
+        Sender(owner),
+        runtime_args! {
+            "initial_supply" => U256::from(0),
+            "name" => "wcspr",
+            "symbol" => "wcspr",
+            "decimals" => 9u8
+        },
+    );
+    let flash_swapper = TestContract::new(
+        &env,
+        "flash-swapper.wasm",
+        "flash_swapper",
+        Sender(owner),
+        runtime_args! {
+            "wcspr" => Key::Hash(wcspr.contract_hash()),
+            "dai" => Key::Hash(wcspr.contract_hash()),
+            "uniswap_v2_factory" => Key::Hash(factory.contract_hash())
+        },
+    );
+    let token_a = deploy_token(&env, "token0", owner);
+    let token_b = deploy_token(&env, "token1", owner);
+    // owner creates the pairs
+    factory.call_contract(
+        Sender(owner),
+        "set_white_list",
+        runtime_args! {"white_list" => Key::from(owner)},
+    );
+    let pool = create_pool(
+        &env,
+        owner,
+        &factory,
+        &flash_swapper,
+        "pair",
+        token_a,
+        token_b,
+    );
+    // 10000 of each token, the owner gets 10000 - 1000 (minimum liquidity) LP tokens
+    mint(
+        &pool.token0,
+        owner,
+        Key::from(pool.package_hash()),
+        10000.into(),
+    );
+    mint(
+        &pool.token1,
+        owner,
+        Key::from(pool.package_hash()),
+        10000.into(),
+    );
+    pool.pair.call_contract(
+        Sender(owner),
+        "mint",
+        runtime_args! {"to" => Key::from(owner)},
+    );
+    // the same for a pool of WCSPR, wrapped from the CSPR of the owner
+    let wcspr_key = Key::Hash(wcspr.contract_hash());
+    let token_c = deploy_token(&env, "token2", owner);
+    let cspr_pool = create_pool(
+        &env,
+        owner,
+        &factory,
+        &flash_swapper,
+        "cspr_pair",
+        wcspr,
+        token_c,
+    );
+    let (_, token_c) = cspr_pool.tokens(wcspr_key);
+    mint(
+        token_c,
+        owner,
+        Key::from(cspr_pool.package_hash()),
+        10000.into(),
+    );
+    deposit_cspr(
+        &env,
+        owner,
+        wcspr_key,
+        Key::from(cspr_pool.package_hash()),
+        10000.into(),
+    );
+    cspr_pool.pair.call_contract(
+        Sender(owner),
+        "mint",
+        runtime_args! {"to" => Key::from(owner)},
+    );
+    let zap = ZAPInstance::new(&env, NAME_ZAP, Sender(owner), wcspr_key);
+    (env, zap, owner, pool, cspr_pool)
+}
+
+/// Creates a pair of `token_a` and `token_b` through the factory, without liquidity.
+fn create_pool(
+    env: &TestEnv,
+    owner: AccountHash,
+    factory: &TestContract,
+    flash_swapper: &TestContract,
+    name: &str,
+    token_a: TestContract,
+    token_b: TestContract,
+) -> Pool {
+    let pair = TestContract::new(
+        env,
+        "pair-token.wasm",
+        name,
+        Sender(owner),
+        runtime_args! {
+            "initial_supply" => U256::from(0),
+            "name" => name,
+            "symbol" => "lp",
+            "decimals" => 9u8,
+            "callee_contract_hash" => Key::Hash(flash_swapper.contract_hash()),
+            "factory_hash" => Key::Hash(factory.contract_hash())
+        },
+    );
+    factory.call_contract(
+        Sender(owner),
+        "create_pair",
+        runtime_args! {
+            "token_a" => Key::Hash(token_a.contract_hash()),
+            "token_b" => Key::Hash(token_b.contract_hash()),
+            "pair_hash" => Key::Hash(pair.contract_hash())
+        },
+    );
+    // the factory sorts the tokens
+    let (token0, token1) =
+        if Key::Hash(token_a.contract_hash()) < Key::Hash(token_b.contract_hash()) {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        };
+    Pool {
+        pair,
+        token0,
+        token1,
+    }
+}
+
+fn deploy_token(env: &TestEnv, name: &str, owner: AccountHash) -> TestContract {
+    TestContract::new(
+        env,
+        "erc20-token.wasm",
+        name,
+        Sender(owner),
+        runtime_args! {
+            "initial_supply" => U256::from(0),
+            "name" => name,
+            "symbol" => "tk",
+            "decimals" => 18u8
+        },
+    )
+}
+
+fn mint(token: &TestContract, owner: AccountHash, to: Key, amount: U256) {
+    token.call_contract(
+        Sender(owner),
+        "mint",
+        runtime_args! {"to" => to, "amount" => amount},
+    );
+}
+
+fn approve(token: &TestContract, owner: AccountHash, spender: Key, amount: U256) {
+    token.call_contract(
+        Sender(owner),
+        "approve",
+        runtime_args! {"spender" => spender, "amount" => amount},
+    );
+}
+
+/// Wraps `amount` of CSPR from the main purse of `sender` into WCSPR for `recipient`.
+fn deposit_cspr(env: &TestEnv, sender: AccountHash, wcspr: Key, recipient: Key, amount: U512) {
+    env.run(
+        Sender(sender),
+        Code::from("zap-deposit-session.wasm"),
+        runtime_args! {
+            "wcspr" => wcspr,
+            "recipient" => recipient,
+            "amount" => amount
+        },
+    );
+}
+
+fn balance_of(token: &TestContract, account: Key) -> U256 {
+    token
+        .query_dictionary("balances", key_to_str(&account))
+        .unwrap_or_default()
+}
+
+fn zap_in(
+    env: &TestEnv,
+    zap: &ZAPInstance,
+    owner: AccountHash,
+    pool: &Pool,
+    min_liquidity: U256,
+) -> AccountHash {
+    let user = env.next_user();
+    mint(&pool.token0, owner, Key::from(user), 1000.into());
+    approve(
+        &pool.token0,
+        user,
+        Key::from(zap.package_hash()),
+        1000.into(),
+    );
+    zap.zap_in(
+        Sender(user),
+        pool.key(),
+        pool.token0_key(),
+        1000.into(),
+        min_liquidity,
+        Key::from(user),
+        u64::MAX,
+    );
+    user
+}
+
+/// Zaps 1000 CSPR of a new user into the WCSPR pool and returns the user.
+fn zap_in_cspr(
+    env: &TestEnv,
+    zap: &ZAPInstance,
+    cspr_pool: &Pool,
+    min_liquidity: U256,
+) -> AccountHash {
+    let user = env.next_user();
+    env.run(
+        Sender(user),
+        Code::from("zap-in-cspr-session.wasm"),
+        runtime_args! {
+            "zap" => Key::Hash(zap.0.contract_hash()),
+            "pair" => cspr_pool.key(),
+            "amount" => U512::from(1000),
+            "min_liquidity" => min_liquidity,
+            "to" => Key::from(user),
+            "deadline" => u64::MAX
+        },
+    );
+    user
+}
+
+fn zap_out(zap: &ZAPInstance, owner: AccountHash, pool: &Pool, amount_out_min: U256) {
+    approve(
+        &pool.pair,
+        owner,
+        Key::from(zap.package_hash()),
+        1000.into(),
+    );
+    zap.zap_out(
+        Sender(owner),
+        pool.key(),
+        1000.into(),
+        pool.token1_key(),
+        amount_out_min,
+        Key::from(owner),
+        u64::MAX,
+    );
+}
+
+#[test]
+fn test_zap_deploy() {
+    let (_env, zap, owner, pool, cspr_pool) = deploy();
+    let (wcspr, _) = cspr_pool.tokens(zap.wcspr());
+    assert_eq!(zap.wcspr(), Key::Hash(wcspr.contract_hash()));
+    assert_eq!(
+        wcspr.query_named_key::<String>(String::from("name")),
+        "wcspr"
+    );
+    assert_eq!(balance_of(&pool.pair, Key::from(owner)), 9000.into());
+    assert_eq!(balance_of(&cspr_pool.pair, Key::from(owner)), 9000.into());
+}
+
+#[test]
+fn test_zap_in() {
+    let (env, zap, owner, pool, _cspr_pool) = deploy();
+    let user = zap_in(&env, &zap, owner, &pool, 485.into());
+    // 488 token0 is swapped for 463 token1, 509 token0 and 463 token1 are added
+    assert_eq!(balance_of(&pool.pair, Key::from(user)), 485.into());
+    assert_eq!(pool.reserves(), (10997.into(), 10000.into()));
+    // the rounding dust goes back to the user
+    assert_eq!(balance_of(&pool.token0, Key::from(user)), 3.into());
+    assert_eq!(balance_of(&pool.token1, Key::from(user)), 0.into());
+    assert_eq!(
+        balance_of(&pool.token0, Key::from(zap.package_hash())),
+        0.into()
+    );
+    assert_eq!(
+        balance_of(&pool.token1, Key::from(zap.package_hash())),
+        0.into()
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_zap_in_below_min_liquidity() {
+    let (env, zap, owner, pool, _cspr_pool) = deploy();
+    zap_in(&env, &zap, owner, &pool, 486.into());
+}
+
+#[test]
+#[should_panic]
+fn test_zap_in_with_token_not_in_pair() {
+    let (env, zap, owner, pool, _cspr_pool) = deploy();
+    let user = env.next_user();
+    let token = deploy_token(&env, "token2", owner);
+    mint(&token, owner, Key::from(user), 1000.into());
+    approve(&token, user, Key::from(zap.package_hash()), 1000.into());
+    zap.zap_in(
+        Sender(user),
+        pool.key(),
+        Key::Hash(token.contract_hash()),
+        1000.into(),
+        0.into(),
+        Key::from(user),
+        u64::MAX,
+    );
+}
+
+#[test]
+fn test_zap_in_cspr() {
+    let (env, zap, _owner, _pool, cspr_pool) = deploy();
+    let user = zap_in_cspr(&env, &zap, &cspr_pool, 485.into());
+    let (wcspr, token) = cspr_pool.tokens(zap.wcspr());
+    // 1000 CSPR is wrapped, then the same as in test_zap_in
+    assert_eq!(balance_of(&cspr_pool.pair, Key::from(user)), 485.into());
+    assert_eq!(
+        cspr_pool.reserves_of(zap.wcspr()),
+        (10997.into(), 10000.into())
+    );
+    // the WCSPR dust goes back to the user
+    assert_eq!(balance_of(wcspr, Key::from(user)), 3.into());
+    assert_eq!(balance_of(token, Key::from(user)), 0.into());
+    assert_eq!(balance_of(wcspr, Key::from(zap.package_hash())), 0.into());
+    assert_eq!(balance_of(token, Key::from(zap.package_hash())), 0.into());
+}
+
+#[test]
+#[should_panic]
+fn test_zap_in_cspr_below_min_liquidity() {
+    let (env, zap, _owner, _pool, cspr_pool) = deploy();
+    zap_in_cspr(&env, &zap, &cspr_pool, 486.into());
+}
+
+#[test]
+fn test_zap_out() {
+    let (_env, zap, owner, pool, _cspr_pool) = deploy();
+    zap_out(&zap, owner, &pool, 1897.into());
+    // 1000 of each token are burned, 1000 token0 is swapped for 897 token1
+    assert_eq!(balance_of(&pool.pair, Key::from(owner)), 8000.into());
+    assert_eq!(balance_of(&pool.token1, Key::from(owner)), 1897.into());
+    assert_eq!(balance_of(&pool.token0, Key::from(owner)), 0.into());
+    assert_eq!(pool.reserves(), (10000.into(), 8103.into()));
+}
+
+#[test]
+#[should_panic]
+fn test_zap_out_below_amount_out_min() {
+    let (_env, zap, owner, pool, _cspr_pool) = deploy();
+    zap_out(&zap, owner, &pool, 1898.into());
+}
@@ -0,0 +1,284 @@
+#![no_main]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{collections::BTreeSet, format, vec};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    runtime_args, CLTyped, CLValue, ContractHash, ContractPackageHash, EntryPoint,
+    EntryPointAccess, EntryPointType, EntryPoints, Group, Key, Parameter, RuntimeArgs, URef, U256,
+    U512,
+};
+use contract_utils::{ContractContext, OnChainContractStorage};
+use zap::{self, ZAP};
+
+#[derive(Default)]
+struct Zap(OnChainContractStorage);
+
+impl ContractContext<OnChainContractStorage> for Zap {
+    fn storage(&self) -> &OnChainContractStorage {
+        &self.0
+    }
+}
+
+impl ZAP<OnChainContractStorage> for Zap {}
+
+impl Zap {
+    fn constructor(
+        &mut self,
+        wcspr: Key,
+        contract_hash: ContractHash,
+        package_hash: ContractPackageHash,
+    ) {
+        ZAP::init(self, wcspr, Key::from(contract_hash), package_hash);
+    }
+}
+
+#[no_mangle]
+fn constructor() {
+    let wcspr: Key = runtime::get_named_arg("wcspr");
+    let contract_hash: ContractHash = runtime::get_named_arg("contract_hash");
+    let package_hash: ContractPackageHash = runtime::get_named_arg("package_hash");
+    Zap::default().constructor(wcspr, contract_hash, package_hash);
+}
+
+/// This function is to add liquidity to a pair with one of its tokens only, part of it is swapped for the other token and what is left over goes back to the caller, it returns the liquidity minted
+///
+/// # Parameters
+///
+/// * `pair` - A Key that holds the Hash of the pair
+///
+/// * `token_in` - A Key that holds the Hash of the token to add, which the caller must have approved for the package hash of this contract
+///
+/// * `amount_in` - A U256 that holds the amount of token_in to add
+///
+/// * `min_liquidity` - A U256 that holds the minimum liquidity to mint
+///
+/// * `to` - A Key that holds the account address or package hash that receives the liquidity
+///
+/// * `deadline` - A u64 that holds the time in seconds after which the zap reverts
+///
+
+#[no_mangle]
+fn zap_in() {
+    let pair: Key = runtime::get_named_arg("pair");
+    let token_in: Key = runtime::get_named_arg("token_in");
+    let amount_in: U256 = runtime::get_named_arg("amount_in");
+    let min_liquidity: U256 = runtime::get_named_arg("min_liquidity");
+    let to: Key = runtime::get_named_arg("to");
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let ret: U256 = Zap::default().zap_in(pair, token_in, amount_in, min_liquidity, to, deadline);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to add liquidity to a pair with CSPR only, the CSPR is wrapped into WCSPR first and what is left over goes back to the caller as WCSPR, it returns the liquidity minted
+///
+/// # Parameters
+///
+/// * `pair` - A Key that holds the Hash of a pair of WCSPR
+///
+/// * `purse` - A URef that holds the purse to take the CSPR from
+///
+/// * `amount` - A U512 that holds the amount of CSPR to add
+///
+/// * `min_liquidity` - A U256 that holds the minimum liquidity to mint
+///
+/// * `to` - A Key that holds the account address or package hash that receives the liquidity
+///
+/// * `deadline` - A u64 that holds the time in seconds after which the zap reverts
+///
+
+#[no_mangle]
+fn zap_in_cspr() {
+    let pair: Key = runtime::get_named_arg("pair");
+    let purse: URef = runtime::get_named_arg("purse");
+    let amount: U512 = runtime::get_named_arg("amount");
+    let min_liquidity: U256 = runtime::get_named_arg("min_liquidity");
+    let to: Key = runtime::get_named_arg("to");
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let ret: U256 = Zap::default().zap_in_cspr(pair, purse, amount, min_liquidity, to, deadline);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to remove liquidity from a pair into one of its tokens only, the other token is swapped for it, it returns the amount sent to `to`
+///
+/// # Parameters
+///
+/// * `pair` - A Key that holds the Hash of the pair
+///
+/// * `liquidity` - A U256 that holds the liquidity to remove, which the caller must have approved for the package hash of this contract
+///
+/// * `token_out` - A Key that holds the Hash of the token to receive
+///
+/// * `amount_out_min` - A U256 that holds the minimum amount of token_out to receive
+///
+/// * `to` - A Key that holds the account address or package hash that receives token_out
+///
+/// * `deadline` - A u64 that holds the time in seconds after which the zap reverts
+///
+
+#[no_mangle]
+fn zap_out() {
+    let pair: Key = runtime::get_named_arg("pair");
+    let liquidity: U256 = runtime::get_named_arg("liquidity");
+    let token_out: Key = runtime::get_named_arg("token_out");
+    let amount_out_min: U256 = runtime::get_named_arg("amount_out_min");
+    let to: Key = runtime::get_named_arg("to");
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let ret: U256 =
+        Zap::default().zap_out(pair, liquidity, token_out, amount_out_min, to, deadline);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to return the Hash of the WCSPR token
+///
+
+#[no_mangle]
+fn wcspr() {
+    let ret: Key = Zap::default().wcspr();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/// This function is to fetch a Contract Package Hash
+///
+
+#[no_mangle]
+fn package_hash() {
+    let ret: ContractPackageHash = Zap::default().get_package_hash();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new("wcspr", Key::cl_type()),
+            Parameter::new("contract_hash", ContractHash::cl_type()),
+            Parameter::new("package_hash", ContractPackageHash::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "zap_in",
+        vec![
+            Parameter::new("pair", Key::cl_type()),
+            Parameter::new("token_in", Key::cl_type()),
+            Parameter::new("amount_in", U256::cl_type()),
+            Parameter::new("min_liquidity", U256::cl_type()),
+            Parameter::new("to", Key::cl_type()),
+            Parameter::new("deadline", u64::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "zap_in_cspr",
+        vec![
+            Parameter::new("pair", Key::cl_type()),
+            Parameter::new("purse", URef::cl_type()),
+            Parameter::new("amount", U512::cl_type()),
+            Parameter::new("min_liquidity", U256::cl_type()),
+            Parameter::new("to", Key::cl_type()),
+            Parameter::new("deadline", u64::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "zap_out",
+        vec![
+            Parameter::new("pair", Key::cl_type()),
+            Parameter::new("liquidity", U256::cl_type()),
+            Parameter::new("token_out", Key::cl_type()),
+            Parameter::new("amount_out_min", U256::cl_type()),
+            Parameter::new("to", Key::cl_type()),
+            Parameter::new("deadline", u64::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "wcspr",
+        vec![],
+        Key::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "package_hash",
+        vec![],
+        ContractPackageHash::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
+
+#[no_mangle]
+fn call() {
+    // Contract name must be same for all new versions of the contracts
+    let contract_name: alloc::string::String = runtime::get_named_arg("contract_name");
+
+    // Build new package with initial a first version of the contract.
+    let (package_hash, access_token) = storage::create_contract_package_at_hash();
+    let (contract_hash, _) =
+        storage::add_contract_version(package_hash, get_entry_points(), Default::default());
+
+    let wcspr: Key = runtime::get_named_arg("wcspr");
+
+    // Prepare constructor args
+    let constructor_args = runtime_args! {
+        "wcspr" => wcspr,
+        "contract_hash" => contract_hash,
+        "package_hash"=> package_hash
+    };
+
+    // Add the constructor group to the package hash with a single URef.
+    let constructor_access: URef =
+        storage::create_contract_user_group(package_hash, "constructor", 1, Default::default())
+            .unwrap_or_revert()
+            .pop()
+            .unwrap_or_revert();
+
+    // Call the constructor entry point
+    let _: () =
+        runtime::call_versioned_contract(package_hash, None, "constructor", constructor_args);
+
+    // Remove all URefs from the constructor group, so no one can call it for the second time.
+    let mut urefs = BTreeSet::new();
+    urefs.insert(constructor_access);
+    storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
+        .unwrap_or_revert();
+
+    // Store contract in the account's named keys.
+    runtime::put_key(
+        &format!("{}_package_hash", contract_name),
+        package_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_hash_wrapped", contract_name),
+        storage::new_uref(package_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash_wrapped", contract_name),
+        storage::new_uref(contract_hash).into(),
+    );
+    runtime::put_key(
+        &format!("{}_package_access_token", contract_name),
+        access_token.into(),
+    );
+}
@@ -0,0 +1,403 @@
+use crate::alloc::string::ToString;
+use crate::data;
+use alloc::collections::BTreeMap;
+use alloc::{string::String, vec::Vec};
+use casper_contract::contract_api::runtime;
+use casper_contract::contract_api::storage;
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{
+    runtime_args, ContractHash, ContractPackageHash, Key, RuntimeArgs, URef, U128, U256, U512,
+};
+use contract_utils::{key_to_contract_hash, ContractContext, ContractStorage};
+use errors::ZapError;
+
+pub enum ZAPEvent {
+    ZapIn {
+        user: Key,
+        pair: Key,
+        token_in: Key,
+        amount_in: U256,
+        liquidity: U256,
+    },
+    ZapOut {
+        user: Key,
+        pair: Key,
+        token_out: Key,
+        liquidity: U256,
+        amount_out: U256,
+    },
+}
+impl ZAPEvent {
+    pub fn type_name(&self) -> String {
+        match self {
+            ZAPEvent::ZapIn {
+                user: _,
+                pair: _,
+                token_in: _,
+                amount_in: _,
+                liquidity: _,
+            } => "zap_in",
+            ZAPEvent::ZapOut {
+                user: _,
+                pair: _,
+                token_out: _,
+                liquidity: _,
+                amount_out: _,
+            } => "zap_out",
+        }
+        .to_string()
+    }
+}
+
+pub trait ZAP<Storage: ContractStorage>: ContractContext<Storage> {
+    fn init(&mut self, wcspr: Key, contract_hash: Key, package_hash: ContractPackageHash) {
+        data::set_wcspr(wcspr);
+        data::set_hash(contract_hash);
+        data::set_package_hash(package_hash);
+    }
+
+    /// Adds liquidity to `pair` with `amount_in` of `token_in` only, which the caller must
+    /// have approved for this contract's package hash. Part of it is swapped for the other
+    /// token of the pair and both are added, the LP tokens go to `to` and what is left
+    /// over goes back to the caller. Returns the liquidity minted.
+    fn zap_in(
+        &mut self,
+        pair: Key,
+        token_in: Key,
+        amount_in: U256,
+        min_liquidity: U256,
+        to: Key,
+        deadline: u64,
+    ) -> U256 {
+        let user: Key = self.get_caller();
+        let zap: Key = Key::from(data::get_package_hash());
+        let balance_before: U256 = token_balance(token_in, zap);
+        let ret: Result<(), u32> = runtime::call_contract(
+            key_to_contract_hash(token_in).unwrap_or_revert(),
+            "transfer_from",
+            runtime_args! {
+                "owner" => user,
+                "recipient" => zap,
+                "amount" => amount_in
+            },
+        );
+        ret.unwrap_or_revert();
+        let received: U256 = token_balance(token_in, zap)
+            .checked_sub(balance_before)
+            .ok_or(ZapError::ZapUnderFlow)
+            .unwrap_or_revert();
+        self.add_liquidity_from(pair, token_in, received, min_liquidity, to, deadline)
+    }
+
+    /// Same as `zap_in` with `amount` of CSPR from `purse`, which is wrapped into WCSPR
+    /// first. Left overs go back to the caller as WCSPR.
+    fn zap_in_cspr(
+        &mut self,
+        pair: Key,
+        purse: URef,
+        amount: U512,
+        min_liquidity: U256,
+        to: Key,
+        deadline: u64,
+    ) -> U256 {
+        let wcspr: Key = data::get_wcspr();
+        let zap: Key = Key::from(data::get_package_hash());
+        let balance_before: U256 = token_balance(wcspr, zap);
+        let ret: Result<(), u32> = runtime::call_contract(
+            key_to_contract_hash(wcspr).unwrap_or_revert(),
+            "deposit",
+            runtime_args! {"amount" => amount, "purse" => purse},
+        );
+        ret.unwrap_or_revert();
+        let received: U256 = token_balance(wcspr, zap)
+            .checked_sub(balance_before)
+            .ok_or(ZapError::ZapUnderFlow)
+            .unwrap_or_revert();
+        self.add_liquidity_from(pair, wcspr, received, min_liquidity, to, deadline)
+    }
+
+    /// Burns `liquidity` of the caller's LP tokens of `pair`, which the caller must have
+    /// approved for this contract's package hash, and swaps the other token for
+    /// `token_out`. The whole amount goes to `to` and is returned.
+    fn zap_out(
+        &mut self,
+        pair: Key,
+        liquidity: U256,
+        token_out: Key,
+        amount_out_min: U256,
+        to: Key,
+        deadline: u64,
+    ) -> U256 {
+        let user: Key = self.get_caller();
+        let zap: Key = Key::from(data::get_package_hash());
+        let pair_hash: ContractHash = key_to_contract_hash(pair).unwrap_or_revert();
+        let pair_package: Key = pair_package_hash(pair_hash);
+        let token_other: Key = other_token(pair_hash, token_out);
+        let out_before: U256 = token_balance(token_out, zap);
+        let other_before: U256 = token_balance(token_other, zap);
+        let ret: Result<(), u32> = runtime::call_contract(
+            pair_hash,
+            "transfer_from",
+            runtime_args! {
+                "owner" => user,
+                "recipient" => pair_package,
+                "amount" => liquidity
+            },
+        );
+        ret.unwrap_or_revert();
+        let _: (U256, U256) = runtime::call_contract(
+            pair_hash,
+            "burn_with_min",
+            runtime_args! {
+                "to" => zap,
+                "amount0_min" => U256::zero(),
+                "amount1_min" => U256::zero(),
+                "deadline" => deadline
+            },
+        );
+        let other_amount: U256 = token_balance(token_other, zap)
+            .checked_sub(other_before)
+            .ok_or(ZapError::ZapUnderFlow)
+            .unwrap_or_revert();
+        transfer(token_other, pair_package, other_amount);
+        swap_to_zap(pair_hash, deadline);
+        let amount_out: U256 = token_balance(token_out, zap)
+            .checked_sub(out_before)
+            .ok_or(ZapError::ZapUnderFlow)
+            .unwrap_or_revert();
+        if amount_out < amount_out_min {
+            runtime::revert(ZapError::ZapInsufficientOutputAmount);
+        }
+        transfer(token_out, to, amount_out);
+        self.emit(&ZAPEvent::ZapOut {
+            user: user,
+            pair: pair,
+            token_out: token_out,
+            liquidity: liquidity,
+            amount_out: amount_out,
+        });
+        amount_out
+    }
+
+    /// Does the work of `zap_in` once this contract holds `amount_in` of `token_in`.
+    /// The liquidity is added in the ratio of the reserves after the swap, so only
+    /// rounding dust is left over.
+    fn add_liquidity_from(
+        &mut self,
+        pair: Key,
+        token_in: Key,
+        amount_in: U256,
+        min_liquidity: U256,
+        to: Key,
+        deadline: u64,
+    ) -> U256 {
+        let user: Key = self.get_caller();
+        let zap: Key = Key::from(data::get_package_hash());
+        let pair_hash: ContractHash = key_to_contract_hash(pair).unwrap_or_revert();
+        let pair_package: Key = pair_package_hash(pair_hash);
+        let token_out: Key = other_token(pair_hash, token_in);
+        let (reserve_in, reserve_out): (U256, U256) = reserves(pair_hash, token_in);
+        if reserve_in.is_zero() || reserve_out.is_zero() {
+            runtime::revert(ZapError::ZapInsufficientLiquidity);
+        }
+        let swap_amount: U256 = optimal_swap_amount(reserve_in, amount_in);
+        let out_before: U256 = token_balance(token_out, zap);
+        transfer(token_in, pair_package, swap_amount);
+        swap_to_zap(pair_hash, deadline);
+        let amount_out: U256 = token_balance(token_out, zap)
+            .checked_sub(out_before)
+            .ok_or(ZapError::ZapUnderFlow)
+            .unwrap_or_revert();
+        let amount_in_left: U256 = amount_in
+            .checked_sub(swap_amount)
+            .ok_or(ZapError::ZapUnderFlow)
+            .unwrap_or_revert();
+
+        let (reserve_in, reserve_out): (U256, U256) = reserves(pair_hash, token_in);
+        let amount_out_optimal: U256 = quote(amount_in_left, reserve_in, reserve_out);
+        let (liquidity_in, liquidity_out): (U256, U256) = if amount_out_optimal <= amount_out {
+            (amount_in_left, amount_out_optimal)
+        } else {
+            (quote(amount_out, reserve_out, reserve_in), amount_out)
+        };
+        transfer(token_in, pair_package, liquidity_in);
+        transfer(token_out, pair_package, liquidity_out);
+        let liquidity: U256 = runtime::call_contract(pair_hash, "mint", runtime_args! {"to" => to});
+        if liquidity < min_liquidity {
+            runtime::revert(ZapError::ZapInsufficientLiquidityMinted);
+        }
+        transfer(token_in, user, amount_in_left - liquidity_in);
+        transfer(token_out, user, amount_out - liquidity_out);
+        self.emit(&ZAPEvent::ZapIn {
+            user: user,
+            pair: pair,
+            token_in: token_in,
+            amount_in: amount_in,
+            liquidity: liquidity,
+        });
+        liquidity
+    }
+
+    fn wcspr(&mut self) -> Key {
+        data::get_wcspr()
+    }
+
+    fn emit(&mut self, zap_event: &ZAPEvent) {
+        let mut events = Vec::new();
+        let package = data::get_package_hash();
+        match zap_event {
+            ZAPEvent::ZapIn {
+                user,
+                pair,
+                token_in,
+                amount_in,
+                liquidity,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", zap_event.type_name());
+                event.insert("user", user.to_string());
+                event.insert("pair", pair.to_string());
+                event.insert("token_in", token_in.to_string());
+                event.insert("amount_in", amount_in.to_string());
+                event.insert("liquidity", liquidity.to_string());
+                events.push(event);
+            }
+            ZAPEvent::ZapOut {
+                user,
+                pair,
+                token_out,
+                liquidity,
+                amount_out,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", package.to_string());
+                event.insert("event_type", zap_event.type_name());
+                event.insert("user", user.to_string());
+                event.insert("pair", pair.to_string());
+                event.insert("token_out", token_out.to_string());
+                event.insert("liquidity", liquidity.to_string());
+                event.insert("amount_out", amount_out.to_string());
+                events.push(event);
+            }
+        };
+
+        for event in events {
+            let _: URef = storage::new_uref(event);
+        }
+    }
+
+    fn get_package_hash(&mut self) -> ContractPackageHash {
+        data::get_package_hash()
+    }
+}
+
+/// Part of `amount_in` to swap so that what is left and the output of the swap are in
+/// the ratio of the reserves after the swap, given the 0.3% fee of the pair:
+/// `(sqrt(r * (r * 3988009 + a * 3988000)) - r * 1997) / 1994`.
+pub fn optimal_swap_amount(reserve_in: U256, amount_in: U256) -> U256 {
+    let root: U256 = sqrt(
+        reserve_in
+            .checked_mul(3_988_009.into())
+            .zip(amount_in.checked_mul(3_988_000.into()))
+            .and_then(|(x, y)| x.checked_add(y))
+            .and_then(|x| x.checked_mul(reserve_in))
+            .ok_or(ZapError::ZapOverFlow)
+            .unwrap_or_revert(),
+    );
+    root.checked_sub(reserve_in * 1997)
+        .ok_or(ZapError::ZapUnderFlow)
+        .unwrap_or_revert()
+        / 1994
+}
+
+/// Same Babylonian method as the `sqrt` of the pair.
+fn sqrt(y: U256) -> U256 {
+    let mut z: U256 = 0.into();
+    if y > 3.into() {
+        z = y;
+        let mut x: U256 = y / 2 + 1;
+        while x < z {
+            z = x;
+            x = (y / x + x) / 2;
+        }
+    } else if y != 0.into() {
+        z = 1.into();
+    }
+    z
+}
+
+fn quote(amount_a: U256, reserve_a: U256, reserve_b: U256) -> U256 {
+    amount_a
+        .checked_mul(reserve_b)
+        .ok_or(ZapError::ZapOverFlow)
+        .unwrap_or_revert()
+        / reserve_a
+}
+
+fn token_balance(token: Key, owner: Key) -> U256 {
+    runtime::call_contract(
+        key_to_contract_hash(token).unwrap_or_revert(),
+        "balance_of",
+        runtime_args! {"owner" => owner},
+    )
+}
+
+fn transfer(token: Key, recipient: Key, amount: U256) {
+    if amount.is_zero() {
+        return;
+    }
+    let ret: Result<(), u32> = runtime::call_contract(
+        key_to_contract_hash(token).unwrap_or_revert(),
+        "transfer",
+        runtime_args! {"recipient" => recipient, "amount" => amount},
+    );
+    ret.unwrap_or_revert();
+}
+
+fn pair_package_hash(pair_hash: ContractHash) -> Key {
+    let package_hash: ContractPackageHash =
+        runtime::call_contract(pair_hash, "package_hash", runtime_args! {});
+    Key::from(package_hash)
+}
+
+/// The token of the pair that is not `token`, which must be one of them.
+fn other_token(pair_hash: ContractHash, token: Key) -> Key {
+    let token0: Key = runtime::call_contract(pair_hash, "token0", runtime_args! {});
+    let token1: Key = runtime::call_contract(pair_hash, "token1", runtime_args! {});
+    if token == token0 {
+        token1
+    } else if token == token1 {
+        token0
+    } else {
+        runtime::revert(ZapError::ZapInvalidToken)
+    }
+}
+
+/// Reserves of the pair as `(reserve of token, reserve of the other token)`.
+fn reserves(pair_hash: ContractHash, token: Key) -> (U256, U256) {
+    let token0: Key = runtime::call_contract(pair_hash, "token0", runtime_args! {});
+    let (reserve0, reserve1, _block_timestamp_last): (U128, U128, u64) =
+        runtime::call_contract(pair_hash, "get_reserves", runtime_args! {});
+    let reserve0: U256 = U256::from(reserve0.as_u128());
+    let reserve1: U256 = U256::from(reserve1.as_u128());
+    if token == token0 {
+        (reserve0, reserve1)
+    } else {
+        (reserve1, reserve0)
+    }
+}
+
+/// Swaps what was sent to the pair before the call, the output goes to this contract.
+fn swap_to_zap(pair_hash: ContractHash, deadline: u64) {
+    let _: (U256, U256) = runtime::call_contract(
+        pair_hash,
+        "swap_with_limits",
+        runtime_args! {
+            "amount0_out_min" => U256::zero(),
+            "amount1_out_min" => U256::zero(),
+            "to" => Key::from(data::get_package_hash()),
+            "deadline" => deadline
+        },
+    );
+}